@@ -29,13 +29,16 @@ pub struct DomainCapabilities {
     pub resource_providers: std::collections::HashSet<String>,
     // Superset of the classes of the resource providers advertised by the nodes.
     pub resource_classes: std::collections::HashSet<String>,
+    // Estimated latency from this domain to other domains, in ms.
+    // Key: identifier of the remote domain.
+    pub inter_domain_latency: std::collections::HashMap<String, u32>,
 }
 
 impl std::fmt::Display for DomainCapabilities {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "{} nodes, {} CPUs ({} cores) with {} MiB, labels [{}], num TEE {}, num TPM {}, runtimes [{}], resources classes [{}] providers [{}], disk space {} MiB, {} GPUs with {} MiB",
+            "{} nodes, {} CPUs ({} cores) with {} MiB, labels [{}], num TEE {}, num TPM {}, runtimes [{}], resources classes [{}] providers [{}], disk space {} MiB, {} GPUs with {} MiB, inter-domain latency [{}]",
             self.num_nodes,
             self.num_cpus,
             self.num_cores,
@@ -49,6 +52,11 @@ impl std::fmt::Display for DomainCapabilities {
             self.disk_tot_space,
             self.num_gpus,
             self.mem_size_gpu,
+            self.inter_domain_latency
+                .iter()
+                .map(|(domain_id, latency)| format!("{}:{} ms", domain_id, latency))
+                .collect::<Vec<String>>()
+                .join(","),
         )
     }
 }
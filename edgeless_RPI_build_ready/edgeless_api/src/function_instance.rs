@@ -12,6 +12,19 @@ pub enum StatePolicy {
     Global,
 }
 
+impl std::str::FromStr for StatePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "transient" => Ok(StatePolicy::Transient),
+            "node-local" => Ok(StatePolicy::NodeLocal),
+            "global" => Ok(StatePolicy::Global),
+            _ => anyhow::bail!("unknown state policy '{}'", s),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct StateSpecification {
     pub state_id: uuid::Uuid,
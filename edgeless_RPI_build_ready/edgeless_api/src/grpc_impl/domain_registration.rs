@@ -117,6 +117,7 @@ pub fn parse_domain_capabilities(api_instance: &crate::grpc_impl::api::DomainCap
         mem_size_gpu: api_instance.mem_size_gpu,
         resource_providers: std::collections::HashSet::from_iter(api_instance.resource_providers.iter().cloned()),
         resource_classes: std::collections::HashSet::from_iter(api_instance.resource_classes.iter().cloned()),
+        inter_domain_latency: api_instance.inter_domain_latency.clone(),
     }
 }
 
@@ -135,6 +136,7 @@ pub fn serialize_domain_capabilities(req: &crate::domain_registration::DomainCap
         mem_size_gpu: req.mem_size_gpu,
         resource_providers: req.resource_providers.iter().cloned().collect::<Vec<String>>(),
         resource_classes: req.resource_classes.iter().cloned().collect::<Vec<String>>(),
+        inter_domain_latency: req.inter_domain_latency.clone(),
     }
 }
 
@@ -240,6 +242,7 @@ mod test {
                     mem_size_gpu: 9,
                     resource_providers: std::collections::HashSet::from(["e".to_string(), "f".to_string()]),
                     resource_classes: std::collections::HashSet::from(["g".to_string(), "h".to_string()]),
+                    inter_domain_latency: std::collections::HashMap::from([("domain-2".to_string(), 10), ("domain-3".to_string(), 20)]),
                 },
                 refresh_deadline: std::time::UNIX_EPOCH + std::time::Duration::from_secs(313714800),
                 counter: 42,
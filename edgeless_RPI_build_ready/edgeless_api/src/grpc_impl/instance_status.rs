@@ -0,0 +1,361 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use std::str::FromStr;
+
+#[derive(Clone)]
+pub struct InstanceStatusClient {
+    client: Option<crate::grpc_impl::api::instance_status_client::InstanceStatusClient<tonic::transport::Channel>>,
+    server_addr: String,
+}
+
+pub struct InstanceStatusAPIService {
+    pub instance_status_api: tokio::sync::Mutex<Box<dyn crate::instance_status::InstanceStatusAPI>>,
+}
+
+impl InstanceStatusClient {
+    pub fn new(server_addr: String) -> Self {
+        Self { client: None, server_addr }
+    }
+
+    /// Try connecting, if not already connected.
+    ///
+    /// If an error is returned, then the client is set to None (disconnected).
+    /// Otherwise, the client is set to some value (connected).
+    async fn try_connect(&mut self) -> anyhow::Result<()> {
+        if self.client.is_none() {
+            self.client = match crate::grpc_impl::api::instance_status_client::InstanceStatusClient::connect(self.server_addr.clone()).await {
+                Ok(client) => {
+                    let client = client.max_decoding_message_size(usize::MAX);
+                    Some(client)
+                }
+                Err(err) => anyhow::bail!(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Disconnect the client.
+    fn disconnect(&mut self) {
+        self.client = None;
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::instance_status::InstanceStatusAPI for InstanceStatusClient {
+    async fn status(
+        &mut self,
+        ids: Vec<crate::function_instance::DomainManagedInstanceId>,
+    ) -> anyhow::Result<std::collections::HashMap<crate::function_instance::DomainManagedInstanceId, crate::instance_status::ComponentStatus>> {
+        match self.try_connect().await {
+            Ok(_) => {
+                if let Some(client) = &mut self.client {
+                    match client
+                        .status(tonic::Request::new(crate::grpc_impl::api::InstanceStatusRequest {
+                            ids: ids.iter().map(|id| id.to_string()).collect(),
+                        }))
+                        .await
+                    {
+                        Ok(res) => parse_instance_status_response(&res.into_inner()),
+                        Err(err) => {
+                            self.disconnect();
+                            anyhow::bail!("Error when retrieving the instance status at {}: {}", self.server_addr, err.to_string());
+                        }
+                    }
+                } else {
+                    panic!("The impossible happened");
+                }
+            }
+            Err(err) => {
+                anyhow::bail!("Error when connecting to {}: {}", self.server_addr, err);
+            }
+        }
+    }
+
+    async fn logs(
+        &mut self,
+        ids: Vec<crate::function_instance::DomainManagedInstanceId>,
+        since: crate::instance_status::LogPosition,
+    ) -> anyhow::Result<std::collections::HashMap<crate::function_instance::DomainManagedInstanceId, Vec<crate::instance_status::InstanceLogEntry>>>
+    {
+        match self.try_connect().await {
+            Ok(_) => {
+                if let Some(client) = &mut self.client {
+                    match client
+                        .logs(tonic::Request::new(crate::grpc_impl::api::InstanceLogsRequest {
+                            ids: ids.iter().map(|id| id.to_string()).collect(),
+                            since: since.seq,
+                            epoch: since.epoch,
+                        }))
+                        .await
+                    {
+                        Ok(res) => parse_instance_logs_response(&res.into_inner()),
+                        Err(err) => {
+                            self.disconnect();
+                            anyhow::bail!("Error when retrieving the instance logs at {}: {}", self.server_addr, err.to_string());
+                        }
+                    }
+                } else {
+                    panic!("The impossible happened");
+                }
+            }
+            Err(err) => {
+                anyhow::bail!("Error when connecting to {}: {}", self.server_addr, err);
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::grpc_impl::api::instance_status_server::InstanceStatus for InstanceStatusAPIService {
+    async fn status(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::InstanceStatusRequest>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::InstanceStatusResponse>, tonic::Status> {
+        let mut ids = vec![];
+        for id in &request.into_inner().ids {
+            match uuid::Uuid::from_str(id) {
+                Ok(id) => ids.push(id),
+                Err(err) => {
+                    return Err(tonic::Status::invalid_argument(format!(
+                        "Error when parsing an InstanceStatusRequest message: {}",
+                        err
+                    )))
+                }
+            }
+        }
+        match self.instance_status_api.lock().await.status(ids).await {
+            Ok(status) => Ok(tonic::Response::new(serialize_instance_status_response(&status))),
+            Err(err) => Err(tonic::Status::internal(format!("Error when retrieving the instance status: {}", err))),
+        }
+    }
+
+    async fn logs(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::InstanceLogsRequest>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::InstanceLogsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let mut ids = vec![];
+        for id in &request.ids {
+            match uuid::Uuid::from_str(id) {
+                Ok(id) => ids.push(id),
+                Err(err) => {
+                    return Err(tonic::Status::invalid_argument(format!(
+                        "Error when parsing an InstanceLogsRequest message: {}",
+                        err
+                    )))
+                }
+            }
+        }
+        let since = crate::instance_status::LogPosition {
+            epoch: request.epoch,
+            seq: request.since,
+        };
+        match self.instance_status_api.lock().await.logs(ids, since).await {
+            Ok(logs) => Ok(tonic::Response::new(serialize_instance_logs_response(&logs))),
+            Err(err) => Err(tonic::Status::internal(format!("Error when retrieving the instance logs: {}", err))),
+        }
+    }
+}
+
+pub fn parse_component_status(api_instance: &crate::grpc_impl::api::ComponentStatus) -> anyhow::Result<crate::instance_status::ComponentStatus> {
+    Ok(crate::instance_status::ComponentStatus {
+        state: match api_instance.state {
+            x if x == crate::grpc_impl::api::ComponentState::Running as i32 => crate::instance_status::ComponentState::Running,
+            x if x == crate::grpc_impl::api::ComponentState::Migrating as i32 => crate::instance_status::ComponentState::Migrating,
+            x if x == crate::grpc_impl::api::ComponentState::Failed as i32 => crate::instance_status::ComponentState::Failed,
+            x if x == crate::grpc_impl::api::ComponentState::Orphan as i32 => crate::instance_status::ComponentState::Orphan,
+            x => anyhow::bail!("Ill-formed ComponentStatus message: unknown state {}", x),
+        },
+        node_ids: api_instance
+            .node_ids
+            .iter()
+            .map(|x| uuid::Uuid::from_str(x))
+            .collect::<Result<Vec<uuid::Uuid>, uuid::Error>>()?,
+        last_invocation: api_instance
+            .last_invocation
+            .map(|x| std::time::UNIX_EPOCH + std::time::Duration::from_millis(x)),
+        error_count: api_instance.error_count,
+        restart_count: api_instance.restart_count,
+    })
+}
+
+pub fn serialize_component_status(req: &crate::instance_status::ComponentStatus) -> crate::grpc_impl::api::ComponentStatus {
+    crate::grpc_impl::api::ComponentStatus {
+        state: match req.state {
+            crate::instance_status::ComponentState::Running => crate::grpc_impl::api::ComponentState::Running as i32,
+            crate::instance_status::ComponentState::Migrating => crate::grpc_impl::api::ComponentState::Migrating as i32,
+            crate::instance_status::ComponentState::Failed => crate::grpc_impl::api::ComponentState::Failed as i32,
+            crate::instance_status::ComponentState::Orphan => crate::grpc_impl::api::ComponentState::Orphan as i32,
+        },
+        node_ids: req.node_ids.iter().map(|x| x.to_string()).collect(),
+        last_invocation: req
+            .last_invocation
+            .map(|x| x.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as u64),
+        error_count: req.error_count,
+        restart_count: req.restart_count,
+    }
+}
+
+fn parse_instance_status_response(
+    api_instance: &crate::grpc_impl::api::InstanceStatusResponse,
+) -> anyhow::Result<std::collections::HashMap<crate::function_instance::DomainManagedInstanceId, crate::instance_status::ComponentStatus>> {
+    let mut ret = std::collections::HashMap::new();
+    for (id, status) in &api_instance.status {
+        ret.insert(uuid::Uuid::from_str(id)?, parse_component_status(status)?);
+    }
+    Ok(ret)
+}
+
+fn serialize_instance_status_response(
+    req: &std::collections::HashMap<crate::function_instance::DomainManagedInstanceId, crate::instance_status::ComponentStatus>,
+) -> crate::grpc_impl::api::InstanceStatusResponse {
+    crate::grpc_impl::api::InstanceStatusResponse {
+        status: req
+            .iter()
+            .map(|(id, status)| (id.to_string(), serialize_component_status(status)))
+            .collect(),
+    }
+}
+
+pub fn parse_instance_log_entries(
+    api_instance: &crate::grpc_impl::api::InstanceLogEntries,
+) -> anyhow::Result<Vec<crate::instance_status::InstanceLogEntry>> {
+    let mut ret = vec![];
+    for entry in &api_instance.entries {
+        ret.push(crate::instance_status::InstanceLogEntry {
+            seq: entry.seq,
+            epoch: entry.epoch,
+            node_id: uuid::Uuid::from_str(&entry.node_id)?,
+            entry: match &entry.entry {
+                Some(entry) => crate::grpc_impl::node_registration::parse_function_log_entry(entry),
+                None => anyhow::bail!("Ill-formed InstanceLogEntry message: entry not present"),
+            },
+        });
+    }
+    Ok(ret)
+}
+
+pub fn serialize_instance_log_entries(req: &[crate::instance_status::InstanceLogEntry]) -> crate::grpc_impl::api::InstanceLogEntries {
+    crate::grpc_impl::api::InstanceLogEntries {
+        entries: req
+            .iter()
+            .map(|entry| crate::grpc_impl::api::InstanceLogEntry {
+                seq: entry.seq,
+                node_id: entry.node_id.to_string(),
+                entry: Some(crate::grpc_impl::node_registration::serialize_function_log_entry(&entry.entry)),
+                epoch: entry.epoch,
+            })
+            .collect(),
+    }
+}
+
+fn parse_instance_logs_response(
+    api_instance: &crate::grpc_impl::api::InstanceLogsResponse,
+) -> anyhow::Result<std::collections::HashMap<crate::function_instance::DomainManagedInstanceId, Vec<crate::instance_status::InstanceLogEntry>>> {
+    let mut ret = std::collections::HashMap::new();
+    for (id, entries) in &api_instance.logs {
+        ret.insert(uuid::Uuid::from_str(id)?, parse_instance_log_entries(entries)?);
+    }
+    Ok(ret)
+}
+
+fn serialize_instance_logs_response(
+    req: &std::collections::HashMap<crate::function_instance::DomainManagedInstanceId, Vec<crate::instance_status::InstanceLogEntry>>,
+) -> crate::grpc_impl::api::InstanceLogsResponse {
+    crate::grpc_impl::api::InstanceLogsResponse {
+        logs: req
+            .iter()
+            .map(|(id, entries)| (id.to_string(), serialize_instance_log_entries(entries)))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::instance_status::ComponentState;
+    use crate::instance_status::ComponentStatus;
+    use crate::instance_status::InstanceLogEntry;
+    use crate::node_registration::FunctionLogEntry;
+
+    #[test]
+    fn serialize_deserialize_instance_status_response() {
+        let messages = vec![
+            std::collections::HashMap::new(),
+            std::collections::HashMap::from([
+                (uuid::Uuid::new_v4(), ComponentStatus::failed()),
+                (uuid::Uuid::new_v4(), ComponentStatus::orphan()),
+                (
+                    uuid::Uuid::new_v4(),
+                    ComponentStatus {
+                        state: ComponentState::Running,
+                        node_ids: vec![uuid::Uuid::new_v4(), uuid::Uuid::new_v4()],
+                        last_invocation: Some(std::time::UNIX_EPOCH + std::time::Duration::from_millis(313714800123)),
+                        error_count: 42,
+                        restart_count: 3,
+                    },
+                ),
+                (
+                    uuid::Uuid::new_v4(),
+                    ComponentStatus {
+                        state: ComponentState::Migrating,
+                        node_ids: vec![],
+                        last_invocation: None,
+                        error_count: 0,
+                        restart_count: 0,
+                    },
+                ),
+            ]),
+        ];
+        for msg in messages {
+            match parse_instance_status_response(&serialize_instance_status_response(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_deserialize_instance_logs_response() {
+        let messages = vec![
+            std::collections::HashMap::new(),
+            std::collections::HashMap::from([
+                (uuid::Uuid::new_v4(), vec![]),
+                (
+                    uuid::Uuid::new_v4(),
+                    vec![
+                        InstanceLogEntry {
+                            seq: 1,
+                            epoch: 7,
+                            node_id: uuid::Uuid::new_v4(),
+                            entry: FunctionLogEntry {
+                                timestamp: std::time::UNIX_EPOCH + std::time::Duration::from_millis(313714800123),
+                                level: "Info".to_string(),
+                                target: "f1".to_string(),
+                                message: "started".to_string(),
+                            },
+                        },
+                        InstanceLogEntry {
+                            seq: 42,
+                            epoch: 7,
+                            node_id: uuid::Uuid::new_v4(),
+                            entry: FunctionLogEntry {
+                                timestamp: std::time::UNIX_EPOCH + std::time::Duration::from_millis(313714800456),
+                                level: "Warn".to_string(),
+                                target: "f1".to_string(),
+                                message: "slow".to_string(),
+                            },
+                        },
+                    ],
+                ),
+            ]),
+        ];
+        for msg in messages {
+            match parse_instance_logs_response(&serialize_instance_logs_response(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+}
@@ -11,6 +11,7 @@ pub mod domain_registration;
 pub mod function_instance;
 pub mod guest_api_function;
 pub mod guest_api_host;
+pub mod instance_status;
 pub mod invocation;
 pub mod node_management;
 pub mod node_registration;
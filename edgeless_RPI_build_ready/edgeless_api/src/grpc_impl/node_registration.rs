@@ -295,6 +295,56 @@ pub fn parse_node_performance_samples(
                 _ => None,
             })
             .collect(),
+        function_errors: api_instance
+            .function_errors
+            .iter()
+            .filter_map(|x| match uuid::Uuid::from_str(&x.id) {
+                Ok(val) => Some((val, x.value)),
+                _ => None,
+            })
+            .collect(),
+        function_restarts: api_instance
+            .function_restarts
+            .iter()
+            .filter_map(|x| match uuid::Uuid::from_str(&x.id) {
+                Ok(val) => Some((val, x.value)),
+                _ => None,
+            })
+            .collect(),
+        function_logs: api_instance
+            .function_logs
+            .iter()
+            .filter_map(|x| match uuid::Uuid::from_str(&x.id) {
+                Ok(val) => Some((val, x.entries.iter().map(parse_function_log_entry).collect())),
+                _ => None,
+            })
+            .collect(),
+        function_exits: api_instance
+            .function_exits
+            .iter()
+            .filter_map(|x| match uuid::Uuid::from_str(&x.id) {
+                Ok(val) => Some((val, x.value)),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+pub fn parse_function_log_entry(api_instance: &crate::grpc_impl::api::FunctionLogEntry) -> crate::node_registration::FunctionLogEntry {
+    crate::node_registration::FunctionLogEntry {
+        timestamp: std::time::UNIX_EPOCH + std::time::Duration::from_millis(api_instance.timestamp),
+        level: api_instance.level.clone(),
+        target: api_instance.target.clone(),
+        message: api_instance.message.clone(),
+    }
+}
+
+pub fn serialize_function_log_entry(req: &crate::node_registration::FunctionLogEntry) -> crate::grpc_impl::api::FunctionLogEntry {
+    crate::grpc_impl::api::FunctionLogEntry {
+        timestamp: req.timestamp.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+        level: req.level.clone(),
+        target: req.target.clone(),
+        message: req.message.clone(),
     }
 }
 
@@ -308,12 +358,45 @@ fn serialize_node_performance_samples(req: &crate::node_registration::NodePerfor
                 samples: samples.clone(),
             })
             .collect(),
+        function_errors: req
+            .function_errors
+            .iter()
+            .map(|(id, value)| crate::grpc_impl::api::Counter {
+                id: id.to_string(),
+                value: *value,
+            })
+            .collect(),
+        function_restarts: req
+            .function_restarts
+            .iter()
+            .map(|(id, value)| crate::grpc_impl::api::Counter {
+                id: id.to_string(),
+                value: *value,
+            })
+            .collect(),
+        function_logs: req
+            .function_logs
+            .iter()
+            .map(|(id, entries)| crate::grpc_impl::api::FunctionLogEntries {
+                id: id.to_string(),
+                entries: entries.iter().map(serialize_function_log_entry).collect(),
+            })
+            .collect(),
+        function_exits: req
+            .function_exits
+            .iter()
+            .map(|(id, value)| crate::grpc_impl::api::Counter {
+                id: id.to_string(),
+                value: *value,
+            })
+            .collect(),
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::node_registration::FunctionLogEntry;
     use crate::node_registration::NodeCapabilities;
     use crate::node_registration::NodeHealthStatus;
     use crate::node_registration::NodePerformanceSamples;
@@ -377,6 +460,29 @@ mod test {
                     (uuid::Uuid::new_v4(), vec![]),
                     (uuid::Uuid::new_v4(), vec![0.1, 0.2, 999.0]),
                 ]),
+                function_errors: std::collections::HashMap::from([(uuid::Uuid::new_v4(), 0), (uuid::Uuid::new_v4(), 42)]),
+                function_restarts: std::collections::HashMap::from([(uuid::Uuid::new_v4(), 3)]),
+                function_exits: std::collections::HashMap::from([(uuid::Uuid::new_v4(), 4)]),
+                function_logs: std::collections::HashMap::from([
+                    (uuid::Uuid::new_v4(), vec![]),
+                    (
+                        uuid::Uuid::new_v4(),
+                        vec![
+                            FunctionLogEntry {
+                                timestamp: std::time::UNIX_EPOCH + std::time::Duration::from_millis(313714800123),
+                                level: "Info".to_string(),
+                                target: "f1".to_string(),
+                                message: "started".to_string(),
+                            },
+                            FunctionLogEntry {
+                                timestamp: std::time::UNIX_EPOCH + std::time::Duration::from_millis(313714800456),
+                                level: "Error".to_string(),
+                                target: "f1".to_string(),
+                                message: "".to_string(),
+                            },
+                        ],
+                    ),
+                ]),
             },
         }];
         for msg in messages {
@@ -7,6 +7,7 @@ pub struct OrchestratorAPIClient {
     function_instance_client: Box<dyn crate::function_instance::FunctionInstanceAPI<crate::function_instance::DomainManagedInstanceId>>,
     resource_configuration_client:
         Box<dyn crate::resource_configuration::ResourceConfigurationAPI<crate::function_instance::DomainManagedInstanceId>>,
+    instance_status_client: Box<dyn crate::instance_status::InstanceStatusAPI>,
 }
 
 impl OrchestratorAPIClient {
@@ -16,6 +17,7 @@ impl OrchestratorAPIClient {
             resource_configuration_client: Box::new(crate::grpc_impl::resource_configuration::ResourceConfigurationClient::new(
                 api_addr.to_string(),
             )),
+            instance_status_client: Box::new(crate::grpc_impl::instance_status::InstanceStatusClient::new(api_addr.to_string())),
         })
     }
 }
@@ -30,6 +32,10 @@ impl crate::outer::orc::OrchestratorAPI for OrchestratorAPIClient {
     ) -> Box<dyn crate::resource_configuration::ResourceConfigurationAPI<crate::function_instance::DomainManagedInstanceId>> {
         self.resource_configuration_client.clone()
     }
+
+    fn instance_status_api(&mut self) -> Box<dyn crate::instance_status::InstanceStatusAPI> {
+        self.instance_status_client.clone()
+    }
 }
 
 pub struct OrchestratorAPIServer {}
@@ -44,6 +50,9 @@ impl OrchestratorAPIServer {
             crate::grpc_impl::resource_configuration::ResourceConfigurationServerHandler::<crate::function_instance::DomainManagedInstanceId> {
                 root_api: tokio::sync::Mutex::new(agent_api.resource_configuration_api()),
             };
+        let instance_status_api = crate::grpc_impl::instance_status::InstanceStatusAPIService {
+            instance_status_api: tokio::sync::Mutex::new(agent_api.instance_status_api()),
+        };
         Box::pin(async move {
             let function_api = function_api;
             if let Ok((_proto, host, port)) = crate::util::parse_http_host(&orchestrator_url) {
@@ -58,6 +67,10 @@ impl OrchestratorAPIServer {
                             crate::grpc_impl::api::resource_configuration_server::ResourceConfigurationServer::new(resource_configuration_api)
                                 .max_decoding_message_size(usize::MAX),
                         )
+                        .add_service(
+                            crate::grpc_impl::api::instance_status_server::InstanceStatusServer::new(instance_status_api)
+                                .max_decoding_message_size(usize::MAX),
+                        )
                         .serve(host)
                         .await
                     {
@@ -65,6 +65,25 @@ impl WorkflowInstanceConverters {
         })
     }
 
+    pub fn parse_workflow_update_request(
+        api_request: &crate::grpc_impl::api::UpdateWorkflowRequest,
+    ) -> anyhow::Result<crate::workflow_instance::UpdateWorkflowRequest> {
+        Ok(crate::workflow_instance::UpdateWorkflowRequest {
+            workflow_id: WorkflowInstanceConverters::parse_workflow_id(match api_request.workflow_id.as_ref() {
+                Some(val) => val,
+                None => {
+                    return Err(anyhow::anyhow!("WorkflowId Missing"));
+                }
+            })?,
+            request: WorkflowInstanceConverters::parse_workflow_spawn_request(match api_request.request.as_ref() {
+                Some(val) => val,
+                None => {
+                    return Err(anyhow::anyhow!("SpawnWorkflowRequest Missing"));
+                }
+            })?,
+        })
+    }
+
     pub fn parse_workflow_function_mapping(
         api_mapping: &crate::grpc_impl::api::WorkflowFunctionMapping,
     ) -> anyhow::Result<crate::workflow_instance::WorkflowFunctionMapping> {
@@ -132,6 +151,41 @@ impl WorkflowInstanceConverters {
         Ok(ret)
     }
 
+    pub fn parse_workflow_logs_request(
+        api_request: &crate::grpc_impl::api::WorkflowLogsRequest,
+    ) -> anyhow::Result<crate::workflow_instance::WorkflowLogsRequest> {
+        Ok(crate::workflow_instance::WorkflowLogsRequest {
+            workflow_id: match &api_request.workflow_id {
+                Some(workflow_id) => Self::parse_workflow_id(workflow_id)?,
+                None => anyhow::bail!("Missing workflow identifier"),
+            },
+            names: api_request.names.clone(),
+            since: api_request
+                .since
+                .iter()
+                .map(|(name, seq)| {
+                    (
+                        name.clone(),
+                        crate::instance_status::LogPosition {
+                            epoch: api_request.epochs.get(name).cloned().unwrap_or_default(),
+                            seq: *seq,
+                        },
+                    )
+                })
+                .collect(),
+        })
+    }
+
+    pub fn parse_workflow_logs_response(
+        api_response: &crate::grpc_impl::api::WorkflowLogsResponse,
+    ) -> anyhow::Result<std::collections::HashMap<String, Vec<crate::instance_status::InstanceLogEntry>>> {
+        let mut ret = std::collections::HashMap::new();
+        for (name, entries) in &api_response.logs {
+            ret.insert(name.clone(), crate::grpc_impl::instance_status::parse_instance_log_entries(entries)?);
+        }
+        Ok(ret)
+    }
+
     pub fn serialize_workflow_id(crate_id: &crate::workflow_instance::WorkflowId) -> crate::grpc_impl::api::WorkflowId {
         crate::grpc_impl::api::WorkflowId {
             workflow_id: crate_id.workflow_id.to_string(),
@@ -170,6 +224,41 @@ impl WorkflowInstanceConverters {
         }
     }
 
+    pub fn serialize_workflow_update_request(
+        crate_request: &crate::workflow_instance::UpdateWorkflowRequest,
+    ) -> crate::grpc_impl::api::UpdateWorkflowRequest {
+        crate::grpc_impl::api::UpdateWorkflowRequest {
+            workflow_id: Some(Self::serialize_workflow_id(&crate_request.workflow_id)),
+            request: Some(Self::serialize_workflow_spawn_request(&crate_request.request)),
+        }
+    }
+
+    pub fn serialize_workflow_logs_request(
+        crate_request: &crate::workflow_instance::WorkflowLogsRequest,
+    ) -> crate::grpc_impl::api::WorkflowLogsRequest {
+        crate::grpc_impl::api::WorkflowLogsRequest {
+            workflow_id: Some(Self::serialize_workflow_id(&crate_request.workflow_id)),
+            names: crate_request.names.clone(),
+            since: crate_request.since.iter().map(|(name, position)| (name.clone(), position.seq)).collect(),
+            epochs: crate_request
+                .since
+                .iter()
+                .map(|(name, position)| (name.clone(), position.epoch))
+                .collect(),
+        }
+    }
+
+    pub fn serialize_workflow_logs_response(
+        crate_response: &std::collections::HashMap<String, Vec<crate::instance_status::InstanceLogEntry>>,
+    ) -> crate::grpc_impl::api::WorkflowLogsResponse {
+        crate::grpc_impl::api::WorkflowLogsResponse {
+            logs: crate_response
+                .iter()
+                .map(|(name, entries)| (name.clone(), crate::grpc_impl::instance_status::serialize_instance_log_entries(entries)))
+                .collect(),
+        }
+    }
+
     pub fn serialize_workflow_spawn_response(
         crate_request: &crate::workflow_instance::SpawnWorkflowResponse,
     ) -> crate::grpc_impl::api::SpawnWorkflowResponse {
@@ -271,6 +360,21 @@ impl crate::workflow_instance::WorkflowInstanceAPI for WorkflowInstanceAPIClient
             Err(err) => Err(anyhow::anyhow!("Communication error while stopping a workflow: {}", err.to_string())),
         }
     }
+    async fn update(
+        &mut self,
+        request: crate::workflow_instance::UpdateWorkflowRequest,
+    ) -> anyhow::Result<crate::workflow_instance::SpawnWorkflowResponse> {
+        let ret = self
+            .client
+            .update(tonic::Request::new(
+                crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::serialize_workflow_update_request(&request),
+            ))
+            .await;
+        match ret {
+            Ok(ret) => return crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::parse_workflow_spawn_response(&ret.into_inner()),
+            Err(err) => Err(anyhow::anyhow!("Communication error while updating a workflow: {}", err.to_string())),
+        }
+    }
     async fn list(&mut self) -> anyhow::Result<Vec<crate::workflow_instance::WorkflowId>> {
         let ret = self.client.list(tonic::Request::new(())).await;
         match ret {
@@ -305,12 +409,35 @@ impl crate::workflow_instance::WorkflowInstanceAPI for WorkflowInstanceAPIClient
                     Some(status) => crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::parse_workflow_instance(status)?,
                     None => anyhow::bail!("Workflow status not present"),
                 };
+                let mut component_status = std::collections::HashMap::new();
+                for (name, value) in &ret.component_status {
+                    component_status.insert(name.clone(), crate::grpc_impl::instance_status::parse_component_status(value)?);
+                }
 
-                return Ok(crate::workflow_instance::WorkflowInfo { request, status });
+                return Ok(crate::workflow_instance::WorkflowInfo {
+                    request,
+                    status,
+                    component_status,
+                });
             }
             Err(err) => Err(anyhow::anyhow!("Communication error while listing workflows: {}", err.to_string())),
         }
     }
+    async fn logs(
+        &mut self,
+        request: crate::workflow_instance::WorkflowLogsRequest,
+    ) -> anyhow::Result<std::collections::HashMap<String, Vec<crate::instance_status::InstanceLogEntry>>> {
+        let ret = self
+            .client
+            .logs(tonic::Request::new(
+                crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::serialize_workflow_logs_request(&request),
+            ))
+            .await;
+        match ret {
+            Ok(ret) => return crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::parse_workflow_logs_response(&ret.into_inner()),
+            Err(err) => Err(anyhow::anyhow!("Communication error while retrieving workflow logs: {}", err.to_string())),
+        }
+    }
     async fn domains(
         &mut self,
         domain_id: String,
@@ -375,6 +502,37 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
         }
     }
 
+    async fn update(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::UpdateWorkflowRequest>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::SpawnWorkflowResponse>, tonic::Status> {
+        let req = match crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::parse_workflow_update_request(&request.into_inner()) {
+            Ok(val) => val,
+            Err(err) => {
+                return Ok(tonic::Response::new(crate::grpc_impl::api::SpawnWorkflowResponse {
+                    response_error: Some(crate::grpc_impl::api::ResponseError {
+                        summary: "Invalid request".to_string(),
+                        detail: Some(err.to_string()),
+                    }),
+                    workflow_status: None,
+                }))
+            }
+        };
+        let ret = self.root_api.lock().await.update(req).await;
+        match ret {
+            Ok(response) => Ok(tonic::Response::new(
+                crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::serialize_workflow_spawn_response(&response),
+            )),
+            Err(err) => Ok(tonic::Response::new(crate::grpc_impl::api::SpawnWorkflowResponse {
+                response_error: Some(crate::grpc_impl::api::ResponseError {
+                    summary: "Request rejected".to_string(),
+                    detail: Some(err.to_string()),
+                }),
+                workflow_status: None,
+            })),
+        }
+    }
+
     async fn list(&self, _request: tonic::Request<()>) -> Result<tonic::Response<crate::grpc_impl::api::WorkflowIdList>, tonic::Status> {
         let ret = self.root_api.lock().await.list().await;
         match ret {
@@ -398,11 +556,32 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
             Ok(info) => Ok(tonic::Response::new(crate::grpc_impl::api::WorkflowInstanceInfo {
                 request: Some(crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::serialize_workflow_spawn_request(&info.request)),
                 status: Some(crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::serialize_workflow_instance(&info.status)),
+                component_status: info
+                    .component_status
+                    .iter()
+                    .map(|(name, value)| (name.clone(), crate::grpc_impl::instance_status::serialize_component_status(value)))
+                    .collect(),
             })),
             Err(err) => Err(tonic::Status::internal(format!("Internal error when inspecting a workflow: {}", err))),
         }
     }
 
+    async fn logs(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::WorkflowLogsRequest>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::WorkflowLogsResponse>, tonic::Status> {
+        let req = match crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::parse_workflow_logs_request(&request.into_inner()) {
+            Ok(val) => val,
+            Err(err) => return Err(tonic::Status::invalid_argument(format!("Invalid request of workflow logs: {}", err))),
+        };
+        match self.root_api.lock().await.logs(req).await {
+            Ok(logs) => Ok(tonic::Response::new(
+                crate::grpc_impl::workflow_instance::WorkflowInstanceConverters::serialize_workflow_logs_response(&logs),
+            )),
+            Err(err) => Err(tonic::Status::internal(format!("Internal error when retrieving workflow logs: {}", err))),
+        }
+    }
+
     async fn domains(
         &self,
         domain_id: tonic::Request<crate::grpc_impl::api::DomainId>,
@@ -425,12 +604,17 @@ mod tests {
 
     use super::*;
     use crate::function_instance::FunctionClassSpecification;
+    use crate::instance_status::InstanceLogEntry;
+    use crate::instance_status::LogPosition;
+    use crate::node_registration::FunctionLogEntry;
     use crate::workflow_instance::SpawnWorkflowRequest;
     use crate::workflow_instance::SpawnWorkflowResponse;
+    use crate::workflow_instance::UpdateWorkflowRequest;
     use crate::workflow_instance::WorkflowFunction;
     use crate::workflow_instance::WorkflowFunctionMapping;
     use crate::workflow_instance::WorkflowId;
     use crate::workflow_instance::WorkflowInstance;
+    use crate::workflow_instance::WorkflowLogsRequest;
     use crate::workflow_instance::WorkflowResource;
 
     #[test]
@@ -519,6 +703,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn serialize_deserialize_workflow_update_request() {
+        let messages = vec![UpdateWorkflowRequest {
+            workflow_id: WorkflowId {
+                workflow_id: uuid::Uuid::new_v4(),
+            },
+            request: SpawnWorkflowRequest {
+                workflow_functions: vec![WorkflowFunction {
+                    name: "f1".to_string(),
+                    function_class_specification: FunctionClassSpecification {
+                        function_class_id: "my_fun_class".to_string(),
+                        function_class_type: "my_fun_class_type".to_string(),
+                        function_class_version: "0.0.2".to_string(),
+                        function_class_code: "byte-code".to_string().as_bytes().to_vec(),
+                        function_class_outputs: vec!["out1".to_string()],
+                    },
+                    output_mapping: HashMap::from([("out1".to_string(), "res1".to_string())]),
+                    annotations: HashMap::from([("ann1".to_string(), "val1".to_string())]),
+                }],
+                annotations: HashMap::new(),
+                workflow_resources: vec![WorkflowResource {
+                    name: "res1".to_string(),
+                    class_type: "my_res_class_type".to_string(),
+                    output_mapping: HashMap::new(),
+                    configurations: HashMap::from([("conf1".to_string(), "val1".to_string())]),
+                }],
+            },
+        }];
+
+        for msg in messages {
+            match WorkflowInstanceConverters::parse_workflow_update_request(&WorkflowInstanceConverters::serialize_workflow_update_request(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
     #[test]
     fn serialize_deserialize_workflow_function_mapping() {
         let messages = vec![WorkflowFunctionMapping {
@@ -564,6 +785,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn serialize_deserialize_workflow_logs_request() {
+        let messages = vec![
+            WorkflowLogsRequest {
+                workflow_id: WorkflowId {
+                    workflow_id: uuid::Uuid::new_v4(),
+                },
+                names: vec![],
+                since: HashMap::new(),
+            },
+            WorkflowLogsRequest {
+                workflow_id: WorkflowId {
+                    workflow_id: uuid::Uuid::new_v4(),
+                },
+                names: vec!["fun1".to_string(), "fun2".to_string()],
+                since: HashMap::from([
+                    ("fun1".to_string(), LogPosition { epoch: 0, seq: 42 }),
+                    ("fun2".to_string(), LogPosition { epoch: 99, seq: 1 }),
+                ]),
+            },
+        ];
+
+        for msg in messages {
+            match WorkflowInstanceConverters::parse_workflow_logs_request(&WorkflowInstanceConverters::serialize_workflow_logs_request(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_deserialize_workflow_logs_response() {
+        let messages = vec![
+            HashMap::new(),
+            HashMap::from([
+                ("fun1".to_string(), vec![]),
+                (
+                    "fun2".to_string(),
+                    vec![InstanceLogEntry {
+                        seq: 7,
+                        epoch: 99,
+                        node_id: uuid::Uuid::new_v4(),
+                        entry: FunctionLogEntry {
+                            timestamp: std::time::UNIX_EPOCH + std::time::Duration::from_millis(313714800123),
+                            level: "Debug".to_string(),
+                            target: "fun2".to_string(),
+                            message: "hello".to_string(),
+                        },
+                    }],
+                ),
+            ]),
+        ];
+
+        for msg in messages {
+            match WorkflowInstanceConverters::parse_workflow_logs_response(&WorkflowInstanceConverters::serialize_workflow_logs_response(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
     #[test]
     fn serialize_deserialize_spawn_response() {
         let messages = vec![SpawnWorkflowResponse::WorkflowInstance(WorkflowInstance {
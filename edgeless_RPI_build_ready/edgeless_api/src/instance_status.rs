@@ -0,0 +1,159 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum ComponentState {
+    // All the instances are running.
+    Running,
+    // The function/resource is being (re-)assigned to a node.
+    Migrating,
+    // The function/resource has failed or it is unknown to the orchestrator.
+    Failed,
+    // The function/resource is not assigned to any orchestration domain.
+    Orphan,
+}
+
+impl std::fmt::Display for ComponentState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ComponentState::Running => "running",
+                ComponentState::Migrating => "migrating",
+                ComponentState::Failed => "failed",
+                ComponentState::Orphan => "orphan",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ComponentStatus {
+    // State of the function/resource.
+    pub state: ComponentState,
+    // Nodes hosting the function/resource instances.
+    pub node_ids: Vec<crate::function_instance::NodeId>,
+    // Last time the function was invoked, if known.
+    // The time resolution is that of the node updates.
+    pub last_invocation: Option<std::time::SystemTime>,
+    // Number of errors reported by the nodes.
+    pub error_count: u64,
+    // Number of restarts of the instances reported by the nodes.
+    pub restart_count: u64,
+}
+
+impl ComponentStatus {
+    pub fn orphan() -> Self {
+        Self {
+            state: ComponentState::Orphan,
+            node_ids: vec![],
+            last_invocation: None,
+            error_count: 0,
+            restart_count: 0,
+        }
+    }
+
+    pub fn failed() -> Self {
+        Self {
+            state: ComponentState::Failed,
+            node_ids: vec![],
+            last_invocation: None,
+            error_count: 0,
+            restart_count: 0,
+        }
+    }
+}
+
+impl std::fmt::Display for ComponentStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, node(s) [{}], last invocation {}, {} error(s), {} restart(s)",
+            self.state,
+            self.node_ids.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(","),
+            match self.last_invocation {
+                Some(last_invocation) => match std::time::SystemTime::now().duration_since(last_invocation) {
+                    Ok(elapsed) => format!("{} s ago", elapsed.as_secs()),
+                    Err(_) => "now".to_string(),
+                },
+                None => "never".to_string(),
+            },
+            self.error_count,
+            self.restart_count
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct InstanceLogEntry {
+    // Sequence number assigned by the orchestrator, which increases with the
+    // order in which the entries are received from the nodes.
+    pub seq: u64,
+    // Epoch of the sequence number, which is drawn at random when the
+    // orchestrator starts, since the sequence numbers restart from 1.
+    pub epoch: u64,
+    // Node hosting the function instance that emitted the entry.
+    pub node_id: crate::function_instance::NodeId,
+    // Log entry.
+    pub entry: crate::node_registration::FunctionLogEntry,
+}
+
+/// Position of a log entry in the sequence of those kept by an orchestrator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct LogPosition {
+    pub epoch: u64,
+    pub seq: u64,
+}
+
+impl LogPosition {
+    /// Position of a log entry.
+    pub fn of(entry: &InstanceLogEntry) -> Self {
+        Self {
+            epoch: entry.epoch,
+            seq: entry.seq,
+        }
+    }
+
+    /// Return true if the entry follows this position. The sequence numbers
+    /// of different epochs cannot be compared: in this case, the entry is
+    /// considered to follow, so that all the entries of a new epoch are
+    /// returned.
+    pub fn precedes(&self, entry: &InstanceLogEntry) -> bool {
+        entry.epoch != self.epoch || entry.seq > self.seq
+    }
+}
+
+#[async_trait::async_trait]
+pub trait InstanceStatusAPI: InstanceStatusAPIClone + Sync + Send {
+    async fn status(
+        &mut self,
+        ids: Vec<crate::function_instance::DomainManagedInstanceId>,
+    ) -> anyhow::Result<std::collections::HashMap<crate::function_instance::DomainManagedInstanceId, ComponentStatus>>;
+
+    /// Return the log entries of the given functions that follow `since`,
+    /// in chronological order.
+    async fn logs(
+        &mut self,
+        ids: Vec<crate::function_instance::DomainManagedInstanceId>,
+        since: LogPosition,
+    ) -> anyhow::Result<std::collections::HashMap<crate::function_instance::DomainManagedInstanceId, Vec<InstanceLogEntry>>>;
+}
+
+// https://stackoverflow.com/a/30353928
+pub trait InstanceStatusAPIClone {
+    fn clone_box(&self) -> Box<dyn InstanceStatusAPI>;
+}
+impl<T> InstanceStatusAPIClone for T
+where
+    T: 'static + InstanceStatusAPI + Clone,
+{
+    fn clone_box(&self) -> Box<dyn InstanceStatusAPI> {
+        Box::new(self.clone())
+    }
+}
+impl Clone for Box<dyn InstanceStatusAPI> {
+    fn clone(&self) -> Box<dyn InstanceStatusAPI> {
+        self.clone_box()
+    }
+}
@@ -11,10 +11,12 @@ pub mod function_instance;
 pub mod grpc_impl;
 pub mod guest_api_function;
 pub mod guest_api_host;
+pub mod instance_status;
 pub mod invocation;
 pub mod node_management;
 pub mod node_registration;
 pub mod outer;
+pub mod output_routing;
 pub mod resource_configuration;
 pub mod util;
 pub mod workflow_instance;
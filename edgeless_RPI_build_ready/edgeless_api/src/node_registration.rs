@@ -175,6 +175,25 @@ impl NodeHealthStatus {
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct NodePerformanceSamples {
     pub function_execution_times: std::collections::HashMap<crate::function_instance::ComponentId, Vec<f64>>,
+    pub function_errors: std::collections::HashMap<crate::function_instance::ComponentId, u64>,
+    pub function_restarts: std::collections::HashMap<crate::function_instance::ComponentId, u64>,
+    // Log entries emitted by the function instances since the last update,
+    // in chronological order.
+    pub function_logs: std::collections::HashMap<crate::function_instance::ComponentId, Vec<FunctionLogEntry>>,
+    // Number of function instances terminated since the last update.
+    pub function_exits: std::collections::HashMap<crate::function_instance::ComponentId, u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FunctionLogEntry {
+    // Time when the entry was emitted.
+    pub timestamp: std::time::SystemTime,
+    // Log level: one of Error, Warn, Info, Debug, Trace.
+    pub level: String,
+    // Target of the log directive.
+    pub target: String,
+    // Log message.
+    pub message: String,
 }
 
 impl std::fmt::Display for NodeHealthStatus {
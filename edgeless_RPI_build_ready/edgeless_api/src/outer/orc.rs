@@ -8,4 +8,5 @@ pub trait OrchestratorAPI: Send {
     fn resource_configuration_api(
         &mut self,
     ) -> Box<dyn crate::resource_configuration::ResourceConfigurationAPI<crate::function_instance::DomainManagedInstanceId>>;
+    fn instance_status_api(&mut self) -> Box<dyn crate::instance_status::InstanceStatusAPI>;
 }
@@ -0,0 +1,294 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Routing rules of the outputs of functions.
+//!
+//! An output of a function can be mapped to multiple targets (fan-out),
+//! possibly only when the payload of the event satisfies a condition.
+//! Each routing rule is encoded in the key of the output mapping as:
+//!
+//! - `<output>[<index>]`: unconditional route;
+//! - `<output>[<index>]?<condition>`: conditional route;
+//!
+//! where `<index>` distinguishes the routes of the same output, so that the
+//! encoding can be carried unmodified in all the output mappings, from the
+//! workflow request down to the node hosting the function instance.
+//!
+//! A condition has the form `<path> <op> <value>`, where:
+//!
+//! - `<path>` is a dot-separated list of fields of the JSON payload, e.g.,
+//!   `result.classification`, with numbers used to index arrays;
+//! - `<op>` is one of `==`, `!=`, `<`, `<=`, `>`, `>=`;
+//! - `<value>` is a JSON value, e.g., `"HighActivity"`, `42`, `true`;
+//!   a value that is not valid JSON is interpreted as a string.
+//!
+//! A condition never matches a payload that is not valid JSON or that
+//! does not contain the field specified.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComparisonOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl std::fmt::Display for ComparisonOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ComparisonOp::Eq => "==",
+                ComparisonOp::Ne => "!=",
+                ComparisonOp::Lt => "<",
+                ComparisonOp::Le => "<=",
+                ComparisonOp::Gt => ">",
+                ComparisonOp::Ge => ">=",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteCondition {
+    // Fields of the JSON payload to be inspected.
+    pub path: Vec<String>,
+    // Comparison operator.
+    pub op: ComparisonOp,
+    // Value to which the field is compared.
+    pub value: serde_json::Value,
+}
+
+impl std::str::FromStr for RouteCondition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pos = match s.find(['=', '!', '<', '>']) {
+            Some(pos) => pos,
+            None => anyhow::bail!("missing comparison operator in condition '{}'", s),
+        };
+        let (lhs, rest) = s.split_at(pos);
+        let (op, rhs) = if let Some(rhs) = rest.strip_prefix("==") {
+            (ComparisonOp::Eq, rhs)
+        } else if let Some(rhs) = rest.strip_prefix("!=") {
+            (ComparisonOp::Ne, rhs)
+        } else if let Some(rhs) = rest.strip_prefix("<=") {
+            (ComparisonOp::Le, rhs)
+        } else if let Some(rhs) = rest.strip_prefix(">=") {
+            (ComparisonOp::Ge, rhs)
+        } else if let Some(rhs) = rest.strip_prefix('<') {
+            (ComparisonOp::Lt, rhs)
+        } else if let Some(rhs) = rest.strip_prefix('>') {
+            (ComparisonOp::Gt, rhs)
+        } else {
+            anyhow::bail!("invalid comparison operator in condition '{}'", s);
+        };
+
+        let lhs = lhs.trim();
+        if lhs.is_empty() {
+            anyhow::bail!("missing field in condition '{}'", s);
+        }
+        let path = lhs.split('.').map(|x| x.trim().to_string()).collect::<Vec<String>>();
+        if path.iter().any(|x| x.is_empty()) {
+            anyhow::bail!("invalid field '{}' in condition '{}'", lhs, s);
+        }
+
+        let rhs = rhs.trim();
+        if rhs.is_empty() {
+            anyhow::bail!("missing value in condition '{}'", s);
+        }
+        let value = serde_json::from_str(rhs).unwrap_or_else(|_| serde_json::Value::String(rhs.to_string()));
+
+        Ok(Self { path, op, value })
+    }
+}
+
+impl std::fmt::Display for RouteCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.path.join("."), self.op, self.value)
+    }
+}
+
+impl RouteCondition {
+    /// Return true if the given payload satisfies the condition.
+    pub fn matches(&self, payload: &str) -> bool {
+        let payload: serde_json::Value = match serde_json::from_str(payload) {
+            Ok(val) => val,
+            Err(_) => return false,
+        };
+        let mut field = &payload;
+        for name in &self.path {
+            field = match field {
+                serde_json::Value::Object(map) => match map.get(name) {
+                    Some(val) => val,
+                    None => return false,
+                },
+                serde_json::Value::Array(vec) => match name.parse::<usize>().ok().and_then(|ndx| vec.get(ndx)) {
+                    Some(val) => val,
+                    None => return false,
+                },
+                _ => return false,
+            };
+        }
+
+        let ordering = match (field, &self.value) {
+            (serde_json::Value::Number(lhs), serde_json::Value::Number(rhs)) => match (lhs.as_f64(), rhs.as_f64()) {
+                (Some(lhs), Some(rhs)) => lhs.partial_cmp(&rhs),
+                _ => None,
+            },
+            (serde_json::Value::String(lhs), serde_json::Value::String(rhs)) => Some(lhs.cmp(rhs)),
+            (lhs, rhs) if lhs == rhs => Some(std::cmp::Ordering::Equal),
+            _ => None,
+        };
+
+        match self.op {
+            ComparisonOp::Eq => ordering == Some(std::cmp::Ordering::Equal),
+            ComparisonOp::Ne => ordering != Some(std::cmp::Ordering::Equal),
+            ComparisonOp::Lt => ordering == Some(std::cmp::Ordering::Less),
+            ComparisonOp::Le => matches!(ordering, Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)),
+            ComparisonOp::Gt => ordering == Some(std::cmp::Ordering::Greater),
+            ComparisonOp::Ge => matches!(ordering, Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputRoute {
+    // Name of the function output.
+    pub output: String,
+    // Index of the route among those of the same output.
+    pub index: usize,
+    // Condition to be satisfied by the payload, if any.
+    pub condition: Option<RouteCondition>,
+}
+
+impl OutputRoute {
+    /// Parse a key of an output mapping.
+    ///
+    /// Return None if the key is a plain output name, i.e., not a route.
+    pub fn parse(key: &str) -> anyhow::Result<Option<Self>> {
+        let (output, rest) = match key.split_once('[') {
+            Some(val) => val,
+            None => return Ok(None),
+        };
+        if output.is_empty() {
+            anyhow::bail!("missing output name in route '{}'", key);
+        }
+        let (index, rest) = match rest.split_once(']') {
+            Some(val) => val,
+            None => anyhow::bail!("missing closing bracket in route '{}'", key),
+        };
+        let index = match index.parse::<usize>() {
+            Ok(val) => val,
+            Err(_) => anyhow::bail!("invalid index '{}' in route '{}'", index, key),
+        };
+        let condition = if rest.is_empty() {
+            None
+        } else if let Some(condition) = rest.strip_prefix('?') {
+            Some(condition.parse::<RouteCondition>()?)
+        } else {
+            anyhow::bail!("unexpected characters after the index in route '{}'", key);
+        };
+        Ok(Some(Self {
+            output: output.to_string(),
+            index,
+            condition,
+        }))
+    }
+
+    /// Key of the output mapping encoding this route.
+    pub fn key(&self) -> String {
+        match &self.condition {
+            Some(condition) => format!("{}[{}]?{}", self.output, self.index, condition),
+            None => format!("{}[{}]", self.output, self.index),
+        }
+    }
+}
+
+/// Check that all the keys of the given output mapping are either plain
+/// output names or well-formed routes.
+pub fn validate_output_mapping(output_mapping: &std::collections::HashMap<String, String>) -> anyhow::Result<()> {
+    for key in output_mapping.keys() {
+        OutputRoute::parse(key)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_route_condition_parse() {
+        let condition = "classification == \"HighActivity\"".parse::<RouteCondition>().unwrap();
+        assert_eq!(vec!["classification".to_string()], condition.path);
+        assert_eq!(ComparisonOp::Eq, condition.op);
+        assert_eq!(serde_json::json!("HighActivity"), condition.value);
+        assert_eq!("classification == \"HighActivity\"", condition.to_string());
+
+        let condition = "a.b.0>=42.5".parse::<RouteCondition>().unwrap();
+        assert_eq!(vec!["a", "b", "0"], condition.path);
+        assert_eq!(ComparisonOp::Ge, condition.op);
+        assert_eq!(serde_json::json!(42.5), condition.value);
+
+        // Bare values are strings.
+        let condition = "x != HighActivity".parse::<RouteCondition>().unwrap();
+        assert_eq!(ComparisonOp::Ne, condition.op);
+        assert_eq!(serde_json::json!("HighActivity"), condition.value);
+
+        for wrong in ["", "x", "x =", "== 1", "x = 1", "x. == 1", "x ! 1"] {
+            assert!(wrong.parse::<RouteCondition>().is_err(), "{}", wrong);
+        }
+    }
+
+    #[test]
+    fn test_route_condition_matches() {
+        let payload = r#"{"classification":"HighActivity","score":0.9,"tags":["a","b"],"nested":{"n":3}}"#;
+        let cases = vec![
+            ("classification == \"HighActivity\"", true),
+            ("classification == \"LowActivity\"", false),
+            ("classification != \"LowActivity\"", true),
+            ("score > 0.5", true),
+            ("score <= 0.5", false),
+            ("nested.n == 3", true),
+            ("nested.n == 3.0", true),
+            ("nested.n < 2", false),
+            ("tags.1 == \"b\"", true),
+            ("tags.2 == \"b\"", false),
+            ("missing == 1", false),
+            ("missing != 1", false),
+            ("classification > 1", false),
+        ];
+        for (condition, expected) in cases {
+            assert_eq!(expected, condition.parse::<RouteCondition>().unwrap().matches(payload), "{}", condition);
+        }
+
+        let condition = "x == 1".parse::<RouteCondition>().unwrap();
+        assert!(!condition.matches("not JSON"));
+        assert!(!condition.matches("[1, 2, 3]"));
+    }
+
+    #[test]
+    fn test_output_route_parse() {
+        assert_eq!(None, OutputRoute::parse("out").unwrap());
+
+        let route = OutputRoute::parse("out[1]").unwrap().unwrap();
+        assert_eq!("out", route.output);
+        assert_eq!(1, route.index);
+        assert!(route.condition.is_none());
+        assert_eq!("out[1]", route.key());
+
+        let route = OutputRoute::parse("out[0]?classification==\"HighActivity\"").unwrap().unwrap();
+        assert_eq!("out", route.output);
+        assert_eq!(0, route.index);
+        assert!(route.condition.is_some());
+        assert_eq!(route, OutputRoute::parse(&route.key()).unwrap().unwrap());
+
+        for wrong in ["[0]", "out[", "out[x]", "out[0]x", "out[0]?x"] {
+            assert!(OutputRoute::parse(wrong).is_err(), "{}", wrong);
+        }
+    }
+}
@@ -73,10 +73,28 @@ pub struct SpawnWorkflowRequest {
     pub annotations: std::collections::HashMap<String, String>,
 }
 
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct UpdateWorkflowRequest {
+    pub workflow_id: WorkflowId,
+    pub request: SpawnWorkflowRequest,
+}
+
 #[derive(Clone, Debug, PartialEq, serde::Serialize)]
 pub struct WorkflowInfo {
     pub request: SpawnWorkflowRequest,
     pub status: WorkflowInstance,
+    // Status of the functions/resources, by name.
+    pub component_status: std::collections::HashMap<String, crate::instance_status::ComponentStatus>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct WorkflowLogsRequest {
+    pub workflow_id: WorkflowId,
+    // Names of the functions whose log entries are requested, all if empty.
+    pub names: Vec<String>,
+    // Position of the last entry already received, by function name.
+    // The functions not present have all their entries returned.
+    pub since: std::collections::HashMap<String, crate::instance_status::LogPosition>,
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize)]
@@ -89,8 +107,13 @@ pub enum SpawnWorkflowResponse {
 pub trait WorkflowInstanceAPI: WorkflowInstanceAPIClone + Send + Sync {
     async fn start(&mut self, request: SpawnWorkflowRequest) -> anyhow::Result<SpawnWorkflowResponse>;
     async fn stop(&mut self, id: WorkflowId) -> anyhow::Result<()>;
+    async fn update(&mut self, request: UpdateWorkflowRequest) -> anyhow::Result<SpawnWorkflowResponse>;
     async fn list(&mut self) -> anyhow::Result<Vec<WorkflowId>>;
     async fn inspect(&mut self, id: WorkflowId) -> anyhow::Result<WorkflowInfo>;
+    async fn logs(
+        &mut self,
+        request: WorkflowLogsRequest,
+    ) -> anyhow::Result<std::collections::HashMap<String, Vec<crate::instance_status::InstanceLogEntry>>>;
     async fn domains(
         &mut self,
         domain_id: String,
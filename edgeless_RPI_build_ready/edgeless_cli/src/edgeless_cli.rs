@@ -2,7 +2,10 @@
 // SPDX-FileCopyrightText: © 2023 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
 // SPDX-FileCopyrightText: © 2023 Siemens AG
 // SPDX-License-Identifier: MIT
+mod workflow_logs;
 mod workflow_spec;
+mod workflow_template;
+mod workflow_validate;
 
 use clap::Parser;
 use edgeless_api::{outer::controller::ControllerAPI, workflow_instance::SpawnWorkflowResponse};
@@ -17,12 +20,54 @@ use std::time::SystemTime;
 use tokio::fs::File;
 use tokio_util::codec::{BytesCodec, FramedRead};
 
+/// Values of the ${name} placeholders in a workflow specification.
+#[derive(Debug, clap::Args)]
+struct WorkflowParameters {
+    /// File with the values of the parameters, in JSON (.json) or TOML format
+    #[arg(long)]
+    params: Option<String>,
+    /// Value of a parameter, as name=value, overriding that in the parameter file
+    #[arg(long = "set", value_name = "NAME=VALUE", value_parser = workflow_template::parse_assignment)]
+    set: Vec<(String, String)>,
+}
+
 #[derive(Debug, clap::Subcommand)]
 enum WorkflowCommands {
-    Start { spec_file: String },
-    Stop { id: String },
+    Start {
+        spec_file: String,
+        #[command(flatten)]
+        parameters: WorkflowParameters,
+    },
+    Stop {
+        id: String,
+    },
+    Update {
+        id: String,
+        spec_file: String,
+        #[command(flatten)]
+        parameters: WorkflowParameters,
+    },
     List {},
-    Inspect { id: String },
+    Inspect {
+        id: String,
+    },
+    Logs {
+        id: String,
+        /// Show only the log entries of the function with the given name
+        #[arg(long)]
+        function: Option<String>,
+        /// Keep waiting for new log entries until interrupted
+        #[arg(long)]
+        follow: bool,
+    },
+    Validate {
+        spec_file: String,
+        #[command(flatten)]
+        parameters: WorkflowParameters,
+        /// Write the workflow graph in the DOT language to the given file
+        #[arg(long)]
+        dot: Option<String>,
+    },
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -47,6 +92,10 @@ enum FunctionCommands {
         binary_name: String,
         function_type: String,
     },
+    Run {
+        code_file: String,
+        script_file: String,
+    },
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -119,6 +168,87 @@ async fn wf_client(config_file: &str) -> anyhow::Result<Box<dyn edgeless_api::wo
     Ok(con_client.workflow_instance_api())
 }
 
+/// Read a JSON workflow specification and replace its placeholders with
+/// the values of the parameters.
+fn read_workflow_spec(spec_file: &str, parameters: &WorkflowParameters) -> anyhow::Result<workflow_spec::WorkflowSpec> {
+    let workflow: workflow_spec::WorkflowSpec = serde_json::from_str(&std::fs::read_to_string(spec_file)?)?;
+    let mut values = match &parameters.params {
+        Some(params) => workflow_template::read_parameters(params)?,
+        None => std::collections::HashMap::new(),
+    };
+    values.extend(parameters.set.iter().cloned());
+    workflow_template::apply(workflow, &values)
+}
+
+/// Read a JSON workflow specification and convert it into a request to be
+/// sent to the controller, loading the code of the functions from the
+/// local filesystem (paths relative to the specification file).
+fn workflow_request(spec_file: &str, parameters: &WorkflowParameters) -> anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowRequest> {
+    let workflow = read_workflow_spec(spec_file, parameters)?;
+    let mut workflow_functions = vec![];
+    for func_spec in workflow.functions {
+        let code = match func_spec.class_specification.code {
+            Some(code) => code,
+            None => anyhow::bail!("missing code for function {}", func_spec.name),
+        };
+        let function_class_code = match func_spec.class_specification.function_type.as_str() {
+            "RUST_WASM" => std::fs::read(std::path::Path::new(spec_file).parent().unwrap().join(code))?,
+            "CONTAINER" => code.as_bytes().to_vec(),
+            _ => anyhow::bail!("unknown function class type: {}", func_spec.class_specification.function_type),
+        };
+
+        let mut output_mapping = func_spec.output_mapping;
+        for (output, routes) in func_spec.output_routes.unwrap_or_default() {
+            for (index, route) in routes.into_iter().enumerate() {
+                let condition = match route.condition {
+                    Some(condition) => match condition.parse::<edgeless_api::output_routing::RouteCondition>() {
+                        Ok(condition) => Some(condition),
+                        Err(err) => anyhow::bail!("invalid route of output {} of function {}: {}", output, func_spec.name, err),
+                    },
+                    None => None,
+                };
+                let key = edgeless_api::output_routing::OutputRoute {
+                    output: output.clone(),
+                    index,
+                    condition,
+                }
+                .key();
+                if output_mapping.insert(key.clone(), route.target).is_some() {
+                    anyhow::bail!("duplicate route {} of function {}", key, func_spec.name);
+                }
+            }
+        }
+
+        workflow_functions.push(edgeless_api::workflow_instance::WorkflowFunction {
+            name: func_spec.name,
+            function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+                function_class_id: func_spec.class_specification.id,
+                function_class_type: func_spec.class_specification.function_type,
+                function_class_version: func_spec.class_specification.version,
+                function_class_code,
+                function_class_outputs: func_spec.class_specification.outputs,
+            },
+            output_mapping,
+            annotations: func_spec.annotations,
+        });
+    }
+
+    Ok(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+        workflow_functions,
+        workflow_resources: workflow
+            .resources
+            .into_iter()
+            .map(|res_spec| edgeless_api::workflow_instance::WorkflowResource {
+                name: res_spec.name,
+                class_type: res_spec.class_type,
+                output_mapping: res_spec.output_mapping,
+                configurations: res_spec.configurations,
+            })
+            .collect(),
+        annotations: workflow.annotations,
+    })
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     env_logger::init();
@@ -132,58 +262,26 @@ async fn main() -> anyhow::Result<()> {
     match args.command {
         None => log::debug!("Bye"),
         Some(x) => match x {
+            Commands::Workflow {
+                workflow_command: WorkflowCommands::Validate { spec_file, parameters, dot },
+            } => {
+                let workflow = read_workflow_spec(&spec_file, &parameters)?;
+                let issues = workflow_validate::validate(&workflow, std::path::Path::new(&spec_file).parent());
+                for issue in &issues {
+                    println!("{}", issue);
+                }
+                if let Some(dot) = dot {
+                    std::fs::write(dot, workflow_validate::to_dot(&workflow))?;
+                }
+                let errors = issues.iter().filter(|x| x.severity == workflow_validate::Severity::Error).count();
+                anyhow::ensure!(errors == 0, "invalid workflow specification: {} error(s)", errors);
+            }
             Commands::Workflow { workflow_command } => {
                 let mut wf_client = wf_client(&args.config_file).await?;
                 match workflow_command {
-                    WorkflowCommands::Start { spec_file } => {
+                    WorkflowCommands::Start { spec_file, parameters } => {
                         log::debug!("Start Workflow");
-                        let workflow: workflow_spec::WorkflowSpec =
-                            serde_json::from_str(&std::fs::read_to_string(spec_file.clone()).unwrap()).unwrap();
-                        let res = wf_client
-                            .start(edgeless_api::workflow_instance::SpawnWorkflowRequest {
-                                workflow_functions: workflow
-                                    .functions
-                                    .into_iter()
-                                    .map(|func_spec| {
-                                        let function_class_code = match func_spec.class_specification.function_type.as_str() {
-                                            "RUST_WASM" => std::fs::read(
-                                                std::path::Path::new(&spec_file)
-                                                    .parent()
-                                                    .unwrap()
-                                                    .join(func_spec.class_specification.code.unwrap()),
-                                            )
-                                            .unwrap(),
-                                            "CONTAINER" => func_spec.class_specification.code.unwrap().as_bytes().to_vec(),
-                                            _ => panic!("unknown function class type: {}", func_spec.class_specification.function_type),
-                                        };
-
-                                        edgeless_api::workflow_instance::WorkflowFunction {
-                                            name: func_spec.name,
-                                            function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
-                                                function_class_id: func_spec.class_specification.id,
-                                                function_class_type: func_spec.class_specification.function_type,
-                                                function_class_version: func_spec.class_specification.version,
-                                                function_class_code,
-                                                function_class_outputs: func_spec.class_specification.outputs,
-                                            },
-                                            output_mapping: func_spec.output_mapping,
-                                            annotations: func_spec.annotations,
-                                        }
-                                    })
-                                    .collect(),
-                                workflow_resources: workflow
-                                    .resources
-                                    .into_iter()
-                                    .map(|res_spec| edgeless_api::workflow_instance::WorkflowResource {
-                                        name: res_spec.name,
-                                        class_type: res_spec.class_type,
-                                        output_mapping: res_spec.output_mapping,
-                                        configurations: res_spec.configurations,
-                                    })
-                                    .collect(),
-                                annotations: workflow.annotations.clone(),
-                            })
-                            .await;
+                        let res = wf_client.start(workflow_request(&spec_file, &parameters)?).await;
                         match res {
                             Ok(response) => {
                                 match &response {
@@ -210,6 +308,33 @@ async fn main() -> anyhow::Result<()> {
                             Err(err) => println!("{}", err),
                         }
                     }
+                    WorkflowCommands::Update { id, spec_file, parameters } => {
+                        log::debug!("Update Workflow");
+                        let res = wf_client
+                            .update(edgeless_api::workflow_instance::UpdateWorkflowRequest {
+                                workflow_id: edgeless_api::workflow_instance::WorkflowId {
+                                    workflow_id: uuid::Uuid::parse_str(&id)?,
+                                },
+                                request: workflow_request(&spec_file, &parameters)?,
+                            })
+                            .await;
+                        match res {
+                            Ok(response) => {
+                                match &response {
+                                    SpawnWorkflowResponse::ResponseError(err) => {
+                                        println!("{:?}", err);
+                                    }
+                                    SpawnWorkflowResponse::WorkflowInstance(val) => {
+                                        for mapping in &val.domain_mapping {
+                                            println!("MAP {} -> {} [logical ID {}]", mapping.name, mapping.domain_id, mapping.function_id);
+                                        }
+                                    }
+                                }
+                                log::info!("{:?}", response)
+                            }
+                            Err(err) => println!("{}", err),
+                        }
+                    }
                     WorkflowCommands::List {} => match wf_client.list().await {
                         Ok(identifiers) => {
                             for wf_id in identifiers {
@@ -254,10 +379,42 @@ async fn main() -> anyhow::Result<()> {
                                 for mapping in info.status.domain_mapping {
                                     println!("MAP {} -> {} [logical ID {}]", mapping.name, mapping.domain_id, mapping.function_id);
                                 }
+                                println!("* status");
+                                let mut component_status = info.component_status.into_iter().collect::<Vec<_>>();
+                                component_status.sort_by(|a, b| a.0.cmp(&b.0));
+                                for (name, status) in component_status {
+                                    println!("STATUS {} -> {}", name, status);
+                                }
                             }
                             Err(err) => println!("{}", err),
                         }
                     }
+                    WorkflowCommands::Logs { id, function, follow } => {
+                        let mut cursor = workflow_logs::LogCursor::new(
+                            edgeless_api::workflow_instance::WorkflowId {
+                                workflow_id: uuid::Uuid::parse_str(&id)?,
+                            },
+                            function,
+                        );
+                        loop {
+                            match cursor.next(&mut wf_client).await {
+                                Ok(entries) => {
+                                    for (name, entry) in entries {
+                                        println!("{}", workflow_logs::format_entry(&name, &entry));
+                                    }
+                                }
+                                Err(err) => {
+                                    println!("{}", err);
+                                    break;
+                                }
+                            }
+                            if !follow {
+                                break;
+                            }
+                            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                        }
+                    }
+                    WorkflowCommands::Validate { .. } => unreachable!(),
                 }
             }
             Commands::Function { function_command } => match function_command {
@@ -527,6 +684,36 @@ async fn main() -> anyhow::Result<()> {
                         .expect("failed to get body");
                     println!("post_response body: {:?}", post_response);
                 }
+
+                FunctionCommands::Run { code_file, script_file } => {
+                    let script_content = std::fs::read_to_string(&script_file)?;
+                    let script = match std::path::Path::new(&script_file).extension().and_then(|x| x.to_str()) {
+                        Some("json") => serde_json::from_str::<edgeless_node::function_harness::HarnessScript>(&script_content)?,
+                        _ => edgeless_node::function_harness::HarnessScript::from_text(&script_content)?,
+                    };
+                    let mut harness = edgeless_node::function_harness::FunctionHarness::new(
+                        &std::fs::read(&code_file)?,
+                        &script.outputs,
+                        &script.call_replies,
+                        &script.annotations,
+                    )
+                    .await?;
+                    // The events recorded are printed even if the function fails.
+                    let res = harness.run(&script).await;
+                    let records = harness.records();
+                    for record in &records {
+                        println!("{}", serde_json::to_string(record)?);
+                    }
+                    res?;
+                    let unmatched = script.unmatched_expectations(&records);
+                    if let Some(first) = unmatched.first() {
+                        anyhow::bail!(
+                            "{} expected event(s) not recorded, the first is: {}",
+                            unmatched.len(),
+                            serde_json::to_string(first)?
+                        );
+                    }
+                }
             },
             Commands::Domain { domain_command } => {
                 let mut wf_client = wf_client(&args.config_file).await?;
@@ -1,4 +1,7 @@
 // SPDX-FileCopyrightText: © 2023 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
 // SPDX-License-Identifier: MIT
 
+pub mod workflow_logs;
 pub mod workflow_spec;
+pub mod workflow_template;
+pub mod workflow_validate;
@@ -0,0 +1,161 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Retrieval of the log entries emitted by the functions of a workflow.
+//!
+//! The log entries are kept by the orchestrators in bounded per-instance
+//! buffers, which are filled with the entries reported periodically by the
+//! nodes, and they are collected by the controller from all the domains
+//! hosting the workflow.
+
+/// Cursor over the log entries of the functions of a workflow, which keeps
+/// track of the last entry received for each function so that the same
+/// entry is never returned twice.
+///
+/// The sequence numbers of the entries are assigned by the orchestrator of
+/// the domain hosting the function, hence the cursor of a function starts
+/// over when its entries are of a new epoch, i.e., if the orchestrator
+/// restarted or the function moved to another domain.
+pub struct LogCursor {
+    workflow_id: edgeless_api::workflow_instance::WorkflowId,
+    // Names of the functions, all if empty.
+    names: Vec<String>,
+    // Position of the last entry received, by function name.
+    since: std::collections::HashMap<String, edgeless_api::instance_status::LogPosition>,
+}
+
+impl LogCursor {
+    /// Create a cursor over the log entries of a workflow, either of all its
+    /// functions or of that with the given name only.
+    pub fn new(workflow_id: edgeless_api::workflow_instance::WorkflowId, function: Option<String>) -> Self {
+        Self {
+            workflow_id,
+            names: function.into_iter().collect(),
+            since: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Retrieve the log entries received since the last call, as pairs of
+    /// function name and entry, sorted by emission time.
+    pub async fn next(
+        &mut self,
+        client: &mut Box<dyn edgeless_api::workflow_instance::WorkflowInstanceAPI>,
+    ) -> anyhow::Result<Vec<(String, edgeless_api::instance_status::InstanceLogEntry)>> {
+        let logs = client
+            .logs(edgeless_api::workflow_instance::WorkflowLogsRequest {
+                workflow_id: self.workflow_id.clone(),
+                names: self.names.clone(),
+                since: self.since.clone(),
+            })
+            .await?;
+        Ok(self.consume(logs))
+    }
+
+    fn consume(
+        &mut self,
+        logs: std::collections::HashMap<String, Vec<edgeless_api::instance_status::InstanceLogEntry>>,
+    ) -> Vec<(String, edgeless_api::instance_status::InstanceLogEntry)> {
+        let mut ret = vec![];
+        for (name, entries) in logs {
+            let since = self.since.entry(name.clone()).or_default();
+            // The entries of a function are sorted by sequence number.
+            for entry in entries {
+                if since.precedes(&entry) {
+                    *since = edgeless_api::instance_status::LogPosition::of(&entry);
+                    ret.push((name.clone(), entry));
+                }
+            }
+        }
+        ret.sort_by_key(|(_, entry)| (entry.entry.timestamp, entry.seq));
+        ret
+    }
+}
+
+/// Format a log entry of a function as a single line.
+pub fn format_entry(name: &str, entry: &edgeless_api::instance_status::InstanceLogEntry) -> String {
+    format!(
+        "{} {:<5} {} [{}] {}",
+        chrono::DateTime::<chrono::Utc>::from(entry.entry.timestamp).to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        entry.entry.level.to_uppercase(),
+        name,
+        entry.node_id,
+        entry.entry.message
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(seq: u64, millis: u64, message: &str) -> edgeless_api::instance_status::InstanceLogEntry {
+        edgeless_api::instance_status::InstanceLogEntry {
+            seq,
+            epoch: 1,
+            node_id: uuid::Uuid::nil(),
+            entry: edgeless_api::node_registration::FunctionLogEntry {
+                timestamp: std::time::UNIX_EPOCH + std::time::Duration::from_millis(millis),
+                level: "Info".to_string(),
+                target: "target".to_string(),
+                message: message.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_log_cursor_consume() {
+        let mut cursor = LogCursor::new(
+            edgeless_api::workflow_instance::WorkflowId {
+                workflow_id: uuid::Uuid::new_v4(),
+            },
+            None,
+        );
+
+        let messages = |entries: Vec<(String, edgeless_api::instance_status::InstanceLogEntry)>| {
+            entries
+                .into_iter()
+                .map(|(name, entry)| format!("{}:{}", name, entry.entry.message))
+                .collect::<Vec<String>>()
+        };
+
+        // Entries of different functions are merged by emission time.
+        let logs = std::collections::HashMap::from([
+            ("f1".to_string(), vec![entry(1, 100, "a"), entry(3, 300, "b")]),
+            ("f2".to_string(), vec![entry(10, 200, "c")]),
+            ("f3".to_string(), vec![]),
+        ]);
+        assert_eq!(vec!["f1:a", "f2:c", "f1:b"], messages(cursor.consume(logs)));
+        let position = |epoch: u64, seq: u64| edgeless_api::instance_status::LogPosition { epoch, seq };
+        assert_eq!(
+            std::collections::HashMap::from([
+                ("f1".to_string(), position(1, 3)),
+                ("f2".to_string(), position(1, 10)),
+                ("f3".to_string(), position(0, 0))
+            ]),
+            cursor.since
+        );
+
+        // Entries already received are skipped.
+        let logs = std::collections::HashMap::from([
+            ("f1".to_string(), vec![entry(3, 300, "b"), entry(4, 400, "d")]),
+            ("f2".to_string(), vec![]),
+        ]);
+        assert_eq!(vec!["f1:d"], messages(cursor.consume(logs)));
+        assert_eq!(Some(&position(1, 4)), cursor.since.get("f1"));
+        assert_eq!(Some(&position(1, 10)), cursor.since.get("f2"));
+
+        // The sequence numbers start over in a new epoch.
+        let mut restarted = entry(2, 500, "e");
+        restarted.epoch = 2;
+        let logs = std::collections::HashMap::from([("f2".to_string(), vec![restarted])]);
+        assert_eq!(vec!["f2:e"], messages(cursor.consume(logs)));
+        assert_eq!(Some(&position(2, 2)), cursor.since.get("f2"));
+    }
+
+    #[test]
+    fn test_format_entry() {
+        assert_eq!(
+            "1979-12-09T01:00:00.123Z INFO  f1 [00000000-0000-0000-0000-000000000000] hello",
+            format_entry("f1", &entry(1, 313549200123, "hello"))
+        );
+    }
+}
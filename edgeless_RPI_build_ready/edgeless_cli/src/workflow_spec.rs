@@ -44,10 +44,27 @@ pub struct WorflowSpecFunction {
     /// resource. Uses the function / resource (logical) name as defined by the
     /// "name" property within the workflow spec.
     pub output_mapping: std::collections::HashMap<String, String>,
+    /// (optional) Maps the output of a function to multiple functions /
+    /// resources, possibly depending on the content of the event: the event
+    /// is forwarded to all the targets whose condition is satisfied, in
+    /// addition to the target in output_mapping, if any.
+    pub output_routes: Option<std::collections::HashMap<String, Vec<WorkflowSpecOutputRoute>>>,
     /// Key-value pairs of annotations for the function
     pub annotations: std::collections::HashMap<String, String>,
 }
 
+/// Defines a routing rule for an output of a function
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema, serde::Serialize)]
+pub struct WorkflowSpecOutputRoute {
+    /// Logical name of the target function / resource within this workflow
+    pub target: String,
+    /// (optional) Condition on the JSON payload of the event, in the form
+    /// `<field> <op> <value>`, e.g., `classification == "HighActivity"`,
+    /// where op is one of: ==, !=, <, <=, >, >=; nested fields are separated
+    /// by dots. If absent, then the event is always forwarded to the target
+    pub condition: Option<String>,
+}
+
 /// Defines the resource as a part of the workflow
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema, serde::Serialize)]
 pub struct WorkflowSpecResourceInstance {
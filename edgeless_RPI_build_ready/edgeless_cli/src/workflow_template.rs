@@ -0,0 +1,260 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Parameter substitution in workflow specifications.
+//!
+//! Any string of a workflow specification, including the keys of maps,
+//! may contain placeholders in the form `${name}`, which are replaced with
+//! the value of the parameter with the given name before the workflow is
+//! submitted. A literal `${` is written as `$${`.
+//!
+//! The parameters are read from a file, in JSON or TOML format depending
+//! on its extension, and from assignments `name=value` given on the
+//! command line, which take precedence.
+
+use crate::workflow_spec::WorkflowSpec;
+
+/// Parse an assignment `name=value`.
+pub fn parse_assignment(s: &str) -> anyhow::Result<(String, String)> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => anyhow::bail!("invalid parameter assignment '{}', expected name=value", s),
+    }
+}
+
+/// Convert a scalar JSON value into the value of a parameter.
+fn to_parameter(name: &str, value: serde_json::Value) -> anyhow::Result<String> {
+    match value {
+        serde_json::Value::String(value) => Ok(value),
+        serde_json::Value::Number(_) | serde_json::Value::Bool(_) => Ok(value.to_string()),
+        _ => anyhow::bail!("invalid value of parameter {}: only strings, numbers, and booleans are allowed", name),
+    }
+}
+
+/// Read the parameters from a file, which is expected to contain a JSON
+/// object, if its extension is .json, or a TOML table otherwise, whose
+/// values are strings, numbers, or booleans.
+pub fn read_parameters(filename: &str) -> anyhow::Result<std::collections::HashMap<String, String>> {
+    let content = std::fs::read_to_string(filename)?;
+    let values: serde_json::Map<String, serde_json::Value> = match filename.ends_with(".json") {
+        true => serde_json::from_str(&content)?,
+        false => serde_json::to_value(toml::from_str::<toml::Table>(&content)?)?
+            .as_object()
+            .cloned()
+            .unwrap_or_default(),
+    };
+    values
+        .into_iter()
+        .map(|(name, value)| Ok((name.clone(), to_parameter(&name, value)?)))
+        .collect()
+}
+
+/// Replace the placeholders in a string with the values of the
+/// parameters, adding the names of those not found to `unresolved`.
+fn substitute(text: &str, parameters: &std::collections::HashMap<String, String>, unresolved: &mut Vec<String>) -> anyhow::Result<String> {
+    let mut ret = String::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find('$') {
+        ret.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if rest.starts_with("$${") {
+            ret.push_str("${");
+            rest = &rest[3..];
+        } else if let Some(placeholder) = rest.strip_prefix("${") {
+            let end = match placeholder.find('}') {
+                Some(end) => end,
+                None => anyhow::bail!("unterminated placeholder in '{}'", text),
+            };
+            let name = &placeholder[..end];
+            match parameters.get(name) {
+                Some(value) => ret.push_str(value),
+                None => unresolved.push(name.to_string()),
+            }
+            rest = &placeholder[end + 1..];
+        } else {
+            ret.push('$');
+            rest = &rest[1..];
+        }
+    }
+    ret.push_str(rest);
+    Ok(ret)
+}
+
+fn substitute_value(
+    value: serde_json::Value,
+    parameters: &std::collections::HashMap<String, String>,
+    unresolved: &mut Vec<String>,
+) -> anyhow::Result<serde_json::Value> {
+    Ok(match value {
+        serde_json::Value::String(text) => serde_json::Value::String(substitute(&text, parameters, unresolved)?),
+        serde_json::Value::Array(values) => serde_json::Value::Array(
+            values
+                .into_iter()
+                .map(|value| substitute_value(value, parameters, unresolved))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        serde_json::Value::Object(values) => serde_json::Value::Object(
+            values
+                .into_iter()
+                .map(|(key, value)| {
+                    Ok((
+                        substitute(&key, parameters, unresolved)?,
+                        substitute_value(value, parameters, unresolved)?,
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        value => value,
+    })
+}
+
+/// Replace the placeholders in all the strings of a workflow specification
+/// with the values of the parameters.
+///
+/// Fails if any placeholder refers to a parameter that is not given.
+pub fn apply(spec: WorkflowSpec, parameters: &std::collections::HashMap<String, String>) -> anyhow::Result<WorkflowSpec> {
+    let mut unresolved = vec![];
+    let value = substitute_value(serde_json::to_value(spec)?, parameters, &mut unresolved)?;
+    if !unresolved.is_empty() {
+        unresolved.sort();
+        unresolved.dedup();
+        anyhow::bail!("unresolved parameters in the workflow specification: {}", unresolved.join(", "));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parameters() -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            ("node".to_string(), "c7126760-223a-44a4-9a61-4ce1eaca8141".to_string()),
+            ("dir".to_string(), "/home/pi/Desktop".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(("a".to_string(), "b=c".to_string()), parse_assignment("a=b=c").unwrap());
+        assert_eq!(("a".to_string(), "".to_string()), parse_assignment("a=").unwrap());
+        assert!(parse_assignment("a").is_err());
+        assert!(parse_assignment("=b").is_err());
+    }
+
+    #[test]
+    fn test_substitute() {
+        let mut unresolved = vec![];
+        for (text, expected) in [
+            ("no placeholders", "no placeholders"),
+            ("${dir}/aoi_log.log", "/home/pi/Desktop/aoi_log.log"),
+            ("${dir}${dir}", "/home/pi/Desktop/home/pi/Desktop"),
+            ("cost: 10$, $dir, $${dir}", "cost: 10$, $dir, ${dir}"),
+            ("$", "$"),
+        ] {
+            assert_eq!(expected, substitute(text, &parameters(), &mut unresolved).unwrap());
+        }
+        assert!(unresolved.is_empty());
+
+        assert_eq!("a  b", substitute("a ${x} b${y}", &parameters(), &mut unresolved).unwrap());
+        assert_eq!(vec!["x", "y"], unresolved);
+
+        assert!(substitute("${dir", &parameters(), &mut unresolved).is_err());
+    }
+
+    #[test]
+    fn test_apply_unresolved() {
+        let spec: WorkflowSpec = serde_json::from_str(
+            r#"{
+                "functions": [
+                    {
+                        "name": "f1",
+                        "class_specification": {
+                            "id": "f1",
+                            "function_type": "RUST_WASM",
+                            "version": "0.1",
+                            "code": "f1.wasm",
+                            "outputs": ["out"]
+                        },
+                        "output_mapping": { "out": "log" },
+                        "annotations": { "node_id_match_any": "${node}", "init-payload": "batch_size=${batch_size}" }
+                    }
+                ],
+                "resources": [
+                    {
+                        "name": "log",
+                        "class_type": "file-log",
+                        "output_mapping": {},
+                        "configurations": { "filename": "${dir}/aoi_log.log" }
+                    }
+                ],
+                "annotations": {}
+            }"#,
+        )
+        .unwrap();
+
+        match apply(spec, &parameters()) {
+            Ok(_) => panic!("unresolved parameter not detected"),
+            Err(err) => assert_eq!("unresolved parameters in the workflow specification: batch_size", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_apply_resolved() {
+        let spec: WorkflowSpec = serde_json::from_str(
+            r#"{
+                "functions": [],
+                "resources": [
+                    {
+                        "name": "${name}",
+                        "class_type": "file-log",
+                        "output_mapping": {},
+                        "configurations": { "filename": "${dir}/aoi_log.log", "add-timestamp": "${timestamp}" }
+                    }
+                ],
+                "annotations": { "${key}": "value" }
+            }"#,
+        )
+        .unwrap();
+
+        let mut parameters = parameters();
+        parameters.insert("name".to_string(), "log".to_string());
+        parameters.insert("timestamp".to_string(), "true".to_string());
+        parameters.insert("key".to_string(), "owner".to_string());
+        let spec = apply(spec, &parameters).unwrap();
+        assert_eq!("log", spec.resources[0].name);
+        assert_eq!("/home/pi/Desktop/aoi_log.log", spec.resources[0].configurations["filename"]);
+        assert_eq!("true", spec.resources[0].configurations["add-timestamp"]);
+        assert_eq!("value", spec.annotations["owner"]);
+    }
+
+    #[test]
+    fn test_read_parameters() {
+        let dir = std::env::temp_dir().join(format!("edgeless_cli_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let toml_file = dir.join("params.toml");
+        std::fs::write(
+            &toml_file,
+            "node = \"c7126760-223a-44a4-9a61-4ce1eaca8141\"\nbatch_size = 100\nverbose = true\n",
+        )
+        .unwrap();
+        let parameters = read_parameters(toml_file.to_str().unwrap()).unwrap();
+        assert_eq!(3, parameters.len());
+        assert_eq!("c7126760-223a-44a4-9a61-4ce1eaca8141", parameters["node"]);
+        assert_eq!("100", parameters["batch_size"]);
+        assert_eq!("true", parameters["verbose"]);
+
+        let json_file = dir.join("params.json");
+        std::fs::write(&json_file, r#"{"dir": "/home/pi/Desktop", "batch_size": 100}"#).unwrap();
+        let parameters = read_parameters(json_file.to_str().unwrap()).unwrap();
+        assert_eq!(2, parameters.len());
+        assert_eq!("/home/pi/Desktop", parameters["dir"]);
+        assert_eq!("100", parameters["batch_size"]);
+
+        std::fs::write(&json_file, r#"{"dir": ["/home/pi/Desktop"]}"#).unwrap();
+        assert!(read_parameters(json_file.to_str().unwrap()).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
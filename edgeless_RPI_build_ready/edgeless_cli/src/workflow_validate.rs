@@ -0,0 +1,451 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Offline validation of a workflow specification.
+//!
+//! The checks performed catch the mistakes that would otherwise only show
+//! up at run time, or be silently ignored, once the workflow is deployed:
+//! mappings to unknown components or from undeclared outputs, invalid
+//! routing conditions, resources of unknown classes or with unknown
+//! configurations, components not connected to the rest of the workflow,
+//! and annotations with invalid values.
+
+use crate::workflow_spec::WorkflowSpec;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Severity {
+    // The workflow cannot be deployed or will not behave as specified.
+    Error,
+    // The workflow can be deployed but is likely to contain a mistake.
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            }
+        )
+    }
+}
+
+/// Problem found in a workflow specification.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    // Logical name of the function or resource affected, empty if the
+    // issue concerns the workflow as a whole.
+    pub component: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.component.is_empty() {
+            true => write!(f, "{}: {}", self.severity, self.message),
+            false => write!(f, "{}: {}: {}", self.severity, self.component, self.message),
+        }
+    }
+}
+
+struct Issues(Vec<Issue>);
+
+impl Issues {
+    fn error(&mut self, component: &str, message: String) {
+        self.0.push(Issue {
+            severity: Severity::Error,
+            component: component.to_string(),
+            message,
+        });
+    }
+
+    fn warning(&mut self, component: &str, message: String) {
+        self.0.push(Issue {
+            severity: Severity::Warning,
+            component: component.to_string(),
+            message,
+        });
+    }
+}
+
+/// Return the entries of a map sorted by key, so that the issues are
+/// always reported in the same order.
+fn sorted<V>(map: &std::collections::HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// Validate a workflow specification.
+///
+/// If `spec_dir` is given, then the code of the RUST_WASM functions is
+/// also expected to be found at the path relative to it.
+pub fn validate(spec: &WorkflowSpec, spec_dir: Option<&std::path::Path>) -> Vec<Issue> {
+    let mut issues = Issues(vec![]);
+
+    // Logical names of all the components.
+    let mut names = std::collections::HashSet::new();
+    for name in spec.functions.iter().map(|x| &x.name).chain(spec.resources.iter().map(|x| &x.name)) {
+        if name.is_empty() {
+            issues.error(name, String::from("empty component name"));
+        } else if !names.insert(name.as_str()) {
+            issues.error(name, String::from("duplicate component name"));
+        }
+    }
+
+    // Logical names of the components that are the target of an output.
+    let mut targets = std::collections::HashSet::new();
+    let mut check_target = |issues: &mut Issues, component: &str, output: &str, target: &str| {
+        if names.contains(target) {
+            targets.insert(target.to_string());
+        } else {
+            issues.error(component, format!("output {} mapped to unknown component {}", output, target));
+        }
+    };
+
+    for function in &spec.functions {
+        let name = function.name.as_str();
+        let class = &function.class_specification;
+
+        match class.function_type.as_str() {
+            "RUST_WASM" => {
+                if let (Some(code), Some(spec_dir)) = (&class.code, spec_dir) {
+                    if !spec_dir.join(code).is_file() {
+                        issues.warning(name, format!("code file not found: {}", code));
+                    }
+                }
+            }
+            "CONTAINER" => {}
+            _ => issues.error(name, format!("unknown function type: {}", class.function_type)),
+        }
+        if class.code.is_none() {
+            issues.error(name, String::from("missing code"));
+        }
+
+        let mut outputs = std::collections::HashSet::new();
+        for output in &class.outputs {
+            if !outputs.insert(output.as_str()) {
+                issues.warning(name, format!("duplicate output {} in the class specification", output));
+            }
+        }
+
+        for (output, target) in sorted(&function.output_mapping) {
+            if !outputs.contains(output.as_str()) {
+                issues.error(name, format!("output {} not declared in the class specification", output));
+            }
+            check_target(&mut issues, name, output, target);
+        }
+
+        for (output, routes) in sorted(function.output_routes.as_ref().unwrap_or(&std::collections::HashMap::new())) {
+            if !outputs.contains(output.as_str()) {
+                issues.error(name, format!("routed output {} not declared in the class specification", output));
+            }
+            for route in routes {
+                if let Some(condition) = &route.condition {
+                    if let Err(err) = condition.parse::<edgeless_api::output_routing::RouteCondition>() {
+                        issues.error(name, format!("invalid condition of a route of output {}: {}", output, err));
+                    }
+                }
+                check_target(&mut issues, name, output, &route.target);
+            }
+        }
+
+        validate_annotations(&mut issues, name, &function.annotations);
+    }
+
+    let resource_specs = edgeless_node::resources::resource_provider_specs::available_resource_specs();
+    for resource in &spec.resources {
+        let name = resource.name.as_str();
+        let resource_spec = resource_specs.iter().find(|x| x.class_type() == resource.class_type);
+        if resource_spec.is_none() {
+            issues.warning(name, format!("unknown resource class type: {}", resource.class_type));
+        }
+
+        let resource_outputs = resource_spec.map(|x| x.instance_outputs(&resource.configurations));
+        for (output, target) in sorted(&resource.output_mapping) {
+            if let Some(resource_outputs) = &resource_outputs {
+                if !resource_outputs.contains(output) {
+                    issues.warning(
+                        name,
+                        format!("output {} not advertised by resources of class {}", output, resource.class_type),
+                    );
+                }
+            }
+            check_target(&mut issues, name, output, target);
+        }
+
+        if let Some(resource_spec) = resource_spec {
+            let configurations = resource_spec.configurations();
+            for (key, _) in sorted(&resource.configurations) {
+                if !configurations.contains_key(key) {
+                    issues.warning(
+                        name,
+                        format!("unknown configuration {} for resources of class {}", key, resource.class_type),
+                    );
+                }
+            }
+        }
+    }
+
+    // A component is dangling if it neither receives nor produces events
+    // within the workflow.
+    if names.len() > 1 {
+        let components = spec
+            .functions
+            .iter()
+            .map(|x| (&x.name, x.output_mapping.is_empty() && x.output_routes.iter().all(|x| x.is_empty())))
+            .chain(spec.resources.iter().map(|x| (&x.name, x.output_mapping.is_empty())));
+        for (name, no_outputs) in components {
+            if no_outputs && !targets.contains(name) {
+                issues.warning(name, String::from("not connected to any other component"));
+            }
+        }
+    }
+
+    issues.0
+}
+
+/// Check the annotations of a function that are interpreted by the
+/// orchestrator and by the node, whose invalid values are ignored there.
+fn validate_annotations(issues: &mut Issues, name: &str, annotations: &std::collections::HashMap<String, String>) {
+    for (key, val) in sorted(annotations) {
+        let valid = match key.as_str() {
+            "max_instances" | "max_memory_mb" => val.parse::<usize>().is_ok(),
+            "max_fuel" | "max_invocation_ms" | "restart_backoff_ms" => val.parse::<u64>().is_ok(),
+            "node_id_match_any" => {
+                for node_id in val.split(",").filter(|x| uuid::Uuid::parse_str(x).is_err()) {
+                    issues.error(name, format!("invalid node identifier in annotation node_id_match_any: {}", node_id));
+                }
+                true
+            }
+            "tee" | "tpm" => matches!(val.to_lowercase().as_str(), "required" | "not-required"),
+            "restart_policy" => val.parse::<edgeless_node::base_runtime::restart_policy::RestartMode>().is_ok(),
+            "state_policy" => val.parse::<edgeless_api::function_instance::StatePolicy>().is_ok(),
+            _ => true,
+        };
+        if !valid {
+            issues.error(name, format!("invalid value of annotation {}: {}", key, val));
+        }
+    }
+}
+
+/// Escape a string to be used within a quoted DOT identifier.
+fn dot_escape(val: &str) -> String {
+    val.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Quote a string as a DOT identifier.
+fn dot_quote(val: &str) -> String {
+    format!("\"{}\"", dot_escape(val))
+}
+
+/// Render the workflow as a graph in the DOT language, with functions
+/// as boxes, resources as ellipses, and an edge for every output mapping
+/// or route, labeled with the output and the condition, if any.
+/// Edges towards unknown components are drawn in red.
+pub fn to_dot(spec: &WorkflowSpec) -> String {
+    let names = spec
+        .functions
+        .iter()
+        .map(|x| x.name.as_str())
+        .chain(spec.resources.iter().map(|x| x.name.as_str()))
+        .collect::<std::collections::HashSet<&str>>();
+    let edge = |source: &str, target: &str, label: &str| {
+        format!(
+            "  {} -> {} [label={}{}];\n",
+            dot_quote(source),
+            dot_quote(target),
+            dot_quote(label),
+            if names.contains(target) { "" } else { ", color=red" }
+        )
+    };
+
+    let mut dot = String::from("digraph workflow {\n");
+    for function in &spec.functions {
+        dot.push_str(&format!(
+            "  {} [shape=box, label=\"{}\\n{}\"];\n",
+            dot_quote(&function.name),
+            dot_escape(&function.name),
+            dot_escape(&function.class_specification.id)
+        ));
+    }
+    for resource in &spec.resources {
+        dot.push_str(&format!(
+            "  {} [shape=ellipse, label=\"{}\\n{}\"];\n",
+            dot_quote(&resource.name),
+            dot_escape(&resource.name),
+            dot_escape(&resource.class_type)
+        ));
+    }
+    for function in &spec.functions {
+        for (output, target) in sorted(&function.output_mapping) {
+            dot.push_str(&edge(&function.name, target, output));
+        }
+        for (output, routes) in sorted(function.output_routes.as_ref().unwrap_or(&std::collections::HashMap::new())) {
+            for route in routes {
+                let label = match &route.condition {
+                    Some(condition) => format!("{} [{}]", output, condition),
+                    None => output.clone(),
+                };
+                dot.push_str(&edge(&function.name, &route.target, &label));
+            }
+        }
+    }
+    for resource in &spec.resources {
+        for (output, target) in sorted(&resource.output_mapping) {
+            dot.push_str(&edge(&resource.name, target, output));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spec(text: &str) -> WorkflowSpec {
+        serde_json::from_str(text).unwrap()
+    }
+
+    fn messages(issues: &[Issue]) -> Vec<String> {
+        issues.iter().map(|x| x.to_string()).collect()
+    }
+
+    const VALID: &str = r#"{
+        "functions": [
+            {
+                "name": "extractor",
+                "class_specification": {
+                    "id": "http_extractor",
+                    "function_type": "RUST_WASM",
+                    "version": "0.1",
+                    "code": "http_extractor.wasm",
+                    "outputs": ["out", "error"]
+                },
+                "output_mapping": { "out": "log" },
+                "output_routes": { "error": [ { "target": "log", "condition": "level == \"high\"" } ] },
+                "annotations": { "max_instances": "2", "restart_policy": "on-failure:5" }
+            }
+        ],
+        "resources": [
+            {
+                "name": "ingress",
+                "class_type": "http-ingress",
+                "output_mapping": { "new_request": "extractor" },
+                "configurations": { "host": "demo.edgeless.com", "methods": "POST" }
+            },
+            {
+                "name": "log",
+                "class_type": "file-log",
+                "output_mapping": {},
+                "configurations": { "filename": "my-local-file.log" }
+            }
+        ],
+        "annotations": {}
+    }"#;
+
+    #[test]
+    fn test_validate_valid_workflow() {
+        assert!(validate(&spec(VALID), None).is_empty());
+    }
+
+    #[test]
+    fn test_validate_invalid_workflow() {
+        let issues = validate(
+            &spec(
+                r#"{
+                "functions": [
+                    {
+                        "name": "f1",
+                        "class_specification": {
+                            "id": "f1",
+                            "function_type": "RUST_WASM",
+                            "version": "0.1",
+                            "code": "does_not_exist.wasm",
+                            "outputs": ["out", "out"]
+                        },
+                        "output_mapping": { "out": "f3", "other": "r1" },
+                        "output_routes": { "out": [ { "target": "r1", "condition": "level ~ 1" } ] },
+                        "annotations": {
+                            "node_id_match_any": "fda6ce79-46df-4f96-a0d2-456f720f606c,not-a-uuid",
+                            "max_instances": "many",
+                            "tee": "yes",
+                            "state_policy": "global",
+                            "restart_policy": "sometimes"
+                        }
+                    },
+                    {
+                        "name": "f2",
+                        "class_specification": {
+                            "id": "f2",
+                            "function_type": "PYTHON",
+                            "version": "0.1",
+                            "outputs": []
+                        },
+                        "output_mapping": {},
+                        "annotations": {}
+                    }
+                ],
+                "resources": [
+                    {
+                        "name": "r1",
+                        "class_type": "http-ingress",
+                        "output_mapping": { "new_requests": "f1", "routed": "f1" },
+                        "configurations": { "hostname": "demo.edgeless.com", "routes": "/api=routed" }
+                    },
+                    {
+                        "name": "r1",
+                        "class_type": "teleporter",
+                        "output_mapping": {},
+                        "configurations": {}
+                    }
+                ],
+                "annotations": {}
+            }"#,
+            ),
+            Some(std::path::Path::new("/nonexistent")),
+        );
+        assert_eq!(
+            vec![
+                "error: r1: duplicate component name",
+                "warning: f1: code file not found: does_not_exist.wasm",
+                "warning: f1: duplicate output out in the class specification",
+                "error: f1: output other not declared in the class specification",
+                "error: f1: output out mapped to unknown component f3",
+                "error: f1: invalid condition of a route of output out: missing comparison operator in condition 'level ~ 1'",
+                "error: f1: invalid value of annotation max_instances: many",
+                "error: f1: invalid node identifier in annotation node_id_match_any: not-a-uuid",
+                "error: f1: invalid value of annotation restart_policy: sometimes",
+                "error: f1: invalid value of annotation tee: yes",
+                "error: f2: unknown function type: PYTHON",
+                "error: f2: missing code",
+                "warning: r1: output new_requests not advertised by resources of class http-ingress",
+                "warning: r1: unknown configuration hostname for resources of class http-ingress",
+                "warning: r1: unknown resource class type: teleporter",
+                "warning: f2: not connected to any other component",
+            ],
+            messages(&issues)
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        assert_eq!(
+            r#"digraph workflow {
+  "extractor" [shape=box, label="extractor\nhttp_extractor"];
+  "ingress" [shape=ellipse, label="ingress\nhttp-ingress"];
+  "log" [shape=ellipse, label="log\nfile-log"];
+  "extractor" -> "log" [label="out"];
+  "extractor" -> "log" [label="error [level == \"high\"]"];
+  "ingress" -> "extractor" [label="new_request"];
+}
+"#,
+            to_dot(&spec(VALID))
+        );
+    }
+}
@@ -9,6 +9,11 @@ pub mod client;
 pub mod controller_task;
 mod deployment_state;
 pub mod domain_register_client;
+mod placement;
+pub mod state_store;
+pub mod state_store_file;
+pub mod state_store_none;
+pub mod state_store_redis;
 #[cfg(test)]
 pub mod test;
 
@@ -24,6 +29,11 @@ pub(crate) enum ControllerRequest {
         tokio::sync::oneshot::Sender<anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowResponse>>,
     ),
     Stop(edgeless_api::workflow_instance::WorkflowId),
+    Update(
+        edgeless_api::workflow_instance::UpdateWorkflowRequest,
+        // Reply Channel
+        tokio::sync::oneshot::Sender<anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowResponse>>,
+    ),
     List(
         // Reply Channel
         tokio::sync::oneshot::Sender<anyhow::Result<Vec<edgeless_api::workflow_instance::WorkflowId>>>,
@@ -33,6 +43,11 @@ pub(crate) enum ControllerRequest {
         // Reply Channel
         tokio::sync::oneshot::Sender<anyhow::Result<edgeless_api::workflow_instance::WorkflowInfo>>,
     ),
+    Logs(
+        edgeless_api::workflow_instance::WorkflowLogsRequest,
+        // Reply Channel
+        tokio::sync::oneshot::Sender<anyhow::Result<std::collections::HashMap<String, Vec<edgeless_api::instance_status::InstanceLogEntry>>>>,
+    ),
     Domains(
         String,
         // Reply Channel
@@ -55,7 +70,7 @@ pub(crate) enum InternalRequest {
     ),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 enum ComponentType {
     Function,
     Resource,
@@ -64,13 +79,14 @@ enum ComponentType {
 type Task = std::pin::Pin<Box<dyn futures::Future<Output = ()> + Send>>;
 
 impl Controller {
-    pub fn new() -> (Self, Task, Task) {
+    pub fn new(state_store: Box<dyn state_store::StateStore>) -> (Self, Task, Task) {
         let (workflow_instance_sender, workflow_instance_receiver) = futures::channel::mpsc::unbounded();
         let (domain_register_sender, domain_register_receiver) = futures::channel::mpsc::unbounded();
         let (internal_sender, internal_receiver) = futures::channel::mpsc::unbounded();
 
         let main_task = Box::pin(async move {
-            let mut controller_task = controller_task::ControllerTask::new(workflow_instance_receiver, domain_register_receiver, internal_receiver);
+            let mut controller_task =
+                controller_task::ControllerTask::new(workflow_instance_receiver, domain_register_receiver, internal_receiver, state_store);
             controller_task.run().await;
         });
 
@@ -52,6 +52,20 @@ impl edgeless_api::workflow_instance::WorkflowInstanceAPI for ControllerWorkflow
             Err(err) => Err(anyhow::anyhow!("Controller Channel Error: {}", err)),
         }
     }
+    async fn update(
+        &mut self,
+        request: edgeless_api::workflow_instance::UpdateWorkflowRequest,
+    ) -> anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowResponse> {
+        let (reply_sender, reply_receiver) =
+            tokio::sync::oneshot::channel::<anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowResponse>>();
+        if let Err(err) = self.sender.send(super::ControllerRequest::Update(request, reply_sender)).await {
+            anyhow::bail!("Controller Channel Error: {}", err);
+        }
+        match reply_receiver.await {
+            Ok(ret) => ret,
+            Err(err) => Err(anyhow::anyhow!("Controller Channel Error: {}", err)),
+        }
+    }
     async fn list(&mut self) -> anyhow::Result<Vec<edgeless_api::workflow_instance::WorkflowId>> {
         let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<anyhow::Result<Vec<edgeless_api::workflow_instance::WorkflowId>>>();
         if let Err(err) = self.sender.send(super::ControllerRequest::List(reply_sender)).await {
@@ -72,6 +86,21 @@ impl edgeless_api::workflow_instance::WorkflowInstanceAPI for ControllerWorkflow
             Err(err) => Err(anyhow::anyhow!("Controller Channel Error: {}", err)),
         }
     }
+    async fn logs(
+        &mut self,
+        request: edgeless_api::workflow_instance::WorkflowLogsRequest,
+    ) -> anyhow::Result<std::collections::HashMap<String, Vec<edgeless_api::instance_status::InstanceLogEntry>>> {
+        let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<
+            anyhow::Result<std::collections::HashMap<String, Vec<edgeless_api::instance_status::InstanceLogEntry>>>,
+        >();
+        if let Err(err) = self.sender.send(super::ControllerRequest::Logs(request, reply_sender)).await {
+            anyhow::bail!("Controller Channel Error: {}", err);
+        }
+        match reply_receiver.await {
+            Ok(ret) => ret,
+            Err(err) => Err(anyhow::anyhow!("Controller Channel Error: {}", err)),
+        }
+    }
     async fn domains(
         &mut self,
         domain_id: String,
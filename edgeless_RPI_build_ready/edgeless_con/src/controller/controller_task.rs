@@ -4,7 +4,17 @@
 // SPDX-License-Identifier: MIT
 
 use futures::StreamExt;
-use rand::{seq::SliceRandom, SeedableRng};
+use rand::SeedableRng;
+
+// Time given to the orchestration domains to register again with the
+// controller after it has recovered its state from a previous run.
+// When it expires, the functions/resources assigned to domains that have not
+// registered again are considered lost.
+const RECOVERY_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(30);
+
+// Time waited before saving again the controller state after a failure,
+// unless the state changes in the meanwhile.
+const SAVE_RETRY_PERIOD: std::time::Duration = std::time::Duration::from_secs(5);
 
 pub struct OrchestratorDesc {
     pub client: Box<dyn edgeless_api::outer::orc::OrchestratorAPI>,
@@ -23,6 +33,15 @@ pub struct ControllerTask {
     active_workflows: std::collections::HashMap<edgeless_api::workflow_instance::WorkflowId, super::deployment_state::ActiveWorkflow>,
     orphan_workflows: std::collections::BTreeMap<edgeless_api::workflow_instance::WorkflowId, edgeless_api::workflow_instance::SpawnWorkflowRequest>,
     rng: rand::rngs::StdRng,
+    // Shared with the task saving the state, which runs off the main loop.
+    state_store: std::sync::Arc<std::sync::Mutex<Box<dyn super::state_store::StateStore>>>,
+    // True if the state has changed since it was last saved.
+    state_changed: bool,
+    // Nonces of the orchestration domains in the recovered state that have
+    // not registered yet.
+    recovered_nonces: std::collections::HashMap<String, u64>,
+    // Deadline for the orchestration domains to register after recovery.
+    recovery_deadline: Option<std::time::SystemTime>,
 }
 
 impl ControllerTask {
@@ -30,8 +49,9 @@ impl ControllerTask {
         workflow_instance_receiver: futures::channel::mpsc::UnboundedReceiver<super::ControllerRequest>,
         domain_registration_receiver: futures::channel::mpsc::UnboundedReceiver<super::DomainRegisterRequest>,
         internal_receiver: futures::channel::mpsc::UnboundedReceiver<super::InternalRequest>,
+        state_store: Box<dyn super::state_store::StateStore>,
     ) -> Self {
-        Self {
+        let mut controller_task = Self {
             workflow_instance_receiver,
             domain_registration_receiver,
             internal_receiver,
@@ -39,17 +59,26 @@ impl ControllerTask {
             active_workflows: std::collections::HashMap::new(),
             orphan_workflows: std::collections::BTreeMap::new(),
             rng: rand::rngs::StdRng::from_entropy(),
-        }
+            state_store: std::sync::Arc::new(std::sync::Mutex::new(state_store)),
+            state_changed: false,
+            recovered_nonces: std::collections::HashMap::new(),
+            recovery_deadline: None,
+        };
+        controller_task.recover();
+        controller_task
     }
 
+    /// Create a controller task with the given orchestration domains, which
+    /// are considered as registered again after the recovery of the state.
     #[cfg(test)]
-    pub fn new_with_orchestrators(
+    pub async fn new_with_orchestrators(
         workflow_instance_receiver: futures::channel::mpsc::UnboundedReceiver<super::ControllerRequest>,
         domain_registration_receiver: futures::channel::mpsc::UnboundedReceiver<super::DomainRegisterRequest>,
         internal_receiver: futures::channel::mpsc::UnboundedReceiver<super::InternalRequest>,
         orchestrators: std::collections::HashMap<String, OrchestratorDesc>,
+        state_store: Box<dyn super::state_store::StateStore>,
     ) -> Self {
-        Self {
+        let mut controller_task = Self {
             workflow_instance_receiver,
             domain_registration_receiver,
             internal_receiver,
@@ -57,12 +86,129 @@ impl ControllerTask {
             active_workflows: std::collections::HashMap::new(),
             orphan_workflows: std::collections::BTreeMap::new(),
             rng: rand::rngs::StdRng::from_entropy(),
+            state_store: std::sync::Arc::new(std::sync::Mutex::new(state_store)),
+            state_changed: false,
+            recovered_nonces: std::collections::HashMap::new(),
+            recovery_deadline: None,
+        };
+        controller_task.recover();
+        let domain_ids = controller_task.orchestrators.keys().cloned().collect::<Vec<String>>();
+        for domain_id in domain_ids {
+            if controller_task.recovered_nonces.remove(&domain_id).is_some() {
+                controller_task.reconcile_domain(&domain_id).await;
+            }
+        }
+        controller_task
+    }
+
+    /// Load the state saved by a previous run of the controller, if any.
+    ///
+    /// The functions/resources of the active workflows are assumed to be
+    /// still running in their orchestration domains until proven otherwise,
+    /// i.e., until the domain registers with a different nonce, it does
+    /// not register within the recovery grace period, or it does not report
+    /// them as running when it registers again (see `reconcile_domain`).
+    fn recover(&mut self) {
+        let state = match self.state_store.lock().unwrap().load() {
+            Ok(Some(state)) => state,
+            Ok(None) => return,
+            Err(err) => {
+                log::error!("could not load the controller state, starting from scratch: {}", err);
+                return;
+            }
+        };
+
+        for workflow in state.workflows {
+            if workflow.components.is_empty() {
+                self.orphan_workflows.insert(workflow.workflow_id(), workflow.request());
+            } else {
+                self.active_workflows.insert(
+                    workflow.workflow_id(),
+                    super::deployment_state::ActiveWorkflow {
+                        desired_state: workflow.request(),
+                        domain_mapping: workflow
+                            .components
+                            .into_iter()
+                            .map(|component| (component.name.clone(), component))
+                            .collect(),
+                    },
+                );
+            }
+        }
+        self.recovered_nonces = state.domain_nonces;
+        self.recovery_deadline = Some(std::time::SystemTime::now() + RECOVERY_GRACE_PERIOD);
+
+        log::info!(
+            "recovered {} active and {} orphan workflows, waiting for domain(s) '{}' to register",
+            self.active_workflows.len(),
+            self.orphan_workflows.len(),
+            self.recovered_nonces.keys().cloned().collect::<Vec<String>>().join(",")
+        );
+    }
+
+    /// Hand over the current state to the task saving it, if it has changed
+    /// since the last time.
+    fn persist(&mut self, state_sender: &tokio::sync::watch::Sender<super::state_store::ControllerState>) {
+        if !self.state_changed {
+            return;
+        }
+        self.state_changed = false;
+
+        let mut domain_nonces = self.recovered_nonces.clone();
+        for (domain_id, desc) in &self.orchestrators {
+            domain_nonces.insert(domain_id.clone(), desc.nonce);
+        }
+        let state = super::state_store::ControllerState {
+            workflows: self
+                .active_workflows
+                .iter()
+                .map(|(wf_id, workflow)| {
+                    super::state_store::PersistedWorkflow::new(wf_id, &workflow.desired_state, workflow.domain_mapping.values().cloned().collect())
+                })
+                .chain(
+                    self.orphan_workflows
+                        .iter()
+                        .map(|(wf_id, request)| super::state_store::PersistedWorkflow::new(wf_id, request, vec![])),
+                )
+                .collect(),
+            domain_nonces,
+        };
+        state_sender.send_replace(state);
+    }
+
+    /// Save the states received, skipping those superseded while a previous
+    /// one was being saved. The blocking operations of the store are run
+    /// outside of the async runtime and a failed save is retried until it
+    /// succeeds or a newer state is received.
+    async fn save_states(
+        state_store: std::sync::Arc<std::sync::Mutex<Box<dyn super::state_store::StateStore>>>,
+        mut state_receiver: tokio::sync::watch::Receiver<super::state_store::ControllerState>,
+    ) {
+        while state_receiver.changed().await.is_ok() {
+            loop {
+                let state = state_receiver.borrow_and_update().clone();
+                let state_store = state_store.clone();
+                let res = tokio::task::spawn_blocking(move || state_store.lock().unwrap().save(&state))
+                    .await
+                    .unwrap_or_else(|err| Err(err.into()));
+                match res {
+                    Ok(_) => break,
+                    Err(err) => log::error!("could not save the controller state, retrying: {}", err),
+                }
+                match tokio::time::timeout(SAVE_RETRY_PERIOD, state_receiver.changed()).await {
+                    Ok(Ok(_)) | Err(_) => {}
+                    // The main loop is over, retry the last state anyway.
+                    Ok(Err(_)) => tokio::time::sleep(SAVE_RETRY_PERIOD).await,
+                }
+            }
         }
     }
 
     /// Main loop of the controller task serving events received on the
     /// WorkflowInstanceAPI or DomainRegistrationAPI.
     pub async fn run(&mut self) {
+        let (state_sender, state_receiver) = tokio::sync::watch::channel(super::state_store::ControllerState::default());
+        tokio::spawn(Self::save_states(self.state_store.clone(), state_receiver));
         loop {
             tokio::select! {
                 biased;
@@ -93,6 +239,12 @@ impl ControllerTask {
                         super::ControllerRequest::Stop(wf_id) => {
                             self.stop_workflow(&wf_id).await;
                         }
+                        super::ControllerRequest::Update(update_workflow_request, reply_sender) => {
+                            let reply = self.update_workflow(update_workflow_request).await;
+                            if let Err(err) = reply_sender.send(reply) {
+                                log::error!("Unhandled: {:?}", err);
+                            }
+                        }
                         super::ControllerRequest::List(reply_sender) => {
                             let reply = self.list();
                             if let Err(err) =  reply_sender.send(Ok(reply)) {
@@ -100,11 +252,17 @@ impl ControllerTask {
                             }
                         }
                         super::ControllerRequest::Inspect(wf_id, reply_sender) => {
-                            let reply = self.inspect(wf_id);
+                            let reply = self.inspect(wf_id).await;
                             if let Err(err) =  reply_sender.send(reply) {
                                 log::error!("Unhandled: {:?}", err);
                             }
                         }
+                        super::ControllerRequest::Logs(logs_request, reply_sender) => {
+                            let reply = self.logs(logs_request).await;
+                            if let Err(err) = reply_sender.send(reply) {
+                                log::error!("Unhandled: {:?}", err);
+                            }
+                        }
                         super::ControllerRequest::Domains(domain_id, reply_sender) => {
                             let reply = self.domains(&domain_id);
                             if let Err(err) = reply_sender.send(reply) {
@@ -122,6 +280,7 @@ impl ControllerTask {
                     }
                 }
             }
+            self.persist(&state_sender);
         }
     }
 
@@ -129,29 +288,24 @@ impl ControllerTask {
         &mut self,
         spawn_workflow_request: edgeless_api::workflow_instance::SpawnWorkflowRequest,
     ) -> anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowResponse, edgeless_api::workflow_instance::SpawnWorkflowRequest> {
-        if !spawn_workflow_request.annotations.is_empty() {
-            log::warn!(
-                "Workflow annotations ({}) are currently ignored",
-                spawn_workflow_request.annotations.len()
-            );
+        if let Err(err) = validate_output_mappings(&spawn_workflow_request) {
+            return Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Workflow creation failed".to_string(),
+                    detail: Some(err),
+                },
+            ));
         }
 
-        // Find a domain that can host all the workflow's functions and
-        // resources.
-        //
-        // [TODO] It is also possible to split a workflow across multiple
-        // domains, but this requires an inter-domain dataplane, which is not
-        // yet supported as of today (Nov 2024).
-        //
-
-        let candidate_domains = Self::compatible_domains(&self.orchestrators, &spawn_workflow_request);
-        let target_domain = match candidate_domains.choose(&mut self.rng) {
-            Some(val) => val,
-            None => {
+        // Assign the workflow's functions and resources to the orchestration
+        // domains, possibly splitting the workflow across multiple domains.
+        let placement = match super::placement::place_workflow(&self.capabilities(), &spawn_workflow_request, &mut self.rng) {
+            Ok(val) => val,
+            Err(err) => {
                 return Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::ResponseError(
                     edgeless_api::common::ResponseError {
                         summary: "Workflow creation failed".to_string(),
-                        detail: Some("No single domain supporting all the functions/resources found".to_string()),
+                        detail: Some(err),
                     },
                 ));
             }
@@ -162,15 +316,24 @@ impl ControllerTask {
             workflow_id: uuid::Uuid::new_v4(),
         };
 
-        self.relocate_workflow(&wf_id, spawn_workflow_request, target_domain).await
+        self.relocate_workflow(&wf_id, spawn_workflow_request, &placement).await
     }
 
+    /// Deploy a workflow according to the given placement, which maps
+    /// each function/resource name to the domain that has to host it.
+    ///
+    /// [TODO] When the workflow is split across multiple domains the
+    /// output mappings refer to components hosted in other domains, which
+    /// requires an inter-domain dataplane to deliver events: until then,
+    /// splitting must be explicitly enabled with the `allow_split`
+    /// workflow annotation.
     async fn relocate_workflow(
         &mut self,
         wf_id: &edgeless_api::workflow_instance::WorkflowId,
         spawn_workflow_request: edgeless_api::workflow_instance::SpawnWorkflowRequest,
-        target_domain: &str,
+        placement: &std::collections::HashMap<String, String>,
     ) -> anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowResponse, edgeless_api::workflow_instance::SpawnWorkflowRequest> {
+        self.state_changed = true;
         self.active_workflows.insert(
             wf_id.clone(),
             super::deployment_state::ActiveWorkflow {
@@ -188,24 +351,30 @@ impl ControllerTask {
         // First pass: create instances for all the functions and resources.
         //
 
-        // Start the functions on the orchestration domain.
+        // Start the functions on their orchestration domains.
         for function in &spawn_workflow_request.workflow_functions {
             if res.is_err() {
                 log::error!("Could not start a function {}", res.clone().unwrap_err());
                 break;
             }
 
-            res = self.start_workflow_function_in_domain(wf_id, function, target_domain).await;
+            res = match placement.get(&function.name) {
+                Some(target_domain) => self.start_workflow_function_in_domain(wf_id, function, target_domain).await,
+                None => Err(format!("No domain assigned to function {}", function.name)),
+            };
         }
 
-        // Start the resources on the orchestration domain.
+        // Start the resources on their orchestration domains.
         for resource in &spawn_workflow_request.workflow_resources {
             if res.is_err() {
                 log::error!("Could not start a resource {}", res.clone().unwrap_err());
                 break;
             }
 
-            res = self.start_workflow_resource_in_domain(wf_id, resource, target_domain).await;
+            res = match placement.get(&resource.name) {
+                Some(target_domain) => self.start_workflow_resource_in_domain(wf_id, resource, target_domain).await,
+                None => Err(format!("No domain assigned to resource {}", resource.name)),
+            };
         }
 
         //
@@ -220,21 +389,7 @@ impl ControllerTask {
                 break;
             }
 
-            // Loop on all the identifiers for this function/resource
-            // (once for each orchestration domain to which the
-            // function/resource was allocated).
-            for origin_fid in self.active_workflows.get_mut(wf_id).unwrap().mapped_fids(component_name).unwrap() {
-                let output_mapping = self.output_mapping_for(wf_id, component_name).await;
-
-                if output_mapping.is_empty() {
-                    continue;
-                }
-
-                let component_type = self.active_workflows.get_mut(wf_id).unwrap().component_type(component_name).unwrap();
-                res = self
-                    .patch_outputs(target_domain, origin_fid, component_type, output_mapping, component_name)
-                    .await;
-            }
+            res = self.patch_component(wf_id, component_name, true).await;
         }
 
         //
@@ -280,42 +435,249 @@ impl ControllerTask {
         };
 
         // Stop all the functions/resources.
-        for component in workflow.domain_mapping.values() {
-            log::debug!("stopping function/resource of workflow {}: {}", wf_id.to_string(), &component);
-            let orc_api = match self.orchestrators.get_mut(&component.domain_id) {
-                None => {
-                    log::warn!(
-                        "Orchestration domain '{}' for workflow '{}' component '{}' disappeared",
-                        &component.domain_id,
-                        wf_id,
-                        &component.name,
-                    );
-                    continue;
-                }
-                Some(val) => val,
-            };
-            let mut fn_client = orc_api.client.function_instance_api();
-            let mut resource_client = orc_api.client.resource_configuration_api();
-            match component.component_type {
-                super::ComponentType::Function => {
-                    if let Err(err) = fn_client.stop(component.lid).await {
-                        log::error!("Unhandled error when stopping wf '{}' function '{}': {}", wf_id, component.name, err);
-                    }
-                }
-                super::ComponentType::Resource => {
-                    if let Err(err) = resource_client.stop(component.lid).await {
-                        log::error!("Unhandled error when stopping wf '{}' resource '{}': {}", wf_id, component.name, err);
-                    }
-                }
-            }
+        for component in workflow
+            .domain_mapping
+            .values()
+            .cloned()
+            .collect::<Vec<super::deployment_state::ActiveComponent>>()
+        {
+            self.stop_component(wf_id, &component).await;
         }
 
         // Remove the workflow from the active set.
+        self.state_changed = true;
         let remove_res = self.active_workflows.remove(wf_id);
         assert!(remove_res.is_some());
         Some(remove_res.unwrap().desired_state)
     }
 
+    /// Stop a function/resource of a workflow in its orchestration domain.
+    async fn stop_component(&mut self, wf_id: &edgeless_api::workflow_instance::WorkflowId, component: &super::deployment_state::ActiveComponent) {
+        log::debug!("stopping function/resource of workflow {}: {}", wf_id.to_string(), &component);
+        let orc_api = match self.orchestrators.get_mut(&component.domain_id) {
+            None => {
+                log::warn!(
+                    "Orchestration domain '{}' for workflow '{}' component '{}' disappeared",
+                    &component.domain_id,
+                    wf_id,
+                    &component.name,
+                );
+                return;
+            }
+            Some(val) => val,
+        };
+        match component.component_type {
+            super::ComponentType::Function => {
+                if let Err(err) = orc_api.client.function_instance_api().stop(component.lid).await {
+                    log::error!("Unhandled error when stopping wf '{}' function '{}': {}", wf_id, component.name, err);
+                }
+            }
+            super::ComponentType::Resource => {
+                if let Err(err) = orc_api.client.resource_configuration_api().stop(component.lid).await {
+                    log::error!("Unhandled error when stopping wf '{}' resource '{}': {}", wf_id, component.name, err);
+                }
+            }
+        }
+    }
+
+    /// Update an active workflow by restarting only the functions/resources
+    /// that have changed and patching the output mappings affected.
+    ///
+    /// The new instances are started, in the current domain if possible,
+    /// before the old ones are stopped. If the update fails, then the
+    /// workflow is rolled back to its previous state.
+    ///
+    /// If the workflow is orphan, then only its desired state is updated.
+    async fn update_workflow(
+        &mut self,
+        update_workflow_request: edgeless_api::workflow_instance::UpdateWorkflowRequest,
+    ) -> anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowResponse> {
+        let wf_id = update_workflow_request.workflow_id;
+        let new_state = update_workflow_request.request;
+
+        if let Err(err) = validate_output_mappings(&new_state) {
+            return Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Workflow update failed".to_string(),
+                    detail: Some(err),
+                },
+            ));
+        }
+
+        if let Some(desired_state) = self.orphan_workflows.get_mut(&wf_id) {
+            log::info!("updating orphan workflow {}", wf_id);
+            *desired_state = new_state;
+            self.state_changed = true;
+            return Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::WorkflowInstance(
+                edgeless_api::workflow_instance::WorkflowInstance {
+                    workflow_id: wf_id,
+                    domain_mapping: vec![],
+                },
+            ));
+        }
+
+        let active_workflow = match self.active_workflows.get(&wf_id) {
+            Some(val) => val.clone(),
+            None => {
+                return Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Workflow update failed".to_string(),
+                        detail: Some(format!("Unknown workflow identifier '{}'", wf_id)),
+                    },
+                ));
+            }
+        };
+
+        let diff = active_workflow.diff(&new_state);
+        log::info!(
+            "updating workflow {}: removed [{}], added [{}], changed [{}], remapped [{}]",
+            wf_id,
+            diff.removed.join(","),
+            diff.added.join(","),
+            diff.changed.join(","),
+            diff.remapped.join(",")
+        );
+        if diff.is_empty() {
+            return Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::WorkflowInstance(
+                edgeless_api::workflow_instance::WorkflowInstance {
+                    workflow_id: wf_id,
+                    domain_mapping: active_workflow.domain_mapping(),
+                },
+            ));
+        }
+        let restarted = diff.restarted();
+        self.state_changed = true;
+
+        // Find the domains for the new instances: the functions/resources
+        // that are not restarted stay where they are, while the others stay
+        // in their current domain if it can still host them.
+        let current = active_workflow
+            .domain_mapping
+            .iter()
+            .filter(|(_, component)| !component.domain_id.is_empty())
+            .map(|(name, component)| (name.clone(), component.domain_id.clone()))
+            .collect();
+        let mut placement = match super::placement::place_workflow_keeping(&self.capabilities(), &new_state, &current, &mut self.rng) {
+            Ok(val) => val,
+            Err(err) => {
+                return Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Workflow update failed".to_string(),
+                        detail: Some(err),
+                    },
+                ));
+            }
+        };
+        for (name, component) in &active_workflow.domain_mapping {
+            if !restarted.contains(name) && placement.contains_key(name) {
+                placement.insert(name.clone(), component.domain_id.clone());
+            }
+        }
+
+        self.active_workflows.get_mut(&wf_id).unwrap().desired_state = new_state.clone();
+
+        // Keep the last error.
+        let mut res: Result<(), String> = Ok(());
+
+        // Start the new instances of the functions/resources added or
+        // changed, while the old instances keep running.
+        for function in new_state.workflow_functions.iter().filter(|function| restarted.contains(&function.name)) {
+            if res.is_err() {
+                break;
+            }
+            res = match placement.get(&function.name) {
+                Some(target_domain) => self.start_workflow_function_in_domain(&wf_id, function, target_domain).await,
+                None => Err(format!("No domain assigned to function {}", function.name)),
+            };
+        }
+        for resource in new_state.workflow_resources.iter().filter(|resource| restarted.contains(&resource.name)) {
+            if res.is_err() {
+                break;
+            }
+            res = match placement.get(&resource.name) {
+                Some(target_domain) => self.start_workflow_resource_in_domain(&wf_id, resource, target_domain).await,
+                None => Err(format!("No domain assigned to resource {}", resource.name)),
+            };
+        }
+
+        // Patch the new instances, those whose output mapping has changed,
+        // and those with an output towards a new instance.
+        let mut patched = vec![];
+        let components = self.active_workflows.get(&wf_id).unwrap().components();
+        for component_name in &components {
+            if res.is_err() {
+                break;
+            }
+            let output_mapping = self.active_workflows.get(&wf_id).unwrap().component_output_mapping(component_name);
+            if restarted.contains(component_name)
+                || diff.remapped.contains(component_name)
+                || output_mapping.values().any(|target| restarted.contains(target))
+            {
+                patched.push(component_name.clone());
+                res = self.patch_component(&wf_id, component_name, false).await;
+            }
+        }
+
+        if let Err(err) = res {
+            log::error!("Workflow update failed, rolling back: {}", err);
+            self.rollback_update(&wf_id, active_workflow, &patched).await;
+            return Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Workflow update failed".to_string(),
+                    detail: Some(format!("{} (rolled back to the previous workflow)", err)),
+                },
+            ));
+        }
+
+        // Stop the old instances of the functions/resources removed or
+        // changed, now that nothing points to them anymore.
+        for name in diff.removed.iter().chain(diff.changed.iter()) {
+            if let Some(component) = active_workflow.domain_mapping.get(name) {
+                self.stop_component(&wf_id, component).await;
+            }
+        }
+        for name in &diff.removed {
+            self.active_workflows.get_mut(&wf_id).unwrap().domain_mapping.remove(name);
+        }
+
+        Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::WorkflowInstance(
+            edgeless_api::workflow_instance::WorkflowInstance {
+                workflow_id: wf_id.clone(),
+                domain_mapping: self.active_workflows.get(&wf_id).unwrap().domain_mapping(),
+            },
+        ))
+    }
+
+    /// Roll back a failed update of a workflow to its `previous` state:
+    /// stop the new instances started and patch again the
+    /// functions/resources in `patched` to restore their output mappings.
+    async fn rollback_update(
+        &mut self,
+        wf_id: &edgeless_api::workflow_instance::WorkflowId,
+        previous: super::deployment_state::ActiveWorkflow,
+        patched: &[String],
+    ) {
+        if let Some(failed) = self.active_workflows.insert(wf_id.clone(), previous.clone()) {
+            for (name, component) in &failed.domain_mapping {
+                if previous.domain_mapping.get(name).map(|component| component.lid) != Some(component.lid) {
+                    self.stop_component(wf_id, component).await;
+                }
+            }
+        }
+        for component_name in patched {
+            if previous.domain_mapping.contains_key(component_name) {
+                if let Err(err) = self.patch_component(wf_id, component_name, false).await {
+                    log::error!(
+                        "Could not restore the output mapping of wf '{}' component '{}': {}",
+                        wf_id,
+                        component_name,
+                        err
+                    );
+                }
+            }
+        }
+    }
+
     fn list(&self) -> Vec<edgeless_api::workflow_instance::WorkflowId> {
         let mut ret: Vec<edgeless_api::workflow_instance::WorkflowId> = vec![];
         for wf_id in self.active_workflows.keys() {
@@ -327,8 +689,9 @@ impl ControllerTask {
         ret
     }
 
-    fn inspect(&self, wf_id: edgeless_api::workflow_instance::WorkflowId) -> anyhow::Result<edgeless_api::workflow_instance::WorkflowInfo> {
-        if let Some(workflow) = self.active_workflows.get(&wf_id) {
+    async fn inspect(&mut self, wf_id: edgeless_api::workflow_instance::WorkflowId) -> anyhow::Result<edgeless_api::workflow_instance::WorkflowInfo> {
+        if let Some(workflow) = self.active_workflows.get(&wf_id).cloned() {
+            let component_status = self.component_status(&workflow).await;
             Ok(edgeless_api::workflow_instance::WorkflowInfo {
                 request: workflow.desired_state.clone(),
                 status: edgeless_api::workflow_instance::WorkflowInstance {
@@ -343,6 +706,7 @@ impl ControllerTask {
                         })
                         .collect(),
                 },
+                component_status,
             })
         } else if let Some(request) = self.orphan_workflows.get(&wf_id) {
             Ok(edgeless_api::workflow_instance::WorkflowInfo {
@@ -351,12 +715,150 @@ impl ControllerTask {
                     workflow_id: wf_id.clone(),
                     domain_mapping: vec![],
                 },
+                component_status: request
+                    .workflow_functions
+                    .iter()
+                    .map(|function| &function.name)
+                    .chain(request.workflow_resources.iter().map(|resource| &resource.name))
+                    .map(|name| (name.clone(), edgeless_api::instance_status::ComponentStatus::orphan()))
+                    .collect(),
             })
         } else {
             anyhow::bail!("Unknown workflow identifier '{}", wf_id);
         }
     }
 
+    /// Return the status of the functions/resources of an active workflow,
+    /// as reported by the orchestration domains hosting them.
+    ///
+    /// The functions/resources not assigned to any domain are orphan, while
+    /// those in a domain that cannot be queried are considered failed.
+    async fn component_status(
+        &mut self,
+        workflow: &super::deployment_state::ActiveWorkflow,
+    ) -> std::collections::HashMap<String, edgeless_api::instance_status::ComponentStatus> {
+        let mut ret = std::collections::HashMap::new();
+
+        // Group the logical identifiers by domain.
+        let mut lids_by_domain: std::collections::HashMap<String, std::collections::HashMap<uuid::Uuid, String>> = std::collections::HashMap::new();
+        for component in workflow.domain_mapping.values() {
+            if component.domain_id.is_empty() || !self.orchestrators.contains_key(&component.domain_id) {
+                ret.insert(component.name.clone(), edgeless_api::instance_status::ComponentStatus::orphan());
+            } else {
+                lids_by_domain
+                    .entry(component.domain_id.clone())
+                    .or_default()
+                    .insert(component.lid, component.name.clone());
+            }
+        }
+
+        for (domain_id, lids) in lids_by_domain {
+            let mut status = match self.orchestrators.get_mut(&domain_id) {
+                Some(desc) => match desc.client.instance_status_api().status(lids.keys().cloned().collect()).await {
+                    Ok(status) => status,
+                    Err(err) => {
+                        log::warn!("Could not retrieve the status of the instances in domain '{}': {}", domain_id, err);
+                        std::collections::HashMap::new()
+                    }
+                },
+                None => std::collections::HashMap::new(),
+            };
+            for (lid, name) in lids {
+                ret.insert(
+                    name,
+                    status.remove(&lid).unwrap_or_else(edgeless_api::instance_status::ComponentStatus::failed),
+                );
+            }
+        }
+
+        // Functions/resources that have not been started yet.
+        for name in workflow.components() {
+            ret.entry(name).or_insert_with(edgeless_api::instance_status::ComponentStatus::orphan);
+        }
+
+        ret
+    }
+
+    /// Return the log entries of the functions of a workflow, as kept by
+    /// the orchestration domains hosting them.
+    ///
+    /// The functions not assigned to any domain, or in a domain that cannot
+    /// be queried, have no log entries.
+    async fn logs(
+        &mut self,
+        request: edgeless_api::workflow_instance::WorkflowLogsRequest,
+    ) -> anyhow::Result<std::collections::HashMap<String, Vec<edgeless_api::instance_status::InstanceLogEntry>>> {
+        let (desired_state, workflow) = if let Some(workflow) = self.active_workflows.get(&request.workflow_id) {
+            (workflow.desired_state.clone(), Some(workflow.clone()))
+        } else if let Some(desired_state) = self.orphan_workflows.get(&request.workflow_id) {
+            (desired_state.clone(), None)
+        } else {
+            anyhow::bail!("Unknown workflow identifier '{}'", request.workflow_id);
+        };
+
+        let functions: Vec<String> = desired_state.workflow_functions.iter().map(|function| function.name.clone()).collect();
+        for name in &request.names {
+            if !functions.contains(name) {
+                anyhow::bail!("Unknown function '{}' in workflow '{}'", name, request.workflow_id);
+            }
+        }
+        let mut ret: std::collections::HashMap<String, Vec<edgeless_api::instance_status::InstanceLogEntry>> = functions
+            .into_iter()
+            .filter(|name| request.names.is_empty() || request.names.contains(name))
+            .map(|name| (name, vec![]))
+            .collect();
+        let workflow = match workflow {
+            Some(workflow) => workflow,
+            None => return Ok(ret),
+        };
+
+        // Group the logical identifiers by domain.
+        let mut lids_by_domain: std::collections::HashMap<String, std::collections::HashMap<uuid::Uuid, String>> = std::collections::HashMap::new();
+        for component in workflow.domain_mapping.values() {
+            if ret.contains_key(&component.name) && self.orchestrators.contains_key(&component.domain_id) {
+                lids_by_domain
+                    .entry(component.domain_id.clone())
+                    .or_default()
+                    .insert(component.lid, component.name.clone());
+            }
+        }
+
+        for (domain_id, lids) in lids_by_domain {
+            // The entries of all the functions are requested from the earliest
+            // position, unless their positions are of different epochs, e.g.,
+            // because a function was moved from another domain: in this case,
+            // all the entries are requested.
+            let positions: Vec<_> = lids.values().map(|name| request.since.get(name).cloned().unwrap_or_default()).collect();
+            let since = match positions.iter().min_by_key(|position| position.seq) {
+                Some(first) if positions.iter().all(|position| position.epoch == first.epoch) => *first,
+                _ => edgeless_api::instance_status::LogPosition::default(),
+            };
+            let mut logs = match self.orchestrators.get_mut(&domain_id) {
+                Some(desc) => match desc.client.instance_status_api().logs(lids.keys().cloned().collect(), since).await {
+                    Ok(logs) => logs,
+                    Err(err) => {
+                        log::warn!("Could not retrieve the logs of the instances in domain '{}': {}", domain_id, err);
+                        std::collections::HashMap::new()
+                    }
+                },
+                None => std::collections::HashMap::new(),
+            };
+            for (lid, name) in lids {
+                let since = request.since.get(&name).cloned().unwrap_or_default();
+                ret.insert(
+                    name,
+                    logs.remove(&lid)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|entry| since.precedes(entry))
+                        .collect(),
+                );
+            }
+        }
+
+        Ok(ret)
+    }
+
     fn domains(&self, domain_id: &str) -> anyhow::Result<std::collections::HashMap<String, edgeless_api::domain_registration::DomainCapabilities>> {
         let mut ret = std::collections::HashMap::new();
 
@@ -405,9 +907,28 @@ impl ControllerTask {
                     },
                 );
 
-                // It is a new orchestration domain. Therefore, we ask the
+                self.state_changed = true;
+
+                // If the orchestration domain was known before the controller
+                // restarted and it has kept the same nonce, then its
+                // functions/resources are still there.
+                // Otherwise, it is a new orchestration domain (or a new
+                // instance of a known one). Therefore, we ask the
                 // orchestrator to reset to a clean state.
-                Ok(edgeless_api::domain_registration::UpdateDomainResponse::Reset)
+                match self.recovered_nonces.remove(&update_domain_request.domain_id) {
+                    Some(nonce) if nonce == update_domain_request.nonce => {
+                        log::info!("Domain '{}' recovered", update_domain_request.domain_id);
+                        self.reconcile_domain(&update_domain_request.domain_id).await;
+                        Ok(edgeless_api::domain_registration::UpdateDomainResponse::Accepted)
+                    }
+                    recovered => {
+                        if recovered.is_some() {
+                            self.invalidate_domain(&update_domain_request.domain_id);
+                            self.find_new_orphans().await;
+                        }
+                        Ok(edgeless_api::domain_registration::UpdateDomainResponse::Reset)
+                    }
+                }
             }
             Some(desc) => {
                 // If the nonce is differen: this is a new instance of an
@@ -443,11 +964,137 @@ impl ControllerTask {
                     }
                 };
                 desc.refresh_deadline = update_domain_request.refresh_deadline;
+
+                // The functions/resources hosted by the previous instance
+                // of the orchestration domain are lost.
+                if let edgeless_api::domain_registration::UpdateDomainResponse::Reset = response {
+                    self.state_changed = true;
+                    self.invalidate_domain(&update_domain_request.domain_id);
+                    self.find_new_orphans().await;
+                }
                 Ok(response)
             }
         }
     }
 
+    /// Reconcile the recovered state with the functions/resources actually
+    /// running in an orchestration domain that has registered again after
+    /// the recovery: those still running are adopted, while those missing
+    /// are started again in the same domain.
+    async fn reconcile_domain(&mut self, domain_id: &str) {
+        let mut lids = std::collections::HashMap::new();
+        for (wf_id, workflow) in &self.active_workflows {
+            for component in workflow.domain_mapping.values() {
+                if component.domain_id == domain_id {
+                    lids.insert(component.lid, (wf_id.clone(), component.name.clone()));
+                }
+            }
+        }
+        if lids.is_empty() {
+            return;
+        }
+
+        let status = match self.orchestrators.get_mut(domain_id) {
+            Some(desc) => match desc.client.instance_status_api().status(lids.keys().cloned().collect()).await {
+                Ok(status) => status,
+                Err(err) => {
+                    log::warn!(
+                        "Could not retrieve the status of the instances in recovered domain '{}', assuming they are running: {}",
+                        domain_id,
+                        err
+                    );
+                    return;
+                }
+            },
+            None => return,
+        };
+
+        let mut missing: std::collections::BTreeMap<edgeless_api::workflow_instance::WorkflowId, Vec<String>> = std::collections::BTreeMap::new();
+        for (lid, (wf_id, name)) in lids {
+            match status.get(&lid).map(|status| &status.state) {
+                Some(edgeless_api::instance_status::ComponentState::Running) | Some(edgeless_api::instance_status::ComponentState::Migrating) => {}
+                _ => missing.entry(wf_id).or_default().push(name),
+            }
+        }
+        log::info!(
+            "Domain '{}' reconciled: {} workflow(s) with functions/resources to be started again",
+            domain_id,
+            missing.len()
+        );
+
+        for (wf_id, names) in missing {
+            self.restart_components(&wf_id, domain_id, &names).await;
+        }
+        self.find_new_orphans().await;
+    }
+
+    /// Start again the given functions/resources of an active workflow in
+    /// an orchestration domain and patch those affected.
+    ///
+    /// If this fails, then the functions/resources are unassigned, which
+    /// makes the workflow orphan.
+    async fn restart_components(&mut self, wf_id: &edgeless_api::workflow_instance::WorkflowId, domain_id: &str, names: &[String]) {
+        self.state_changed = true;
+        let previous = self.active_workflows.get(wf_id).unwrap().clone();
+
+        // Keep the last error.
+        let mut res: Result<(), String> = Ok(());
+
+        for function in previous
+            .desired_state
+            .workflow_functions
+            .iter()
+            .filter(|function| names.contains(&function.name))
+        {
+            if res.is_err() {
+                break;
+            }
+            res = self.start_workflow_function_in_domain(wf_id, function, domain_id).await;
+        }
+        for resource in previous
+            .desired_state
+            .workflow_resources
+            .iter()
+            .filter(|resource| names.contains(&resource.name))
+        {
+            if res.is_err() {
+                break;
+            }
+            res = self.start_workflow_resource_in_domain(wf_id, resource, domain_id).await;
+        }
+
+        // Patch the new instances and those with an output towards them.
+        for component_name in previous.components() {
+            if res.is_err() {
+                break;
+            }
+            let output_mapping = previous.component_output_mapping(&component_name);
+            if names.contains(&component_name) || output_mapping.values().any(|target| names.contains(target)) {
+                res = self.patch_component(wf_id, &component_name, false).await;
+            }
+        }
+
+        if let Err(err) = res {
+            log::error!(
+                "Could not start again the functions/resources of workflow '{}' in domain '{}': {}",
+                wf_id,
+                domain_id,
+                err
+            );
+            let current = self.active_workflows.get(wf_id).unwrap().clone();
+            for name in names {
+                if let Some(component) = current.domain_mapping.get(name) {
+                    if previous.domain_mapping.get(name).map(|component| component.lid) != Some(component.lid) {
+                        self.stop_component(wf_id, component).await;
+                    }
+                }
+                if let Some(component) = self.active_workflows.get_mut(wf_id).unwrap().domain_mapping.get_mut(name) {
+                    component.domain_id.clear();
+                }
+            }
+        }
+    }
+
     async fn refresh(&mut self) {
         log::debug!("Checking domains");
 
@@ -462,17 +1109,23 @@ impl ControllerTask {
 
         // Delete all stale domains, also invalidating all mapping of functions
         // and resources of active flows.
-        let domains_removed = !stale_domains.is_empty();
+        let mut domains_removed = !stale_domains.is_empty();
         for stale_domain in stale_domains {
             log::info!("Removing domain '{}' because it is stale", stale_domain);
             self.orchestrators.remove(&stale_domain);
+            self.state_changed = true;
+            self.invalidate_domain(&stale_domain);
+        }
 
-            for workflow in &mut self.active_workflows.values_mut() {
-                for component in workflow.domain_mapping.values_mut() {
-                    if component.domain_id == stale_domain {
-                        component.domain_id.clear();
-                    }
-                }
+        // If the recovery grace period has expired, then the domains
+        // that have not registered again are considered removed.
+        if self.recovery_deadline.is_some_and(|deadline| std::time::SystemTime::now() > deadline) {
+            self.recovery_deadline = None;
+            for (missing_domain, _nonce) in std::mem::take(&mut self.recovered_nonces) {
+                log::info!("Removing domain '{}' because it has not registered after recovery", missing_domain);
+                self.state_changed = true;
+                self.invalidate_domain(&missing_domain);
+                domains_removed = true;
             }
         }
 
@@ -485,39 +1138,24 @@ impl ControllerTask {
         self.try_fix_orphans().await;
     }
 
-    /// Return true if the given orchestration domain is compatible with the
-    /// workflow request, i.e., it can host all its functions and resources.
-    fn is_compatible(desc: &OrchestratorDesc, workflow: &edgeless_api::workflow_instance::SpawnWorkflowRequest) -> bool {
-        for function in &workflow.workflow_functions {
-            if !desc
-                .capabilities
-                .runtimes
-                .contains(&function.function_class_specification.function_class_type)
-            {
-                return false;
-            }
-        }
-        for resource in &workflow.workflow_resources {
-            if !desc.capabilities.resource_classes.contains(&resource.class_type) {
-                return false;
-            }
-        }
-        true
+    /// Return the capabilities of all the orchestration domains.
+    fn capabilities(&self) -> std::collections::HashMap<String, edgeless_api::domain_registration::DomainCapabilities> {
+        self.orchestrators
+            .iter()
+            .map(|(domain_id, desc)| (domain_id.clone(), desc.capabilities.clone()))
+            .collect()
     }
 
-    /// Return the list of orchestration domains that are compatible with the
-    /// given workflow request.
-    fn compatible_domains(
-        orchestrators: &std::collections::HashMap<String, OrchestratorDesc>,
-        workflow_request: &edgeless_api::workflow_instance::SpawnWorkflowRequest,
-    ) -> Vec<String> {
-        let mut ret = vec![];
-        for (domain_id, desc) in orchestrators {
-            if Self::is_compatible(desc, workflow_request) {
-                ret.push(domain_id.clone());
+    /// Invalidate the mapping of all the functions/resources of active
+    /// workflows assigned to the given orchestration domain.
+    fn invalidate_domain(&mut self, domain_id: &str) {
+        for workflow in self.active_workflows.values_mut() {
+            for component in workflow.domain_mapping.values_mut() {
+                if component.domain_id == domain_id {
+                    component.domain_id.clear();
+                }
             }
         }
-        ret
     }
 
     /// Check all active workflows.
@@ -531,6 +1169,7 @@ impl ControllerTask {
             }
         }
         for wf_id in new_orphans {
+            self.state_changed = true;
             let active_workflow = self
                 .active_workflows
                 .remove(&wf_id)
@@ -543,15 +1182,16 @@ impl ControllerTask {
     /// Try to fix all orphan workflows by stopping it on their current domain
     /// and starting it again on another that compatible with it.
     async fn try_fix_orphans(&mut self) {
-        // Find workflows that can be fixed, i.e., assigned to a compatible domain.
+        // Find workflows that can be fixed, i.e., assigned to compatible domains.
+        let capabilities = self.capabilities();
         let mut workflow_requests_fixable = vec![];
         let mut workflow_requests_unfixable = std::collections::BTreeMap::new();
         while let Some((wf_id, workflow_request)) = self.orphan_workflows.pop_first() {
-            match Self::compatible_domains(&self.orchestrators, &workflow_request).choose(&mut self.rng) {
-                None => {
+            match super::placement::place_workflow(&capabilities, &workflow_request, &mut self.rng) {
+                Err(_) => {
                     workflow_requests_unfixable.insert(wf_id, workflow_request);
                 }
-                Some(new_domain) => workflow_requests_fixable.push((new_domain.clone(), wf_id, workflow_request)),
+                Ok(new_placement) => workflow_requests_fixable.push((new_placement, wf_id, workflow_request)),
             };
         }
         assert!(self.orphan_workflows.is_empty());
@@ -561,12 +1201,15 @@ impl ControllerTask {
         // Try to deploy the orphan workflows to the assigned orchestration
         // domains. If this fails for some workflows, they go back to the
         // orphan list.
-        for (new_domain, wf_id, workflow_request) in workflow_requests_fixable {
-            assert!(!new_domain.is_empty());
-            match self.relocate_workflow(&wf_id, workflow_request, &new_domain).await {
+        for (new_placement, wf_id, workflow_request) in workflow_requests_fixable {
+            assert!(!new_placement.is_empty());
+            match self.relocate_workflow(&wf_id, workflow_request, &new_placement).await {
                 Ok(response) => {
                     if let edgeless_api::workflow_instance::SpawnWorkflowResponse::WorkflowInstance(_) = response {
-                        log::info!("orphan workflow assigned to domain '{}'", new_domain);
+                        let mut new_domains = new_placement.values().cloned().collect::<Vec<String>>();
+                        new_domains.sort();
+                        new_domains.dedup();
+                        log::info!("orphan workflow assigned to domain(s) '{}'", new_domains.join(","));
                         continue;
                     }
                 }
@@ -583,10 +1226,13 @@ impl ControllerTask {
         function: &edgeless_api::workflow_instance::WorkflowFunction,
         domain: &str,
     ) -> Result<(), String> {
-        // [TODO] Issue#95
-        // The state_specification configuration should be
-        // read from the function annotations.
-        log::debug!("state specifications currently forced to NodeLocal");
+        let state_policy = match function.annotations.get("state_policy") {
+            Some(val) => val.parse::<edgeless_api::function_instance::StatePolicy>().unwrap_or_else(|err| {
+                log::warn!("ignoring invalid annotation state_policy of function {}: {}", function.name, err);
+                edgeless_api::function_instance::StatePolicy::NodeLocal
+            }),
+            None => edgeless_api::function_instance::StatePolicy::NodeLocal,
+        };
         let response = self
             .fn_client(domain)
             .ok_or(format!("No function client for domain: {}", domain))?
@@ -596,7 +1242,7 @@ impl ControllerTask {
                 annotations: function.annotations.clone(),
                 state_specification: edgeless_api::function_instance::StateSpecification {
                     state_id: uuid::Uuid::new_v4(),
-                    state_policy: edgeless_api::function_instance::StatePolicy::NodeLocal,
+                    state_policy,
                 },
             })
             .await;
@@ -637,7 +1283,7 @@ impl ControllerTask {
             .ok_or(format!("No resource client for domain: {}", domain))?
             .start(edgeless_api::resource_configuration::ResourceInstanceSpecification {
                 class_type: resource.class_type.clone(),
-                configuration: resource.configurations.clone(),
+                configuration: super::placement::resource_configuration(resource),
                 output_mapping: std::collections::HashMap::new(),
             })
             .await;
@@ -704,6 +1350,39 @@ impl ControllerTask {
         output_mapping
     }
 
+    /// Patch the outputs of a function/resource of a workflow according to
+    /// the current mapping of the target components to logical identifiers.
+    ///
+    /// If `skip_empty` is true then no patch is sent when the output
+    /// mapping is empty.
+    async fn patch_component(
+        &mut self,
+        wf_id: &edgeless_api::workflow_instance::WorkflowId,
+        component_name: &str,
+        skip_empty: bool,
+    ) -> Result<(), String> {
+        let origin_domain = match self.active_workflows.get(wf_id).unwrap().domain_mapping.get(component_name) {
+            Some(component) => component.domain_id.clone(),
+            None => return Err(format!("No domain assigned to component {}", component_name)),
+        };
+
+        // Loop on all the identifiers for this function/resource
+        // (once for each orchestration domain to which the
+        // function/resource was allocated).
+        for origin_fid in self.active_workflows.get(wf_id).unwrap().mapped_fids(component_name).unwrap() {
+            let output_mapping = self.output_mapping_for(wf_id, component_name).await;
+
+            if skip_empty && output_mapping.is_empty() {
+                continue;
+            }
+
+            let component_type = self.active_workflows.get(wf_id).unwrap().component_type(component_name).unwrap();
+            self.patch_outputs(&origin_domain, origin_fid, component_type, output_mapping, component_name)
+                .await?;
+        }
+        Ok(())
+    }
+
     async fn patch_outputs(
         &mut self,
         origin_domain: &str,
@@ -752,3 +1431,25 @@ impl ControllerTask {
         Some(self.orchestrators.get_mut(domain)?.client.resource_configuration_api())
     }
 }
+
+/// Check that the output mappings of a workflow are well-formed: the
+/// functions can use routing rules, while the resources only support
+/// plain output names.
+fn validate_output_mappings(workflow: &edgeless_api::workflow_instance::SpawnWorkflowRequest) -> Result<(), String> {
+    for function in &workflow.workflow_functions {
+        if let Err(err) = edgeless_api::output_routing::validate_output_mapping(&function.output_mapping) {
+            return Err(format!("invalid output mapping of function '{}': {}", function.name, err));
+        }
+    }
+    for resource in &workflow.workflow_resources {
+        for key in resource.output_mapping.keys() {
+            if !matches!(edgeless_api::output_routing::OutputRoute::parse(key), Ok(None)) {
+                return Err(format!(
+                    "routes are not supported in the output mapping of resource '{}': {}",
+                    resource.name, key
+                ));
+            }
+        }
+    }
+    Ok(())
+}
@@ -11,7 +11,7 @@ pub struct ActiveWorkflow {
     pub domain_mapping: std::collections::HashMap<String, ActiveComponent>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ActiveComponent {
     // Function or resource.
     pub component_type: super::ComponentType,
@@ -32,6 +32,35 @@ pub struct ActiveComponent {
     pub lid: edgeless_api::function_instance::ComponentId,
 }
 
+// Differences between the desired state of an active workflow and the
+// state requested by an update.
+#[derive(Debug, Default, PartialEq)]
+pub struct WorkflowDiff {
+    // Functions/resources that are not present anymore.
+    pub removed: Vec<String>,
+
+    // Functions/resources that are new.
+    pub added: Vec<String>,
+
+    // Functions/resources that must be restarted because their function
+    // class, annotations, resource class or configuration have changed.
+    pub changed: Vec<String>,
+
+    // Functions/resources whose output mapping only has changed.
+    pub remapped: Vec<String>,
+}
+
+impl WorkflowDiff {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty() && self.changed.is_empty() && self.remapped.is_empty()
+    }
+
+    // Functions/resources for which a new instance must be started.
+    pub fn restarted(&self) -> std::collections::HashSet<String> {
+        self.added.iter().chain(self.changed.iter()).cloned().collect()
+    }
+}
+
 impl ActiveWorkflow {
     pub fn is_orphan(&self) -> bool {
         for component in self.domain_mapping.values() {
@@ -76,6 +105,58 @@ impl ActiveWorkflow {
         component_names
     }
 
+    pub fn diff(&self, new_state: &edgeless_api::workflow_instance::SpawnWorkflowRequest) -> WorkflowDiff {
+        enum Spec<'a> {
+            Function(&'a edgeless_api::workflow_instance::WorkflowFunction),
+            Resource(&'a edgeless_api::workflow_instance::WorkflowResource),
+        }
+        fn specs(state: &edgeless_api::workflow_instance::SpawnWorkflowRequest) -> std::collections::HashMap<&str, Spec<'_>> {
+            let mut ret = std::collections::HashMap::new();
+            for function in &state.workflow_functions {
+                ret.insert(function.name.as_str(), Spec::Function(function));
+            }
+            for resource in &state.workflow_resources {
+                ret.insert(resource.name.as_str(), Spec::Resource(resource));
+            }
+            ret
+        }
+        let old_specs = specs(&self.desired_state);
+        let new_specs = specs(new_state);
+
+        let mut diff = WorkflowDiff::default();
+        for (name, old_spec) in &old_specs {
+            let name = name.to_string();
+            match (old_spec, new_specs.get(name.as_str())) {
+                (_, None) => diff.removed.push(name),
+                (Spec::Function(old), Some(Spec::Function(new))) => {
+                    if old.function_class_specification != new.function_class_specification || old.annotations != new.annotations {
+                        diff.changed.push(name);
+                    } else if old.output_mapping != new.output_mapping {
+                        diff.remapped.push(name);
+                    }
+                }
+                (Spec::Resource(old), Some(Spec::Resource(new))) => {
+                    if old.class_type != new.class_type || old.configurations != new.configurations {
+                        diff.changed.push(name);
+                    } else if old.output_mapping != new.output_mapping {
+                        diff.remapped.push(name);
+                    }
+                }
+                (_, Some(_)) => diff.changed.push(name),
+            }
+        }
+        for name in new_specs.keys() {
+            if !old_specs.contains_key(name) {
+                diff.added.push(name.to_string());
+            }
+        }
+        diff.removed.sort();
+        diff.added.sort();
+        diff.changed.sort();
+        diff.remapped.sort();
+        diff
+    }
+
     pub fn component_output_mapping(&self, component_name: &str) -> std::collections::HashMap<String, String> {
         if let Some(function) = self
             .desired_state
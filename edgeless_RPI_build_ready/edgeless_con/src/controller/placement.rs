@@ -0,0 +1,484 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use rand::seq::SliceRandom;
+
+/// Latency assumed between two domains when neither of them advertises it.
+const UNKNOWN_LATENCY: u32 = u32::MAX;
+
+/// Placement requirements of a workflow, as specified by its annotations.
+#[derive(PartialEq, Debug, Clone)]
+pub struct WorkflowRequirements {
+    /// Maximum end-to-end latency of the workflow, in ms.
+    /// None means unconstrained.
+    pub max_latency: Option<u32>,
+    /// If true, then the workflow can be split across multiple domains
+    /// when there is no single domain that can host it entirely.
+    /// Disabled by default because there is no inter-domain dataplane yet,
+    /// hence the events between components in different domains are lost.
+    pub allow_split: bool,
+}
+
+impl WorkflowRequirements {
+    /// Placement requirements from the annotations in the workflow request.
+    pub fn from_annotations(annotations: &std::collections::HashMap<String, String>) -> Self {
+        let mut max_latency = None;
+        if let Some(val) = annotations.get("max_latency") {
+            max_latency = val.parse::<u32>().ok();
+        }
+
+        let mut allow_split = false;
+        if let Some(val) = annotations.get("allow_split") {
+            allow_split = val.to_lowercase() == "true";
+        }
+
+        Self { max_latency, allow_split }
+    }
+}
+
+/// Placement requirements of a function/resource, as specified by its
+/// annotations.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ComponentRequirements {
+    /// The component must be assigned to one of the given domains, if any.
+    pub domain_match_any: Vec<String>,
+    /// The component must be assigned to a domain that advertises all the
+    /// labels specified, if any.
+    pub label_match_all: Vec<String>,
+}
+
+impl ComponentRequirements {
+    /// Annotations/configurations used for the placement of a
+    /// function/resource.
+    pub const KEYS: [&'static str; 2] = ["domain_match_any", "label_match_all"];
+
+    /// Placement requirements from the annotations of a function/resource.
+    pub fn from_annotations(annotations: &std::collections::HashMap<String, String>) -> Self {
+        let mut domain_match_any = vec![];
+        if let Some(val) = annotations.get("domain_match_any") {
+            domain_match_any = val.split(",").map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect();
+        }
+
+        let mut label_match_all = vec![];
+        if let Some(val) = annotations.get("label_match_all") {
+            label_match_all = val.split(",").map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect();
+        }
+
+        Self {
+            domain_match_any,
+            label_match_all,
+        }
+    }
+
+    fn is_satisfied_by(&self, domain_id: &str, capabilities: &edgeless_api::domain_registration::DomainCapabilities) -> bool {
+        if !self.domain_match_any.is_empty() && !self.domain_match_any.iter().any(|x| x == domain_id) {
+            return false;
+        }
+        self.label_match_all.iter().all(|x| capabilities.labels.contains(x))
+    }
+}
+
+/// Estimated latency between two domains, in ms.
+///
+/// The value advertised by the origin domain is used, if present, otherwise
+/// that advertised by the target domain.
+pub fn latency(capabilities: &std::collections::HashMap<String, edgeless_api::domain_registration::DomainCapabilities>, from: &str, to: &str) -> u32 {
+    if from == to {
+        return 0;
+    }
+    if let Some(val) = capabilities.get(from).and_then(|caps| caps.inter_domain_latency.get(to)) {
+        return *val;
+    }
+    if let Some(val) = capabilities.get(to).and_then(|caps| caps.inter_domain_latency.get(from)) {
+        return *val;
+    }
+    UNKNOWN_LATENCY
+}
+
+/// Return the list of domains that can host the given function.
+fn candidate_domains_function(
+    capabilities: &std::collections::HashMap<String, edgeless_api::domain_registration::DomainCapabilities>,
+    function: &edgeless_api::workflow_instance::WorkflowFunction,
+) -> Vec<String> {
+    let requirements = ComponentRequirements::from_annotations(&function.annotations);
+    let mut ret = capabilities
+        .iter()
+        .filter(|(domain_id, caps)| {
+            caps.runtimes.contains(&function.function_class_specification.function_class_type) && requirements.is_satisfied_by(domain_id, caps)
+        })
+        .map(|(domain_id, _caps)| domain_id.clone())
+        .collect::<Vec<String>>();
+    ret.sort();
+    ret
+}
+
+/// Return the list of domains that can host the given resource.
+///
+/// Resources have no annotations, hence the placement requirements are
+/// read from their configuration, see also `resource_configuration`.
+fn candidate_domains_resource(
+    capabilities: &std::collections::HashMap<String, edgeless_api::domain_registration::DomainCapabilities>,
+    resource: &edgeless_api::workflow_instance::WorkflowResource,
+) -> Vec<String> {
+    let requirements = ComponentRequirements::from_annotations(&resource.configurations);
+    let mut ret = capabilities
+        .iter()
+        .filter(|(domain_id, caps)| caps.resource_classes.contains(&resource.class_type) && requirements.is_satisfied_by(domain_id, caps))
+        .map(|(domain_id, _caps)| domain_id.clone())
+        .collect::<Vec<String>>();
+    ret.sort();
+    ret
+}
+
+/// Return the configuration of a resource without the placement
+/// requirements, which are not meant for the resource provider.
+pub fn resource_configuration(resource: &edgeless_api::workflow_instance::WorkflowResource) -> std::collections::HashMap<String, String> {
+    resource
+        .configurations
+        .iter()
+        .filter(|(key, _)| !ComponentRequirements::KEYS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Return the edges of the workflow graph, as (origin, target) component names.
+fn edges(workflow: &edgeless_api::workflow_instance::SpawnWorkflowRequest) -> Vec<(String, String)> {
+    let mut ret = vec![];
+    for function in &workflow.workflow_functions {
+        for target in function.output_mapping.values() {
+            ret.push((function.name.clone(), target.clone()));
+        }
+    }
+    for resource in &workflow.workflow_resources {
+        for target in resource.output_mapping.values() {
+            ret.push((resource.name.clone(), target.clone()));
+        }
+    }
+    ret
+}
+
+/// Return the end-to-end latency of a workflow with a given placement, in ms,
+/// i.e., the maximum sum of the inter-domain latencies along any path
+/// of the workflow graph. Cycles are only traversed once.
+pub fn end_to_end_latency(
+    capabilities: &std::collections::HashMap<String, edgeless_api::domain_registration::DomainCapabilities>,
+    workflow: &edgeless_api::workflow_instance::SpawnWorkflowRequest,
+    placement: &std::collections::HashMap<String, String>,
+) -> u32 {
+    let mut successors: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
+    for (origin, target) in edges(workflow) {
+        successors.entry(origin).or_default().push(target);
+    }
+
+    fn longest_path(
+        component: &str,
+        successors: &std::collections::HashMap<String, Vec<String>>,
+        cost: &dyn Fn(&str, &str) -> u32,
+        visited: &mut std::collections::HashSet<String>,
+    ) -> u32 {
+        let mut ret = 0;
+        visited.insert(component.to_string());
+        for next in successors.get(component).unwrap_or(&vec![]) {
+            if visited.contains(next) {
+                continue;
+            }
+            ret = std::cmp::max(ret, cost(component, next).saturating_add(longest_path(next, successors, cost, visited)));
+        }
+        visited.remove(component);
+        ret
+    }
+
+    let cost = |from: &str, to: &str| match (placement.get(from), placement.get(to)) {
+        (Some(from_domain), Some(to_domain)) => latency(capabilities, from_domain, to_domain),
+        _ => 0,
+    };
+    placement
+        .keys()
+        .map(|component| longest_path(component, &successors, &cost, &mut std::collections::HashSet::new()))
+        .max()
+        .unwrap_or_default()
+}
+
+/// Assign each function and resource of a workflow to an orchestration
+/// domain.
+///
+/// If there are domains that can host the whole workflow, one of them is
+/// selected at random. Otherwise, if the workflow annotations allow it, the
+/// workflow is split across domains: components are assigned one by one,
+/// the most constrained first, each to the candidate domain with the
+/// minimum latency towards the domains of its already-assigned neighbors.
+/// This way, components bound to resources only available at the edge
+/// stay there, while those requiring, e.g., labels only advertised by
+/// a cloud domain are assigned to the latter.
+/// In both cases the resulting end-to-end latency must not exceed the
+/// `max_latency` workflow annotation, if present.
+///
+/// Return the map of component names to domain identifiers or a
+/// human-readable reason why no placement was found.
+pub fn place_workflow(
+    capabilities: &std::collections::HashMap<String, edgeless_api::domain_registration::DomainCapabilities>,
+    workflow: &edgeless_api::workflow_instance::SpawnWorkflowRequest,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    place_workflow_keeping(capabilities, workflow, &std::collections::HashMap::new(), rng)
+}
+
+/// Same as `place_workflow`, but the components in `current`, which maps
+/// their names to the domains currently hosting them, stay there as long as
+/// the latter can still host them.
+pub fn place_workflow_keeping(
+    capabilities: &std::collections::HashMap<String, edgeless_api::domain_registration::DomainCapabilities>,
+    workflow: &edgeless_api::workflow_instance::SpawnWorkflowRequest,
+    current: &std::collections::HashMap<String, String>,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let requirements = WorkflowRequirements::from_annotations(&workflow.annotations);
+
+    // Find the candidate domains of every component.
+    let mut candidates = vec![];
+    for function in &workflow.workflow_functions {
+        candidates.push((function.name.clone(), candidate_domains_function(capabilities, function)));
+    }
+    for resource in &workflow.workflow_resources {
+        candidates.push((resource.name.clone(), candidate_domains_resource(capabilities, resource)));
+    }
+    if let Some((name, _)) = candidates.iter().find(|(_, domains)| domains.is_empty()) {
+        return Err(format!("No domain can host the function/resource '{}'", name));
+    }
+
+    // Components that stay where they are.
+    let pinned: std::collections::HashMap<String, String> = candidates
+        .iter()
+        .filter_map(|(name, domains)| {
+            current
+                .get(name)
+                .filter(|domain_id| domains.contains(domain_id))
+                .map(|domain_id| (name.clone(), domain_id.clone()))
+        })
+        .collect();
+
+    // Try to find a single domain that can host all the components.
+    let mut common_domains = capabilities.keys().cloned().collect::<Vec<String>>();
+    common_domains.retain(|domain_id| {
+        candidates.iter().all(|(_, domains)| domains.contains(domain_id)) && pinned.values().all(|pinned_domain| pinned_domain == domain_id)
+    });
+    common_domains.sort();
+    if let Some(domain_id) = common_domains.choose(rng) {
+        return Ok(candidates.into_iter().map(|(name, _)| (name, domain_id.clone())).collect());
+    }
+
+    if !requirements.allow_split {
+        return Err("No single domain supporting all the functions/resources found".to_string());
+    }
+
+    // Split the workflow across multiple domains.
+    let edges = edges(workflow);
+    candidates.retain(|(name, _)| !pinned.contains_key(name));
+    candidates.sort_by_key(|(_, domains)| domains.len());
+    let mut placement = pinned;
+    for (name, domains) in candidates {
+        // The cost of a candidate domain is the sum of the latencies towards
+        // the neighbors already assigned, with ties broken in favor of the
+        // domain closest to the upstream components.
+        let cost = |domain_id: &String| -> (u64, u64) {
+            let mut total = 0;
+            let mut upstream = 0;
+            for (origin, target) in &edges {
+                if *origin == name {
+                    if let Some(other_domain) = placement.get(target) {
+                        total += latency(capabilities, domain_id, other_domain) as u64;
+                    }
+                } else if *target == name {
+                    if let Some(other_domain) = placement.get(origin) {
+                        total += latency(capabilities, other_domain, domain_id) as u64;
+                        upstream += latency(capabilities, other_domain, domain_id) as u64;
+                    }
+                }
+            }
+            (total, upstream)
+        };
+        let best = domains
+            .iter()
+            .min_by_key(|domain_id| cost(domain_id))
+            .expect("empty candidate list")
+            .clone();
+        placement.insert(name, best);
+    }
+
+    if let Some(max_latency) = requirements.max_latency {
+        let latency = end_to_end_latency(capabilities, workflow, &placement);
+        if latency > max_latency {
+            return Err(format!(
+                "End-to-end latency of the best placement found ({} ms) exceeds the maximum allowed ({} ms)",
+                latency, max_latency
+            ));
+        }
+    }
+
+    Ok(placement)
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn function(name: &str, output_mapping: Vec<(&str, &str)>, annotations: Vec<(&str, &str)>) -> edgeless_api::workflow_instance::WorkflowFunction {
+        edgeless_api::workflow_instance::WorkflowFunction {
+            name: name.to_string(),
+            function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+                function_class_id: name.to_string(),
+                function_class_type: "RUST_WASM".to_string(),
+                function_class_version: "0.1".to_string(),
+                function_class_code: vec![],
+                function_class_outputs: vec![],
+            },
+            output_mapping: output_mapping.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            annotations: annotations.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    fn edge_cloud() -> std::collections::HashMap<String, edgeless_api::domain_registration::DomainCapabilities> {
+        let mut edge = edgeless_api::domain_registration::DomainCapabilities::default();
+        edge.runtimes.insert("RUST_WASM".to_string());
+        edge.resource_classes.insert("sensor".to_string());
+        edge.labels.insert("edge".to_string());
+        edge.inter_domain_latency.insert("cloud".to_string(), 40);
+        let mut cloud = edgeless_api::domain_registration::DomainCapabilities::default();
+        cloud.runtimes.insert("RUST_WASM".to_string());
+        cloud.labels.insert("cloud".to_string());
+        cloud.labels.insert("gpu".to_string());
+        std::collections::HashMap::from([("edge".to_string(), edge), ("cloud".to_string(), cloud)])
+    }
+
+    fn workflow(annotations: Vec<(&str, &str)>) -> edgeless_api::workflow_instance::SpawnWorkflowRequest {
+        edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            workflow_functions: vec![
+                function("features", vec![("out", "classify")], vec![]),
+                function("classify", vec![("out", "alarm")], vec![("label_match_all", "gpu")]),
+                function("alarm", vec![], vec![]),
+            ],
+            workflow_resources: vec![edgeless_api::workflow_instance::WorkflowResource {
+                name: "sensor".to_string(),
+                class_type: "sensor".to_string(),
+                output_mapping: std::collections::HashMap::from([("out".to_string(), "features".to_string())]),
+                configurations: std::collections::HashMap::new(),
+            }],
+            annotations: annotations.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn test_place_workflow_single_domain() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let caps = edge_cloud();
+        let mut wf = workflow(vec![]);
+        wf.workflow_functions[1].annotations.clear();
+        wf.workflow_resources.clear();
+
+        // All functions can run anywhere: they must end up in the same domain.
+        let placement = place_workflow(&caps, &wf, &mut rng).unwrap();
+        assert_eq!(3, placement.len());
+        assert_eq!(1, placement.values().collect::<std::collections::HashSet<&String>>().len());
+        assert_eq!(0, end_to_end_latency(&caps, &wf, &placement));
+    }
+
+    #[test]
+    fn test_place_workflow_split() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let caps = edge_cloud();
+
+        let placement = place_workflow(&caps, &workflow(vec![("allow_split", "true")]), &mut rng).unwrap();
+        assert_eq!("edge", placement.get("sensor").unwrap());
+        assert_eq!("edge", placement.get("features").unwrap());
+        assert_eq!("cloud", placement.get("classify").unwrap());
+        assert_eq!("cloud", placement.get("alarm").unwrap());
+        assert_eq!(40, end_to_end_latency(&caps, &workflow(vec![]), &placement));
+
+        // Latency constraint satisfied.
+        assert!(place_workflow(&caps, &workflow(vec![("allow_split", "true"), ("max_latency", "50")]), &mut rng).is_ok());
+
+        // Latency constraint not satisfied.
+        assert!(place_workflow(&caps, &workflow(vec![("allow_split", "true"), ("max_latency", "30")]), &mut rng).is_err());
+
+        // Split not allowed, explicitly or by default.
+        assert!(place_workflow(&caps, &workflow(vec![("allow_split", "false")]), &mut rng).is_err());
+        assert!(place_workflow(&caps, &workflow(vec![]), &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_place_workflow_keeping() {
+        let caps = edge_cloud();
+        let mut wf = workflow(vec![]);
+        wf.workflow_functions[1].annotations.clear();
+        wf.workflow_resources.clear();
+
+        // The components stay in their current domain, if possible.
+        let current = std::collections::HashMap::from([("features".to_string(), "cloud".to_string())]);
+        for seed in 0..10 {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            let placement = place_workflow_keeping(&caps, &wf, &current, &mut rng).unwrap();
+            assert!(placement.values().all(|domain_id| domain_id == "cloud"), "{:?}", placement);
+        }
+
+        // The components in different domains stay there, too, if splitting
+        // is allowed, while the new components are placed nearby.
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let current = std::collections::HashMap::from([
+            ("features".to_string(), "edge".to_string()),
+            ("classify".to_string(), "cloud".to_string()),
+        ]);
+        assert!(place_workflow_keeping(&caps, &wf, &current, &mut rng).is_err());
+        wf.annotations.insert("allow_split".to_string(), "true".to_string());
+        let placement = place_workflow_keeping(&caps, &wf, &current, &mut rng).unwrap();
+        assert_eq!("edge", placement.get("features").unwrap());
+        assert_eq!("cloud", placement.get("classify").unwrap());
+        assert_eq!("cloud", placement.get("alarm").unwrap());
+
+        // A component moves if its current domain cannot host it anymore.
+        let current = std::collections::HashMap::from([("classify".to_string(), "edge".to_string())]);
+        let mut wf = workflow(vec![("allow_split", "true")]);
+        wf.workflow_resources.clear();
+        let placement = place_workflow_keeping(&caps, &wf, &current, &mut rng).unwrap();
+        assert_eq!("cloud", placement.get("classify").unwrap());
+    }
+
+    #[test]
+    fn test_place_workflow_infeasible() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let caps = edge_cloud();
+        let mut wf = workflow(vec![]);
+        wf.workflow_functions[2]
+            .annotations
+            .insert("domain_match_any".to_string(), "fog".to_string());
+        assert!(place_workflow(&caps, &wf, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_resource_configuration() {
+        let resource = edgeless_api::workflow_instance::WorkflowResource {
+            name: "sensor".to_string(),
+            class_type: "sensor".to_string(),
+            output_mapping: std::collections::HashMap::new(),
+            configurations: std::collections::HashMap::from([
+                ("domain_match_any".to_string(), "edge".to_string()),
+                ("label_match_all".to_string(), "gpu".to_string()),
+                ("topic".to_string(), "readings".to_string()),
+            ]),
+        };
+        assert_eq!(
+            std::collections::HashMap::from([("topic".to_string(), "readings".to_string())]),
+            resource_configuration(&resource)
+        );
+    }
+
+    #[test]
+    fn test_latency() {
+        let caps = edge_cloud();
+        assert_eq!(0, latency(&caps, "edge", "edge"));
+        assert_eq!(40, latency(&caps, "edge", "cloud"));
+        assert_eq!(40, latency(&caps, "cloud", "edge"));
+        assert_eq!(UNKNOWN_LATENCY, latency(&caps, "cloud", "fog"));
+    }
+}
@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// Store used by the controller to persist its state, so that it can
+/// recover the active and orphan workflows after a restart.
+pub trait StateStore: Send {
+    /// Save the controller state, replacing the one previously saved.
+    fn save(&mut self, state: &ControllerState) -> anyhow::Result<()>;
+
+    /// Load the last controller state saved, if any.
+    fn load(&mut self) -> anyhow::Result<Option<ControllerState>>;
+}
+
+/// Snapshot of the controller state.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ControllerState {
+    // Active and orphan workflows.
+    pub workflows: Vec<PersistedWorkflow>,
+
+    // Last nonce announced by each orchestration domain, used upon recovery
+    // to tell whether an orchestrator has been restarted in the meanwhile.
+    pub domain_nonces: std::collections::HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PersistedWorkflow {
+    pub workflow_id: uuid::Uuid,
+    pub functions: Vec<PersistedFunction>,
+    pub resources: Vec<PersistedResource>,
+    pub annotations: std::collections::HashMap<String, String>,
+
+    // Mapping of the functions/resources to the domains.
+    // Empty if the workflow is orphan.
+    pub components: Vec<super::deployment_state::ActiveComponent>,
+}
+
+// Same as edgeless_api::workflow_instance::WorkflowFunction, but the function
+// code is serialized, too.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PersistedFunction {
+    pub name: String,
+    pub function_class_id: String,
+    pub function_class_type: String,
+    pub function_class_version: String,
+    pub function_class_code: Vec<u8>,
+    pub function_class_outputs: Vec<String>,
+    pub output_mapping: std::collections::HashMap<String, String>,
+    pub annotations: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PersistedResource {
+    pub name: String,
+    pub class_type: String,
+    pub output_mapping: std::collections::HashMap<String, String>,
+    pub configurations: std::collections::HashMap<String, String>,
+}
+
+impl PersistedWorkflow {
+    pub fn new(
+        wf_id: &edgeless_api::workflow_instance::WorkflowId,
+        request: &edgeless_api::workflow_instance::SpawnWorkflowRequest,
+        components: Vec<super::deployment_state::ActiveComponent>,
+    ) -> Self {
+        Self {
+            workflow_id: wf_id.workflow_id,
+            functions: request
+                .workflow_functions
+                .iter()
+                .map(|function| PersistedFunction {
+                    name: function.name.clone(),
+                    function_class_id: function.function_class_specification.function_class_id.clone(),
+                    function_class_type: function.function_class_specification.function_class_type.clone(),
+                    function_class_version: function.function_class_specification.function_class_version.clone(),
+                    function_class_code: function.function_class_specification.function_class_code.clone(),
+                    function_class_outputs: function.function_class_specification.function_class_outputs.clone(),
+                    output_mapping: function.output_mapping.clone(),
+                    annotations: function.annotations.clone(),
+                })
+                .collect(),
+            resources: request
+                .workflow_resources
+                .iter()
+                .map(|resource| PersistedResource {
+                    name: resource.name.clone(),
+                    class_type: resource.class_type.clone(),
+                    output_mapping: resource.output_mapping.clone(),
+                    configurations: resource.configurations.clone(),
+                })
+                .collect(),
+            annotations: request.annotations.clone(),
+            components,
+        }
+    }
+
+    pub fn workflow_id(&self) -> edgeless_api::workflow_instance::WorkflowId {
+        edgeless_api::workflow_instance::WorkflowId {
+            workflow_id: self.workflow_id,
+        }
+    }
+
+    pub fn request(&self) -> edgeless_api::workflow_instance::SpawnWorkflowRequest {
+        edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            workflow_functions: self
+                .functions
+                .iter()
+                .map(|function| edgeless_api::workflow_instance::WorkflowFunction {
+                    name: function.name.clone(),
+                    function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+                        function_class_id: function.function_class_id.clone(),
+                        function_class_type: function.function_class_type.clone(),
+                        function_class_version: function.function_class_version.clone(),
+                        function_class_code: function.function_class_code.clone(),
+                        function_class_outputs: function.function_class_outputs.clone(),
+                    },
+                    output_mapping: function.output_mapping.clone(),
+                    annotations: function.annotations.clone(),
+                })
+                .collect(),
+            workflow_resources: self
+                .resources
+                .iter()
+                .map(|resource| edgeless_api::workflow_instance::WorkflowResource {
+                    name: resource.name.clone(),
+                    class_type: resource.class_type.clone(),
+                    output_mapping: resource.output_mapping.clone(),
+                    configurations: resource.configurations.clone(),
+                })
+                .collect(),
+            annotations: self.annotations.clone(),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// A state store that saves the controller state as a JSON structure
+/// in a local file.
+///
+/// The file is first written to a temporary file in the same directory,
+/// which is then renamed, so that a crash while saving never leaves a
+/// truncated state behind.
+pub struct StateStoreFile {
+    path: std::path::PathBuf,
+}
+
+impl StateStoreFile {
+    pub fn new(path: &str) -> anyhow::Result<Self> {
+        if path.is_empty() {
+            anyhow::bail!("empty path of the controller state file");
+        }
+        log::info!("persisting the controller state to file {}", path);
+        Ok(Self {
+            path: std::path::PathBuf::from(path),
+        })
+    }
+
+    fn tmp_path(&self) -> std::path::PathBuf {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        std::path::PathBuf::from(tmp_path)
+    }
+}
+
+impl super::state_store::StateStore for StateStoreFile {
+    fn save(&mut self, state: &super::state_store::ControllerState) -> anyhow::Result<()> {
+        let tmp_path = self.tmp_path();
+        std::fs::write(&tmp_path, serde_json::to_vec(state)?)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    fn load(&mut self) -> anyhow::Result<Option<super::state_store::ControllerState>> {
+        match std::fs::read(&self.path) {
+            Ok(content) => Ok(Some(serde_json::from_slice(&content)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::state_store::StateStore;
+
+    #[test]
+    fn test_state_store_file_save_load() {
+        let path = std::env::temp_dir().join(format!("edgeless_con_state_{}.json", uuid::Uuid::new_v4()));
+        let mut store = super::StateStoreFile::new(path.to_str().unwrap()).unwrap();
+
+        // Nothing saved yet.
+        assert!(store.load().unwrap().is_none());
+
+        let wf_id = edgeless_api::workflow_instance::WorkflowId {
+            workflow_id: uuid::Uuid::new_v4(),
+        };
+        let request = edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            workflow_functions: vec![edgeless_api::workflow_instance::WorkflowFunction {
+                name: "f1".to_string(),
+                function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+                    function_class_id: "fc1".to_string(),
+                    function_class_type: "RUST_WASM".to_string(),
+                    function_class_version: "0.1".to_string(),
+                    function_class_code: vec![0, 97, 115, 109],
+                    function_class_outputs: vec!["out".to_string()],
+                },
+                output_mapping: std::collections::HashMap::from([("out".to_string(), "r1".to_string())]),
+                annotations: std::collections::HashMap::new(),
+            }],
+            workflow_resources: vec![edgeless_api::workflow_instance::WorkflowResource {
+                name: "r1".to_string(),
+                class_type: "file-log".to_string(),
+                output_mapping: std::collections::HashMap::new(),
+                configurations: std::collections::HashMap::from([("filename".to_string(), "out.log".to_string())]),
+            }],
+            annotations: std::collections::HashMap::from([("max_latency".to_string(), "10".to_string())]),
+        };
+        let components = vec![super::super::deployment_state::ActiveComponent {
+            component_type: super::super::ComponentType::Function,
+            name: "f1".to_string(),
+            domain_id: "domain-1".to_string(),
+            lid: uuid::Uuid::new_v4(),
+        }];
+        let state = super::super::state_store::ControllerState {
+            workflows: vec![super::super::state_store::PersistedWorkflow::new(&wf_id, &request, components)],
+            domain_nonces: std::collections::HashMap::from([("domain-1".to_string(), 42)]),
+        };
+
+        store.save(&state).unwrap();
+        let loaded = store.load().unwrap().unwrap();
+        assert_eq!(state, loaded);
+        assert_eq!(wf_id, loaded.workflows[0].workflow_id());
+        assert_eq!(request, loaded.workflows[0].request());
+
+        // Overwrite the previous state.
+        store.save(&super::super::state_store::ControllerState::default()).unwrap();
+        assert!(store.load().unwrap().unwrap().workflows.is_empty());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
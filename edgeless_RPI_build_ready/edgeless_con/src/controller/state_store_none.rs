@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// A state store that does not persist anything.
+pub struct StateStoreNone {}
+
+impl super::state_store::StateStore for StateStoreNone {
+    fn save(&mut self, _state: &super::state_store::ControllerState) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn load(&mut self) -> anyhow::Result<Option<super::state_store::ControllerState>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,61 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use redis::Commands;
+
+/// A state store that saves the controller state as a JSON structure
+/// in a Redis in-memory database.
+///
+/// The following key is written:
+/// - controller:state
+///
+/// If an operation fails, the connection is re-established upon the next one.
+pub struct StateStoreRedis {
+    client: redis::Client,
+    // Connection to Redis, none if the last operation failed.
+    connection: Option<redis::Connection>,
+}
+
+const STATE_KEY: &str = "controller:state";
+
+impl StateStoreRedis {
+    pub fn new(redis_url: &str) -> anyhow::Result<Self> {
+        log::info!("persisting the controller state to Redis at URL {}", redis_url);
+        let client = redis::Client::open(redis_url)?;
+        let connection = Some(client.get_connection()?);
+        Ok(Self { client, connection })
+    }
+
+    fn connection(&mut self) -> anyhow::Result<&mut redis::Connection> {
+        if self.connection.is_none() {
+            log::info!("re-connecting to Redis to persist the controller state");
+            self.connection = Some(self.client.get_connection()?);
+        }
+        Ok(self.connection.as_mut().unwrap())
+    }
+
+    // Drop the connection if the operation failed, e.g., because the
+    // server has been restarted.
+    fn check<T>(&mut self, res: redis::RedisResult<T>) -> anyhow::Result<T> {
+        if res.is_err() {
+            self.connection = None;
+        }
+        Ok(res?)
+    }
+}
+
+impl super::state_store::StateStore for StateStoreRedis {
+    fn save(&mut self, state: &super::state_store::ControllerState) -> anyhow::Result<()> {
+        let content = serde_json::to_string(state)?;
+        let res = self.connection()?.set::<&str, String, ()>(STATE_KEY, content);
+        self.check(res)
+    }
+
+    fn load(&mut self) -> anyhow::Result<Option<super::state_store::ControllerState>> {
+        let res = self.connection()?.get::<&str, Option<String>>(STATE_KEY);
+        match self.check(res)? {
+            Some(content) => Ok(Some(serde_json::from_str(&content)?)),
+            None => Ok(None),
+        }
+    }
+}
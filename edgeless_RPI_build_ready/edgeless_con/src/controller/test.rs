@@ -29,32 +29,120 @@ enum MockFunctionInstanceEvent {
     Patch(edgeless_api::common::PatchRequest),
 }
 
+// Identifiers of the instances running in a mock orchestration domain.
+type MockRunning = std::sync::Arc<std::sync::Mutex<std::collections::HashSet<edgeless_api::function_instance::DomainManagedInstanceId>>>;
+
 struct MockOrchestrator {
     sender: futures::channel::mpsc::UnboundedSender<MockFunctionInstanceEvent>,
+    running: MockRunning,
 }
 
 impl edgeless_api::outer::orc::OrchestratorAPI for MockOrchestrator {
     fn function_instance_api(
         &mut self,
     ) -> Box<dyn edgeless_api::function_instance::FunctionInstanceAPI<edgeless_api::function_instance::DomainManagedInstanceId>> {
-        Box::new(MockFunctionInstanceAPI { sender: self.sender.clone() })
+        Box::new(MockFunctionInstanceAPI {
+            sender: self.sender.clone(),
+            running: self.running.clone(),
+        })
     }
 
     fn resource_configuration_api(
         &mut self,
     ) -> Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::DomainManagedInstanceId>> {
-        Box::new(MockResourceConfigurationAPI { sender: self.sender.clone() })
+        Box::new(MockResourceConfigurationAPI {
+            sender: self.sender.clone(),
+            running: self.running.clone(),
+        })
+    }
+
+    fn instance_status_api(&mut self) -> Box<dyn edgeless_api::instance_status::InstanceStatusAPI> {
+        Box::new(MockInstanceStatusAPI {
+            running: self.running.clone(),
+        })
     }
 }
 
 #[derive(Clone)]
 struct MockFunctionInstanceAPI {
     sender: futures::channel::mpsc::UnboundedSender<MockFunctionInstanceEvent>,
+    running: MockRunning,
 }
 
 #[derive(Clone)]
 struct MockResourceConfigurationAPI {
     sender: futures::channel::mpsc::UnboundedSender<MockFunctionInstanceEvent>,
+    running: MockRunning,
+}
+
+// Epoch of the log entries returned by MockInstanceStatusAPI.
+const MOCK_LOG_EPOCH: u64 = 1;
+
+// Reports the instances started and not stopped as running, the others as
+// unknown.
+#[derive(Clone)]
+struct MockInstanceStatusAPI {
+    running: MockRunning,
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::instance_status::InstanceStatusAPI for MockInstanceStatusAPI {
+    async fn status(
+        &mut self,
+        ids: Vec<edgeless_api::function_instance::DomainManagedInstanceId>,
+    ) -> anyhow::Result<
+        std::collections::HashMap<edgeless_api::function_instance::DomainManagedInstanceId, edgeless_api::instance_status::ComponentStatus>,
+    > {
+        let running = self.running.lock().unwrap();
+        Ok(ids
+            .into_iter()
+            .filter(|id| running.contains(id))
+            .map(|id| {
+                (
+                    id,
+                    edgeless_api::instance_status::ComponentStatus {
+                        state: edgeless_api::instance_status::ComponentState::Running,
+                        node_ids: vec![uuid::Uuid::nil()],
+                        last_invocation: None,
+                        error_count: 0,
+                        restart_count: 0,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    async fn logs(
+        &mut self,
+        ids: Vec<edgeless_api::function_instance::DomainManagedInstanceId>,
+        since: edgeless_api::instance_status::LogPosition,
+    ) -> anyhow::Result<
+        std::collections::HashMap<edgeless_api::function_instance::DomainManagedInstanceId, Vec<edgeless_api::instance_status::InstanceLogEntry>>,
+    > {
+        // Every instance has emitted three log entries, with sequence numbers 1, 2, 3.
+        Ok(ids
+            .into_iter()
+            .map(|id| {
+                (
+                    id,
+                    (1..=3)
+                        .map(|seq| edgeless_api::instance_status::InstanceLogEntry {
+                            seq,
+                            epoch: MOCK_LOG_EPOCH,
+                            node_id: uuid::Uuid::nil(),
+                            entry: edgeless_api::node_registration::FunctionLogEntry {
+                                timestamp: std::time::UNIX_EPOCH,
+                                level: "Info".to_string(),
+                                target: id.to_string(),
+                                message: format!("entry-{}", seq),
+                            },
+                        })
+                        .filter(|entry| since.precedes(entry))
+                        .collect(),
+                )
+            })
+            .collect())
+    }
 }
 
 #[async_trait::async_trait]
@@ -63,7 +151,17 @@ impl edgeless_api::function_instance::FunctionInstanceAPI<edgeless_api::function
         &mut self,
         spawn_request: edgeless_api::function_instance::SpawnFunctionRequest,
     ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::DomainManagedInstanceId>> {
+        // Version "fail" is used by the tests to simulate a failed start.
+        if spawn_request.code.function_class_version == "fail" {
+            return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Function start failed".to_string(),
+                    detail: None,
+                },
+            ));
+        }
         let new_id = uuid::Uuid::new_v4();
+        self.running.lock().unwrap().insert(new_id);
         self.sender
             .send(MockFunctionInstanceEvent::StartFunction((new_id, spawn_request)))
             .await
@@ -71,6 +169,7 @@ impl edgeless_api::function_instance::FunctionInstanceAPI<edgeless_api::function
         Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
     }
     async fn stop(&mut self, id: edgeless_api::function_instance::DomainManagedInstanceId) -> anyhow::Result<()> {
+        self.running.lock().unwrap().remove(&id);
         self.sender.send(MockFunctionInstanceEvent::StopFunction(id)).await.unwrap();
         Ok(())
     }
@@ -89,6 +188,7 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
     ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::DomainManagedInstanceId>> {
         let new_id = uuid::Uuid::new_v4();
+        self.running.lock().unwrap().insert(new_id);
         self.sender
             .send(MockFunctionInstanceEvent::StartResource((new_id, instance_specification)))
             .await
@@ -96,6 +196,7 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
     }
     async fn stop(&mut self, resource_id: edgeless_api::function_instance::DomainManagedInstanceId) -> anyhow::Result<()> {
+        self.running.lock().unwrap().remove(&resource_id);
         self.sender.send(MockFunctionInstanceEvent::StopResource(resource_id)).await.unwrap();
         Ok(())
     }
@@ -105,12 +206,43 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
     }
 }
 
+// Keeps the state in memory.
+struct MockStateStore {
+    state: Option<state_store::ControllerState>,
+}
+
+impl state_store::StateStore for MockStateStore {
+    fn save(&mut self, state: &state_store::ControllerState) -> anyhow::Result<()> {
+        self.state = Some(state.clone());
+        Ok(())
+    }
+
+    fn load(&mut self) -> anyhow::Result<Option<state_store::ControllerState>> {
+        Ok(self.state.clone())
+    }
+}
+
 async fn test_setup() -> (
     Box<dyn edgeless_api::workflow_instance::WorkflowInstanceAPI>,
     futures::channel::mpsc::UnboundedReceiver<MockFunctionInstanceEvent>,
+) {
+    test_setup_recovered(None, vec![]).await
+}
+
+// Set up a controller recovering the given state, with a single
+// orchestration domain in which the given instances are running.
+async fn test_setup_recovered(
+    state: Option<state_store::ControllerState>,
+    running: Vec<edgeless_api::function_instance::DomainManagedInstanceId>,
+) -> (
+    Box<dyn edgeless_api::workflow_instance::WorkflowInstanceAPI>,
+    futures::channel::mpsc::UnboundedReceiver<MockFunctionInstanceEvent>,
 ) {
     let (mock_orc_sender, mock_orc_receiver) = futures::channel::mpsc::unbounded::<MockFunctionInstanceEvent>();
-    let mock_orc = MockOrchestrator { sender: mock_orc_sender };
+    let mock_orc = MockOrchestrator {
+        sender: mock_orc_sender,
+        running: std::sync::Arc::new(std::sync::Mutex::new(running.into_iter().collect())),
+    };
 
     let (workflow_instance_sender, workflow_instance_receiver) = futures::channel::mpsc::unbounded();
     let (_domain_registration_sender, domain_registration_receiver) = futures::channel::mpsc::unbounded();
@@ -137,7 +269,9 @@ async fn test_setup() -> (
             domain_registration_receiver,
             internal_receiver,
             orchestrators,
-        );
+            Box::new(MockStateStore { state }),
+        )
+        .await;
         controller_task.run().await;
     });
     tokio::spawn(controller_task);
@@ -432,3 +566,490 @@ async fn function_link_loop_start_stop() {
     }
     assert!(fids.is_empty());
 }
+
+#[tokio::test]
+async fn function_chain_update() {
+    let (mut wf_client, mut mock_orc_receiver) = test_setup().await;
+
+    assert!(mock_orc_receiver.try_next().is_err());
+
+    let function = |name: &str, version: &str, output_mapping: Vec<(&str, &str)>| edgeless_api::workflow_instance::WorkflowFunction {
+        name: name.to_string(),
+        function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+            function_class_id: name.to_string(),
+            function_class_type: "RUST_WASM".to_string(),
+            function_class_version: version.to_string(),
+            function_class_code: vec![],
+            function_class_outputs: vec!["out".to_string()],
+        },
+        output_mapping: output_mapping.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        annotations: std::collections::HashMap::new(),
+    };
+
+    let response = wf_client
+        .start(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            workflow_functions: vec![function("f1", "0.1", vec![("out", "f2")]), function("f2", "0.1", vec![])],
+            workflow_resources: vec![],
+            annotations: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+
+    let instance = match &response {
+        SpawnWorkflowResponse::ResponseError(err) => panic!("{}", err),
+        SpawnWorkflowResponse::WorkflowInstance(val) => val,
+    };
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let mut fids = vec![];
+    for _i in 0..2 {
+        if let MockFunctionInstanceEvent::StartFunction((id, _spawn_req)) = mock_orc_receiver.try_next().unwrap().unwrap() {
+            fids.push(id);
+        } else {
+            panic!();
+        }
+    }
+    let (f1_id, f2_id) = (fids[0], fids[1]);
+    if let MockFunctionInstanceEvent::Patch(patch_req) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(f1_id, patch_req.function_id);
+        assert_eq!(f2_id, patch_req.output_mapping.get("out").unwrap().function_id);
+    } else {
+        panic!();
+    }
+    assert!(mock_orc_receiver.try_next().is_err());
+
+    // Update with no changes: nothing happens.
+    let response = wf_client
+        .update(edgeless_api::workflow_instance::UpdateWorkflowRequest {
+            workflow_id: instance.workflow_id.clone(),
+            request: edgeless_api::workflow_instance::SpawnWorkflowRequest {
+                workflow_functions: vec![function("f1", "0.1", vec![("out", "f2")]), function("f2", "0.1", vec![])],
+                workflow_resources: vec![],
+                annotations: std::collections::HashMap::new(),
+            },
+        })
+        .await
+        .unwrap();
+    assert!(matches!(response, SpawnWorkflowResponse::WorkflowInstance(_)));
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    assert!(mock_orc_receiver.try_next().is_err());
+
+    // Update the version of f2: only f2 is restarted, then f1 is re-patched
+    // and finally the old instance of f2 is stopped.
+    let response = wf_client
+        .update(edgeless_api::workflow_instance::UpdateWorkflowRequest {
+            workflow_id: instance.workflow_id.clone(),
+            request: edgeless_api::workflow_instance::SpawnWorkflowRequest {
+                workflow_functions: vec![function("f1", "0.1", vec![("out", "f2")]), function("f2", "0.2", vec![])],
+                workflow_resources: vec![],
+                annotations: std::collections::HashMap::new(),
+            },
+        })
+        .await
+        .unwrap();
+    assert!(matches!(response, SpawnWorkflowResponse::WorkflowInstance(_)));
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let new_f2_id = if let MockFunctionInstanceEvent::StartFunction((id, spawn_req)) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!("0.2", spawn_req.code.function_class_version);
+        id
+    } else {
+        panic!();
+    };
+    if let MockFunctionInstanceEvent::Patch(patch_req) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(f1_id, patch_req.function_id);
+        assert_eq!(new_f2_id, patch_req.output_mapping.get("out").unwrap().function_id);
+    } else {
+        panic!();
+    }
+    if let MockFunctionInstanceEvent::Patch(patch_req) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(new_f2_id, patch_req.function_id);
+        assert!(patch_req.output_mapping.is_empty());
+    } else {
+        panic!();
+    }
+    if let MockFunctionInstanceEvent::StopFunction(id) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(f2_id, id);
+    } else {
+        panic!();
+    }
+    assert!(mock_orc_receiver.try_next().is_err());
+
+    // Update both f1 and f2, but the new f2 fails to start: the new f1 is
+    // stopped and the old instances are left untouched.
+    let response = wf_client
+        .update(edgeless_api::workflow_instance::UpdateWorkflowRequest {
+            workflow_id: instance.workflow_id.clone(),
+            request: edgeless_api::workflow_instance::SpawnWorkflowRequest {
+                workflow_functions: vec![function("f1", "0.2", vec![("out", "f2")]), function("f2", "fail", vec![])],
+                workflow_resources: vec![],
+                annotations: std::collections::HashMap::new(),
+            },
+        })
+        .await
+        .unwrap();
+    assert!(matches!(response, SpawnWorkflowResponse::ResponseError(_)));
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let new_f1_id = if let MockFunctionInstanceEvent::StartFunction((id, spawn_req)) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!("0.2", spawn_req.code.function_class_version);
+        id
+    } else {
+        panic!();
+    };
+    if let MockFunctionInstanceEvent::StopFunction(id) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(new_f1_id, id);
+    } else {
+        panic!();
+    }
+    assert!(mock_orc_receiver.try_next().is_err());
+
+    let info = wf_client.inspect(instance.workflow_id.clone()).await.unwrap();
+    assert_eq!(
+        "0.1",
+        info.request.workflow_functions[0].function_class_specification.function_class_version
+    );
+    for mapping in &info.status.domain_mapping {
+        match mapping.name.as_str() {
+            "f1" => assert_eq!(f1_id, mapping.function_id),
+            "f2" => assert_eq!(new_f2_id, mapping.function_id),
+            _ => panic!(),
+        }
+    }
+
+    // Remove f2: f1 is re-patched with no outputs, then f2 is stopped.
+    let response = wf_client
+        .update(edgeless_api::workflow_instance::UpdateWorkflowRequest {
+            workflow_id: instance.workflow_id.clone(),
+            request: edgeless_api::workflow_instance::SpawnWorkflowRequest {
+                workflow_functions: vec![function("f1", "0.1", vec![])],
+                workflow_resources: vec![],
+                annotations: std::collections::HashMap::new(),
+            },
+        })
+        .await
+        .unwrap();
+    match response {
+        SpawnWorkflowResponse::ResponseError(err) => panic!("{}", err),
+        SpawnWorkflowResponse::WorkflowInstance(val) => assert_eq!(1, val.domain_mapping.len()),
+    };
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    if let MockFunctionInstanceEvent::Patch(patch_req) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(f1_id, patch_req.function_id);
+        assert!(patch_req.output_mapping.is_empty());
+    } else {
+        panic!();
+    }
+    if let MockFunctionInstanceEvent::StopFunction(id) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(new_f2_id, id);
+    } else {
+        panic!();
+    }
+    assert!(mock_orc_receiver.try_next().is_err());
+}
+
+#[tokio::test]
+async fn workflow_inspect_component_status() {
+    let (mut wf_client, _mock_orc_receiver) = test_setup().await;
+
+    let response = wf_client
+        .start(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            workflow_functions: vec![edgeless_api::workflow_instance::WorkflowFunction {
+                name: "f1".to_string(),
+                function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+                    function_class_id: "fc1".to_string(),
+                    function_class_type: "RUST_WASM".to_string(),
+                    function_class_version: "0.1".to_string(),
+                    function_class_code: vec![],
+                    function_class_outputs: vec![],
+                },
+                output_mapping: std::collections::HashMap::new(),
+                annotations: std::collections::HashMap::new(),
+            }],
+            workflow_resources: vec![],
+            annotations: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    let instance = match &response {
+        SpawnWorkflowResponse::ResponseError(err) => panic!("{}", err),
+        SpawnWorkflowResponse::WorkflowInstance(val) => val,
+    };
+
+    let info = wf_client.inspect(instance.workflow_id.clone()).await.unwrap();
+    assert_eq!(1, info.component_status.len());
+    let status = info.component_status.get("f1").unwrap();
+    assert_eq!(edgeless_api::instance_status::ComponentState::Running, status.state);
+    assert_eq!(vec![uuid::Uuid::nil()], status.node_ids);
+
+    assert!(wf_client
+        .inspect(edgeless_api::workflow_instance::WorkflowId {
+            workflow_id: uuid::Uuid::new_v4()
+        })
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn workflow_logs() {
+    let (mut wf_client, _mock_orc_receiver) = test_setup().await;
+
+    let function = |name: &str| edgeless_api::workflow_instance::WorkflowFunction {
+        name: name.to_string(),
+        function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+            function_class_id: name.to_string(),
+            function_class_type: "RUST_WASM".to_string(),
+            function_class_version: "0.1".to_string(),
+            function_class_code: vec![],
+            function_class_outputs: vec![],
+        },
+        output_mapping: std::collections::HashMap::new(),
+        annotations: std::collections::HashMap::new(),
+    };
+    let response = wf_client
+        .start(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            workflow_functions: vec![function("f1"), function("f2")],
+            workflow_resources: vec![],
+            annotations: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    let workflow_id = match &response {
+        SpawnWorkflowResponse::ResponseError(err) => panic!("{}", err),
+        SpawnWorkflowResponse::WorkflowInstance(val) => val.workflow_id.clone(),
+    };
+    let messages =
+        |entries: &Vec<edgeless_api::instance_status::InstanceLogEntry>| entries.iter().map(|x| x.entry.message.clone()).collect::<Vec<String>>();
+
+    // All the functions, from the beginning.
+    let logs = wf_client
+        .logs(edgeless_api::workflow_instance::WorkflowLogsRequest {
+            workflow_id: workflow_id.clone(),
+            names: vec![],
+            since: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(2, logs.len());
+    assert_eq!(vec!["entry-1", "entry-2", "entry-3"], messages(&logs["f1"]));
+    assert_eq!(vec!["entry-1", "entry-2", "entry-3"], messages(&logs["f2"]));
+
+    // One function, from a given sequence number.
+    let position = |epoch: u64, seq: u64| edgeless_api::instance_status::LogPosition { epoch, seq };
+    let logs = wf_client
+        .logs(edgeless_api::workflow_instance::WorkflowLogsRequest {
+            workflow_id: workflow_id.clone(),
+            names: vec!["f2".to_string()],
+            since: std::collections::HashMap::from([
+                ("f1".to_string(), position(MOCK_LOG_EPOCH, 1)),
+                ("f2".to_string(), position(MOCK_LOG_EPOCH, 2)),
+            ]),
+        })
+        .await
+        .unwrap();
+    assert_eq!(1, logs.len());
+    assert_eq!(vec!["entry-3"], messages(&logs["f2"]));
+
+    // All the entries of a function are returned if the sequence number is
+    // of another epoch, e.g., before the orchestrator restarted.
+    let logs = wf_client
+        .logs(edgeless_api::workflow_instance::WorkflowLogsRequest {
+            workflow_id: workflow_id.clone(),
+            names: vec![],
+            since: std::collections::HashMap::from([
+                ("f1".to_string(), position(MOCK_LOG_EPOCH, 2)),
+                ("f2".to_string(), position(MOCK_LOG_EPOCH + 1, 2)),
+            ]),
+        })
+        .await
+        .unwrap();
+    assert_eq!(vec!["entry-3"], messages(&logs["f1"]));
+    assert_eq!(vec!["entry-1", "entry-2", "entry-3"], messages(&logs["f2"]));
+
+    // Unknown function or workflow.
+    assert!(wf_client
+        .logs(edgeless_api::workflow_instance::WorkflowLogsRequest {
+            workflow_id: workflow_id.clone(),
+            names: vec!["f3".to_string()],
+            since: std::collections::HashMap::new(),
+        })
+        .await
+        .is_err());
+    assert!(wf_client
+        .logs(edgeless_api::workflow_instance::WorkflowLogsRequest {
+            workflow_id: edgeless_api::workflow_instance::WorkflowId {
+                workflow_id: uuid::Uuid::new_v4()
+            },
+            names: vec![],
+            since: std::collections::HashMap::new(),
+        })
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn function_output_routes() {
+    let (mut wf_client, mut mock_orc_receiver) = test_setup().await;
+
+    let function = |name: &str, output_mapping: Vec<(&str, &str)>| edgeless_api::workflow_instance::WorkflowFunction {
+        name: name.to_string(),
+        function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+            function_class_id: name.to_string(),
+            function_class_type: "RUST_WASM".to_string(),
+            function_class_version: "0.1".to_string(),
+            function_class_code: vec![],
+            function_class_outputs: vec!["out".to_string()],
+        },
+        output_mapping: output_mapping.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        annotations: std::collections::HashMap::new(),
+    };
+
+    // Ill-formed routes are rejected.
+    let response = wf_client
+        .start(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            workflow_functions: vec![function("f1", vec![("out[0]?classification", "f2")]), function("f2", vec![])],
+            workflow_resources: vec![],
+            annotations: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    assert!(matches!(response, SpawnWorkflowResponse::ResponseError(_)));
+
+    // Routes are not supported by resources.
+    let response = wf_client
+        .start(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            workflow_functions: vec![function("f1", vec![])],
+            workflow_resources: vec![edgeless_api::workflow_instance::WorkflowResource {
+                name: "r1".to_string(),
+                class_type: "test-res".to_string(),
+                output_mapping: std::collections::HashMap::from([("test_out[0]".to_string(), "f1".to_string())]),
+                configurations: std::collections::HashMap::new(),
+            }],
+            annotations: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    assert!(matches!(response, SpawnWorkflowResponse::ResponseError(_)));
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    assert!(mock_orc_receiver.try_next().is_err());
+
+    // Fan-out with a conditional route: the keys are passed unmodified.
+    let alarm_route = "out[0]?classification == \"HighActivity\"";
+    let response = wf_client
+        .start(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            workflow_functions: vec![
+                function("f1", vec![(alarm_route, "f2"), ("out[1]", "f3")]),
+                function("f2", vec![]),
+                function("f3", vec![]),
+            ],
+            workflow_resources: vec![],
+            annotations: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    if let SpawnWorkflowResponse::ResponseError(err) = response {
+        panic!("{}", err);
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let mut fids = vec![];
+    for _i in 0..3 {
+        if let MockFunctionInstanceEvent::StartFunction((id, _spawn_req)) = mock_orc_receiver.try_next().unwrap().unwrap() {
+            fids.push(id);
+        } else {
+            panic!();
+        }
+    }
+    if let MockFunctionInstanceEvent::Patch(patch_req) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(fids[0], patch_req.function_id);
+        assert_eq!(2, patch_req.output_mapping.len());
+        assert_eq!(fids[1], patch_req.output_mapping.get(alarm_route).unwrap().function_id);
+        assert_eq!(fids[2], patch_req.output_mapping.get("out[1]").unwrap().function_id);
+    } else {
+        panic!();
+    }
+    assert!(mock_orc_receiver.try_next().is_err());
+}
+
+#[tokio::test]
+async fn recovery_reconcile() {
+    let function = |name: &str, output_mapping: Vec<(&str, &str)>| edgeless_api::workflow_instance::WorkflowFunction {
+        name: name.to_string(),
+        function_class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+            function_class_id: name.to_string(),
+            function_class_type: "RUST_WASM".to_string(),
+            function_class_version: "0.1".to_string(),
+            function_class_code: vec![],
+            function_class_outputs: vec!["out".to_string()],
+        },
+        output_mapping: output_mapping.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        annotations: std::collections::HashMap::new(),
+    };
+    let component = |name: &str, lid: uuid::Uuid| deployment_state::ActiveComponent {
+        component_type: ComponentType::Function,
+        name: name.to_string(),
+        domain_id: "domain-1".to_string(),
+        lid,
+    };
+
+    // f1 is still running in the domain, while f2 is not.
+    let wf_id = edgeless_api::workflow_instance::WorkflowId {
+        workflow_id: uuid::Uuid::new_v4(),
+    };
+    let (f1_id, f2_id) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+    let state = state_store::ControllerState {
+        workflows: vec![state_store::PersistedWorkflow::new(
+            &wf_id,
+            &edgeless_api::workflow_instance::SpawnWorkflowRequest {
+                workflow_functions: vec![function("f1", vec![("out", "f2")]), function("f2", vec![])],
+                workflow_resources: vec![],
+                annotations: std::collections::HashMap::new(),
+            },
+            vec![component("f1", f1_id), component("f2", f2_id)],
+        )],
+        domain_nonces: std::collections::HashMap::from([("domain-1".to_string(), 42)]),
+    };
+
+    let (mut wf_client, mut mock_orc_receiver) = test_setup_recovered(Some(state), vec![f1_id]).await;
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // f2 is started again and f1 is patched towards it.
+    let new_f2_id = if let MockFunctionInstanceEvent::StartFunction((id, spawn_req)) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!("f2", spawn_req.code.function_class_id);
+        id
+    } else {
+        panic!();
+    };
+    if let MockFunctionInstanceEvent::Patch(patch_req) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(f1_id, patch_req.function_id);
+        assert_eq!(new_f2_id, patch_req.output_mapping.get("out").unwrap().function_id);
+    } else {
+        panic!();
+    }
+    if let MockFunctionInstanceEvent::Patch(patch_req) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(new_f2_id, patch_req.function_id);
+        assert!(patch_req.output_mapping.is_empty());
+    } else {
+        panic!();
+    }
+    assert!(mock_orc_receiver.try_next().is_err());
+
+    let info = wf_client.inspect(wf_id).await.unwrap();
+    for mapping in &info.status.domain_mapping {
+        match mapping.name.as_str() {
+            "f1" => assert_eq!(f1_id, mapping.function_id),
+            "f2" => assert_eq!(new_f2_id, mapping.function_id),
+            _ => panic!(),
+        }
+    }
+    for status in info.component_status.values() {
+        assert_eq!(edgeless_api::instance_status::ComponentState::Running, status.state);
+    }
+}
@@ -9,13 +9,56 @@ mod controller;
 pub struct EdgelessConSettings {
     pub controller_url: String,
     pub domain_register_url: String,
+    /// Settings on whether/how to persist the controller state.
+    pub persistence: Option<EdgelessConPersistenceSettings>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct EdgelessConPersistenceSettings {
+    /// Type of the store where the state of the controller is saved
+    /// upon every change and from which it is recovered at start-up:
+    /// "None", "File", or "Redis".
+    pub store_type: String,
+    /// If store_type is "File" then this is the path of the file.
+    pub file_path: Option<String>,
+    /// If store_type is "Redis" then this is the URL of the Redis server.
+    pub redis_url: Option<String>,
+}
+
+fn make_state_store(settings: Option<EdgelessConPersistenceSettings>) -> anyhow::Result<Box<dyn controller::state_store::StateStore>> {
+    if let Some(settings) = settings {
+        match settings.store_type.to_lowercase().as_str() {
+            "none" => {}
+            "file" => {
+                return Ok(Box::new(controller::state_store_file::StateStoreFile::new(
+                    &settings.file_path.unwrap_or_default(),
+                )?))
+            }
+            "redis" => {
+                return Ok(Box::new(controller::state_store_redis::StateStoreRedis::new(
+                    &settings.redis_url.unwrap_or_default(),
+                )?))
+            }
+            _ => anyhow::bail!("unknown store type: {}", settings.store_type),
+        }
+    }
+    Ok(Box::new(controller::state_store_none::StateStoreNone {}))
 }
 
 pub async fn edgeless_con_main(settings: EdgelessConSettings) {
     log::info!("Starting Edgeless Controller at {}", settings.controller_url);
     log::debug!("Settings: {:?}", settings);
 
-    let (mut controller, controller_task, refresh_task) = controller::Controller::new();
+    // Do not start without the persistence requested, otherwise the state
+    // would be silently lost upon the next restart.
+    let state_store = match make_state_store(settings.persistence.clone()) {
+        Ok(state_store) => state_store,
+        Err(err) => {
+            log::error!("Could not create the controller state store, not starting: {}", err);
+            return;
+        }
+    };
+    let (mut controller, controller_task, refresh_task) = controller::Controller::new(state_store);
 
     let workflow_instance_server_task = edgeless_api::grpc_impl::outer::controller::WorkflowInstanceAPIServer::run(
         controller.get_workflow_instance_client(),
@@ -34,6 +77,11 @@ pub fn edgeless_con_default_conf() -> String {
     String::from(
         r##"controller_url = "http://127.0.0.1:7001"
 domain_register_url = "http://127.0.0.1:7004"
+
+[persistence]
+store_type = "None"
+file_path = "controller_state.json"
+redis_url = "redis://127.0.0.1:6379"
 "##,
     )
 }
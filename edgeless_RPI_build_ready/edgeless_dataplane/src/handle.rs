@@ -15,8 +15,10 @@ pub struct DataplaneHandle {
     slf: edgeless_api::function_instance::InstanceId,
     receiver: std::sync::Arc<tokio::sync::Mutex<futures::channel::mpsc::UnboundedReceiver<DataplaneEvent>>>,
     output_chain: std::sync::Arc<tokio::sync::Mutex<Vec<Box<dyn DataPlaneLink>>>>,
-    receiver_overwrites: std::sync::Arc<tokio::sync::Mutex<TemporaryReceivers>>,
-    next_id: u64,
+    receiver_overwrites: std::sync::Arc<std::sync::Mutex<TemporaryReceivers>>,
+    // Shared by the clones of the handle, so that they can be used for
+    // concurrent calls.
+    next_id: std::sync::Arc<std::sync::atomic::AtomicU64>,
 }
 
 impl DataplaneHandle {
@@ -26,7 +28,7 @@ impl DataplaneHandle {
         receiver: futures::channel::mpsc::UnboundedReceiver<DataplaneEvent>,
     ) -> Self {
         let (main_sender, main_receiver) = futures::channel::mpsc::unbounded::<DataplaneEvent>();
-        let receiver_overwrites = std::sync::Arc::new(tokio::sync::Mutex::new(TemporaryReceivers {
+        let receiver_overwrites = std::sync::Arc::new(std::sync::Mutex::new(TemporaryReceivers {
             temporary_receivers: std::collections::HashMap::new(),
         }));
 
@@ -42,7 +44,8 @@ impl DataplaneHandle {
                     message,
                 }) = receiver.next().await
                 {
-                    if let Some(sender) = clone_overwrites.lock().await.temporary_receivers.remove(&channel_id) {
+                    let sender = clone_overwrites.lock().unwrap().temporary_receivers.remove(&channel_id);
+                    if let Some(sender) = sender {
                         match sender.send((source_id, message.clone())) {
                             Ok(_) => {
                                 continue;
@@ -74,7 +77,7 @@ impl DataplaneHandle {
             receiver: std::sync::Arc::new(tokio::sync::Mutex::new(main_receiver)),
             output_chain: std::sync::Arc::new(tokio::sync::Mutex::new(output_chain)),
             receiver_overwrites,
-            next_id: 1,
+            next_id: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(1)),
         }
     }
 
@@ -109,12 +112,15 @@ impl DataplaneHandle {
 
     // Send a `call` event and wait for the return event.
     // Internally, this sets up a receiver override to handle the message before it would be sent to the `receive_next` function.
+    // The override is removed if the call is cancelled, e.g., by a timeout.
     pub async fn call(&mut self, target: edgeless_api::function_instance::InstanceId, msg: String) -> CallRet {
         let (sender, receiver) = futures::channel::oneshot::channel::<(edgeless_api::function_instance::InstanceId, Message)>();
-        let channel_id = self.next_id;
-        self.next_id += 1;
-        // Potential Leak: This is only received if a message is received (or the handle is dropped)
-        self.receiver_overwrites.lock().await.temporary_receivers.insert(channel_id, sender);
+        let channel_id = self.next_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.receiver_overwrites.lock().unwrap().temporary_receivers.insert(channel_id, sender);
+        let _guard = TemporaryReceiverGuard {
+            receivers: self.receiver_overwrites.clone(),
+            channel_id,
+        };
         self.send_inner(target, Message::Call(msg), channel_id).await;
         match receiver.await {
             Ok((_src, msg)) => match msg {
@@ -155,6 +161,19 @@ struct TemporaryReceivers {
     temporary_receivers: std::collections::HashMap<u64, futures::channel::oneshot::Sender<(edgeless_api::function_instance::InstanceId, Message)>>,
 }
 
+/// Removes the receiver override of a call when the call is completed or
+/// cancelled.
+struct TemporaryReceiverGuard {
+    receivers: std::sync::Arc<std::sync::Mutex<TemporaryReceivers>>,
+    channel_id: u64,
+}
+
+impl Drop for TemporaryReceiverGuard {
+    fn drop(&mut self) {
+        self.receivers.lock().unwrap().temporary_receivers.remove(&self.channel_id);
+    }
+}
+
 #[derive(Clone)]
 pub struct DataplaneProvider {
     local_provider: std::sync::Arc<tokio::sync::Mutex<NodeLocalLinkProvider>>,
@@ -272,6 +291,49 @@ mod test {
         assert_eq!(std::mem::discriminant(&CallRet::NoReply), std::mem::discriminant(&repl));
     }
 
+    #[tokio::test]
+    async fn local_concurrent_calls() {
+        let node_id = uuid::Uuid::new_v4();
+        let fid_1 = edgeless_api::function_instance::InstanceId::new(node_id);
+        let fid_2 = edgeless_api::function_instance::InstanceId::new(node_id);
+
+        let mut provider = DataplaneProvider::new(node_id, "http://127.0.0.1:7095".to_string(), None).await;
+
+        let handle_1 = provider.get_handle_for(fid_1).await;
+        let mut handle_2 = provider.get_handle_for(fid_2).await;
+
+        // Calls from clones of the same handle are in flight at the same time.
+        let mut return_handles = vec![];
+        for i in 0..2 {
+            let mut cloned_handle_1 = handle_1.clone();
+            return_handles.push(tokio::spawn(async move { cloned_handle_1.call(fid_2, i.to_string()).await }));
+        }
+        let mut requests = vec![];
+        for _ in 0..2 {
+            requests.push(handle_2.receive_next().await);
+        }
+        assert_ne!(requests[0].channel_id, requests[1].channel_id);
+        for req in requests.into_iter().rev() {
+            let msg = match req.message {
+                crate::core::Message::Call(msg) => msg,
+                _ => panic!("wrong message type"),
+            };
+            handle_2.reply(req.source_id, req.channel_id, CallRet::Reply(msg)).await;
+        }
+        for (i, return_handle) in return_handles.into_iter().enumerate() {
+            assert_eq!(CallRet::Reply(i.to_string()), return_handle.await.unwrap());
+        }
+
+        // The receiver override of a call cancelled is removed.
+        let mut cloned_handle_1 = handle_1.clone();
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(100), cloned_handle_1.call(fid_2, "Test".to_string()))
+                .await
+                .is_err()
+        );
+        assert!(handle_1.receiver_overwrites.lock().unwrap().temporary_receivers.is_empty());
+    }
+
     #[tokio::test]
     async fn grpc_impl_e2e() {
         let node_id = uuid::Uuid::new_v4();
@@ -0,0 +1,289 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Codecs to encode/decode the messages exchanged by the functions.
+//!
+//! The following codecs are available:
+//!
+//! - `Json`: JSON, human-readable, interoperable with the resources, always
+//!   available;
+//! - `Cbor`: CBOR, binary, self-describing, enabled by the `cbor` feature;
+//! - `Postcard`: postcard, binary, compact, not self-describing, enabled by
+//!   the `postcard` feature.
+//!
+//! Since the messages are carried as UTF-8 strings by the EDGELESS data
+//! plane, the output of the binary codecs is Base64-encoded.
+//!
+//! `DefaultCodec` uses JSON, unless another format is selected at run-time
+//! with `set_default`, e.g., based on the configuration of the function.
+
+#[derive(Debug)]
+pub enum CodecError {
+    Encode(String),
+    Decode(String),
+}
+
+impl std::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CodecError::Encode(err) => write!(f, "encode error: {}", err),
+            CodecError::Decode(err) => write!(f, "decode error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+pub trait Codec {
+    /// Name of the codec, used in the log entries.
+    const NAME: &'static str;
+
+    /// Name of the codec actually used, which may be selected at run-time.
+    fn name() -> &'static str {
+        Self::NAME
+    }
+
+    fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, CodecError>;
+
+    fn decode<T: serde::de::DeserializeOwned>(msg: &[u8]) -> Result<T, CodecError>;
+}
+
+pub struct Json;
+
+impl Codec for Json {
+    const NAME: &'static str = "json";
+
+    fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, CodecError> {
+        serde_json::to_vec(value).map_err(|err| CodecError::Encode(err.to_string()))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(msg: &[u8]) -> Result<T, CodecError> {
+        serde_json::from_slice(msg).map_err(|err| CodecError::Decode(err.to_string()))
+    }
+}
+
+#[cfg(feature = "cbor")]
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl Codec for Cbor {
+    const NAME: &'static str = "cbor";
+
+    fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, CodecError> {
+        let mut buf = vec![];
+        ciborium::into_writer(value, &mut buf).map_err(|err| CodecError::Encode(err.to_string()))?;
+        Ok(base64_encode(&buf))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(msg: &[u8]) -> Result<T, CodecError> {
+        let buf = base64_decode(msg)?;
+        ciborium::from_reader(buf.as_slice()).map_err(|err| CodecError::Decode(err.to_string()))
+    }
+}
+
+#[cfg(feature = "postcard")]
+pub struct Postcard;
+
+#[cfg(feature = "postcard")]
+impl Codec for Postcard {
+    const NAME: &'static str = "postcard";
+
+    fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, CodecError> {
+        let buf = postcard::to_stdvec(value).map_err(|err| CodecError::Encode(err.to_string()))?;
+        Ok(base64_encode(&buf))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(msg: &[u8]) -> Result<T, CodecError> {
+        let buf = base64_decode(msg)?;
+        postcard::from_bytes(&buf).map_err(|err| CodecError::Decode(err.to_string()))
+    }
+}
+
+/// Formats that can be selected at run-time for `DefaultCodec`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    Json,
+    #[cfg(feature = "cbor")]
+    Cbor,
+    #[cfg(feature = "postcard")]
+    Postcard,
+}
+
+impl Format {
+    fn name(&self) -> &'static str {
+        match self {
+            Format::Json => Json::NAME,
+            #[cfg(feature = "cbor")]
+            Format::Cbor => Cbor::NAME,
+            #[cfg(feature = "postcard")]
+            Format::Postcard => Postcard::NAME,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            #[cfg(feature = "cbor")]
+            1 => Format::Cbor,
+            #[cfg(feature = "postcard")]
+            2 => Format::Postcard,
+            _ => Format::Json,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Format::Json => 0,
+            #[cfg(feature = "cbor")]
+            Format::Cbor => 1,
+            #[cfg(feature = "postcard")]
+            Format::Postcard => 2,
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = CodecError;
+
+    /// Parse the name of a codec, which must be enabled.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            #[cfg(feature = "cbor")]
+            "cbor" => Ok(Format::Cbor),
+            #[cfg(feature = "postcard")]
+            "postcard" => Ok(Format::Postcard),
+            _ => Err(CodecError::Decode(format!("unknown or disabled codec: {}", s))),
+        }
+    }
+}
+
+static DEFAULT_FORMAT: core::sync::atomic::AtomicU8 = core::sync::atomic::AtomicU8::new(0);
+
+/// Select the format of `DefaultCodec`, which is JSON if never called.
+pub fn set_default(format: Format) {
+    DEFAULT_FORMAT.store(format.to_u8(), core::sync::atomic::Ordering::Relaxed);
+}
+
+/// Return the format of `DefaultCodec`.
+pub fn default_format() -> Format {
+    Format::from_u8(DEFAULT_FORMAT.load(core::sync::atomic::Ordering::Relaxed))
+}
+
+/// Codec used by the typed API when not specified, see `set_default`.
+pub struct DefaultCodec;
+
+impl Codec for DefaultCodec {
+    const NAME: &'static str = "default";
+
+    fn name() -> &'static str {
+        default_format().name()
+    }
+
+    fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, CodecError> {
+        match default_format() {
+            Format::Json => Json::encode(value),
+            #[cfg(feature = "cbor")]
+            Format::Cbor => Cbor::encode(value),
+            #[cfg(feature = "postcard")]
+            Format::Postcard => Postcard::encode(value),
+        }
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(msg: &[u8]) -> Result<T, CodecError> {
+        match default_format() {
+            Format::Json => Json::decode(msg),
+            #[cfg(feature = "cbor")]
+            Format::Cbor => Cbor::decode(msg),
+            #[cfg(feature = "postcard")]
+            Format::Postcard => Postcard::decode(msg),
+        }
+    }
+}
+
+#[cfg(any(feature = "cbor", feature = "postcard"))]
+fn base64_encode(buf: &[u8]) -> Vec<u8> {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(buf).into_bytes()
+}
+
+#[cfg(any(feature = "cbor", feature = "postcard"))]
+fn base64_decode(msg: &[u8]) -> Result<Vec<u8>, CodecError> {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD
+        .decode(msg)
+        .map_err(|err| CodecError::Decode(err.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Sample {
+        batch_id: u64,
+        values: Vec<f64>,
+        label: Option<String>,
+    }
+
+    fn check_codec<C: Codec>() {
+        let sample = Sample {
+            batch_id: 42,
+            values: vec![1.0, -2.5, 3.25],
+            label: Some("HighActivity".to_string()),
+        };
+        let msg = C::encode(&sample).unwrap();
+        assert!(std::str::from_utf8(&msg).is_ok());
+        assert_eq!(sample, C::decode::<Sample>(&msg).unwrap());
+
+        assert!(C::decode::<Sample>(b"not a valid message").is_err());
+        assert!(C::decode::<Sample>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_codec_json() {
+        check_codec::<Json>();
+        assert_eq!(
+            r#"{"batch_id":1,"values":[],"label":null}"#,
+            std::str::from_utf8(
+                &Json::encode(&Sample {
+                    batch_id: 1,
+                    values: vec![],
+                    label: None
+                })
+                .unwrap()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_codec_cbor() {
+        check_codec::<Cbor>();
+    }
+
+    #[test]
+    fn test_codec_postcard() {
+        check_codec::<Postcard>();
+    }
+
+    #[test]
+    fn test_default_codec() {
+        let sample = Sample {
+            batch_id: 7,
+            values: vec![0.5],
+            label: None,
+        };
+        assert_eq!(Format::Json, default_format());
+        assert_eq!("json", DefaultCodec::name());
+        assert_eq!(Json::encode(&sample).unwrap(), DefaultCodec::encode(&sample).unwrap());
+
+        set_default("cbor".parse().unwrap());
+        assert_eq!("cbor", DefaultCodec::name());
+        let msg = DefaultCodec::encode(&sample).unwrap();
+        assert_eq!(sample, Cbor::decode::<Sample>(&msg).unwrap());
+        set_default(Format::Json);
+
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -14,6 +14,7 @@ macro_rules! export {
             };
 
             $fun::handle_cast(instance_id, payload);
+            $crate::__after_handler(false);
         }
 
         #[no_mangle]
@@ -33,6 +34,7 @@ macro_rules! export {
             };
 
             let ret = $fun::handle_call(instance_id, payload);
+            $crate::__after_handler(false);
 
             let (ret, output_params) = match ret {
                 CallRet::NoReply => (0, None),
@@ -66,11 +68,13 @@ macro_rules! export {
             };
 
             $fun::handle_init(payload, serialized_state);
+            $crate::__after_handler(false);
         }
 
         #[no_mangle]
         pub extern "C" fn handle_stop_asm() {
-            $fun::handle_stop()
+            $fun::handle_stop();
+            $crate::__after_handler(true);
         }
     };
 }
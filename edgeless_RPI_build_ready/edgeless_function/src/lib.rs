@@ -33,6 +33,54 @@ pub mod memory;
 pub mod output_api;
 pub use output_api::*;
 
+/// Provides the codecs to encode/decode typed messages (JSON, CBOR, postcard).
+#[cfg(feature = "serde")]
+pub mod codec;
+
+/// Provides the typed counterparts of the output API and of `EdgeFunction`.
+#[cfg(feature = "serde")]
+pub mod typed;
+#[cfg(feature = "serde")]
+pub use typed::*;
+
+/// Provides the function state managed by the SDK, which is synced automatically.
+#[cfg(feature = "serde")]
+pub mod state;
+#[cfg(feature = "serde")]
+pub use state::{ManagedState, State, SyncPolicy};
+
+/// Hooks called after every handler of the function, see `__after_handler`.
+#[cfg(feature = "std")]
+#[allow(clippy::type_complexity)]
+static AFTER_HANDLER_HOOKS: std::sync::Mutex<Vec<Box<dyn Fn(bool) + Send>>> = std::sync::Mutex::new(Vec::new());
+
+/// Register a hook to be called after every handler of the function, with
+/// `true` only after `handle_stop`.
+#[cfg(feature = "std")]
+pub fn register_after_handler(hook: Box<dyn Fn(bool) + Send>) {
+    AFTER_HANDLER_HOOKS.lock().unwrap().push(hook);
+}
+
+/// Called by the code generated by `export` after every handler.
+///
+/// The hooks are called without holding the lock, so that they can register
+/// other hooks, which are called from the next handler on.
+#[doc(hidden)]
+pub fn __after_handler(last: bool) {
+    #[cfg(feature = "std")]
+    {
+        let hooks = std::mem::take(&mut *AFTER_HANDLER_HOOKS.lock().unwrap());
+        for hook in &hooks {
+            hook(last);
+        }
+        let mut lck = AFTER_HANDLER_HOOKS.lock().unwrap();
+        let registered = std::mem::replace(&mut *lck, hooks);
+        lck.extend(registered);
+    }
+    #[cfg(not(feature = "std"))]
+    let _ = last;
+}
+
 pub enum CallRet {
     NoReply,
     Reply(owned_data::OwnedByteBuff),
@@ -164,4 +212,22 @@ mod test {
         assert_eq!(vec![1, 3, 5], arg_to_vec::<usize>("vec5", ":", &arguments));
         assert!(arg_to_vec::<usize>("non-existing", "@", &arguments).is_empty());
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_after_handler_hook_registering_hook() {
+        static CALLS: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+        register_after_handler(Box::new(|_last| {
+            if CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                register_after_handler(Box::new(|_last| {
+                    CALLS.fetch_add(10, std::sync::atomic::Ordering::SeqCst);
+                }));
+            }
+        }));
+
+        __after_handler(false);
+        assert_eq!(1, CALLS.load(std::sync::atomic::Ordering::SeqCst));
+        __after_handler(true);
+        assert_eq!(12, CALLS.load(std::sync::atomic::Ordering::SeqCst));
+    }
 }
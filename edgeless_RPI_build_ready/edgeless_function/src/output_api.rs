@@ -90,3 +90,8 @@ pub fn sync(state: &[u8]) {
         crate::imports::sync_asm(state.as_ptr(), state.len() as u32);
     }
 }
+
+/// Wall-clock time provided by the host, in ms since the UNIX epoch.
+pub fn time_ms() -> u64 {
+    unsafe { crate::imports::time_ms_asm() as u64 }
+}
@@ -0,0 +1,239 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Function state managed by the SDK.
+//!
+//! A `ManagedState` is meant to be declared as a `static` variable, it is
+//! initialized in `handle_init` from the serialized state provided by the
+//! node (if any), and it is synced automatically with `sync` after the
+//! handlers that updated it, according to its `SyncPolicy`.
+//!
+//! Whether the state is actually persisted, and where, depends on the
+//! state policy of the function in the workflow (`NodeLocal` or `Global`).
+
+use crate::codec::Codec;
+
+/// When the state is synced automatically after a handler.
+///
+/// The state is synced only if it has been updated since the last sync,
+/// and it is always synced after `handle_stop`, unless the policy is
+/// `Manual`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SyncPolicy {
+    // Never synced automatically: the function calls `ManagedState::sync`.
+    Manual,
+    // Synced every given number of handler invocations.
+    EveryInvocations(u32),
+    // Synced after a handler if at least the given time, in ms, has
+    // elapsed since the last sync.
+    EveryMillis(u64),
+}
+
+/// Book-keeping to decide when to sync the state.
+#[derive(Debug, Default)]
+struct SyncTracker {
+    // True if the state has been updated since the last sync.
+    dirty: bool,
+    // Number of handlers invoked since the last sync.
+    invocations: u32,
+    // Time of the last sync, in ms since the UNIX epoch.
+    last_sync_ms: u64,
+}
+
+impl SyncTracker {
+    /// Account for the end of a handler and return true if the state must
+    /// be synced now. The current time is queried only if needed.
+    fn after_handler(&mut self, policy: SyncPolicy, last: bool, now_ms: impl FnOnce() -> u64) -> bool {
+        self.invocations = self.invocations.saturating_add(1);
+        if !self.dirty {
+            return false;
+        }
+        match policy {
+            SyncPolicy::Manual => false,
+            _ if last => true,
+            SyncPolicy::EveryInvocations(invocations) => self.invocations >= invocations,
+            SyncPolicy::EveryMillis(period) => now_ms().saturating_sub(self.last_sync_ms) >= period,
+        }
+    }
+
+    fn synced(&mut self, now_ms: u64) {
+        self.dirty = false;
+        self.invocations = 0;
+        self.last_sync_ms = now_ms;
+    }
+}
+
+struct StateInner<T> {
+    value: T,
+    tracker: SyncTracker,
+}
+
+/// State of a function, encoded with the codec `C` when synced.
+pub struct ManagedState<T, C: Codec> {
+    policy: SyncPolicy,
+    inner: std::sync::Mutex<Option<StateInner<T>>>,
+    registered: core::sync::atomic::AtomicBool,
+    _codec: core::marker::PhantomData<fn() -> C>,
+}
+
+/// State of a function encoded with `crate::codec::DefaultCodec`.
+pub type State<T> = ManagedState<T, crate::codec::DefaultCodec>;
+
+impl<T, C: Codec> ManagedState<T, C>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Send + 'static,
+{
+    pub const fn new(policy: SyncPolicy) -> Self {
+        Self {
+            policy,
+            inner: std::sync::Mutex::new(None),
+            registered: core::sync::atomic::AtomicBool::new(false),
+            _codec: core::marker::PhantomData,
+        }
+    }
+
+    /// Initialize the state from the serialized state received by
+    /// `handle_init` or, if there is none or it cannot be decoded, with
+    /// the given default value.
+    pub fn init(&'static self, serialized_state: Option<&[u8]>, default: impl FnOnce() -> T) {
+        let value = match serialized_state.and_then(crate::typed::decode_with::<C, T>) {
+            Some(value) => value,
+            None => default(),
+        };
+        *self.inner.lock().unwrap() = Some(StateInner {
+            value,
+            tracker: SyncTracker::default(),
+        });
+
+        if !self.registered.swap(true, core::sync::atomic::Ordering::SeqCst) {
+            crate::register_after_handler(Box::new(move |last| self.after_handler(last)));
+        }
+    }
+
+    /// True if `init` has been called.
+    pub fn is_initialized(&self) -> bool {
+        self.inner.lock().unwrap().is_some()
+    }
+
+    /// Read the state.
+    ///
+    /// Panics if the state has not been initialized.
+    pub fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        let inner = self.inner.lock().unwrap();
+        f(&inner.as_ref().expect("function state not initialized").value)
+    }
+
+    /// Update the state, which is then synced according to the policy.
+    ///
+    /// Panics if the state has not been initialized.
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut inner = self.inner.lock().unwrap();
+        let inner = inner.as_mut().expect("function state not initialized");
+        inner.tracker.dirty = true;
+        f(&mut inner.value)
+    }
+
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.read(|value| value.clone())
+    }
+
+    pub fn set(&self, value: T) {
+        self.update(|state| *state = value)
+    }
+
+    /// Sync the state now, regardless of the policy.
+    pub fn sync(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(inner) = inner.as_mut() {
+            Self::sync_inner(self.policy, inner);
+        }
+    }
+
+    fn after_handler(&self, last: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(inner) = inner.as_mut() {
+            if inner.tracker.after_handler(self.policy, last, crate::time_ms) {
+                Self::sync_inner(self.policy, inner);
+            }
+        }
+    }
+
+    fn sync_inner(policy: SyncPolicy, inner: &mut StateInner<T>) {
+        if let Some(serialized_state) = crate::typed::encode_with::<C, T>(&inner.value) {
+            crate::sync(&serialized_state);
+            // The time is only needed by the EveryMillis policy.
+            let now_ms = match policy {
+                SyncPolicy::EveryMillis(_) => crate::time_ms(),
+                _ => 0,
+            };
+            inner.tracker.synced(now_ms);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn no_time() -> u64 {
+        panic!("time queried")
+    }
+
+    #[test]
+    fn test_sync_tracker_manual() {
+        let mut tracker = SyncTracker {
+            dirty: true,
+            ..Default::default()
+        };
+        assert!(!tracker.after_handler(SyncPolicy::Manual, false, no_time));
+        assert!(!tracker.after_handler(SyncPolicy::Manual, true, no_time));
+    }
+
+    #[test]
+    fn test_sync_tracker_every_invocations() {
+        let policy = SyncPolicy::EveryInvocations(3);
+        let mut tracker = SyncTracker::default();
+
+        // Not updated: never synced, not even at stop.
+        for _ in 0..5 {
+            assert!(!tracker.after_handler(policy, false, no_time));
+        }
+        assert!(!tracker.after_handler(policy, true, no_time));
+
+        tracker.synced(0);
+        tracker.dirty = true;
+        assert!(!tracker.after_handler(policy, false, no_time));
+        assert!(!tracker.after_handler(policy, false, no_time));
+        assert!(tracker.after_handler(policy, false, no_time));
+
+        tracker.synced(0);
+        tracker.dirty = true;
+        assert!(tracker.after_handler(policy, true, no_time));
+
+        let policy = SyncPolicy::EveryInvocations(1);
+        tracker.synced(0);
+        tracker.dirty = true;
+        assert!(tracker.after_handler(policy, false, no_time));
+    }
+
+    #[test]
+    fn test_sync_tracker_every_millis() {
+        let policy = SyncPolicy::EveryMillis(100);
+        let mut tracker = SyncTracker::default();
+        tracker.synced(1000);
+
+        assert!(!tracker.after_handler(policy, false, no_time));
+
+        tracker.dirty = true;
+        assert!(!tracker.after_handler(policy, false, || 1050));
+        assert!(tracker.after_handler(policy, false, || 1100));
+
+        tracker.synced(1100);
+        tracker.dirty = true;
+        assert!(!tracker.after_handler(policy, false, || 1100));
+        assert!(tracker.after_handler(policy, true, no_time));
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Typed counterparts of the output API and of `EdgeFunction`, which
+//! encode/decode the messages with one of the codecs in `crate::codec`.
+//!
+//! The functions without the `_with` suffix use `crate::codec::DefaultCodec`,
+//! i.e., JSON unless selected otherwise with `crate::codec::set_default`.
+//!
+//! Messages that cannot be decoded (or encoded) are dropped, counted, and
+//! reported through `telemetry_log` with the error level, so that they are
+//! included in the function errors collected by the node.
+
+use crate::codec::Codec;
+
+/// Return value of typed calls / value to be returned from
+/// `TypedEdgeFunction::handle_call`.
+pub enum TypedCallRet<T> {
+    NoReply,
+    Reply(T),
+    Err,
+}
+
+pub trait TypedEdgeFunction {
+    /// Codec of the messages received and of the replies.
+    type Codec: Codec;
+    /// Type of the messages received with casts.
+    type Cast: serde::de::DeserializeOwned;
+    /// Type of the messages received with calls.
+    type Call: serde::de::DeserializeOwned;
+    /// Type of the replies to calls.
+    type Reply: serde::Serialize;
+
+    fn handle_cast(src: crate::InstanceId, msg: Self::Cast);
+
+    fn handle_call(_src: crate::InstanceId, _msg: Self::Call) -> TypedCallRet<Self::Reply> {
+        TypedCallRet::NoReply
+    }
+
+    fn handle_init(payload: Option<&[u8]>, serialized_state: Option<&[u8]>);
+
+    fn handle_stop() {}
+}
+
+/// Adapter of a `TypedEdgeFunction` to `EdgeFunction`, see `export_typed`.
+pub struct Typed<F: TypedEdgeFunction>(core::marker::PhantomData<F>);
+
+impl<F: TypedEdgeFunction> crate::EdgeFunction for Typed<F> {
+    fn handle_cast(src: crate::InstanceId, encoded_message: &[u8]) {
+        if let Some(msg) = decode_with::<F::Codec, F::Cast>(encoded_message) {
+            F::handle_cast(src, msg);
+        }
+    }
+
+    fn handle_call(src: crate::InstanceId, encoded_message: &[u8]) -> crate::CallRet {
+        let msg = match decode_with::<F::Codec, F::Call>(encoded_message) {
+            Some(msg) => msg,
+            None => return crate::CallRet::Err,
+        };
+        match F::handle_call(src, msg) {
+            TypedCallRet::NoReply => crate::CallRet::NoReply,
+            TypedCallRet::Reply(reply) => match encode_with::<F::Codec, F::Reply>(&reply) {
+                Some(reply) => crate::CallRet::Reply(crate::OwnedByteBuff::new_from_slice(&reply)),
+                None => crate::CallRet::Err,
+            },
+            TypedCallRet::Err => crate::CallRet::Err,
+        }
+    }
+
+    fn handle_init(payload: Option<&[u8]>, serialized_state: Option<&[u8]>) {
+        F::handle_init(payload, serialized_state)
+    }
+
+    fn handle_stop() {
+        F::handle_stop()
+    }
+}
+
+/// Export a `TypedEdgeFunction`, like `export` does for an `EdgeFunction`.
+#[macro_export]
+macro_rules! export_typed {
+    ( $fun:ident ) => {
+        type EdgelessTypedFunction = $crate::typed::Typed<$fun>;
+        $crate::export!(EdgelessTypedFunction);
+    };
+}
+
+static CODEC_ERRORS: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
+
+/// Number of messages that could not be decoded or encoded so far.
+pub fn codec_errors() -> u64 {
+    CODEC_ERRORS.load(core::sync::atomic::Ordering::Relaxed)
+}
+
+fn report_codec_error<C: Codec, T>(err: crate::codec::CodecError) {
+    let count = CODEC_ERRORS.fetch_add(1, core::sync::atomic::Ordering::Relaxed) + 1;
+    crate::telemetry_log(
+        crate::logging::rust_to_api(log::Level::Error) as usize,
+        "edgeless_function::typed",
+        &format!(
+            "{} message of type {} dropped ({} so far): {}",
+            C::name(),
+            core::any::type_name::<T>(),
+            count,
+            err
+        ),
+    );
+}
+
+/// Decode a message, reporting the error if it fails.
+pub fn decode_with<C: Codec, T: serde::de::DeserializeOwned>(msg: &[u8]) -> Option<T> {
+    match C::decode(msg) {
+        Ok(val) => Some(val),
+        Err(err) => {
+            report_codec_error::<C, T>(err);
+            None
+        }
+    }
+}
+
+/// Encode a message, reporting the error if it fails.
+pub fn encode_with<C: Codec, T: serde::Serialize>(msg: &T) -> Option<Vec<u8>> {
+    match C::encode(msg) {
+        Ok(val) => Some(val),
+        Err(err) => {
+            report_codec_error::<C, T>(err);
+            None
+        }
+    }
+}
+
+pub fn cast_typed_with<C: Codec, T: serde::Serialize>(name: &str, msg: &T) {
+    if let Some(msg) = encode_with::<C, T>(msg) {
+        crate::cast(name, &msg);
+    }
+}
+
+pub fn cast_raw_typed_with<C: Codec, T: serde::Serialize>(target: crate::InstanceId, msg: &T) {
+    if let Some(msg) = encode_with::<C, T>(msg) {
+        crate::cast_raw(target, &msg);
+    }
+}
+
+pub fn delayed_cast_typed_with<C: Codec, T: serde::Serialize>(delay_ms: u64, name: &str, msg: &T) {
+    if let Some(msg) = encode_with::<C, T>(msg) {
+        crate::delayed_cast(delay_ms, name, &msg);
+    }
+}
+
+pub fn call_typed_with<C: Codec, T: serde::Serialize, R: serde::de::DeserializeOwned>(name: &str, msg: &T) -> TypedCallRet<R> {
+    let msg = match encode_with::<C, T>(msg) {
+        Some(msg) => msg,
+        None => return TypedCallRet::Err,
+    };
+    match crate::call(name, &msg) {
+        crate::CallRet::NoReply => TypedCallRet::NoReply,
+        crate::CallRet::Reply(reply) => match decode_with::<C, R>(&reply) {
+            Some(reply) => TypedCallRet::Reply(reply),
+            None => TypedCallRet::Err,
+        },
+        crate::CallRet::Err => TypedCallRet::Err,
+    }
+}
+
+pub fn decode<T: serde::de::DeserializeOwned>(msg: &[u8]) -> Option<T> {
+    decode_with::<crate::codec::DefaultCodec, T>(msg)
+}
+
+pub fn encode<T: serde::Serialize>(msg: &T) -> Option<Vec<u8>> {
+    encode_with::<crate::codec::DefaultCodec, T>(msg)
+}
+
+pub fn cast_typed<T: serde::Serialize>(name: &str, msg: &T) {
+    cast_typed_with::<crate::codec::DefaultCodec, T>(name, msg)
+}
+
+pub fn cast_raw_typed<T: serde::Serialize>(target: crate::InstanceId, msg: &T) {
+    cast_raw_typed_with::<crate::codec::DefaultCodec, T>(target, msg)
+}
+
+pub fn delayed_cast_typed<T: serde::Serialize>(delay_ms: u64, name: &str, msg: &T) {
+    delayed_cast_typed_with::<crate::codec::DefaultCodec, T>(delay_ms, name, msg)
+}
+
+pub fn call_typed<T: serde::Serialize, R: serde::de::DeserializeOwned>(name: &str, msg: &T) -> TypedCallRet<R> {
+    call_typed_with::<crate::codec::DefaultCodec, T, R>(name, msg)
+}
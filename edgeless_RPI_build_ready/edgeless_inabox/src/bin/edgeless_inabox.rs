@@ -87,6 +87,7 @@ fn generate_configs(config_path: String, number_of_nodes: u32, metrics_collector
             orchestrator_url_announced: "".to_string(),
             node_register_url: next_url(),
             node_register_coap_url: None,
+            inter_domain_latency: None,
         },
         baseline: edgeless_orc::EdgelessOrcBaselineSettings {
             orchestration_strategy: edgeless_orc::OrchestrationStrategy::Random,
@@ -109,6 +110,7 @@ fn generate_configs(config_path: String, number_of_nodes: u32, metrics_collector
     let con_conf = edgeless_con::EdgelessConSettings {
         controller_url,
         domain_register_url,
+        persistence: None,
     };
 
     // Nodes
@@ -132,17 +134,24 @@ fn generate_configs(config_path: String, number_of_nodes: u32, metrics_collector
                 log_level: None,
                 performance_samples: false,
             },
-            wasm_runtime: Some(edgeless_node::EdgelessNodeWasmRuntimeSettings { enabled: true }),
+            wasm_runtime: Some(edgeless_node::EdgelessNodeWasmRuntimeSettings {
+                enabled: true,
+                module_cache_dir: None,
+                module_cache_capacity: None,
+            }),
             container_runtime: None,
             resources: Some(EdgelessNodeResourceSettings {
                 http_ingress_url: match counter == 0 {
                     true => Some(next_url()),
                     false => None,
                 },
+                http_ingress_tls_cert: None,
+                http_ingress_tls_key: None,
                 http_ingress_provider: match counter == 0 {
                     true => Some("http-ingress-1".to_string()),
                     false => None,
                 },
+                http_ingress_outputs: None,
                 http_egress_provider: match counter == 0 {
                     true => Some("http-egress-1".to_string()),
                     false => None,
@@ -161,9 +170,39 @@ fn generate_configs(config_path: String, number_of_nodes: u32, metrics_collector
                 },
                 ollama_provider: None,
                 kafka_egress_provider: None,
+                kafka_ingress_provider: None,
+                mqtt_ingress_provider: match counter == 0 {
+                    true => Some("mqtt-ingress-1".to_string()),
+                    false => None,
+                },
+                mqtt_egress_provider: match counter == 0 {
+                    true => Some("mqtt-egress-1".to_string()),
+                    false => None,
+                },
+                dataset_replay_provider: match counter == 0 {
+                    true => Some("dataset-replay-1".to_string()),
+                    false => None,
+                },
+                timer_provider: match counter == 0 {
+                    true => Some("timer-1".to_string()),
+                    false => None,
+                },
+                tsdb_sink_provider: match counter == 0 {
+                    true => Some("tsdb-sink-1".to_string()),
+                    false => None,
+                },
+                websocket_url: match counter == 0 {
+                    true => Some(next_url()),
+                    false => None,
+                },
+                websocket_provider: match counter == 0 {
+                    true => Some("websocket-1".to_string()),
+                    false => None,
+                },
                 metrics_collector_provider: None,
             }),
             user_node_capabilities: None,
+            state: None,
         });
     }
 
@@ -189,20 +228,36 @@ fn generate_configs(config_path: String, number_of_nodes: u32, metrics_collector
             container_runtime: None,
             resources: Some(EdgelessNodeResourceSettings {
                 http_ingress_url: None,
+                http_ingress_tls_cert: None,
+                http_ingress_tls_key: None,
                 http_ingress_provider: None,
+                http_ingress_outputs: None,
                 http_egress_provider: None,
                 file_log_provider: None,
                 redis_provider: None,
                 dda_provider: None,
                 ollama_provider: None,
                 kafka_egress_provider: None,
+                kafka_ingress_provider: None,
+                mqtt_ingress_provider: None,
+                mqtt_egress_provider: None,
+                dataset_replay_provider: None,
+                timer_provider: None,
+                tsdb_sink_provider: None,
+                websocket_url: None,
+                websocket_provider: None,
                 metrics_collector_provider: Some(edgeless_node::MetricsCollectorProviderSettings {
                     collector_type: String::from("Redis"),
                     redis_url: Some(String::from("redis://127.0.0.1:6379")),
                     provider: String::from("metrics-collector-1"),
+                    histogram_buckets: None,
+                    percentiles: None,
+                    prometheus_url: None,
+                    csv_filename: None,
                 }),
             }),
             user_node_capabilities: None,
+            state: None,
         });
     }
 
@@ -4,9 +4,26 @@
 // SPDX-License-Identifier: MIT
 /// Struct representing the updatable callbacks/aliases of a function instance.
 /// Shared between a function instance's host and guest.
+///
+/// Besides plain aliases, an alias can have multiple routes, possibly
+/// conditional on the payload, see edgeless_api::output_routing.
 #[derive(Clone)]
 pub struct AliasMapping {
-    mapping: std::sync::Arc<tokio::sync::Mutex<std::collections::HashMap<String, edgeless_api::function_instance::InstanceId>>>,
+    mapping: std::sync::Arc<tokio::sync::Mutex<AliasMappingInner>>,
+}
+
+#[derive(Default)]
+struct AliasMappingInner {
+    // Plain aliases.
+    aliases: std::collections::HashMap<String, edgeless_api::function_instance::InstanceId>,
+    // Routes of each alias, sorted by index.
+    routes: std::collections::HashMap<
+        String,
+        Vec<(
+            Option<edgeless_api::output_routing::RouteCondition>,
+            edgeless_api::function_instance::InstanceId,
+        )>,
+    >,
 }
 
 impl Default for AliasMapping {
@@ -18,15 +35,117 @@ impl Default for AliasMapping {
 impl AliasMapping {
     pub fn new() -> Self {
         AliasMapping {
-            mapping: std::sync::Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+            mapping: std::sync::Arc::new(tokio::sync::Mutex::new(AliasMappingInner::default())),
         }
     }
 
+    /// Return the target of an alias, irrespective of the payload.
+    ///
+    /// If the alias has routes, then the first unconditional one is used.
     pub async fn get_mapping(&self, alias: &str) -> Option<edgeless_api::function_instance::InstanceId> {
-        self.mapping.lock().await.get(alias).copied()
+        let mapping = self.mapping.lock().await;
+        if let Some(target) = mapping.aliases.get(alias) {
+            return Some(*target);
+        }
+        mapping
+            .routes
+            .get(alias)
+            .and_then(|routes| routes.iter().find(|(condition, _target)| condition.is_none()))
+            .map(|(_condition, target)| *target)
+    }
+
+    /// Return the targets of an alias for a given payload, i.e., the plain
+    /// target, if any, followed by those of the routes whose condition is
+    /// satisfied.
+    ///
+    /// Return None if the alias is unknown.
+    pub async fn get_targets(&self, alias: &str, payload: &str) -> Option<Vec<edgeless_api::function_instance::InstanceId>> {
+        let mapping = self.mapping.lock().await;
+        let plain = mapping.aliases.get(alias);
+        let routes = mapping.routes.get(alias);
+        if plain.is_none() && routes.is_none() {
+            return None;
+        }
+        let mut targets: Vec<edgeless_api::function_instance::InstanceId> = plain.into_iter().copied().collect();
+        for (condition, target) in routes.into_iter().flatten() {
+            if condition.as_ref().map_or(true, |condition| condition.matches(payload)) {
+                targets.push(*target);
+            }
+        }
+        Some(targets)
     }
 
     pub async fn update(&mut self, new_mapping: std::collections::HashMap<String, edgeless_api::function_instance::InstanceId>) {
-        *self.mapping.lock().await = new_mapping;
+        let mut aliases = std::collections::HashMap::new();
+        let mut indexed_routes = std::collections::HashMap::new();
+        for (key, target) in new_mapping {
+            match edgeless_api::output_routing::OutputRoute::parse(&key) {
+                Ok(Some(route)) => indexed_routes
+                    .entry(route.output)
+                    .or_insert_with(Vec::new)
+                    .push((route.index, route.condition, target)),
+                Ok(None) => {
+                    aliases.insert(key, target);
+                }
+                Err(err) => log::warn!("ignoring invalid route '{}': {}", key, err),
+            }
+        }
+        let mut routes = std::collections::HashMap::new();
+        for (alias, mut alias_routes) in indexed_routes {
+            alias_routes.sort_by_key(|(index, _condition, _target)| *index);
+            routes.insert(
+                alias,
+                alias_routes.into_iter().map(|(_index, condition, target)| (condition, target)).collect(),
+            );
+        }
+        *self.mapping.lock().await = AliasMappingInner { aliases, routes };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_alias_mapping_routes() {
+        let target = |n: u128| edgeless_api::function_instance::InstanceId {
+            node_id: uuid::Uuid::nil(),
+            function_id: uuid::Uuid::from_u128(n),
+        };
+
+        let mut mapping = AliasMapping::new();
+        mapping
+            .update(std::collections::HashMap::from([
+                ("plain".to_string(), target(1)),
+                ("out[2]".to_string(), target(4)),
+                ("out[0]?classification == \"HighActivity\"".to_string(), target(2)),
+                ("out[1]?score > 0.5".to_string(), target(3)),
+                ("wrong[x]".to_string(), target(5)),
+            ]))
+            .await;
+
+        assert_eq!(Some(target(1)), mapping.get_mapping("plain").await);
+        assert_eq!(Some(target(4)), mapping.get_mapping("out").await);
+        assert_eq!(None, mapping.get_mapping("wrong").await);
+        assert_eq!(None, mapping.get_mapping("unknown").await);
+
+        assert_eq!(Some(vec![target(1)]), mapping.get_targets("plain", "any").await);
+        assert_eq!(
+            Some(vec![target(2), target(3), target(4)]),
+            mapping.get_targets("out", r#"{"classification":"HighActivity","score":0.9}"#).await
+        );
+        assert_eq!(
+            Some(vec![target(3), target(4)]),
+            mapping.get_targets("out", r#"{"classification":"LowActivity","score":0.9}"#).await
+        );
+        assert_eq!(Some(vec![target(4)]), mapping.get_targets("out", "not JSON").await);
+        assert_eq!(None, mapping.get_targets("unknown", "any").await);
+
+        // Routes without a match.
+        mapping
+            .update(std::collections::HashMap::from([("out[0]?score > 0.5".to_string(), target(3))]))
+            .await;
+        assert_eq!(Some(vec![]), mapping.get_targets("out", r#"{"score":0.1}"#).await);
+        assert_eq!(None, mapping.get_mapping("out").await);
     }
 }
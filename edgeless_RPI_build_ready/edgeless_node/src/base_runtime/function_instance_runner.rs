@@ -28,11 +28,13 @@ struct FunctionInstanceTask<FunctionInstanceType: FunctionInstance> {
     telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
     guest_api_host_register: std::sync::Arc<tokio::sync::Mutex<Box<dyn super::runtime::GuestAPIHostRegister + Send>>>,
     code: Vec<u8>,
+    limits: super::limits::FunctionInstanceLimits,
     data_plane: edgeless_dataplane::handle::DataplaneHandle,
     serialized_state: Option<String>,
     init_payload: Option<String>,
     runtime_api: futures::channel::mpsc::UnboundedSender<super::runtime::RuntimeRequest>,
     instance_id: edgeless_api::function_instance::InstanceId,
+    context: FunctionInstanceType::Context,
 }
 
 impl<FunctionInstanceType: FunctionInstance> FunctionInstanceRunner<FunctionInstanceType> {
@@ -43,6 +45,7 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceRunner<FunctionInst
         state_handle: Box<dyn crate::state_management::StateHandleAPI>,
         telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
         guest_api_host_register: std::sync::Arc<tokio::sync::Mutex<Box<dyn super::runtime::GuestAPIHostRegister + Send>>>,
+        context: FunctionInstanceType::Context,
     ) -> Self {
         let instance_id = spawn_req.instance_id.unwrap();
         let mut telemetry_handle = telemetry_handle;
@@ -69,11 +72,13 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceRunner<FunctionInst
                 guest_api_host_register,
                 guest_api_host,
                 spawn_req.code.function_class_code.clone(),
+                super::limits::FunctionInstanceLimits::from_annotations(&spawn_req.annotations),
                 data_plane,
                 serialized_state,
                 spawn_req.annotations.get("init-payload").cloned(),
                 runtime_api,
                 instance_id,
+                context,
             )
             .await,
         );
@@ -112,11 +117,13 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceTask<FunctionInstan
         guest_api_host_register: std::sync::Arc<tokio::sync::Mutex<Box<dyn super::runtime::GuestAPIHostRegister + Send>>>,
         guest_api_host: super::guest_api::GuestAPIHost,
         code: Vec<u8>,
+        limits: super::limits::FunctionInstanceLimits,
         data_plane: edgeless_dataplane::handle::DataplaneHandle,
         serialized_state: Option<String>,
         init_param: Option<String>,
         runtime_api: futures::channel::mpsc::UnboundedSender<super::runtime::RuntimeRequest>,
         instance_id: edgeless_api::function_instance::InstanceId,
+        context: FunctionInstanceType::Context,
     ) -> Self {
         Self {
             poison_pill_receiver,
@@ -125,11 +132,13 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceTask<FunctionInstan
             telemetry_handle,
             guest_api_host_register,
             code,
+            limits,
             data_plane,
             serialized_state,
             init_payload: init_param,
             runtime_api,
             instance_id,
+            context,
         }
     }
 
@@ -161,8 +170,17 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceTask<FunctionInstan
             runtime_configuration = register.configuration();
         }
 
-        self.function_instance =
-            Some(FunctionInstanceType::instantiate(&self.instance_id, runtime_configuration, &mut self.guest_api_host.take(), &self.code).await?);
+        self.function_instance = Some(
+            FunctionInstanceType::instantiate(
+                &self.context,
+                &self.instance_id,
+                runtime_configuration,
+                &mut self.guest_api_host.take(),
+                &self.code,
+                &self.limits,
+            )
+            .await?,
+        );
 
         self.telemetry_handle.observe(
             edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionInstantiate(start.elapsed()),
@@ -302,6 +320,10 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceTask<FunctionInstan
                         // telemetry endpoint
                         edgeless_telemetry::telemetry_events::FunctionExitStatus::CodeError
                     }
+                    FunctionInstanceError::LimitExceeded(limit) => {
+                        log::warn!("function instance {:?} terminated: {}", self.instance_id, limit);
+                        edgeless_telemetry::telemetry_events::FunctionExitStatus::InternalError
+                    }
                     _ => edgeless_telemetry::telemetry_events::FunctionExitStatus::InternalError,
                 },
             }),
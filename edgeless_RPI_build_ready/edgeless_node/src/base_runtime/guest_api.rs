@@ -29,8 +29,13 @@ impl GuestAPIHost {
         if alias == "self" {
             self.data_plane.send(self.instance_id, msg.to_string()).await;
             Ok(())
-        } else if let Some(target) = self.callback_table.get_mapping(alias).await {
-            self.data_plane.send(target, msg.to_string()).await;
+        } else if let Some(targets) = self.callback_table.get_targets(alias, msg).await {
+            if targets.is_empty() {
+                log::debug!("no route matched for alias {}: dropped", alias);
+            }
+            for target in targets {
+                self.data_plane.send(target, msg.to_string()).await;
+            }
             Ok(())
         } else {
             Err(GuestAPIError::UnknownAlias)
@@ -46,7 +51,14 @@ impl GuestAPIHost {
         if alias == "self" {
             self.call_raw(self.instance_id, msg).await
             // return Ok(self.data_plane.call(self.instance_id.clone(), msg.to_string()).await);
-        } else if let Some(target) = self.callback_table.get_mapping(alias).await {
+        } else if let Some(target) = self
+            .callback_table
+            .get_targets(alias, msg)
+            .await
+            .and_then(|targets| targets.first().copied())
+        {
+            // A call has a single return value, hence only the first
+            // matching route is used.
             return self.call_raw(target, msg).await;
             // return Ok(self.data_plane.call(target.clone(), msg.to_string()).await);
         } else {
@@ -85,10 +97,10 @@ impl GuestAPIHost {
         let mut cloned_plane = self.data_plane.clone();
         let cloned_msg = payload.to_string();
 
-        let target_instance_id = if target_alias == "self" {
-            self.instance_id
-        } else if let Some(targted_id) = self.callback_table.get_mapping(target_alias).await {
-            targted_id
+        let target_instance_ids = if target_alias == "self" {
+            vec![self.instance_id]
+        } else if let Some(targeted_ids) = self.callback_table.get_targets(target_alias, payload).await {
+            targeted_ids
         } else {
             log::warn!("Unknown alias.");
             return Err(GuestAPIError::UnknownAlias);
@@ -96,15 +108,35 @@ impl GuestAPIHost {
 
         tokio::spawn(async move {
             tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
-            cloned_plane.send(target_instance_id, cloned_msg).await;
+            for target_instance_id in target_instance_ids {
+                cloned_plane.send(target_instance_id, cloned_msg.clone()).await;
+            }
         });
 
         Ok(())
     }
 
+    /// Save the state of the function. A failure is not returned to the
+    /// function, which would be terminated, but it is reported as an error
+    /// of the function through the telemetry.
     pub async fn sync(&mut self, serialized_state: &str) -> Result<(), GuestAPIError> {
-        self.state_handle.set(serialized_state.to_string()).await;
-        log::info!("Function State Sync: {}", serialized_state);
+        match self.state_handle.set(serialized_state.to_string()).await {
+            Ok(_) => log::info!("Function State Sync: {}", serialized_state),
+            Err(err) => {
+                log::error!("Function State Sync failed: {}", err);
+                self.telemetry_log(edgeless_telemetry::telemetry_events::TelemetryLogLevel::Error, "state", &err.to_string())
+                    .await;
+            }
+        }
         Ok(())
     }
+
+    /// Wall-clock time, in ms since the UNIX epoch, which is not available
+    /// to the guest in the WASM sandbox.
+    pub fn time_ms(&self) -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|x| x.as_millis() as i64)
+            .unwrap_or_default()
+    }
 }
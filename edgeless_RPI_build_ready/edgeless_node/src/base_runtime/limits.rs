@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// Limits on the resources used by a function instance, which are set
+/// through the annotations of the function and enforced by the runners.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FunctionInstanceLimits {
+    /// Maximum amount of fuel, i.e., roughly the number of WASM
+    /// instructions, that can be consumed by a single invocation.
+    pub max_fuel: Option<u64>,
+    /// Maximum size of the linear memory, in bytes.
+    pub max_memory: Option<usize>,
+    /// Maximum duration of a single invocation.
+    pub max_invocation_time: Option<std::time::Duration>,
+}
+
+impl FunctionInstanceLimits {
+    /// Read the limits from the following annotations, if present:
+    ///
+    /// - `max_fuel`: maximum fuel per invocation;
+    /// - `max_memory_mb`: maximum linear memory, in MiB;
+    /// - `max_invocation_ms`: maximum duration of an invocation, in ms.
+    ///
+    /// Invalid values are ignored.
+    pub fn from_annotations(annotations: &std::collections::HashMap<String, String>) -> Self {
+        fn parse<T: std::str::FromStr>(annotations: &std::collections::HashMap<String, String>, key: &str) -> Option<T> {
+            let val = annotations.get(key)?;
+            match val.parse::<T>() {
+                Ok(val) => Some(val),
+                Err(_) => {
+                    log::warn!("ignoring invalid value of annotation {}: {}", key, val);
+                    None
+                }
+            }
+        }
+
+        Self {
+            max_fuel: parse::<u64>(annotations, "max_fuel"),
+            max_memory: parse::<usize>(annotations, "max_memory_mb").map(|x| x.saturating_mul(1024 * 1024)),
+            max_invocation_time: parse::<u64>(annotations, "max_invocation_ms").map(std::time::Duration::from_millis),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Limiter of the linear memory of a WASM function instance, which records
+/// whether a growth has been denied so that the runner can tell an
+/// exceeded limit from a generic failure of the function.
+pub struct MemoryLimiter {
+    max_memory: Option<usize>,
+    exceeded: bool,
+}
+
+impl MemoryLimiter {
+    pub fn new(max_memory: Option<usize>) -> Self {
+        Self { max_memory, exceeded: false }
+    }
+
+    /// Return true if the memory can grow to the given size, in bytes.
+    pub fn allow(&mut self, desired: usize) -> bool {
+        match self.max_memory {
+            Some(max_memory) if desired > max_memory => {
+                self.exceeded = true;
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// True if a memory growth has been denied since the last reset.
+    pub fn exceeded(&self) -> bool {
+        self.exceeded
+    }
+
+    /// Clear the exceeded flag, which is done before every invocation.
+    pub fn reset(&mut self) {
+        self.exceeded = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_function_instance_limits_from_annotations() {
+        assert!(FunctionInstanceLimits::from_annotations(&std::collections::HashMap::new()).is_empty());
+
+        let limits = FunctionInstanceLimits::from_annotations(&std::collections::HashMap::from([
+            ("max_fuel".to_string(), "1000000".to_string()),
+            ("max_memory_mb".to_string(), "16".to_string()),
+            ("max_invocation_ms".to_string(), "250".to_string()),
+            ("init-payload".to_string(), "anything".to_string()),
+        ]));
+        assert_eq!(Some(1000000), limits.max_fuel);
+        assert_eq!(Some(16 * 1024 * 1024), limits.max_memory);
+        assert_eq!(Some(std::time::Duration::from_millis(250)), limits.max_invocation_time);
+
+        let limits = FunctionInstanceLimits::from_annotations(&std::collections::HashMap::from([
+            ("max_fuel".to_string(), "-1".to_string()),
+            ("max_memory_mb".to_string(), "16".to_string()),
+            ("max_invocation_ms".to_string(), "one second".to_string()),
+        ]));
+        assert_eq!(None, limits.max_fuel);
+        assert_eq!(Some(16 * 1024 * 1024), limits.max_memory);
+        assert_eq!(None, limits.max_invocation_time);
+    }
+
+    #[test]
+    fn test_memory_limiter() {
+        let mut limiter = MemoryLimiter::new(None);
+        assert!(limiter.allow(usize::MAX));
+        assert!(!limiter.exceeded());
+
+        let mut limiter = MemoryLimiter::new(Some(1024));
+        assert!(limiter.allow(1024));
+        assert!(!limiter.exceeded());
+        assert!(!limiter.allow(1025));
+        assert!(limiter.exceeded());
+        limiter.reset();
+        assert!(!limiter.exceeded());
+    }
+}
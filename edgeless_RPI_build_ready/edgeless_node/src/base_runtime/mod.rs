@@ -5,6 +5,8 @@
 pub mod alias_mapping;
 pub mod function_instance_runner;
 pub mod guest_api;
+pub mod limits;
+pub mod restart_policy;
 pub mod runtime;
 
 /// (Deprecated) Trait to be implemented by each runtime.
@@ -20,11 +22,17 @@ pub trait RuntimeAPI {
 /// As suggested by the name, it contains a single instance of a function.
 #[async_trait::async_trait]
 pub trait FunctionInstance: Send + 'static {
+    /// State shared by all the function instances of a runner, e.g., a
+    /// cache of the compiled code.
+    type Context: Clone + Send + Sync + 'static;
+
     async fn instantiate(
+        context: &Self::Context,
         instance_id: &edgeless_api::function_instance::InstanceId,
         runtime_configuration: std::collections::HashMap<String, String>,
         guest_api_host: &mut Option<crate::base_runtime::guest_api::GuestAPIHost>,
         code: &[u8],
+        limits: &crate::base_runtime::limits::FunctionInstanceLimits,
     ) -> Result<Box<Self>, FunctionInstanceError>;
     async fn init(&mut self, init_payload: Option<&str>, serialized_state: Option<&str>) -> Result<(), FunctionInstanceError>;
     async fn cast(&mut self, src: &edgeless_api::function_instance::InstanceId, msg: &str) -> Result<(), FunctionInstanceError>;
@@ -40,4 +48,6 @@ pub trait FunctionInstance: Send + 'static {
 pub enum FunctionInstanceError {
     BadCode(String),
     InternalError,
+    // A resource limit of the function instance has been exceeded.
+    LimitExceeded(String),
 }
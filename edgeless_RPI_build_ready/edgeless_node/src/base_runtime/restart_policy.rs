@@ -0,0 +1,172 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// Default maximum number of restarts with the `on-failure` policy.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first restart.
+pub const DEFAULT_BACKOFF: std::time::Duration = std::time::Duration::from_millis(1000);
+
+/// Maximum delay before a restart.
+pub const MAX_BACKOFF: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RestartMode {
+    // The function instance is never restarted.
+    Never,
+    // The function instance is restarted only if it exits with an error,
+    // up to the given number of times.
+    OnFailure(u32),
+    // The function instance is always restarted, unless stopped on request.
+    Always,
+}
+
+impl std::str::FromStr for RestartMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "never" => Ok(RestartMode::Never),
+            None if s == "always" => Ok(RestartMode::Always),
+            None if s == "on-failure" => Ok(RestartMode::OnFailure(DEFAULT_MAX_RETRIES)),
+            Some(("on-failure", max_retries)) => match max_retries.parse::<u32>() {
+                Ok(max_retries) => Ok(RestartMode::OnFailure(max_retries)),
+                Err(_) => anyhow::bail!("invalid maximum number of retries in restart policy '{}'", s),
+            },
+            _ => anyhow::bail!("unknown restart policy '{}'", s),
+        }
+    }
+}
+
+/// Policy to restart a function instance that has exited, which is
+/// enforced locally by the node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    // Delay before the first restart, doubled at every subsequent restart.
+    pub backoff: std::time::Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::Never,
+            backoff: DEFAULT_BACKOFF,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Read the restart policy from the following annotations, if present:
+    ///
+    /// - `restart_policy`: one of `never` (default), `always`, `on-failure`,
+    ///   or `on-failure:<max_retries>`;
+    /// - `restart_backoff_ms`: delay before the first restart, in ms.
+    ///
+    /// Invalid values are ignored.
+    pub fn from_annotations(annotations: &std::collections::HashMap<String, String>) -> Self {
+        let mut policy = Self::default();
+        if let Some(val) = annotations.get("restart_policy") {
+            match val.parse::<RestartMode>() {
+                Ok(mode) => policy.mode = mode,
+                Err(err) => log::warn!("ignoring invalid annotation restart_policy: {}", err),
+            }
+        }
+        if let Some(val) = annotations.get("restart_backoff_ms") {
+            match val.parse::<u64>() {
+                Ok(backoff) => policy.backoff = std::time::Duration::from_millis(backoff),
+                Err(_) => log::warn!("ignoring invalid value of annotation restart_backoff_ms: {}", val),
+            }
+        }
+        policy
+    }
+
+    /// Return true if a function instance that exited with the given status,
+    /// after having been already restarted the given number of times, must
+    /// be restarted again.
+    pub fn must_restart(&self, exit_status: &Result<(), super::FunctionInstanceError>, restarts: u32) -> bool {
+        match self.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure(max_retries) => exit_status.is_err() && restarts < max_retries,
+            RestartMode::Always => true,
+        }
+    }
+
+    /// Delay before the restart following the given number of restarts.
+    pub fn backoff(&self, restarts: u32) -> std::time::Duration {
+        self.backoff.saturating_mul(2_u32.saturating_pow(restarts)).min(MAX_BACKOFF)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_restart_policy_from_annotations() {
+        assert_eq!(
+            RestartPolicy::default(),
+            RestartPolicy::from_annotations(&std::collections::HashMap::new())
+        );
+
+        for (val, expected) in [
+            ("never", RestartMode::Never),
+            ("always", RestartMode::Always),
+            ("on-failure", RestartMode::OnFailure(DEFAULT_MAX_RETRIES)),
+            ("on-failure:5", RestartMode::OnFailure(5)),
+            ("on-failure:x", RestartMode::Never),
+            ("sometimes", RestartMode::Never),
+            ("always:5", RestartMode::Never),
+        ] {
+            let policy = RestartPolicy::from_annotations(&std::collections::HashMap::from([("restart_policy".to_string(), val.to_string())]));
+            assert_eq!(expected, policy.mode, "{}", val);
+            assert_eq!(DEFAULT_BACKOFF, policy.backoff);
+        }
+
+        let policy = RestartPolicy::from_annotations(&std::collections::HashMap::from([
+            ("restart_policy".to_string(), "always".to_string()),
+            ("restart_backoff_ms".to_string(), "250".to_string()),
+        ]));
+        assert_eq!(RestartMode::Always, policy.mode);
+        assert_eq!(std::time::Duration::from_millis(250), policy.backoff);
+    }
+
+    #[test]
+    fn test_restart_policy_must_restart() {
+        let ok = Ok(());
+        let err = Err(crate::base_runtime::FunctionInstanceError::BadCode("panic".to_string()));
+
+        let policy = RestartPolicy::default();
+        assert!(!policy.must_restart(&ok, 0));
+        assert!(!policy.must_restart(&err, 0));
+
+        let policy = RestartPolicy {
+            mode: RestartMode::OnFailure(2),
+            ..Default::default()
+        };
+        assert!(!policy.must_restart(&ok, 0));
+        assert!(policy.must_restart(&err, 0));
+        assert!(policy.must_restart(&err, 1));
+        assert!(!policy.must_restart(&err, 2));
+
+        let policy = RestartPolicy {
+            mode: RestartMode::Always,
+            ..Default::default()
+        };
+        assert!(policy.must_restart(&ok, 0));
+        assert!(policy.must_restart(&err, 1000));
+    }
+
+    #[test]
+    fn test_restart_policy_backoff() {
+        let policy = RestartPolicy {
+            mode: RestartMode::Always,
+            backoff: std::time::Duration::from_millis(100),
+        };
+        assert_eq!(std::time::Duration::from_millis(100), policy.backoff(0));
+        assert_eq!(std::time::Duration::from_millis(200), policy.backoff(1));
+        assert_eq!(std::time::Duration::from_millis(800), policy.backoff(3));
+        assert_eq!(MAX_BACKOFF, policy.backoff(20));
+        assert_eq!(MAX_BACKOFF, policy.backoff(u32::MAX));
+    }
+}
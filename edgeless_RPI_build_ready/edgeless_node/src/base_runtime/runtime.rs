@@ -38,6 +38,18 @@ pub struct RuntimeTask<FunctionInstanceType: super::FunctionInstance> {
     guest_api_host_register: std::sync::Arc<tokio::sync::Mutex<Box<dyn GuestAPIHostRegister + Send>>>,
     slf_channel: futures::channel::mpsc::UnboundedSender<RuntimeRequest>,
     functions: std::collections::HashMap<uuid::Uuid, super::function_instance_runner::FunctionInstanceRunner<FunctionInstanceType>>,
+    restart_info: std::collections::HashMap<uuid::Uuid, RestartInfo>,
+    context: FunctionInstanceType::Context,
+}
+
+/// Information needed to restart a function instance after it exits.
+struct RestartInfo {
+    spawn_request: edgeless_api::function_instance::SpawnFunctionRequest,
+    // Last output mapping received.
+    output_mapping: std::collections::HashMap<String, edgeless_api::function_instance::InstanceId>,
+    policy: super::restart_policy::RestartPolicy,
+    // Number of restarts so far.
+    restarts: u32,
 }
 
 pub enum RuntimeRequest {
@@ -45,6 +57,8 @@ pub enum RuntimeRequest {
     Stop(edgeless_api::function_instance::InstanceId),
     Patch(edgeless_api::common::PatchRequest),
     FunctionExit(edgeless_api::function_instance::InstanceId, Result<(), super::FunctionInstanceError>),
+    // Restart a function instance, with the number of restarts so far.
+    Restart(edgeless_api::function_instance::InstanceId, u32),
 }
 
 /// Entrypoint for all runtimes based on the base_runtime.
@@ -53,6 +67,7 @@ pub fn create<FunctionInstanceType: super::FunctionInstance>(
     state_manager: Box<dyn crate::state_management::StateManagerAPI>,
     telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
     guest_api_host_register: std::sync::Arc<tokio::sync::Mutex<Box<dyn GuestAPIHostRegister + Send>>>,
+    context: FunctionInstanceType::Context,
 ) -> (RuntimeClient, RuntimeTask<FunctionInstanceType>) {
     let (sender, receiver) = futures::channel::mpsc::unbounded();
     let task: RuntimeTask<FunctionInstanceType> = RuntimeTask::new(
@@ -62,6 +77,7 @@ pub fn create<FunctionInstanceType: super::FunctionInstance>(
         telemetry_handle,
         guest_api_host_register,
         sender.clone(),
+        context,
     );
 
     let client = RuntimeClient::new(sender);
@@ -77,6 +93,7 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
         telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
         guest_api_host_register: std::sync::Arc<tokio::sync::Mutex<Box<dyn GuestAPIHostRegister + Send>>>,
         slf_channel: futures::channel::mpsc::UnboundedSender<RuntimeRequest>,
+        context: FunctionInstanceType::Context,
     ) -> Self {
         Self {
             receiver,
@@ -86,6 +103,8 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
             guest_api_host_register,
             slf_channel,
             functions: std::collections::HashMap::new(),
+            restart_info: std::collections::HashMap::new(),
+            context,
         }
     }
 
@@ -105,6 +124,9 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
                 RuntimeRequest::FunctionExit(id, status) => {
                     self.function_exit(id, status).await;
                 }
+                RuntimeRequest::Restart(id, restarts) => {
+                    self.restart_function(id, restarts).await;
+                }
             }
         }
     }
@@ -117,6 +139,23 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
                 return;
             }
         };
+        self.restart_info.insert(
+            instance_id.function_id,
+            RestartInfo {
+                spawn_request: spawn_request.clone(),
+                output_mapping: std::collections::HashMap::new(),
+                policy: super::restart_policy::RestartPolicy::from_annotations(&spawn_request.annotations),
+                restarts: 0,
+            },
+        );
+        self.spawn_runner(instance_id, spawn_request).await;
+    }
+
+    async fn spawn_runner(
+        &mut self,
+        instance_id: edgeless_api::function_instance::InstanceId,
+        spawn_request: edgeless_api::function_instance::SpawnFunctionRequest,
+    ) {
         let cloned_req = spawn_request.clone();
         let data_plane = self.data_plane_provider.get_handle_for(instance_id).await;
         let instance = super::function_instance_runner::FunctionInstanceRunner::new(
@@ -131,6 +170,7 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
                 instance_id.function_id.to_string(),
             )])),
             self.guest_api_host_register.clone(),
+            self.context.clone(),
         )
         .await;
         self.functions.insert(instance_id.function_id, instance);
@@ -138,13 +178,19 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
 
     async fn stop_function(&mut self, instance_id: edgeless_api::function_instance::InstanceId) {
         log::info!("Stop Function {:?}", instance_id);
-        if let Some(instance) = self.functions.get_mut(&instance_id.function_id) {
+        // Removing the function instance before it exits prevents it from
+        // being restarted, also if a restart is pending.
+        self.restart_info.remove(&instance_id.function_id);
+        if let Some(mut instance) = self.functions.remove(&instance_id.function_id) {
             instance.stop().await;
         }
     }
 
     async fn patch_function_links(&mut self, update_request: edgeless_api::common::PatchRequest) {
         log::info!("Patch Function {:?}", update_request.function_id);
+        if let Some(restart_info) = self.restart_info.get_mut(&update_request.function_id) {
+            restart_info.output_mapping = update_request.output_mapping.clone();
+        }
         if let Some(instance) = self.functions.get_mut(&update_request.function_id) {
             instance.patch(update_request).await;
         }
@@ -152,7 +198,66 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
 
     async fn function_exit(&mut self, instance_id: edgeless_api::function_instance::InstanceId, status: Result<(), super::FunctionInstanceError>) {
         log::info!("Function Exit Event: {:?} {:?}", instance_id, status);
-        self.functions.remove(&instance_id.function_id);
+        if self.functions.remove(&instance_id.function_id).is_none() {
+            // The function instance has been stopped on request.
+            return;
+        }
+
+        let restart_info = match self.restart_info.get_mut(&instance_id.function_id) {
+            Some(restart_info) => restart_info,
+            None => return,
+        };
+        if !restart_info.policy.must_restart(&status, restart_info.restarts) {
+            self.restart_info.remove(&instance_id.function_id);
+            return;
+        }
+
+        let delay = restart_info.policy.backoff(restart_info.restarts);
+        restart_info.restarts += 1;
+        let restarts = restart_info.restarts;
+        log::info!(
+            "Restarting function {:?} in {} ms (restart #{})",
+            instance_id,
+            delay.as_millis(),
+            restarts
+        );
+        let mut slf_channel = self.slf_channel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _ = slf_channel.send(RuntimeRequest::Restart(instance_id, restarts)).await;
+        });
+    }
+
+    async fn restart_function(&mut self, instance_id: edgeless_api::function_instance::InstanceId, restarts: u32) {
+        let (spawn_request, output_mapping) = match self.restart_info.get(&instance_id.function_id) {
+            // Skip the restart if the function instance has been stopped,
+            // possibly started again, in the meanwhile.
+            Some(restart_info) if restart_info.restarts == restarts && !self.functions.contains_key(&instance_id.function_id) => {
+                (restart_info.spawn_request.clone(), restart_info.output_mapping.clone())
+            }
+            _ => return,
+        };
+        log::info!("Restart Function {:?}", instance_id);
+
+        self.spawn_runner(instance_id, spawn_request).await;
+        if let Some(instance) = self.functions.get_mut(&instance_id.function_id) {
+            instance
+                .patch(edgeless_api::common::PatchRequest {
+                    function_id: instance_id.function_id,
+                    output_mapping,
+                })
+                .await;
+        }
+
+        self.telemetry_handle
+            .fork(std::collections::BTreeMap::from([(
+                "FUNCTION_ID".to_string(),
+                instance_id.function_id.to_string(),
+            )]))
+            .observe(
+                edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionRestart(restarts),
+                std::collections::BTreeMap::new(),
+            );
     }
 }
 
@@ -3,17 +3,7 @@
 // SPDX-FileCopyrightText: © 2023 Siemens AG
 // SPDX-License-Identifier: MIT
 use clap::Parser;
-use edgeless_node::resources::dda::DdaResourceSpec;
-use edgeless_node::resources::file_log::FileLogResourceSpec;
-use edgeless_node::resources::http_egress::HttpEgressResourceSpec;
-use edgeless_node::resources::http_ingress::HttpIngressResourceSpec;
-#[cfg(feature = "rdkafka")]
-use edgeless_node::resources::kafka_egress::KafkaEgressResourceSpec;
-use edgeless_node::resources::metrics_collector::MetricsCollectorResourceSpec;
-use edgeless_node::resources::ollama::OllamasResourceSpec;
-use edgeless_node::resources::redis::RedisResourceSpec;
 use edgeless_node::resources::resource_provider_specs::ResourceProviderSpecOutput;
-use edgeless_node::resources::resource_provider_specs::ResourceProviderSpecs;
 
 #[derive(Debug, clap::Parser)]
 #[command(long_about = None)]
@@ -37,18 +27,7 @@ fn main() -> anyhow::Result<()> {
 
     let args = Args::parse();
     if args.available_resources {
-        #[allow(unused_mut)]
-        let mut specs: Vec<Box<dyn ResourceProviderSpecs>> = vec![
-            Box::new(DdaResourceSpec {}),
-            Box::new(FileLogResourceSpec {}),
-            Box::new(HttpEgressResourceSpec {}),
-            Box::new(HttpIngressResourceSpec {}),
-            Box::new(OllamasResourceSpec {}),
-            Box::new(RedisResourceSpec {}),
-            Box::new(MetricsCollectorResourceSpec {}),
-        ];
-        #[cfg(feature = "rdkafka")]
-        specs.push(Box::new(KafkaEgressResourceSpec {}));
+        let specs = edgeless_node::resources::resource_provider_specs::available_resource_specs();
 
         if args.output_json {
             println!(
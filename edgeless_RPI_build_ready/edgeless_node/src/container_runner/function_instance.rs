@@ -18,14 +18,21 @@ pub struct ContainerFunctionInstance {
 
 #[async_trait::async_trait]
 impl crate::base_runtime::FunctionInstance for ContainerFunctionInstance {
+    type Context = ();
+
     async fn instantiate(
+        _context: &Self::Context,
         instance_id: &edgeless_api::function_instance::InstanceId,
         runtime_configuration: std::collections::HashMap<String, String>,
         _guest_api_host: &mut Option<crate::base_runtime::guest_api::GuestAPIHost>,
         code: &[u8],
+        limits: &crate::base_runtime::limits::FunctionInstanceLimits,
     ) -> Result<Box<Self>, crate::base_runtime::FunctionInstanceError> {
         let fun_spec = String::from_utf8(code.to_vec()).unwrap_or_default();
         log::info!("container run-time: instantiate {}", fun_spec);
+        if !limits.is_empty() {
+            log::warn!("container run-time: resource limits are not enforced: {:?}", limits);
+        }
 
         // Assume the fun_spec is one of (examples):
         // - container:edgeless_function:latest
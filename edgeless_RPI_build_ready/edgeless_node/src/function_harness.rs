@@ -0,0 +1,596 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Harness to run a WASM function locally, without a controller, an
+//! orchestrator, or a node, e.g., to test it.
+//!
+//! The function is executed by the wasmtime run-time of the node, with a
+//! GuestAPIHost whose outputs, state, and telemetry are mocked: everything
+//! the function emits (casts, delayed casts, and calls on its outputs, log
+//! entries, and state syncs) is recorded as a `HarnessRecord`.
+
+use crate::base_runtime::FunctionInstance;
+
+/// Name of the output recorded for the events sent by the function to the
+/// source of the inputs, i.e., with `cast_raw`/`call_raw` to the `src`.
+pub const SOURCE_OUTPUT: &str = "src";
+
+/// Event emitted by the function, or reply to an input.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HarnessEvent {
+    // Cast, or delayed cast, on an output.
+    Cast { output: String, payload: String },
+    // Call on an output.
+    Call { output: String, payload: String },
+    // Log entry, with level error, warn, info, debug, or trace.
+    Log { level: String, target: String, message: String },
+    // State synced.
+    Sync { state: String },
+    // Reply of the function to a call step, None if there is no reply.
+    Reply { payload: Option<String> },
+    // Failure of the function, e.g., a panic or an exceeded limit.
+    Error { message: String },
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HarnessRecord {
+    /// Time elapsed since the harness was created, in ms.
+    pub elapsed_ms: u64,
+    /// Index of the step being executed when the event was recorded,
+    /// None if during the initialization or after the last step.
+    pub step: Option<usize>,
+    #[serde(flatten)]
+    pub event: HarnessEvent,
+}
+
+/// Input to the function.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HarnessStep {
+    Cast { payload: String },
+    Call { payload: String },
+    // Wait, e.g., for delayed casts.
+    Sleep { ms: u64 },
+}
+
+/// Sequence of inputs to the function, with its configuration.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HarnessScript {
+    /// Outputs of the function, which must be all those used.
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    /// Reply to the calls on an output, no reply if not present.
+    #[serde(default)]
+    pub call_replies: std::collections::HashMap<String, String>,
+    /// Annotations of the function, e.g., to set resource limits.
+    #[serde(default)]
+    pub annotations: std::collections::HashMap<String, String>,
+    /// Payload passed to handle_init.
+    #[serde(default)]
+    pub init_payload: Option<String>,
+    /// Serialized state passed to handle_init.
+    #[serde(default)]
+    pub serialized_state: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<HarnessStep>,
+    /// Events that must have been recorded, in this order but possibly
+    /// interleaved with others.
+    #[serde(default)]
+    pub expected: Vec<HarnessEvent>,
+}
+
+impl HarnessScript {
+    /// Parse a script in text format, with one command per line:
+    ///
+    /// - `output <name>`: add an output;
+    /// - `reply <output> <payload>`: reply to calls on the output;
+    /// - `annotation <key>=<value>`: add an annotation;
+    /// - `init <payload>`: set the payload of handle_init;
+    /// - `state <serialized_state>`: set the state of handle_init;
+    /// - `cast <payload>`, `call <payload>`, `sleep <ms>`: add a step.
+    ///
+    /// Empty lines and lines beginning with `#` are ignored.
+    pub fn from_text(text: &str) -> anyhow::Result<Self> {
+        let mut script = Self::default();
+        for (lineno, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (command, arg) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let arg = arg.trim_start();
+            match command {
+                "output" => script.outputs.push(arg.to_string()),
+                "reply" => match arg.split_once(char::is_whitespace) {
+                    Some((output, payload)) => {
+                        script.call_replies.insert(output.to_string(), payload.trim_start().to_string());
+                    }
+                    None => anyhow::bail!("line {}: missing payload of reply", lineno + 1),
+                },
+                "annotation" => match arg.split_once('=') {
+                    Some((key, value)) => {
+                        script.annotations.insert(key.to_string(), value.to_string());
+                    }
+                    None => anyhow::bail!("line {}: invalid annotation '{}'", lineno + 1, arg),
+                },
+                "init" => script.init_payload = Some(arg.to_string()),
+                "state" => script.serialized_state = Some(arg.to_string()),
+                "cast" => script.steps.push(HarnessStep::Cast { payload: arg.to_string() }),
+                "call" => script.steps.push(HarnessStep::Call { payload: arg.to_string() }),
+                "sleep" => match arg.parse::<u64>() {
+                    Ok(ms) => script.steps.push(HarnessStep::Sleep { ms }),
+                    Err(_) => anyhow::bail!("line {}: invalid sleep duration '{}'", lineno + 1, arg),
+                },
+                _ => anyhow::bail!("line {}: unknown command '{}'", lineno + 1, command),
+            }
+        }
+        Ok(script)
+    }
+
+    /// Return the expected events that have not been recorded, i.e., the
+    /// first one not found in order and all the following ones.
+    pub fn unmatched_expectations(&self, records: &[HarnessRecord]) -> Vec<HarnessEvent> {
+        let mut records = records.iter();
+        for (i, expected) in self.expected.iter().enumerate() {
+            if !records.any(|record| record.event == *expected) {
+                return self.expected[i..].to_vec();
+            }
+        }
+        vec![]
+    }
+}
+
+/// Recorder shared by the harness and the mocks.
+#[derive(Clone)]
+struct Recorder {
+    start: std::time::Instant,
+    step: std::sync::Arc<std::sync::Mutex<Option<usize>>>,
+    records: std::sync::Arc<std::sync::Mutex<Vec<HarnessRecord>>>,
+}
+
+impl Recorder {
+    fn new() -> Self {
+        Self {
+            start: std::time::Instant::now(),
+            step: std::sync::Arc::new(std::sync::Mutex::new(None)),
+            records: std::sync::Arc::new(std::sync::Mutex::new(vec![])),
+        }
+    }
+
+    fn record(&self, event: HarnessEvent) {
+        self.records.lock().unwrap().push(HarnessRecord {
+            elapsed_ms: self.start.elapsed().as_millis() as u64,
+            step: *self.step.lock().unwrap(),
+            event,
+        });
+    }
+}
+
+struct HarnessTelemetryHandle {
+    recorder: Recorder,
+}
+
+impl edgeless_telemetry::telemetry_events::TelemetryHandleAPI for HarnessTelemetryHandle {
+    fn observe(&mut self, event: edgeless_telemetry::telemetry_events::TelemetryEvent, _event_tags: std::collections::BTreeMap<String, String>) {
+        if let edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionLogEntry(level, target, message) = event {
+            self.recorder.record(HarnessEvent::Log {
+                level: format!("{:?}", level).to_lowercase(),
+                target,
+                message,
+            });
+        }
+    }
+
+    fn fork(&mut self, _child_tags: std::collections::BTreeMap<String, String>) -> Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI> {
+        Box::new(HarnessTelemetryHandle {
+            recorder: self.recorder.clone(),
+        })
+    }
+}
+
+struct HarnessStateHandle {
+    recorder: Recorder,
+}
+
+#[async_trait::async_trait]
+impl crate::state_management::StateHandleAPI for HarnessStateHandle {
+    async fn get(&mut self) -> Option<String> {
+        None
+    }
+
+    async fn set(&mut self, serialized_state: String) -> anyhow::Result<()> {
+        self.recorder.record(HarnessEvent::Sync { state: serialized_state });
+        Ok(())
+    }
+}
+
+/// A WASM function instance whose interactions are recorded.
+pub struct FunctionHarness {
+    instance: Box<crate::wasm_runner::function_instance::WASMFunctionInstance>,
+    source_id: edgeless_api::function_instance::InstanceId,
+    recorder: Recorder,
+    output_tasks: Vec<tokio::task::JoinHandle<()>>,
+    // Kept to not interrupt the calls of the function.
+    _poison_pill_sender: tokio::sync::broadcast::Sender<()>,
+}
+
+impl Drop for FunctionHarness {
+    fn drop(&mut self) {
+        for task in &self.output_tasks {
+            task.abort();
+        }
+    }
+}
+
+impl FunctionHarness {
+    /// Create a function instance from its WASM code.
+    ///
+    /// Parameters:
+    /// - `code`: the WASM module of the function.
+    /// - `outputs`: the outputs of the function.
+    /// - `call_replies`: the reply to the calls on an output, if any.
+    /// - `annotations`: the annotations of the function.
+    pub async fn new(
+        code: &[u8],
+        outputs: &[String],
+        call_replies: &std::collections::HashMap<String, String>,
+        annotations: &std::collections::HashMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        let recorder = Recorder::new();
+        let node_id = uuid::Uuid::new_v4();
+        let instance_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let source_id = edgeless_api::function_instance::InstanceId::new(node_id);
+
+        // The events are only exchanged within this node, but the data plane
+        // always starts an invocation server: let the OS choose the port.
+        let mut dataplane_provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, "http://127.0.0.1:0".to_string(), None).await;
+
+        let mut output_tasks = vec![];
+        let mut output_mapping = std::collections::HashMap::new();
+        for output in outputs.iter().map(|x| x.as_str()).chain(std::iter::once(SOURCE_OUTPUT)) {
+            let target_id = match output {
+                SOURCE_OUTPUT => source_id,
+                _ => {
+                    let target_id = edgeless_api::function_instance::InstanceId::new(node_id);
+                    output_mapping.insert(output.to_string(), target_id);
+                    target_id
+                }
+            };
+            let handle = dataplane_provider.get_handle_for(target_id).await;
+            output_tasks.push(tokio::spawn(Self::output_task(
+                handle,
+                output.to_string(),
+                call_replies.get(output).cloned(),
+                recorder.clone(),
+            )));
+        }
+
+        let mut callback_table = crate::base_runtime::alias_mapping::AliasMapping::new();
+        callback_table.update(output_mapping).await;
+
+        let (poison_pill_sender, poison_pill_receiver) = tokio::sync::broadcast::channel::<()>(1);
+        let mut guest_api_host = Some(crate::base_runtime::guest_api::GuestAPIHost {
+            instance_id,
+            data_plane: dataplane_provider.get_handle_for(instance_id).await,
+            callback_table,
+            state_handle: Box::new(HarnessStateHandle { recorder: recorder.clone() }),
+            telemetry_handle: Box::new(HarnessTelemetryHandle { recorder: recorder.clone() }),
+            poison_pill_receiver,
+        });
+
+        // The module is compiled once per harness, without persisting it.
+        let module_cache = std::sync::Arc::new(crate::wasm_runner::module_cache::ModuleCache::new(
+            None,
+            crate::wasm_runner::module_cache::DEFAULT_CAPACITY,
+        ));
+        let instance = crate::wasm_runner::function_instance::WASMFunctionInstance::instantiate(
+            &module_cache,
+            &instance_id,
+            std::collections::HashMap::new(),
+            &mut guest_api_host,
+            code,
+            &crate::base_runtime::limits::FunctionInstanceLimits::from_annotations(annotations),
+        )
+        .await
+        .map_err(|err| anyhow::anyhow!("could not instantiate the function: {:?}", err))?;
+
+        Ok(Self {
+            instance,
+            source_id,
+            recorder,
+            output_tasks,
+            _poison_pill_sender: poison_pill_sender,
+        })
+    }
+
+    /// Record the events received on an output, replying to calls.
+    async fn output_task(mut handle: edgeless_dataplane::handle::DataplaneHandle, output: String, reply: Option<String>, recorder: Recorder) {
+        loop {
+            let event = handle.receive_next().await;
+            match event.message {
+                edgeless_dataplane::core::Message::Cast(payload) => recorder.record(HarnessEvent::Cast {
+                    output: output.clone(),
+                    payload,
+                }),
+                edgeless_dataplane::core::Message::Call(payload) => {
+                    recorder.record(HarnessEvent::Call {
+                        output: output.clone(),
+                        payload,
+                    });
+                    let reply = match &reply {
+                        Some(reply) => edgeless_dataplane::core::CallRet::Reply(reply.clone()),
+                        None => edgeless_dataplane::core::CallRet::NoReply,
+                    };
+                    handle.reply(event.source_id, event.channel_id, reply).await;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check<T>(&self, res: Result<T, crate::base_runtime::FunctionInstanceError>) -> anyhow::Result<T> {
+        res.map_err(|err| {
+            let message = format!("{:?}", err);
+            self.recorder.record(HarnessEvent::Error { message: message.clone() });
+            anyhow::anyhow!(message)
+        })
+    }
+
+    pub async fn init(&mut self, payload: Option<&str>, serialized_state: Option<&str>) -> anyhow::Result<()> {
+        let res = self.instance.init(payload, serialized_state).await;
+        self.check(res)
+    }
+
+    pub async fn cast(&mut self, msg: &str) -> anyhow::Result<()> {
+        let res = self.instance.cast(&self.source_id, msg).await;
+        self.check(res)
+    }
+
+    pub async fn call(&mut self, msg: &str) -> anyhow::Result<edgeless_dataplane::core::CallRet> {
+        let res = self.instance.call(&self.source_id, msg).await;
+        let ret = self.check(res)?;
+        self.recorder.record(HarnessEvent::Reply {
+            payload: match &ret {
+                edgeless_dataplane::core::CallRet::Reply(payload) => Some(payload.clone()),
+                _ => None,
+            },
+        });
+        Ok(ret)
+    }
+
+    pub async fn stop(&mut self) -> anyhow::Result<()> {
+        let res = self.instance.stop().await;
+        self.check(res)
+    }
+
+    /// Set the index of the step recorded with the next events.
+    pub fn set_step(&mut self, step: Option<usize>) {
+        *self.recorder.step.lock().unwrap() = step;
+    }
+
+    /// Events recorded so far, sorted by time.
+    pub fn records(&self) -> Vec<HarnessRecord> {
+        let mut records = self.recorder.records.lock().unwrap().clone();
+        records.sort_by_key(|record| record.elapsed_ms);
+        records
+    }
+
+    /// Run the steps of a script, then stop the function.
+    ///
+    /// If the function fails, the error is recorded, the script is
+    /// interrupted, and the error is returned; the events recorded until
+    /// then remain available with `records()`.
+    pub async fn run(&mut self, script: &HarnessScript) -> anyhow::Result<()> {
+        let res = self.run_steps(script).await;
+
+        // Let the last events emitted be delivered to the outputs.
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        res
+    }
+
+    async fn run_steps(&mut self, script: &HarnessScript) -> anyhow::Result<()> {
+        self.init(script.init_payload.as_deref(), script.serialized_state.as_deref()).await?;
+        for (i, step) in script.steps.iter().enumerate() {
+            self.set_step(Some(i));
+            match step {
+                HarnessStep::Cast { payload } => self.cast(payload).await?,
+                HarnessStep::Call { payload } => {
+                    self.call(payload).await?;
+                }
+                HarnessStep::Sleep { ms } => tokio::time::sleep(tokio::time::Duration::from_millis(*ms)).await,
+            }
+            // Let the events emitted during this step be recorded before the
+            // next one begins.
+            tokio::task::yield_now().await;
+        }
+        self.set_step(None);
+        self.stop().await
+    }
+}
+
+/// Run a script on a WASM function and return the events recorded.
+///
+/// The function is stopped after the last step. If the function fails, the
+/// script is interrupted and the error is returned.
+pub async fn run_script(code: &[u8], script: &HarnessScript) -> anyhow::Result<Vec<HarnessRecord>> {
+    let mut harness = FunctionHarness::new(code, &script.outputs, &script.call_replies, &script.annotations).await?;
+    harness.run(script).await?;
+    Ok(harness.records())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_harness_script_from_text() {
+        let script = HarnessScript::from_text(
+            r#"
+# comment
+output out
+output err
+reply out  {"ok": true}
+annotation max_fuel=1000
+init a=1,b=2
+cast 42
+call hello world
+sleep 100
+"#,
+        )
+        .unwrap();
+        assert_eq!(vec!["out".to_string(), "err".to_string()], script.outputs);
+        assert_eq!(Some(&r#"{"ok": true}"#.to_string()), script.call_replies.get("out"));
+        assert_eq!(Some(&"1000".to_string()), script.annotations.get("max_fuel"));
+        assert_eq!(Some("a=1,b=2".to_string()), script.init_payload);
+        assert_eq!(None, script.serialized_state);
+        assert_eq!(
+            vec![
+                HarnessStep::Cast { payload: "42".to_string() },
+                HarnessStep::Call {
+                    payload: "hello world".to_string()
+                },
+                HarnessStep::Sleep { ms: 100 }
+            ],
+            script.steps
+        );
+
+        assert!(HarnessScript::from_text("sleep forever").is_err());
+        assert!(HarnessScript::from_text("reply out").is_err());
+        assert!(HarnessScript::from_text("annotation max_fuel").is_err());
+        assert!(HarnessScript::from_text("invoke 42").is_err());
+    }
+
+    #[test]
+    fn test_harness_script_json() {
+        let script: HarnessScript = serde_json::from_str(
+            r#"{
+                "outputs": ["out"],
+                "steps": [{"type": "cast", "payload": "42"}, {"type": "sleep", "ms": 10}],
+                "expected": [{"event": "cast", "output": "out", "payload": "42"}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(vec!["out".to_string()], script.outputs);
+        assert_eq!(2, script.steps.len());
+        assert_eq!(1, script.expected.len());
+    }
+
+    #[test]
+    fn test_harness_unmatched_expectations() {
+        let record = |event| HarnessRecord {
+            elapsed_ms: 0,
+            step: None,
+            event,
+        };
+        let cast = |payload: &str| HarnessEvent::Cast {
+            output: "out".to_string(),
+            payload: payload.to_string(),
+        };
+        let records = vec![record(cast("1")), record(cast("2")), record(cast("3"))];
+
+        let mut script = HarnessScript::default();
+        assert!(script.unmatched_expectations(&records).is_empty());
+        script.expected = vec![cast("1"), cast("3")];
+        assert!(script.unmatched_expectations(&records).is_empty());
+        script.expected = vec![cast("3"), cast("1")];
+        assert_eq!(vec![cast("1")], script.unmatched_expectations(&records));
+        script.expected = vec![cast("4"), cast("1")];
+        assert_eq!(vec![cast("4"), cast("1")], script.unmatched_expectations(&records));
+    }
+
+    #[tokio::test]
+    async fn test_harness_run_script() {
+        let script = HarnessScript {
+            outputs: vec!["test".to_string()],
+            steps: vec![
+                HarnessStep::Cast {
+                    payload: "test_cast_output".to_string(),
+                },
+                HarnessStep::Cast {
+                    payload: "test_call_output".to_string(),
+                },
+                HarnessStep::Cast {
+                    payload: "test_cast_raw_output".to_string(),
+                },
+                HarnessStep::Call {
+                    payload: "test_ret".to_string(),
+                },
+                HarnessStep::Cast {
+                    payload: "test_delayed_cast_output".to_string(),
+                },
+                HarnessStep::Sleep { ms: 200 },
+            ],
+            expected: vec![
+                HarnessEvent::Log {
+                    level: "info".to_string(),
+                    target: "messaging_test".to_string(),
+                    message: "Messaging Test Init".to_string(),
+                },
+                HarnessEvent::Cast {
+                    output: "test".to_string(),
+                    payload: "cast_output".to_string(),
+                },
+                HarnessEvent::Call {
+                    output: "test".to_string(),
+                    payload: "call_output".to_string(),
+                },
+                HarnessEvent::Cast {
+                    output: SOURCE_OUTPUT.to_string(),
+                    payload: "cast_raw_output".to_string(),
+                },
+                HarnessEvent::Reply {
+                    payload: Some("test_reply".to_string()),
+                },
+                HarnessEvent::Cast {
+                    output: "test".to_string(),
+                    payload: "delayed_cast_output".to_string(),
+                },
+                HarnessEvent::Log {
+                    level: "info".to_string(),
+                    target: "messaging_test".to_string(),
+                    message: "Messaging Test Stop".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let records = run_script(include_bytes!("../../functions/messaging_test/messaging_test.wasm"), &script)
+            .await
+            .unwrap();
+        assert!(script.unmatched_expectations(&records).is_empty(), "{:?}", records);
+
+        let delayed = records
+            .iter()
+            .find(|record| {
+                record.event
+                    == HarnessEvent::Cast {
+                        output: "test".to_string(),
+                        payload: "delayed_cast_output".to_string(),
+                    }
+            })
+            .unwrap();
+        assert_eq!(Some(5), delayed.step);
+    }
+
+    #[tokio::test]
+    async fn test_harness_run_script_failure() {
+        // The function runs out of fuel during init.
+        let script = HarnessScript {
+            annotations: std::collections::HashMap::from([("max_fuel".to_string(), "1".to_string())]),
+            steps: vec![HarnessStep::Cast {
+                payload: "test_cast_output".to_string(),
+            }],
+            ..Default::default()
+        };
+        let code = include_bytes!("../../functions/messaging_test/messaging_test.wasm");
+        assert!(run_script(code, &script).await.is_err());
+
+        let mut harness = FunctionHarness::new(code, &script.outputs, &script.call_replies, &script.annotations)
+            .await
+            .unwrap();
+        assert!(harness.run(&script).await.is_err());
+        let records = harness.records();
+        assert!(matches!(records.last().unwrap().event, HarnessEvent::Error { .. }), "{:?}", records);
+    }
+}
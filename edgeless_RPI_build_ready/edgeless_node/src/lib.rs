@@ -7,7 +7,10 @@ use resources::resource_provider_specs::ResourceProviderSpecs;
 
 pub mod agent;
 pub mod base_runtime;
+#[cfg(feature = "container")]
 pub mod container_runner;
+#[cfg(feature = "wasmtime")]
+pub mod function_harness;
 pub mod gpu_info;
 pub mod node_subscriber;
 pub mod resources;
@@ -31,6 +34,8 @@ pub struct EdgelessNodeSettings {
     pub resources: Option<EdgelessNodeResourceSettings>,
     /// User-specific capabilities.
     pub user_node_capabilities: Option<NodeCapabilitiesUser>,
+    /// Function state settings. Only the node-local state is supported if not present.
+    pub state: Option<EdgelessNodeStateSettings>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -47,6 +52,14 @@ pub struct EdgelessNodeTelemetrySettings {
 pub struct EdgelessNodeWasmRuntimeSettings {
     /// True if WASM is enabled.
     pub enabled: bool,
+    /// If not empty, the WASM modules compiled by the wasmtime run-time are
+    /// also saved in this directory, so that the functions do not need to be
+    /// compiled again after a restart of the node.
+    pub module_cache_dir: Option<String>,
+    /// Maximum number of compiled WASM modules that are kept in memory when
+    /// no function instance uses them. If not specified, use a default value.
+    #[serde(default)]
+    pub module_cache_capacity: Option<usize>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -57,6 +70,16 @@ pub struct EdgelessNodeContainerRuntimeSettings {
     pub guest_api_host_url: String,
 }
 
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct EdgelessNodeStateSettings {
+    /// URL of the Redis server where the state of the functions with global
+    /// state policy is saved. The global state is not supported if empty.
+    pub global_redis_url: String,
+    /// If true, a node cannot write a global state that has been written by
+    /// another node after it was last read or written by this node.
+    pub global_compare_and_set: bool,
+}
+
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct EdgelessNodeGeneralSettings {
     /// The UUID of this node.
@@ -88,8 +111,20 @@ pub struct EdgelessNodeResourceSettings {
     /// If `http_ingress_provider` is not empty, this is the URL of the
     /// HTTP web server exposed by the http-ingress resource for this node.
     pub http_ingress_url: Option<String>,
+    /// Path of the PEM file with the TLS certificate (chain) of the web
+    /// servers of the http-ingress and websocket resources, required if the
+    /// scheme of `http_ingress_url` or `websocket_url` is https.
+    pub http_ingress_tls_cert: Option<String>,
+    /// Path of the PEM file with the TLS private key of the web servers of
+    /// the http-ingress and websocket resources, required if the scheme of
+    /// `http_ingress_url` or `websocket_url` is https.
+    pub http_ingress_tls_key: Option<String>,
     /// If not empty, a http-ingress resource provider with that name is created.
     pub http_ingress_provider: Option<String>,
+    /// Output channels, besides new_request, to which the http-ingress
+    /// resources can route requests by path prefix.
+    #[serde(default)]
+    pub http_ingress_outputs: Option<Vec<String>>,
     /// If not empty, a http-egress resource provider with that name is created.
     pub http_egress_provider: Option<String>,
     /// If not empty, a file-log resource provider with that name is created.
@@ -108,6 +143,39 @@ pub struct EdgelessNodeResourceSettings {
     /// The resource will connect to a remote Kafka server to stream the
     /// messages received on a given topic.
     pub kafka_egress_provider: Option<String>,
+    /// If not empty, a kafka-ingress resource provider with that name is created.
+    /// The resource will connect to a remote Kafka server to consume the
+    /// records of given topics and cast them to the workflow.
+    pub kafka_ingress_provider: Option<String>,
+    /// If not empty, a mqtt-ingress resource provider with that name is created.
+    /// The resource will subscribe to a topic on a remote MQTT broker and
+    /// cast the messages received to the workflow.
+    pub mqtt_ingress_provider: Option<String>,
+    /// If not empty, a mqtt-egress resource provider with that name is created.
+    /// The resource will publish the messages received on a topic of a
+    /// remote MQTT broker.
+    pub mqtt_egress_provider: Option<String>,
+    /// If not empty, a dataset-replay resource provider with that name is created.
+    /// The resource will read records from a node-local file and cast
+    /// them in batches to the workflow.
+    pub dataset_replay_provider: Option<String>,
+    /// If not empty, a timer resource provider with that name is created.
+    /// The resource will cast ticks to the workflow periodically or
+    /// according to a cron expression.
+    pub timer_provider: Option<String>,
+    /// If not empty, a tsdb-sink resource provider with that name is created.
+    /// The resource will write the messages received as points of a time
+    /// series to an InfluxDB server, a node-local file, or a node-local
+    /// SQLite database.
+    pub tsdb_sink_provider: Option<String>,
+    /// If `websocket_provider` is not empty, this is the URL of the
+    /// web server exposed by the websocket resource for this node.
+    pub websocket_url: Option<String>,
+    /// If not empty, a websocket resource provider with that name is created.
+    /// The resource will broadcast the messages received to the WebSocket
+    /// clients connected and cast the messages from the clients to the
+    /// workflow.
+    pub websocket_provider: Option<String>,
     /// The metrics collector settings.
     pub metrics_collector_provider: Option<MetricsCollectorProviderSettings>,
 }
@@ -133,6 +201,18 @@ pub struct MetricsCollectorProviderSettings {
     pub redis_url: Option<String>,
     /// If not empty, a metrics collector resource provider with that name is created.
     pub provider: String,
+    /// Upper bounds of the buckets of the latency histograms, in ms.
+    /// If not specified, default buckets from 1 ms to 10 s are used.
+    pub histogram_buckets: Option<Vec<f64>>,
+    /// Percentiles of the latency distributions.
+    /// If not specified, the 50th, 90th, 95th, and 99th are used.
+    pub percentiles: Option<Vec<f64>>,
+    /// If specified, the latency statistics are exposed to Prometheus at
+    /// /metrics on a web server with this URL, e.g., http://0.0.0.0:7005.
+    pub prometheus_url: Option<String>,
+    /// If specified, the latency statistics of all the epochs are saved
+    /// to this file in CSV format.
+    pub csv_filename: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -241,23 +321,36 @@ async fn fill_resources(
             if !http_ingress_url.is_empty() && !provider_id.is_empty() {
                 let class_type = resources::http_ingress::HttpIngressResourceSpec {}.class_type();
                 log::info!("Creating http-ingress resource provider '{}' at {}", provider_id, http_ingress_url);
-                ret.insert(
-                    provider_id.clone(),
-                    agent::ResourceDesc {
-                        class_type: class_type.clone(),
-                        client: resources::http_ingress::ingress_task(
-                            data_plane.clone(),
-                            edgeless_api::function_instance::InstanceId::new(node_id),
-                            http_ingress_url.clone(),
-                        )
-                        .await,
-                    },
-                );
-                provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
-                    provider_id: provider_id.clone(),
-                    class_type,
-                    outputs: resources::http_ingress::HttpIngressResourceSpec {}.outputs(),
-                });
+                let tls_cert_key = match (&settings.http_ingress_tls_cert, &settings.http_ingress_tls_key) {
+                    (Some(cert), Some(key)) if !cert.is_empty() && !key.is_empty() => Some((cert.clone(), key.clone())),
+                    _ => None,
+                };
+                let routed_outputs = settings.http_ingress_outputs.clone().unwrap_or_default();
+                match resources::http_ingress::ingress_task(
+                    data_plane.clone(),
+                    edgeless_api::function_instance::InstanceId::new(node_id),
+                    http_ingress_url.clone(),
+                    tls_cert_key,
+                    routed_outputs.clone(),
+                )
+                .await
+                {
+                    Ok(client) => {
+                        ret.insert(
+                            provider_id.clone(),
+                            agent::ResourceDesc {
+                                class_type: class_type.clone(),
+                                client,
+                            },
+                        );
+                        provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                            provider_id: provider_id.clone(),
+                            class_type,
+                            outputs: [resources::http_ingress::HttpIngressResourceSpec {}.outputs(), routed_outputs].concat(),
+                        });
+                    }
+                    Err(err) => log::error!("Could not create resource provider '{}': {}", provider_id, err),
+                }
             }
         }
 
@@ -427,6 +520,199 @@ async fn fill_resources(
             }
         }
 
+        if let Some(provider_id) = &settings.kafka_ingress_provider {
+            if !provider_id.is_empty() {
+                #[cfg(feature = "rdkafka")]
+                {
+                    log::info!("Creating kafka-ingress resource provider '{}'", provider_id);
+                    let class_type = resources::kafka_ingress::KafkaIngressResourceSpec {}.class_type();
+                    ret.insert(
+                        provider_id.clone(),
+                        agent::ResourceDesc {
+                            class_type: class_type.clone(),
+                            client: Box::new(
+                                resources::kafka_ingress::KafkaIngressResourceProvider::new(
+                                    data_plane.clone(),
+                                    edgeless_api::function_instance::InstanceId::new(node_id),
+                                )
+                                .await,
+                            ),
+                        },
+                    );
+                    provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                        provider_id: provider_id.clone(),
+                        class_type,
+                        outputs: resources::kafka_ingress::KafkaIngressResourceSpec {}.outputs(),
+                    });
+                }
+                #[cfg(not(feature = "rdkafka"))]
+                log::error!(
+                    "Could not create resource provider '{}' because rdkafka was disabled at compile time",
+                    provider_id
+                );
+            }
+        }
+
+        if let Some(provider_id) = &settings.mqtt_ingress_provider {
+            if !provider_id.is_empty() {
+                log::info!("Creating mqtt-ingress resource provider '{}'", provider_id);
+                let class_type = resources::mqtt_ingress::MqttIngressResourceSpec {}.class_type();
+                ret.insert(
+                    provider_id.clone(),
+                    agent::ResourceDesc {
+                        class_type: class_type.clone(),
+                        client: Box::new(
+                            resources::mqtt_ingress::MqttIngressResourceProvider::new(
+                                data_plane.clone(),
+                                edgeless_api::function_instance::InstanceId::new(node_id),
+                            )
+                            .await,
+                        ),
+                    },
+                );
+                provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                    provider_id: provider_id.clone(),
+                    class_type,
+                    outputs: resources::mqtt_ingress::MqttIngressResourceSpec {}.outputs(),
+                });
+            }
+        }
+
+        if let Some(provider_id) = &settings.mqtt_egress_provider {
+            if !provider_id.is_empty() {
+                log::info!("Creating mqtt-egress resource provider '{}'", provider_id);
+                let class_type = resources::mqtt_egress::MqttEgressResourceSpec {}.class_type();
+                ret.insert(
+                    provider_id.clone(),
+                    agent::ResourceDesc {
+                        class_type: class_type.clone(),
+                        client: Box::new(
+                            resources::mqtt_egress::MqttEgressResourceProvider::new(
+                                data_plane.clone(),
+                                edgeless_api::function_instance::InstanceId::new(node_id),
+                            )
+                            .await,
+                        ),
+                    },
+                );
+                provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                    provider_id: provider_id.clone(),
+                    class_type,
+                    outputs: resources::mqtt_egress::MqttEgressResourceSpec {}.outputs(),
+                });
+            }
+        }
+
+        if let Some(provider_id) = &settings.dataset_replay_provider {
+            if !provider_id.is_empty() {
+                log::info!("Creating dataset-replay resource provider '{}'", provider_id);
+                let class_type = resources::dataset_replay::DatasetReplayResourceSpec {}.class_type();
+                ret.insert(
+                    provider_id.clone(),
+                    agent::ResourceDesc {
+                        class_type: class_type.clone(),
+                        client: Box::new(
+                            resources::dataset_replay::DatasetReplayResourceProvider::new(
+                                data_plane.clone(),
+                                edgeless_api::function_instance::InstanceId::new(node_id),
+                            )
+                            .await,
+                        ),
+                    },
+                );
+                provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                    provider_id: provider_id.clone(),
+                    class_type,
+                    outputs: resources::dataset_replay::DatasetReplayResourceSpec {}.outputs(),
+                });
+            }
+        }
+
+        if let Some(provider_id) = &settings.timer_provider {
+            if !provider_id.is_empty() {
+                log::info!("Creating timer resource provider '{}'", provider_id);
+                let class_type = resources::timer::TimerResourceSpec {}.class_type();
+                ret.insert(
+                    provider_id.clone(),
+                    agent::ResourceDesc {
+                        class_type: class_type.clone(),
+                        client: Box::new(
+                            resources::timer::TimerResourceProvider::new(
+                                data_plane.clone(),
+                                edgeless_api::function_instance::InstanceId::new(node_id),
+                            )
+                            .await,
+                        ),
+                    },
+                );
+                provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                    provider_id: provider_id.clone(),
+                    class_type,
+                    outputs: resources::timer::TimerResourceSpec {}.outputs(),
+                });
+            }
+        }
+
+        if let Some(provider_id) = &settings.tsdb_sink_provider {
+            if !provider_id.is_empty() {
+                log::info!("Creating tsdb-sink resource provider '{}'", provider_id);
+                let class_type = resources::tsdb_sink::TsdbSinkResourceSpec {}.class_type();
+                ret.insert(
+                    provider_id.clone(),
+                    agent::ResourceDesc {
+                        class_type: class_type.clone(),
+                        client: Box::new(
+                            resources::tsdb_sink::TsdbSinkResourceProvider::new(
+                                data_plane.clone(),
+                                edgeless_api::function_instance::InstanceId::new(node_id),
+                            )
+                            .await,
+                        ),
+                    },
+                );
+                provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                    provider_id: provider_id.clone(),
+                    class_type,
+                    outputs: resources::tsdb_sink::TsdbSinkResourceSpec {}.outputs(),
+                });
+            }
+        }
+
+        if let (Some(websocket_url), Some(provider_id)) = (&settings.websocket_url, &settings.websocket_provider) {
+            if !websocket_url.is_empty() && !provider_id.is_empty() {
+                let class_type = resources::websocket::WebSocketResourceSpec {}.class_type();
+                log::info!("Creating websocket resource provider '{}' at {}", provider_id, websocket_url);
+                let tls_cert_key = match (&settings.http_ingress_tls_cert, &settings.http_ingress_tls_key) {
+                    (Some(cert), Some(key)) if !cert.is_empty() && !key.is_empty() => Some((cert.clone(), key.clone())),
+                    _ => None,
+                };
+                match resources::websocket::WebSocketResourceProvider::new(
+                    data_plane.clone(),
+                    edgeless_api::function_instance::InstanceId::new(node_id),
+                    websocket_url.clone(),
+                    tls_cert_key,
+                )
+                .await
+                {
+                    Ok(provider) => {
+                        ret.insert(
+                            provider_id.clone(),
+                            agent::ResourceDesc {
+                                class_type: class_type.clone(),
+                                client: Box::new(provider),
+                            },
+                        );
+                        provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                            provider_id: provider_id.clone(),
+                            class_type,
+                            outputs: resources::websocket::WebSocketResourceSpec {}.outputs(),
+                        });
+                    }
+                    Err(err) => log::error!("Could not create resource provider '{}': {}", provider_id, err),
+                }
+            }
+        }
+
         if let Some(settings) = &settings.metrics_collector_provider {
             if !settings.provider.is_empty() {
                 match settings.collector_type.to_lowercase().as_str() {
@@ -441,27 +727,41 @@ async fn fill_resources(
                                             settings.provider,
                                             redis_url
                                         );
-                                        ret.insert(
-                                            settings.provider.clone(),
-                                            agent::ResourceDesc {
-                                                class_type: class_type.clone(),
-                                                client: Box::new(
-                                                    resources::metrics_collector::MetricsCollectorResourceProvider::new(
-                                                        data_plane.clone(),
-                                                        edgeless_api::function_instance::InstanceId::new(node_id),
-                                                        redis_connection,
-                                                    )
-                                                    .await,
-                                                ),
-                                            },
-                                        );
-                                        provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
-                                            provider_id: settings.provider.clone(),
-                                            class_type,
-                                            outputs: vec![],
-                                        });
+                                        let mut options = resources::metrics_collector::LatencyStatisticsOptions::default();
+                                        if let Some(buckets) = &settings.histogram_buckets {
+                                            options.buckets = buckets.clone();
+                                        }
+                                        if let Some(percentiles) = &settings.percentiles {
+                                            options.percentiles = percentiles.clone();
+                                        }
+                                        options.csv_filename = settings.csv_filename.clone().filter(|x| !x.is_empty());
+                                        match resources::metrics_collector::MetricsCollectorResourceProvider::new(
+                                            data_plane.clone(),
+                                            edgeless_api::function_instance::InstanceId::new(node_id),
+                                            redis_connection,
+                                            options,
+                                            settings.prometheus_url.clone().filter(|x| !x.is_empty()),
+                                        )
+                                        .await
+                                        {
+                                            Ok(provider) => {
+                                                ret.insert(
+                                                    settings.provider.clone(),
+                                                    agent::ResourceDesc {
+                                                        class_type: class_type.clone(),
+                                                        client: Box::new(provider),
+                                                    },
+                                                );
+                                                provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                                                    provider_id: settings.provider.clone(),
+                                                    class_type,
+                                                    outputs: vec![],
+                                                });
 
-                                        log::info!("metrics collector connected to Redis at {}", redis_url);
+                                                log::info!("metrics collector connected to Redis at {}", redis_url);
+                                            }
+                                            Err(err) => log::error!("Could not create resource provider '{}': {}", settings.provider, err),
+                                        }
                                     }
                                     Err(err) => log::error!("error when connecting to Redis at {}: {}", redis_url, err),
                                 },
@@ -488,7 +788,7 @@ pub async fn edgeless_node_main(settings: EdgelessNodeSettings) {
     log::debug!("Settings: {:?}", settings);
 
     // Create the state manager.
-    let state_manager = Box::new(state_management::StateManager::new().await);
+    let state_manager = Box::new(state_management::StateManager::new_with_settings(settings.state.clone()).await);
 
     // Create the data plane.
     let data_plane = edgeless_dataplane::handle::DataplaneProvider::new(
@@ -530,6 +830,16 @@ pub async fn edgeless_node_main(settings: EdgelessNodeSettings) {
                     #[allow(unused_variables)]
                     #[cfg(feature = "wasmtime")]
                     {
+                        let module_cache = std::sync::Arc::new(crate::wasm_runner::module_cache::ModuleCache::new(
+                            wasm_runtime_settings
+                                .module_cache_dir
+                                .clone()
+                                .filter(|x| !x.is_empty())
+                                .map(std::path::PathBuf::from),
+                            wasm_runtime_settings
+                                .module_cache_capacity
+                                .unwrap_or(crate::wasm_runner::module_cache::DEFAULT_CAPACITY),
+                        ));
                         let (wasmtime_runtime_client, mut wasmtime_runtime_task_s) =
                             base_runtime::runtime::create::<wasm_runner::function_instance::WASMFunctionInstance>(
                                 data_plane.clone(),
@@ -540,6 +850,7 @@ pub async fn edgeless_node_main(settings: EdgelessNodeSettings) {
                                     ("NODE_ID".to_string(), settings.general.node_id.to_string()),
                                 ]))),
                                 std::sync::Arc::new(tokio::sync::Mutex::new(Box::new(crate::wasm_runner::runtime::WasmRuntime::new()))),
+                                module_cache,
                             );
                         runners.insert("RUST_WASM".to_string(), Box::new(wasmtime_runtime_client.clone()));
                         tokio::spawn(async move {
@@ -560,6 +871,7 @@ pub async fn edgeless_node_main(settings: EdgelessNodeSettings) {
                                 ("NODE_ID".to_string(), settings.general.node_id.to_string()),
                             ]))),
                             std::sync::Arc::new(tokio::sync::Mutex::new(Box::new(crate::wasmi_runner::runtime::WasmiRuntime::new()))),
+                            (),
                         );
                         runners.insert("RUST_WASM".to_string(), Box::new(wasmi_runtime_client.clone()));
                         tokio::spawn(async move {
@@ -577,28 +889,40 @@ pub async fn edgeless_node_main(settings: EdgelessNodeSettings) {
     let container_runtime_task = match settings.container_runtime {
         Some(container_runtime_settings) => match container_runtime_settings.enabled {
             true => {
-                let (container_runtime, container_runtime_task, container_runtime_api) = container_runner::container_runtime::ContainerRuntime::new(
-                    std::collections::HashMap::from([("guest_api_host_url".to_string(), container_runtime_settings.guest_api_host_url.clone())]),
-                );
-                let server_task = edgeless_api::grpc_impl::outer::container_runtime::GuestAPIHostServer::run(
-                    container_runtime_api,
-                    container_runtime_settings.guest_api_host_url,
-                );
-
-                let (container_runtime_client, mut container_runtime_task_s) =
-                    base_runtime::runtime::create::<container_runner::function_instance::ContainerFunctionInstance>(
-                        data_plane.clone(),
-                        state_manager.clone(),
-                        Box::new(telemetry_provider.get_handle(std::collections::BTreeMap::from([
-                            ("FUNCTION_TYPE".to_string(), "CONTAINER".to_string()),
-                            ("NODE_ID".to_string(), settings.general.node_id.to_string()),
-                        ]))),
-                        container_runtime.clone(),
+                #[cfg(feature = "container")]
+                {
+                    let (container_runtime, container_runtime_task, container_runtime_api) =
+                        container_runner::container_runtime::ContainerRuntime::new(std::collections::HashMap::from([(
+                            "guest_api_host_url".to_string(),
+                            container_runtime_settings.guest_api_host_url.clone(),
+                        )]));
+                    let server_task = edgeless_api::grpc_impl::outer::container_runtime::GuestAPIHostServer::run(
+                        container_runtime_api,
+                        container_runtime_settings.guest_api_host_url,
                     );
-                runners.insert("CONTAINER".to_string(), Box::new(container_runtime_client.clone()));
-                tokio::spawn(async move {
-                    futures::join!(container_runtime_task_s.run(), container_runtime_task, server_task);
-                })
+
+                    let (container_runtime_client, mut container_runtime_task_s) =
+                        base_runtime::runtime::create::<container_runner::function_instance::ContainerFunctionInstance>(
+                            data_plane.clone(),
+                            state_manager.clone(),
+                            Box::new(telemetry_provider.get_handle(std::collections::BTreeMap::from([
+                                ("FUNCTION_TYPE".to_string(), "CONTAINER".to_string()),
+                                ("NODE_ID".to_string(), settings.general.node_id.to_string()),
+                            ]))),
+                            container_runtime.clone(),
+                            (),
+                        );
+                    runners.insert("CONTAINER".to_string(), Box::new(container_runtime_client.clone()));
+                    tokio::spawn(async move {
+                        futures::join!(container_runtime_task_s.run(), container_runtime_task, server_task);
+                    })
+                }
+
+                #[cfg(not(feature = "container"))]
+                {
+                    log::error!("Container run-time disabled at compile time");
+                    tokio::spawn(async {})
+                }
             }
             false => tokio::spawn(async {}),
         },
@@ -663,6 +987,8 @@ performance_samples = true
 
 [wasm_runtime]
 enabled = true
+module_cache_dir = ""
+module_cache_capacity = 16
 
 [container_runtime]
 enabled = false
@@ -671,11 +997,20 @@ guest_api_host_url = "http://127.0.0.1:7100"
 [resources]
 http_ingress_url = "http://127.0.0.1:7035"
 http_ingress_provider = "http-ingress-1"
+http_ingress_outputs = []
 http_egress_provider = "http-egress-1"
 file_log_provider = "file-log-1"
 redis_provider = "redis-1"
 dda_provider = "dda-1"
 kafka_egress_provider = "kafka-egress-1"
+kafka_ingress_provider = "kafka-ingress-1"
+mqtt_ingress_provider = "mqtt-ingress-1"
+mqtt_egress_provider = "mqtt-egress-1"
+dataset_replay_provider = "dataset-replay-1"
+timer_provider = "timer-1"
+tsdb_sink_provider = "tsdb-sink-1"
+websocket_url = "http://127.0.0.1:7040"
+websocket_provider = "websocket-1"
 
 #[resources.ollama_provider]
 #host = "localhost"
@@ -687,6 +1022,14 @@ kafka_egress_provider = "kafka-egress-1"
 #collector_type = "Redis"
 #redis_url = "redis://localhost:6379"
 #provider = "metrics-collector-1"
+#histogram_buckets = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0]
+#percentiles = [50.0, 90.0, 95.0, 99.0]
+#prometheus_url = "http://127.0.0.1:7005"
+#csv_filename = "latency-statistics.csv"
+
+#[state]
+#global_redis_url = "redis://127.0.0.1:6379/1"
+#global_compare_and_set = false
 
 [user_node_capabilities]
 "##,
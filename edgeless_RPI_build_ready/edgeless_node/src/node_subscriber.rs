@@ -104,8 +104,19 @@ impl NodeSubscriber {
                         refresh_deadline: std::time::SystemTime::now() + std::time::Duration::from_secs(subscription_refresh_interval_sec * 2),
                         nonce,
                         health_status: Self::get_health_status(&mut sys, &mut networks, &mut disks, own_pid),
-                        performance_samples: edgeless_api::node_registration::NodePerformanceSamples {
-                            function_execution_times: telemetry_performance_target.get_metrics().function_execution_times,
+                        performance_samples: {
+                            let metrics = telemetry_performance_target.get_metrics();
+                            edgeless_api::node_registration::NodePerformanceSamples {
+                                function_execution_times: metrics.function_execution_times,
+                                function_errors: metrics.function_errors,
+                                function_restarts: metrics.function_restarts,
+                                function_logs: metrics
+                                    .function_logs
+                                    .into_iter()
+                                    .map(|(function_id, entries)| (function_id, entries.into()))
+                                    .collect(),
+                                function_exits: metrics.function_exits,
+                            }
                         },
                     };
                     match client.node_registration_api().update_node(update_node_request).await {
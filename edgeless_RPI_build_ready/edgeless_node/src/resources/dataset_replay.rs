@@ -0,0 +1,634 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+pub struct DatasetReplayResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for DatasetReplayResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("dataset-replay")
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![String::from("new_batch")]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (
+                String::from("filename"),
+                String::from("Name of the node-local file containing the records, one per line"),
+            ),
+            (
+                String::from("format"),
+                String::from("Format of the file: csv (with header) or jsonl, by default derived from the file extension"),
+            ),
+            (
+                String::from("batch_size"),
+                String::from("Number of records cast in every batch (default: 1)"),
+            ),
+            (
+                String::from("timing"),
+                String::from("original (default): batches are cast with the timing of the records; rate: batches are cast at a fixed rate"),
+            ),
+            (
+                String::from("speedup"),
+                String::from("Speed-up factor applied to the original timing (default: 1)"),
+            ),
+            (String::from("rate"), String::from("Number of batches cast per second with rate timing")),
+            (
+                String::from("timestamp_field"),
+                String::from("Name of the field containing the timestamp of a record (default: timestamp)"),
+            ),
+            (String::from("time_unit"), String::from("Unit of the timestamps: s, ms (default), or us")),
+            (
+                String::from("loop"),
+                String::from("If true, restart from the first record at the end of the file, which requires batches not all cast at the same time (default: false)"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+/// Format of the file containing the records to be replayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DatasetFormat {
+    /// Comma-separated values, with a header line containing the field
+    /// names. Quoted fields are not supported.
+    Csv,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+/// Timing of the batches cast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayTiming {
+    /// Use the timestamps of the records, divided by a speed-up factor.
+    Original(f64),
+    /// Cast the given number of batches per second.
+    Rate(f64),
+}
+
+/// Configuration of a dataset-replay resource instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetReplayConfiguration {
+    pub filename: String,
+    pub format: DatasetFormat,
+    pub batch_size: usize,
+    pub timing: ReplayTiming,
+    pub timestamp_field: String,
+    // Duration of one unit of the timestamps, in s.
+    pub time_unit: f64,
+    pub looping: bool,
+}
+
+impl DatasetReplayConfiguration {
+    /// Parse the run-time configuration of a resource instance.
+    pub fn from(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let get = |key: &str| configuration.get(key).filter(|x| !x.is_empty());
+        let parse_positive = |key: &str, default: f64| -> anyhow::Result<f64> {
+            match get(key) {
+                Some(value) => match value.parse::<f64>() {
+                    Ok(value) if value > 0.0 && value.is_finite() => Ok(value),
+                    _ => anyhow::bail!("Invalid {}: {}", key, value),
+                },
+                None => Ok(default),
+            }
+        };
+
+        let filename = match get("filename") {
+            Some(filename) => filename.clone(),
+            None => anyhow::bail!("Missing filename"),
+        };
+        let format = match get("format").map(|x| x.to_lowercase()) {
+            Some(format) => match format.as_str() {
+                "csv" => DatasetFormat::Csv,
+                "jsonl" | "json" => DatasetFormat::JsonLines,
+                _ => anyhow::bail!("Invalid format: {}", format),
+            },
+            None => match filename.to_lowercase().ends_with(".csv") {
+                true => DatasetFormat::Csv,
+                false => DatasetFormat::JsonLines,
+            },
+        };
+        let batch_size = match get("batch_size") {
+            Some(value) => match value.parse::<usize>() {
+                Ok(value) if value > 0 => value,
+                _ => anyhow::bail!("Invalid batch_size: {}", value),
+            },
+            None => 1,
+        };
+        let timing = match get("timing").map(|x| x.to_lowercase()) {
+            None => ReplayTiming::Original(parse_positive("speedup", 1.0)?),
+            Some(timing) => match timing.as_str() {
+                "original" => ReplayTiming::Original(parse_positive("speedup", 1.0)?),
+                "rate" => match get("rate") {
+                    Some(value) => {
+                        let rate = parse_positive("rate", 1.0)?;
+                        // The interval between consecutive batches must be representable.
+                        if duration_from_secs(1.0 / rate).is_err() {
+                            anyhow::bail!("Invalid rate: {}", value);
+                        }
+                        ReplayTiming::Rate(rate)
+                    }
+                    None => anyhow::bail!("Missing rate"),
+                },
+                _ => anyhow::bail!("Invalid timing: {}", timing),
+            },
+        };
+        let time_unit = match get("time_unit").map(|x| x.as_str()) {
+            Some("s") => 1.0,
+            None | Some("ms") => 1e-3,
+            Some("us") => 1e-6,
+            Some(value) => anyhow::bail!("Invalid time_unit: {}", value),
+        };
+        let looping = match get("loop").map(|x| x.to_lowercase()) {
+            None => false,
+            Some(value) => match value.as_str() {
+                "false" => false,
+                "true" => true,
+                _ => anyhow::bail!("Invalid loop: {}", value),
+            },
+        };
+
+        Ok(Self {
+            filename,
+            format,
+            batch_size,
+            timing,
+            timestamp_field: get("timestamp_field").cloned().unwrap_or(String::from("timestamp")),
+            time_unit,
+            looping,
+        })
+    }
+}
+
+/// Convert a number of seconds into a duration, failing if the number is
+/// negative, not finite, or too large.
+fn duration_from_secs(secs: f64) -> anyhow::Result<std::time::Duration> {
+    std::time::Duration::try_from_secs_f64(secs).map_err(|_| anyhow::anyhow!("Invalid time interval: {} s", secs))
+}
+
+/// Parse the records contained in a dataset, skipping empty lines.
+pub fn parse_records(content: &str, format: DatasetFormat) -> anyhow::Result<Vec<serde_json::Map<String, serde_json::Value>>> {
+    let mut lines = content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let mut records = vec![];
+    match format {
+        DatasetFormat::Csv => {
+            let header: Vec<String> = match lines.next() {
+                Some((_, line)) => line.split(',').map(|x| x.trim().to_string()).collect(),
+                None => return Ok(records),
+            };
+            for (num, line) in lines {
+                let values: Vec<&str> = line.split(',').map(|x| x.trim()).collect();
+                if values.len() != header.len() {
+                    anyhow::bail!(
+                        "Invalid number of fields at line {}: {} instead of {}",
+                        num + 1,
+                        values.len(),
+                        header.len()
+                    );
+                }
+                records.push(
+                    header
+                        .iter()
+                        .zip(values)
+                        .map(|(name, value)| {
+                            let value = match value.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+                                Some(number) => serde_json::Value::Number(number),
+                                None => serde_json::Value::String(value.to_string()),
+                            };
+                            (name.clone(), value)
+                        })
+                        .collect(),
+                );
+            }
+        }
+        DatasetFormat::JsonLines => {
+            for (num, line) in lines {
+                match serde_json::from_str::<serde_json::Value>(line) {
+                    Ok(serde_json::Value::Object(record)) => records.push(record),
+                    Ok(_) => anyhow::bail!("Not a JSON object at line {}", num + 1),
+                    Err(err) => anyhow::bail!("Invalid JSON at line {}: {}", num + 1, err),
+                }
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Batch of records to be cast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    /// Time at which the batch must be cast, since the beginning of the replay.
+    pub offset: std::time::Duration,
+    pub records: Vec<serde_json::Value>,
+}
+
+/// Split the records into batches and compute the time at which each batch
+/// must be cast, relative to the first one.
+pub fn make_batches(
+    records: Vec<serde_json::Map<String, serde_json::Value>>,
+    configuration: &DatasetReplayConfiguration,
+) -> anyhow::Result<Vec<Batch>> {
+    let mut batches = vec![];
+    let mut first_timestamp = None;
+    let mut last_offset = std::time::Duration::ZERO;
+    let mut records = records.into_iter().peekable();
+    while records.peek().is_some() {
+        let records: Vec<serde_json::Map<String, serde_json::Value>> = records.by_ref().take(configuration.batch_size).collect();
+        let offset = match configuration.timing {
+            ReplayTiming::Original(speedup) => {
+                let timestamp = match records[0].get(&configuration.timestamp_field).and_then(|x| x.as_f64()) {
+                    Some(timestamp) => timestamp,
+                    None => anyhow::bail!(
+                        "Missing or invalid timestamp field '{}' in record #{}",
+                        configuration.timestamp_field,
+                        batches.len() * configuration.batch_size
+                    ),
+                };
+                let elapsed = (timestamp - *first_timestamp.get_or_insert(timestamp)) * configuration.time_unit / speedup;
+                // Records out of order are cast immediately after the previous ones.
+                std::cmp::max(last_offset, duration_from_secs(elapsed.max(0.0))?)
+            }
+            ReplayTiming::Rate(rate) => duration_from_secs(batches.len() as f64 / rate)?,
+        };
+        last_offset = offset;
+        batches.push(Batch {
+            offset,
+            records: records.into_iter().map(serde_json::Value::Object).collect(),
+        });
+    }
+    Ok(batches)
+}
+
+#[derive(Clone)]
+pub struct DatasetReplayResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<DatasetReplayResourceProviderInner>>,
+}
+
+pub struct DatasetReplayResourceProviderInner {
+    resource_provider_id: edgeless_api::function_instance::InstanceId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, DatasetReplayResource>,
+}
+
+pub struct DatasetReplayResource {
+    // Component receiving the batches on the new_batch output channel.
+    target_sender: tokio::sync::watch::Sender<Option<edgeless_api::function_instance::InstanceId>>,
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for DatasetReplayResource {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+impl DatasetReplayResource {
+    /// Create a new dataset-replay resource, which reads the records from
+    /// a file and casts them in batches to the component mapped to the
+    /// new_batch output channel, as soon as the latter becomes known.
+    ///
+    /// Every batch is a JSON object with the sequence number of the batch
+    /// (`batch_id`) and the array of records (`batch`).
+    ///
+    /// - `dataplane_handle`: gives access to the EDGELESS dataplane.
+    /// - `configuration`: the configuration of the resource instance.
+    async fn new(dataplane_handle: edgeless_dataplane::handle::DataplaneHandle, configuration: DatasetReplayConfiguration) -> anyhow::Result<Self> {
+        let mut dataplane_handle = dataplane_handle;
+
+        let content = tokio::fs::read_to_string(&configuration.filename).await?;
+        let batches = make_batches(parse_records(&content, configuration.format)?, &configuration)?;
+        if batches.is_empty() {
+            anyhow::bail!("No records found in file: {}", configuration.filename);
+        }
+
+        // Space out the last batch and the first one of the next round as
+        // consecutive batches, when looping.
+        let gap = match configuration.timing {
+            ReplayTiming::Original(_) => match batches.len() {
+                1 => std::time::Duration::ZERO,
+                n => batches[n - 1].offset.div_f64((n - 1) as f64),
+            },
+            ReplayTiming::Rate(rate) => duration_from_secs(1.0 / rate)?,
+        };
+        // Otherwise, the batches would be cast endlessly without pause.
+        if configuration.looping && gap.is_zero() {
+            anyhow::bail!(
+                "Cannot loop over batches all cast at the same time, e.g., records with the same timestamp: {}",
+                configuration.filename
+            );
+        }
+
+        log::info!(
+            "DatasetReplayResource created, reading from file: {}, {} batches, timing: {:?}, loop: {}",
+            configuration.filename,
+            batches.len(),
+            configuration.timing,
+            configuration.looping
+        );
+
+        let (target_sender, mut target_receiver) = tokio::sync::watch::channel(None);
+
+        let handle = tokio::spawn(async move {
+            // Do not start the replay until the target is known.
+            if target_receiver.wait_for(|target| target.is_some()).await.is_err() {
+                return;
+            }
+
+            let mut batch_id: u64 = 0;
+            loop {
+                let start = tokio::time::Instant::now();
+                for batch in &batches {
+                    tokio::time::sleep_until(start + batch.offset).await;
+                    let payload = serde_json::json!({"batch_id": batch_id, "batch": batch.records});
+                    let target = *target_receiver.borrow();
+                    if let Some(target) = target {
+                        dataplane_handle.send(target, payload.to_string()).await;
+                    }
+                    batch_id += 1;
+                }
+                if !configuration.looping {
+                    log::info!("Replay of {} completed", configuration.filename);
+                    break;
+                }
+                tokio::time::sleep(gap).await;
+            }
+        });
+
+        Ok(Self {
+            target_sender,
+            join_handle: handle,
+        })
+    }
+}
+
+impl DatasetReplayResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        resource_provider_id: edgeless_api::function_instance::InstanceId,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(DatasetReplayResourceProviderInner {
+                resource_provider_id,
+                dataplane_provider,
+                instances: std::collections::HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for DatasetReplayResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let configuration = match DatasetReplayConfiguration::from(&instance_specification.configuration) {
+            Ok(configuration) => configuration,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
+            }
+        };
+
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+
+        match DatasetReplayResource::new(dataplane_handle, configuration).await {
+            Ok(resource) => {
+                lck.instances.insert(new_id.function_id, resource);
+                Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+            }
+            Err(err) => Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Invalid resource configuration".to_string(),
+                    detail: Some(err.to_string()),
+                },
+            )),
+        }
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        // Find the target component to which we have to send the batches
+        // on the "new_batch" output channel.
+        let target = match update.output_mapping.get("new_batch") {
+            Some(val) => *val,
+            None => {
+                anyhow::bail!("Missing mapping of channel: new_batch");
+            }
+        };
+
+        let lck = self.inner.lock().await;
+        match lck.instances.get(&update.function_id) {
+            Some(resource) => {
+                resource.target_sender.send_replace(Some(target));
+                Ok(())
+            }
+            None => {
+                anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    #[test]
+    fn test_dataset_replay_configuration() {
+        assert!(DatasetReplayConfiguration::from(&configuration(&[])).is_err());
+
+        let conf = DatasetReplayConfiguration::from(&configuration(&[("filename", "trace.csv")])).unwrap();
+        assert_eq!(
+            DatasetReplayConfiguration {
+                filename: "trace.csv".to_string(),
+                format: DatasetFormat::Csv,
+                batch_size: 1,
+                timing: ReplayTiming::Original(1.0),
+                timestamp_field: "timestamp".to_string(),
+                time_unit: 1e-3,
+                looping: false,
+            },
+            conf
+        );
+
+        let conf = DatasetReplayConfiguration::from(&configuration(&[
+            ("filename", "trace.txt"),
+            ("batch_size", "100"),
+            ("timing", "rate"),
+            ("rate", "2.5"),
+            ("timestamp_field", "t"),
+            ("time_unit", "s"),
+            ("loop", "true"),
+        ]))
+        .unwrap();
+        assert_eq!(DatasetFormat::JsonLines, conf.format);
+        assert_eq!(100, conf.batch_size);
+        assert_eq!(ReplayTiming::Rate(2.5), conf.timing);
+        assert_eq!("t", conf.timestamp_field);
+        assert_eq!(1.0, conf.time_unit);
+        assert!(conf.looping);
+
+        let conf = DatasetReplayConfiguration::from(&configuration(&[("filename", "trace.csv"), ("format", "jsonl"), ("speedup", "10")])).unwrap();
+        assert_eq!(DatasetFormat::JsonLines, conf.format);
+        assert_eq!(ReplayTiming::Original(10.0), conf.timing);
+
+        for wrong in [
+            ("format", "xml"),
+            ("batch_size", "0"),
+            ("timing", "fast"),
+            ("speedup", "-1"),
+            ("speedup", "NaN"),
+            ("speedup", "inf"),
+            ("time_unit", "h"),
+            ("loop", "yes"),
+        ] {
+            assert!(DatasetReplayConfiguration::from(&configuration(&[("filename", "trace.csv"), wrong])).is_err());
+        }
+        assert!(DatasetReplayConfiguration::from(&configuration(&[("filename", "trace.csv"), ("timing", "rate")])).is_err());
+        for wrong_rate in ["0", "-1", "NaN", "inf", "1e-300"] {
+            assert!(
+                DatasetReplayConfiguration::from(&configuration(&[("filename", "trace.csv"), ("timing", "rate"), ("rate", wrong_rate)])).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_dataset_replay_parse_records() {
+        let records = parse_records("timestamp,x,label\n0,1.5,walk\n\n10,-2,run\n", DatasetFormat::Csv).unwrap();
+        assert_eq!(
+            vec![
+                serde_json::json!({"timestamp": 0.0, "x": 1.5, "label": "walk"}),
+                serde_json::json!({"timestamp": 10.0, "x": -2.0, "label": "run"})
+            ],
+            records.into_iter().map(serde_json::Value::Object).collect::<Vec<_>>()
+        );
+        assert!(parse_records("timestamp,x\n0,1,2\n", DatasetFormat::Csv).is_err());
+        assert!(parse_records("", DatasetFormat::Csv).unwrap().is_empty());
+
+        let records = parse_records(
+            "{\"timestamp\": 0, \"x\": 1}\n{\"timestamp\": 5, \"y\": [1, 2]}\n",
+            DatasetFormat::JsonLines,
+        )
+        .unwrap();
+        assert_eq!(2, records.len());
+        assert_eq!(Some(&serde_json::json!([1, 2])), records[1].get("y"));
+        assert!(parse_records("{\"x\": 1}\n[1, 2]\n", DatasetFormat::JsonLines).is_err());
+        assert!(parse_records("{\"x\": 1\n", DatasetFormat::JsonLines).is_err());
+    }
+
+    #[test]
+    fn test_dataset_replay_make_batches() {
+        let records = parse_records("timestamp,x\n1000,1\n1100,2\n1200,3\n1300,4\n1400,5\n", DatasetFormat::Csv).unwrap();
+        let mut conf = DatasetReplayConfiguration::from(&configuration(&[("filename", "trace.csv"), ("batch_size", "2")])).unwrap();
+
+        // Original timing.
+        let batches = make_batches(records.clone(), &conf).unwrap();
+        assert_eq!(vec![0, 200, 400], batches.iter().map(|x| x.offset.as_millis()).collect::<Vec<u128>>());
+        assert_eq!(vec![2, 2, 1], batches.iter().map(|x| x.records.len()).collect::<Vec<usize>>());
+        assert_eq!(serde_json::json!({"timestamp": 1200.0, "x": 3.0}), batches[1].records[0]);
+
+        // Original timing with speed-up.
+        conf.timing = ReplayTiming::Original(4.0);
+        let batches = make_batches(records.clone(), &conf).unwrap();
+        assert_eq!(vec![0, 50, 100], batches.iter().map(|x| x.offset.as_millis()).collect::<Vec<u128>>());
+
+        // Fixed rate, timestamps are not needed.
+        conf.timing = ReplayTiming::Rate(5.0);
+        conf.timestamp_field = "missing".to_string();
+        let batches = make_batches(records.clone(), &conf).unwrap();
+        assert_eq!(vec![0, 200, 400], batches.iter().map(|x| x.offset.as_millis()).collect::<Vec<u128>>());
+
+        // Original timing without timestamps.
+        conf.timing = ReplayTiming::Original(1.0);
+        assert!(make_batches(records.clone(), &conf).is_err());
+
+        // Offsets too large to be represented.
+        conf.timestamp_field = "timestamp".to_string();
+        conf.timing = ReplayTiming::Original(1e-300);
+        assert!(make_batches(records, &conf).is_err());
+
+        // Records out of order.
+        let records = parse_records("timestamp\n0\n100\n50\n200\n", DatasetFormat::Csv).unwrap();
+        conf.timing = ReplayTiming::Original(1.0);
+        conf.batch_size = 1;
+        let batches = make_batches(records, &conf).unwrap();
+        assert_eq!(
+            vec![0, 100, 100, 200],
+            batches.iter().map(|x| x.offset.as_millis()).collect::<Vec<u128>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dataset_replay_resource() {
+        let filename = std::env::temp_dir().join(format!("dataset-replay-{}.jsonl", uuid::Uuid::new_v4()));
+        std::fs::write(&filename, "{\"x\": 1}\n{\"x\": 2}\n{\"x\": 3}\n").unwrap();
+
+        let mut node = TestNode::new().await;
+        let mut provider = DatasetReplayResourceProvider::new(node.dataplane_provider.clone(), node.provider_id()).await;
+
+        let resource_id = start(
+            &mut provider,
+            "dataset-replay",
+            &[
+                ("filename", filename.to_str().unwrap()),
+                ("batch_size", "2"),
+                ("timing", "rate"),
+                ("rate", "100"),
+                ("loop", "true"),
+            ],
+        )
+        .await;
+        let (target_id, mut target_handle) = node.function().await;
+        patch(&mut provider, resource_id, "new_batch", target_id).await;
+
+        // The replay restarts from the first record.
+        for expected in [
+            serde_json::json!({"batch_id": 0, "batch": [{"x": 1}, {"x": 2}]}),
+            serde_json::json!({"batch_id": 1, "batch": [{"x": 3}]}),
+            serde_json::json!({"batch_id": 2, "batch": [{"x": 1}, {"x": 2}]}),
+        ] {
+            let (source_id, payload) = receive_cast(&mut target_handle).await;
+            assert_eq!(resource_id, source_id);
+            assert_eq!(expected, serde_json::from_str::<serde_json::Value>(&payload).unwrap());
+        }
+
+        provider.stop(resource_id).await.unwrap();
+
+        // A file replayed in zero time, with original timing, cannot be looped.
+        std::fs::write(&filename, "{\"timestamp\": 5, \"x\": 1}\n{\"timestamp\": 5, \"x\": 2}\n").unwrap();
+        for batch_size in ["1", "2"] {
+            let entries = [("filename", filename.to_str().unwrap()), ("batch_size", batch_size), ("loop", "true")];
+            assert!(matches!(
+                try_start(&mut provider, "dataset-replay", &entries).await,
+                edgeless_api::common::StartComponentResponse::ResponseError(_)
+            ));
+            let resource_id = start(&mut provider, "dataset-replay", &entries[..2]).await;
+            provider.stop(resource_id).await.unwrap();
+        }
+        let _ = std::fs::remove_file(filename);
+
+        // A missing file is reported when starting the resource.
+        assert!(matches!(
+            try_start(&mut provider, "dataset-replay", &[("filename", "/non/existing/file.csv")]).await,
+            edgeless_api::common::StartComponentResponse::ResponseError(_)
+        ));
+    }
+}
@@ -18,16 +18,335 @@ impl super::resource_provider_specs::ResourceProviderSpecs for FileLogResourceSp
 
     fn configurations(&self) -> std::collections::HashMap<String, String> {
         std::collections::HashMap::from([
+            (String::from("filename"), String::from("Name of the file where to append the log lines")),
             (
                 String::from("add-source-id"),
                 String::from("If specified adds the InstanceId of the source component"),
             ),
             (String::from("add-timestamp"), String::from("If specified adds a timestamp")),
+            (
+                String::from("format"),
+                String::from("Format of the log lines: plain (default), csv, or jsonl"),
+            ),
+            (
+                String::from("rotate-size"),
+                String::from("Rotate the file when it exceeds this size, in bytes, optionally with a K, M, or G suffix"),
+            ),
+            (
+                String::from("rotate-interval-sec"),
+                String::from("Rotate the file when it has been written for longer than this interval, in s"),
+            ),
+            (
+                String::from("retain"),
+                String::from("Number of rotated files that are kept, as filename.1 (newest) to filename.N (default: 5)"),
+            ),
+            (
+                String::from("write-mode"),
+                String::from("direct (default): write every line; buffered: flush every second; fsync: sync every line to disk"),
+            ),
         ])
     }
 
     fn version(&self) -> String {
-        String::from("1.0")
+        String::from("1.1")
+    }
+}
+
+/// Default number of rotated files kept.
+pub const DEFAULT_RETAIN: usize = 5;
+
+/// Maximum time for which the lines are kept in memory with buffered writes.
+const FLUSH_PERIOD: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Format of the log lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    /// The payload, optionally preceded by timestamp and source identifier.
+    Plain,
+    /// Comma-separated timestamp, source node and function identifiers,
+    /// and quoted payload, with a header line at the beginning of every file.
+    Csv,
+    /// JSON object with timestamp, source node and function identifiers,
+    /// and payload fields.
+    JsonLines,
+}
+
+/// How the log lines are written to the file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WriteMode {
+    /// Every line is written to the file when received.
+    Direct,
+    /// Lines are buffered in memory and flushed periodically.
+    Buffered,
+    /// Every line is written and synced to the disk when received.
+    Fsync,
+}
+
+/// Configuration of a file-log resource instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileLogConfiguration {
+    pub filename: String,
+    pub format: LogFormat,
+    // Only used with the plain format.
+    pub add_source_id: bool,
+    // Only used with the plain format.
+    pub add_timestamp: bool,
+    pub rotate_size: Option<u64>,
+    pub rotate_interval: Option<std::time::Duration>,
+    pub retain: usize,
+    pub write_mode: WriteMode,
+}
+
+impl FileLogConfiguration {
+    /// Parse the run-time configuration of a resource instance.
+    pub fn from(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let get = |key: &str| configuration.get(key).filter(|x| !x.is_empty());
+
+        let filename = match get("filename") {
+            Some(filename) => filename.clone(),
+            None => anyhow::bail!("Field 'filename' missing"),
+        };
+        let format = match get("format").map(|x| x.to_lowercase()) {
+            None => LogFormat::Plain,
+            Some(format) => match format.as_str() {
+                "plain" => LogFormat::Plain,
+                "csv" => LogFormat::Csv,
+                "jsonl" | "json" => LogFormat::JsonLines,
+                _ => anyhow::bail!("Invalid format: {}", format),
+            },
+        };
+        let rotate_size = match get("rotate-size") {
+            Some(value) => match parse_size(value) {
+                Some(size) if size > 0 => Some(size),
+                _ => anyhow::bail!("Invalid rotate-size: {}", value),
+            },
+            None => None,
+        };
+        let rotate_interval = match get("rotate-interval-sec") {
+            Some(value) => match value.parse::<u64>() {
+                Ok(interval) if interval > 0 => Some(std::time::Duration::from_secs(interval)),
+                _ => anyhow::bail!("Invalid rotate-interval-sec: {}", value),
+            },
+            None => None,
+        };
+        let retain = match get("retain") {
+            Some(value) => match value.parse::<usize>() {
+                Ok(retain) => retain,
+                Err(_) => anyhow::bail!("Invalid retain: {}", value),
+            },
+            None => DEFAULT_RETAIN,
+        };
+        let write_mode = match get("write-mode").map(|x| x.to_lowercase()) {
+            None => WriteMode::Direct,
+            Some(write_mode) => match write_mode.as_str() {
+                "direct" => WriteMode::Direct,
+                "buffered" => WriteMode::Buffered,
+                "fsync" => WriteMode::Fsync,
+                _ => anyhow::bail!("Invalid write-mode: {}", write_mode),
+            },
+        };
+
+        Ok(Self {
+            filename,
+            format,
+            add_source_id: configuration.contains_key("add-source-id"),
+            add_timestamp: configuration.contains_key("add-timestamp"),
+            rotate_size,
+            rotate_interval,
+            retain,
+            write_mode,
+        })
+    }
+}
+
+/// Parse a size in bytes, with an optional K, M, or G (binary) suffix.
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_uppercase();
+    let value = value.strip_suffix('B').unwrap_or(&value);
+    let (number, multiplier) = match value.chars().last()? {
+        'K' => (&value[..value.len() - 1], 1 << 10),
+        'M' => (&value[..value.len() - 1], 1 << 20),
+        'G' => (&value[..value.len() - 1], 1 << 30),
+        _ => (value, 1),
+    };
+    number.trim().parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Writer of the log lines to a file, which is rotated depending on its
+/// size or age, if configured.
+///
+/// All the methods perform blocking file operations, hence they must be
+/// called with `tokio::task::spawn_blocking` from async code.
+pub struct LogWriter {
+    configuration: FileLogConfiguration,
+    writer: std::io::BufWriter<std::fs::File>,
+    // Current size of the file, in bytes.
+    size: u64,
+    // Size of the header of the file, in bytes.
+    header_size: u64,
+    // Time when the file was opened.
+    opened_at: chrono::DateTime<chrono::Utc>,
+    // Time when the lines were last flushed.
+    flushed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl LogWriter {
+    const CSV_HEADER: &'static str = "timestamp,node_id,function_id,payload\n";
+
+    /// Open the log file, appending to it if it exists already.
+    pub fn new(configuration: FileLogConfiguration, now: chrono::DateTime<chrono::Utc>) -> anyhow::Result<Self> {
+        let (writer, size, header_size) = Self::open(&configuration)?;
+        Ok(Self {
+            configuration,
+            writer,
+            size,
+            header_size,
+            opened_at: now,
+            flushed_at: now,
+        })
+    }
+
+    fn open(configuration: &FileLogConfiguration) -> anyhow::Result<(std::io::BufWriter<std::fs::File>, u64, u64)> {
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(&configuration.filename)?;
+        let mut size = file.metadata()?.len();
+        let mut writer = std::io::BufWriter::new(file);
+        let header_size = match configuration.format {
+            LogFormat::Csv => {
+                if size == 0 {
+                    writer.write_all(Self::CSV_HEADER.as_bytes())?;
+                    writer.flush()?;
+                    size = Self::CSV_HEADER.len() as u64;
+                }
+                Self::CSV_HEADER.len() as u64
+            }
+            LogFormat::Plain | LogFormat::JsonLines => 0,
+        };
+        Ok((writer, size, header_size))
+    }
+
+    /// Return the write mode.
+    pub fn write_mode(&self) -> WriteMode {
+        self.configuration.write_mode
+    }
+
+    /// Format a log line, without the trailing newline.
+    pub fn format_line(&self, now: chrono::DateTime<chrono::Utc>, source_id: &edgeless_api::function_instance::InstanceId, payload: &str) -> String {
+        let timestamp = || now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        match self.configuration.format {
+            LogFormat::Plain => {
+                // Compose the line piece by piece.
+                let mut line = "".to_string();
+                if self.configuration.add_timestamp {
+                    line.push_str(format!("{} ", now.to_rfc3339()).as_str());
+                }
+                if self.configuration.add_source_id {
+                    line.push_str(format!("{} ", source_id).as_str());
+                }
+                line.push_str(payload);
+                line
+            }
+            LogFormat::Csv => format!(
+                "{},{},{},\"{}\"",
+                timestamp(),
+                source_id.node_id,
+                source_id.function_id,
+                payload.replace('"', "\"\"")
+            ),
+            LogFormat::JsonLines => serde_json::json!({
+                "timestamp": timestamp(),
+                "node_id": source_id.node_id.to_string(),
+                "function_id": source_id.function_id.to_string(),
+                "payload": payload,
+            })
+            .to_string(),
+        }
+    }
+
+    /// Write a log line, rotating the file first if needed.
+    pub fn write(
+        &mut self,
+        now: chrono::DateTime<chrono::Utc>,
+        source_id: &edgeless_api::function_instance::InstanceId,
+        payload: &str,
+    ) -> anyhow::Result<()> {
+        let mut line = self.format_line(now, source_id, payload);
+        line.push('\n');
+
+        if self.must_rotate(now, line.len() as u64) {
+            self.rotate(now)?;
+        }
+
+        self.writer.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+
+        match self.configuration.write_mode {
+            WriteMode::Direct => self.writer.flush()?,
+            WriteMode::Buffered => {
+                if (now - self.flushed_at).to_std().unwrap_or_default() >= FLUSH_PERIOD {
+                    self.flush(now)?;
+                }
+            }
+            WriteMode::Fsync => {
+                self.writer.flush()?;
+                self.writer.get_ref().sync_data()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flush the lines buffered, if any.
+    pub fn flush(&mut self, now: chrono::DateTime<chrono::Utc>) -> anyhow::Result<()> {
+        self.flushed_at = now;
+        Ok(self.writer.flush()?)
+    }
+
+    fn must_rotate(&self, now: chrono::DateTime<chrono::Utc>, line_size: u64) -> bool {
+        // A file is never rotated if it contains no lines.
+        if self.size <= self.header_size {
+            return false;
+        }
+        if let Some(rotate_size) = self.configuration.rotate_size {
+            if self.size + line_size > rotate_size {
+                return true;
+            }
+        }
+        if let Some(rotate_interval) = self.configuration.rotate_interval {
+            if (now - self.opened_at).to_std().unwrap_or_default() >= rotate_interval {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Rename the current file to filename.1, shifting the older ones,
+    /// and open a new file.
+    fn rotate(&mut self, now: chrono::DateTime<chrono::Utc>) -> anyhow::Result<()> {
+        self.writer.flush()?;
+        if self.configuration.write_mode == WriteMode::Fsync {
+            self.writer.get_ref().sync_data()?;
+        }
+
+        let filename = &self.configuration.filename;
+        let rotated = |index: usize| format!("{}.{}", filename, index);
+        if self.configuration.retain == 0 {
+            std::fs::remove_file(filename)?;
+        } else {
+            for index in (1..self.configuration.retain).rev() {
+                if std::path::Path::new(&rotated(index)).exists() {
+                    std::fs::rename(rotated(index), rotated(index + 1))?;
+                }
+            }
+            std::fs::rename(filename, rotated(1))?;
+        }
+        log::debug!("Rotated log file {}", filename);
+
+        let (writer, size, header_size) = Self::open(&self.configuration)?;
+        self.writer = writer;
+        self.size = size;
+        self.header_size = header_size;
+        self.opened_at = now;
+        self.flushed_at = now;
+        Ok(())
     }
 }
 
@@ -53,25 +372,42 @@ impl Drop for FileLogResource {
 }
 
 impl FileLogResource {
-    async fn new(
-        dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
-        filename: &str,
-        add_source_id: bool,
-        add_timestamp: bool,
-    ) -> anyhow::Result<Self> {
+    async fn new(dataplane_handle: edgeless_dataplane::handle::DataplaneHandle, configuration: FileLogConfiguration) -> anyhow::Result<Self> {
         let mut dataplane_handle = dataplane_handle;
 
-        let mut outfile = std::fs::OpenOptions::new().create(true).append(true).open(filename)?;
+        let filename = configuration.filename.clone();
+        let writer = tokio::task::spawn_blocking(move || LogWriter::new(configuration, chrono::Utc::now())).await??;
+        let write_mode = writer.write_mode();
+        let writer = std::sync::Arc::new(std::sync::Mutex::new(writer));
 
         log::info!("FileLogResource created, writing to file: {}", filename);
 
         let handle = tokio::spawn(async move {
             loop {
+                // With buffered writes, flush the lines when idle.
+                let event = match write_mode {
+                    WriteMode::Buffered => match tokio::time::timeout(FLUSH_PERIOD, dataplane_handle.receive_next()).await {
+                        Ok(event) => event,
+                        Err(_) => {
+                            let res = tokio::task::spawn_blocking({
+                                let writer = writer.clone();
+                                move || writer.lock().unwrap().flush(chrono::Utc::now())
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(e.into()));
+                            if let Err(e) = res {
+                                log::error!("Could not flush the file {}: {}", filename, e);
+                            }
+                            continue;
+                        }
+                    },
+                    WriteMode::Direct | WriteMode::Fsync => dataplane_handle.receive_next().await,
+                };
                 let edgeless_dataplane::core::DataplaneEvent {
                     source_id,
                     channel_id,
                     message,
-                } = dataplane_handle.receive_next().await;
+                } = event;
                 let mut need_reply = false;
                 let message_data = match message {
                     Message::Call(data) => {
@@ -84,20 +420,17 @@ impl FileLogResource {
                     }
                 };
 
-                // Compose the line piece by piece.
-                let mut line = "".to_string();
-                if add_timestamp {
-                    line.push_str(format!("{} ", chrono::Utc::now().to_rfc3339()).as_str());
-                }
-                if add_source_id {
-                    line.push_str(format!("{} ", source_id).as_str());
-                }
-                line.push_str(&message_data);
-
                 // Dump the line to the output file.
-                log::debug!("{}", line);
-                if let Err(e) = writeln!(outfile, "{}", line) {
-                    log::error!("Could not write to file the message '{}': {}", line, e);
+                log::debug!("{}", message_data);
+                let res = tokio::task::spawn_blocking({
+                    let writer = writer.clone();
+                    let message_data = message_data.clone();
+                    move || writer.lock().unwrap().write(chrono::Utc::now(), &source_id, &message_data)
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.into()));
+                if let Err(e) = res {
+                    log::error!("Could not write to file the message '{}': {}", message_data, e);
                 }
 
                 // Reply to the caller if the resource instance was called.
@@ -134,40 +467,35 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         &mut self,
         instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
     ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
-        if let Some(filename) = instance_specification.configuration.get("filename") {
-            let mut lck = self.inner.lock().await;
-
-            let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
-            let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
-
-            match FileLogResource::new(
-                dataplane_handle,
-                filename,
-                instance_specification.configuration.contains_key("add-source-id"),
-                instance_specification.configuration.contains_key("add-timestamp"),
-            )
-            .await
-            {
-                Ok(resource) => {
-                    lck.instances.insert(new_id, resource);
-                    return Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id));
-                }
-                Err(err) => {
-                    return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
-                        edgeless_api::common::ResponseError {
-                            summary: "Invalid resource configuration".to_string(),
-                            detail: Some(err.to_string()),
-                        },
-                    ));
-                }
+        let configuration = match FileLogConfiguration::from(&instance_specification.configuration) {
+            Ok(configuration) => configuration,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
             }
+        };
+
+        let mut lck = self.inner.lock().await;
+
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+
+        match FileLogResource::new(dataplane_handle, configuration).await {
+            Ok(resource) => {
+                lck.instances.insert(new_id, resource);
+                Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+            }
+            Err(err) => Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Invalid resource configuration".to_string(),
+                    detail: Some(err.to_string()),
+                },
+            )),
         }
-        Ok(edgeless_api::common::StartComponentResponse::ResponseError(
-            edgeless_api::common::ResponseError {
-                summary: "Invalid resource configuration".to_string(),
-                detail: Some("Field 'filename' missing".to_string()),
-            },
-        ))
     }
 
     async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
@@ -180,3 +508,167 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    fn source_id() -> edgeless_api::function_instance::InstanceId {
+        edgeless_api::function_instance::InstanceId {
+            node_id: uuid::Uuid::nil(),
+            function_id: uuid::Uuid::from_u128(1),
+        }
+    }
+
+    fn temp_filename() -> String {
+        std::env::temp_dir()
+            .join(format!("file-log-{}.log", uuid::Uuid::new_v4()))
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_file_log_configuration() {
+        assert!(FileLogConfiguration::from(&configuration(&[])).is_err());
+
+        let conf = FileLogConfiguration::from(&configuration(&[("filename", "my.log"), ("add-timestamp", "true")])).unwrap();
+        assert_eq!(
+            FileLogConfiguration {
+                filename: "my.log".to_string(),
+                format: LogFormat::Plain,
+                add_source_id: false,
+                add_timestamp: true,
+                rotate_size: None,
+                rotate_interval: None,
+                retain: DEFAULT_RETAIN,
+                write_mode: WriteMode::Direct,
+            },
+            conf
+        );
+
+        let conf = FileLogConfiguration::from(&configuration(&[
+            ("filename", "my.log"),
+            ("format", "csv"),
+            ("rotate-size", "10M"),
+            ("rotate-interval-sec", "3600"),
+            ("retain", "0"),
+            ("write-mode", "fsync"),
+        ]))
+        .unwrap();
+        assert_eq!(LogFormat::Csv, conf.format);
+        assert_eq!(Some(10 << 20), conf.rotate_size);
+        assert_eq!(Some(std::time::Duration::from_secs(3600)), conf.rotate_interval);
+        assert_eq!(0, conf.retain);
+        assert_eq!(WriteMode::Fsync, conf.write_mode);
+
+        for wrong in [
+            ("format", "xml"),
+            ("rotate-size", "0"),
+            ("rotate-size", "big"),
+            ("rotate-interval-sec", "-1"),
+            ("retain", "all"),
+            ("write-mode", "lazy"),
+        ] {
+            assert!(FileLogConfiguration::from(&configuration(&[("filename", "my.log"), wrong])).is_err());
+        }
+
+        assert_eq!(Some(1000), parse_size("1000"));
+        assert_eq!(Some(2048), parse_size("2k"));
+        assert_eq!(Some(3 << 20), parse_size("3 MB"));
+        assert_eq!(Some(1 << 30), parse_size("1G"));
+        assert_eq!(None, parse_size("M"));
+        assert_eq!(None, parse_size("1T"));
+    }
+
+    #[test]
+    fn test_file_log_format_line() {
+        let filename = temp_filename();
+        let now = datetime("2024-01-01T10:00:00.123Z");
+        let writer = |entries: &[(&str, &str)]| {
+            let mut entries = entries.to_vec();
+            entries.push(("filename", &filename));
+            LogWriter::new(FileLogConfiguration::from(&configuration(&entries)).unwrap(), now).unwrap()
+        };
+
+        assert_eq!("hello", writer(&[]).format_line(now, &source_id(), "hello"));
+        assert_eq!(
+            "2024-01-01T10:00:00.123+00:00 InstanceId(node_id: 00000000-0000-0000-0000-000000000000, function_id: 00000000-0000-0000-0000-000000000001) hello",
+            writer(&[("add-timestamp", ""), ("add-source-id", "")]).format_line(now, &source_id(), "hello")
+        );
+        assert_eq!(
+            "2024-01-01T10:00:00.123Z,00000000-0000-0000-0000-000000000000,00000000-0000-0000-0000-000000000001,\"say \"\"hi\"\", 42\"",
+            writer(&[("format", "csv")]).format_line(now, &source_id(), "say \"hi\", 42")
+        );
+        assert_eq!(
+            serde_json::json!({
+                "timestamp": "2024-01-01T10:00:00.123Z",
+                "node_id": "00000000-0000-0000-0000-000000000000",
+                "function_id": "00000000-0000-0000-0000-000000000001",
+                "payload": "say \"hi\"",
+            }),
+            serde_json::from_str::<serde_json::Value>(&writer(&[("format", "jsonl")]).format_line(now, &source_id(), "say \"hi\"")).unwrap()
+        );
+
+        let _ = std::fs::remove_file(filename);
+    }
+
+    #[test]
+    fn test_file_log_rotation() {
+        let filename = temp_filename();
+        let rotated = |index: usize| format!("{}.{}", filename, index);
+        let read = |filename: &str| std::fs::read_to_string(filename).unwrap_or_default();
+        let now = datetime("2024-01-01T10:00:00Z");
+
+        // Size-based rotation, keeping two old files.
+        let mut writer = LogWriter::new(
+            FileLogConfiguration::from(&configuration(&[
+                ("filename", &filename),
+                ("format", "csv"),
+                ("rotate-size", "100"),
+                ("retain", "2"),
+            ]))
+            .unwrap(),
+            now,
+        )
+        .unwrap();
+        let header = "timestamp,node_id,function_id,payload\n";
+        assert_eq!(header, read(&filename));
+
+        // Every line is longer than 50 bytes, thus the file is rotated at every line.
+        for i in 0..4 {
+            writer.write(now, &source_id(), &format!("{}", i)).unwrap();
+        }
+        assert!(read(&filename).starts_with(header));
+        assert!(read(&filename).ends_with(",\"3\"\n"));
+        assert!(read(&rotated(1)).ends_with(",\"2\"\n"));
+        assert!(read(&rotated(2)).ends_with(",\"1\"\n"));
+        assert!(!std::path::Path::new(&rotated(3)).exists());
+
+        // Time-based rotation, not keeping old files, on the same file.
+        let mut writer = LogWriter::new(
+            FileLogConfiguration::from(&configuration(&[
+                ("filename", &filename),
+                ("rotate-interval-sec", "60"),
+                ("retain", "0"),
+                ("write-mode", "buffered"),
+            ]))
+            .unwrap(),
+            now,
+        )
+        .unwrap();
+        writer.write(now + chrono::Duration::seconds(59), &source_id(), "a").unwrap();
+        writer.flush(now).unwrap();
+        assert!(read(&filename).ends_with(",\"3\"\na\n"));
+        writer.write(now + chrono::Duration::seconds(60), &source_id(), "b").unwrap();
+        writer.write(now + chrono::Duration::seconds(61), &source_id(), "c").unwrap();
+        writer.flush(now).unwrap();
+        assert_eq!("b\nc\n", read(&filename));
+        assert!(read(&rotated(1)).ends_with(",\"2\"\n"));
+
+        for filename in [filename.clone(), rotated(1), rotated(2)] {
+            let _ = std::fs::remove_file(filename);
+        }
+    }
+}
@@ -4,7 +4,6 @@
 // SPDX-License-Identifier: MIT
 use edgeless_api::function_instance::{ComponentId, InstanceId};
 use http_body_util::BodyExt;
-use std::str::FromStr;
 
 pub struct HttpIngressResourceSpec {}
 
@@ -17,36 +16,173 @@ impl super::resource_provider_specs::ResourceProviderSpecs for HttpIngressResour
         vec!["new_request".to_string()]
     }
 
+    fn instance_outputs(&self, configuration: &std::collections::HashMap<String, String>) -> Vec<String> {
+        let mut outputs = self.outputs();
+        if let Some(routes) = configuration.get("routes").and_then(|routes| parse_routes(routes).ok()) {
+            outputs.extend(routes.into_iter().map(|(_, output)| output).filter(|output| output != "new_request"));
+        }
+        outputs
+    }
+
     fn configurations(&self) -> std::collections::HashMap<String, String> {
         std::collections::HashMap::from([
             (
                 String::from("host"),
                 String::from("Hostname that is used to match incoming HTTP commands"),
             ),
-            (String::from("method"), String::from("Comma-separated list of HTTP methods allowed")),
+            (String::from("methods"), String::from("Comma-separated list of HTTP methods allowed")),
+            (
+                String::from("routes"),
+                String::from("Comma-separated list of prefix=output, to dispatch requests by path prefix to other outputs than new_request"),
+            ),
+            (String::from("mode"), String::from("One of: call (default), sync, async")),
+            (
+                String::from("timeout_ms"),
+                String::from("Maximum time to wait for the reply of the target, in ms (default: 30000)"),
+            ),
+            (
+                String::from("max_body_size"),
+                String::from("Maximum size of the body of requests, in bytes (default: unlimited)"),
+            ),
         ])
     }
 
     fn version(&self) -> String {
-        String::from("1.0")
+        String::from("1.1")
     }
 }
 
-struct ResourceDesc {
+/// Default maximum time to wait for the reply of the target.
+const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How the HTTP requests are dispatched to the target and answered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HttpIngressMode {
+    /// The request is sent with a call and the reply of the target is an
+    /// EdgelessHTTPResponse.
+    Call,
+    /// The request is sent with a call and the reply of the target is
+    /// the body of a 200 OK response.
+    Sync,
+    /// The request is sent with a cast and a 202 Accepted response is
+    /// returned immediately.
+    Async,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpIngressConfiguration {
     host: String,
     allow: std::collections::HashSet<edgeless_http::EdgelessHTTPMethod>,
+    // Path prefixes and output channels, sorted by decreasing prefix length.
+    routes: Vec<(String, String)>,
+    mode: HttpIngressMode,
+    timeout: std::time::Duration,
+    // Maximum size of the body, in bytes, unlimited if not set.
+    max_body_size: Option<usize>,
+}
+
+/// Parse a comma-separated list of prefix=output routes, sorted by
+/// decreasing prefix length, so that the first match is the longest.
+fn parse_routes(routes: &str) -> anyhow::Result<Vec<(String, String)>> {
+    let mut ret = vec![];
+    for route in routes.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()) {
+        match route.split_once('=') {
+            Some((prefix, output)) if prefix.trim().starts_with('/') && !output.trim().is_empty() => {
+                ret.push((prefix.trim().to_string(), output.trim().to_string()));
+            }
+            _ => anyhow::bail!("Invalid HTTP route: {}", route),
+        }
+    }
+    ret.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+    Ok(ret)
+}
+
+/// Return true if the path matches the prefix on a segment boundary,
+/// e.g., /api matches /api and /api/v1 but not /apis.
+fn match_prefix(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => prefix.ends_with('/') || rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+impl HttpIngressConfiguration {
+    /// Parse the run-time configuration of an http-ingress resource instance.
+    fn from(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let host = match configuration.get("host") {
+            Some(host) if !host.is_empty() => host.clone(),
+            _ => anyhow::bail!("Missing host"),
+        };
+        let allow = match configuration.get("methods") {
+            Some(methods) => methods
+                .split(',')
+                .map(|x| x.trim())
+                .filter(|x| !x.is_empty())
+                .map(|x| edgeless_http::string_method_to_edgeless(&x.to_uppercase()).map_err(|_| anyhow::anyhow!("Invalid HTTP method: {}", x)))
+                .collect::<anyhow::Result<std::collections::HashSet<edgeless_http::EdgelessHTTPMethod>>>()?,
+            None => anyhow::bail!("Missing methods"),
+        };
+        let routes = parse_routes(configuration.get("routes").map(|x| x.as_str()).unwrap_or_default())?;
+        let mode = match configuration.get("mode").map(|x| x.to_lowercase()) {
+            None => HttpIngressMode::Call,
+            Some(mode) => match mode.as_str() {
+                "" | "call" => HttpIngressMode::Call,
+                "sync" => HttpIngressMode::Sync,
+                "async" => HttpIngressMode::Async,
+                _ => anyhow::bail!("Invalid HTTP ingress mode: {}", mode),
+            },
+        };
+        let timeout = match configuration.get("timeout_ms").filter(|x| !x.is_empty()) {
+            None => DEFAULT_TIMEOUT,
+            Some(timeout) => match timeout.parse::<u64>() {
+                Ok(timeout) if timeout > 0 => std::time::Duration::from_millis(timeout),
+                _ => anyhow::bail!("Invalid timeout: {}", timeout),
+            },
+        };
+        let max_body_size = match configuration.get("max_body_size").filter(|x| !x.is_empty()) {
+            None => None,
+            Some(size) => match size.parse::<usize>() {
+                Ok(size) => Some(size),
+                Err(_) => anyhow::bail!("Invalid maximum body size: {}", size),
+            },
+        };
+        Ok(Self {
+            host,
+            allow,
+            routes,
+            mode,
+            timeout,
+            max_body_size,
+        })
+    }
+
+    /// Output channel to which requests for the given path are dispatched.
+    fn output(&self, path: &str) -> &str {
+        self.routes
+            .iter()
+            .find(|(prefix, _)| match_prefix(path, prefix))
+            .map(|(_, output)| output.as_str())
+            .unwrap_or("new_request")
+    }
 }
 
 struct IngressState {
     interests: Vec<HTTPIngressInterest>,
-    active_resources: std::collections::HashMap<ComponentId, ResourceDesc>,
-    dataplane: edgeless_dataplane::handle::DataplaneHandle,
+    active_resources: std::collections::HashMap<ComponentId, std::sync::Arc<HttpIngressConfiguration>>,
 }
 
 #[derive(Clone)]
 struct IngressService {
     listen_addr: String,
     interests: std::sync::Arc<tokio::sync::Mutex<IngressState>>,
+    // Cloned for every request, so that calls are in flight concurrently.
+    dataplane: edgeless_dataplane::handle::DataplaneHandle,
+}
+
+pub(crate) fn simple_response(status: hyper::StatusCode, body: &str) -> hyper::Response<http_body_util::Full<hyper::body::Bytes>> {
+    let mut response = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from(body.to_string())));
+    *response.status_mut() = status;
+    response
 }
 
 impl hyper::service::Service<hyper::Request<hyper::body::Incoming>> for IngressService {
@@ -59,9 +195,8 @@ impl hyper::service::Service<hyper::Request<hyper::body::Incoming>> for IngressS
     fn call(&self, req: hyper::Request<hyper::body::Incoming>) -> Self::Future {
         let cloned = self.interests.clone();
         let cloned_addr = self.listen_addr.clone();
+        let mut dataplane = self.dataplane.clone();
         Box::pin(async move {
-            let mut lck = cloned.lock().await;
-
             let (parts, body) = req.into_parts();
 
             let host = match parts.headers.get(hyper::header::HOST) {
@@ -69,116 +204,276 @@ impl hyper::service::Service<hyper::Request<hyper::body::Incoming>> for IngressS
                 None => &cloned_addr,
             };
             let method = edgeless_http::hyper_method_to_edgeless(&parts.method)?;
-            let data = body.collect().await?.to_bytes();
 
-            if let Some((host, target)) = lck.interests.iter().find_map(|intr| {
-                if host == intr.host && intr.allow.contains(&method) {
-                    Some((intr.host.clone(), intr.target))
+            let (conf, target) = match cloned.lock().await.interests.iter().find_map(|intr| {
+                if host == intr.conf.host && intr.conf.allow.contains(&method) {
+                    intr.targets
+                        .get(intr.conf.output(parts.uri.path()))
+                        .map(|target| (intr.conf.clone(), *target))
                 } else {
                     None
                 }
             }) {
-                let msg = edgeless_http::EdgelessHTTPRequest {
-                    host: host.to_string(),
-                    protocol: edgeless_http::EdgelessHTTPProtocol::Unknown,
-                    method: method.clone(),
-                    path: parts.uri.to_string(),
-                    body: Some(Vec::from(data)),
-                    headers: parts
-                        .headers
-                        .iter()
-                        .filter_map(|(k, v)| match v.to_str() {
-                            Ok(header_value) => Some((k.to_string(), header_value.to_string())),
-                            Err(_) => {
-                                log::warn!("Bad Header Value.");
-                                None
-                            }
-                        })
-                        .collect(),
-                };
-                let serialized_msg = serde_json::to_string(&msg)?;
-                let res = lck.dataplane.call(target, serialized_msg).await;
-                if let edgeless_dataplane::core::CallRet::Reply(data) = res {
-                    let processor_response: edgeless_http::EdgelessHTTPResponse = serde_json::from_str(&data)?;
-                    let mut response_builder = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from(
-                        processor_response.body.unwrap_or_default(),
-                    )));
-                    *response_builder.status_mut() = hyper::StatusCode::from_u16(processor_response.status)?;
-                    {
-                        let headers = response_builder.headers_mut();
-                        for (header_key, header_val) in processor_response.headers {
-                            if let (Ok(key), Ok(value)) = (
-                                hyper::header::HeaderName::from_bytes(header_key.as_bytes()),
-                                hyper::header::HeaderValue::from_str(&header_val),
-                            ) {
-                                headers.append(key, value);
-                            }
+                Some(val) => val,
+                None => return Ok(simple_response(hyper::StatusCode::NOT_FOUND, "Not Found")),
+            };
+
+            let data = match conf.max_body_size {
+                Some(max_body_size) => match http_body_util::Limited::new(body, max_body_size).collect().await {
+                    Ok(data) => data.to_bytes(),
+                    Err(err) if err.is::<http_body_util::LengthLimitError>() => {
+                        return Ok(simple_response(hyper::StatusCode::PAYLOAD_TOO_LARGE, "Payload Too Large"))
+                    }
+                    Err(err) => anyhow::bail!(err),
+                },
+                None => body.collect().await?.to_bytes(),
+            };
+
+            let msg = edgeless_http::EdgelessHTTPRequest {
+                host: conf.host.clone(),
+                protocol: edgeless_http::EdgelessHTTPProtocol::Unknown,
+                method: method.clone(),
+                path: parts.uri.to_string(),
+                body: Some(Vec::from(data)),
+                headers: parts
+                    .headers
+                    .iter()
+                    .filter_map(|(k, v)| match v.to_str() {
+                        Ok(header_value) => Some((k.to_string(), header_value.to_string())),
+                        Err(_) => {
+                            log::warn!("Bad Header Value.");
+                            None
                         }
+                    })
+                    .collect(),
+            };
+            let serialized_msg = serde_json::to_string(&msg)?;
+
+            if conf.mode == HttpIngressMode::Async {
+                dataplane.send(target, serialized_msg).await;
+                return Ok(simple_response(hyper::StatusCode::ACCEPTED, ""));
+            }
+
+            let res = match tokio::time::timeout(conf.timeout, dataplane.call(target, serialized_msg)).await {
+                Ok(res) => res,
+                Err(_) => return Ok(simple_response(hyper::StatusCode::GATEWAY_TIMEOUT, "Gateway Timeout")),
+            };
+            let data = match res {
+                edgeless_dataplane::core::CallRet::Reply(data) => data,
+                edgeless_dataplane::core::CallRet::NoReply | edgeless_dataplane::core::CallRet::Err => {
+                    return Ok(simple_response(hyper::StatusCode::BAD_GATEWAY, "Bad Gateway"))
+                }
+            };
+
+            if conf.mode == HttpIngressMode::Sync {
+                return Ok(simple_response(hyper::StatusCode::OK, &data));
+            }
+
+            let processor_response: edgeless_http::EdgelessHTTPResponse = match serde_json::from_str(&data) {
+                Ok(processor_response) => processor_response,
+                Err(err) => {
+                    log::warn!("Invalid HTTP response from {}: {}", target, err);
+                    return Ok(simple_response(hyper::StatusCode::BAD_GATEWAY, "Bad Gateway"));
+                }
+            };
+            let status = match hyper::StatusCode::from_u16(processor_response.status) {
+                Ok(status) => status,
+                Err(_) => {
+                    log::warn!("Invalid HTTP status from {}: {}", target, processor_response.status);
+                    return Ok(simple_response(hyper::StatusCode::BAD_GATEWAY, "Bad Gateway"));
+                }
+            };
+            let mut response_builder = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from(
+                processor_response.body.unwrap_or_default(),
+            )));
+            *response_builder.status_mut() = status;
+            {
+                let headers = response_builder.headers_mut();
+                for (header_key, header_val) in processor_response.headers {
+                    if let (Ok(key), Ok(value)) = (
+                        hyper::header::HeaderName::from_bytes(header_key.as_bytes()),
+                        hyper::header::HeaderValue::from_str(&header_val),
+                    ) {
+                        headers.append(key, value);
                     }
-                    return Ok(response_builder);
                 }
             }
+            Ok(response_builder)
+        })
+    }
+}
+
+/// Create the TLS acceptor of a web server from the paths of the PEM files
+/// of the certificate (chain) and private key.
+fn tls_acceptor(cert: &str, key: &str) -> anyhow::Result<openssl::ssl::SslAcceptor> {
+    let mut builder = openssl::ssl::SslAcceptor::mozilla_intermediate_v5(openssl::ssl::SslMethod::tls())?;
+    builder
+        .set_certificate_chain_file(cert)
+        .map_err(|err| anyhow::anyhow!("Invalid TLS certificate '{}': {}", cert, err))?;
+    builder
+        .set_private_key_file(key, openssl::ssl::SslFiletype::PEM)
+        .map_err(|err| anyhow::anyhow!("Invalid TLS private key '{}': {}", key, err))?;
+    builder.check_private_key()?;
+    Ok(builder.build())
+}
 
-            let mut not_found = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from("Not Found")));
-            *not_found.status_mut() = hyper::StatusCode::NOT_FOUND;
-            Ok(not_found)
+/// A listening web server, not yet serving connections.
+pub(crate) struct WebServer {
+    listener: tokio::net::TcpListener,
+    acceptor: Option<openssl::ssl::SslAcceptor>,
+    // Address in the URL of the web server, as host:port, with the port
+    // actually bound if the URL has port 0.
+    listen_addr: String,
+}
+
+impl WebServer {
+    /// Bind a web server.
+    ///
+    /// - `url`: the URL of the web server, e.g., http://0.0.0.0:7035
+    ///   or https://0.0.0.0:7036 to use TLS.
+    /// - `tls_cert_key`: the paths of the PEM files of the TLS certificate
+    ///   and private key, required if the scheme of the URL is https.
+    pub(crate) async fn bind(url: &str, tls_cert_key: Option<(String, String)>) -> anyhow::Result<Self> {
+        let (proto, host, port) = edgeless_api::util::parse_http_host(url).map_err(|_| anyhow::anyhow!("Invalid URL: {}", url))?;
+        let acceptor = match (proto, tls_cert_key) {
+            (edgeless_api::util::Proto::HTTP, _) => None,
+            (edgeless_api::util::Proto::HTTPS, Some((cert, key))) => Some(tls_acceptor(&cert, &key)?),
+            (edgeless_api::util::Proto::HTTPS, None) => anyhow::bail!("Missing TLS certificate and private key for: {}", url),
+            (edgeless_api::util::Proto::COAP, _) => anyhow::bail!("Invalid URL scheme: {}", url),
+        };
+        let listener = tokio::net::TcpListener::bind((host.as_str(), port)).await?;
+        let listen_addr = format!("{}:{}", host, listener.local_addr()?.port());
+        Ok(Self {
+            listener,
+            acceptor,
+            listen_addr,
         })
     }
+
+    /// Address of the web server, as host:port.
+    pub(crate) fn listen_addr(&self) -> &str {
+        &self.listen_addr
+    }
+
+    /// Serve the connections accepted, each with a new service, in the
+    /// background. Connections can be upgraded, e.g., to WebSocket.
+    pub(crate) fn serve<T, F>(self, make_service: F)
+    where
+        F: Fn() -> T + Send + 'static,
+        T: hyper::service::Service<
+                hyper::Request<hyper::body::Incoming>,
+                Response = hyper::Response<http_body_util::Full<hyper::body::Bytes>>,
+                Error = anyhow::Error,
+            > + Send
+            + 'static,
+        T::Future: Send + 'static,
+    {
+        let _web_task = tokio::spawn(async move {
+            loop {
+                let (stream, _) = match self.listener.accept().await {
+                    Ok(val) => val,
+                    Err(_) => {
+                        log::error!("Accept Error");
+                        continue;
+                    }
+                };
+                let service = make_service();
+                let acceptor = self.acceptor.clone();
+                tokio::task::spawn(async move {
+                    match acceptor {
+                        None => serve_connection(stream, service).await,
+                        Some(acceptor) => {
+                            let mut stream =
+                                match openssl::ssl::Ssl::new(acceptor.context()).and_then(|ssl| tokio_openssl::SslStream::new(ssl, stream)) {
+                                    Ok(stream) => stream,
+                                    Err(err) => {
+                                        log::error!("Could not create TLS stream: {}", err);
+                                        return;
+                                    }
+                                };
+                            if let Err(err) = std::pin::Pin::new(&mut stream).accept().await {
+                                log::warn!("TLS handshake error: {}", err);
+                                return;
+                            }
+                            serve_connection(stream, service).await
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
+
+async fn serve_connection<S, T>(stream: S, service: T)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+    T: hyper::service::Service<
+        hyper::Request<hyper::body::Incoming>,
+        Response = hyper::Response<http_body_util::Full<hyper::body::Bytes>>,
+        Error = anyhow::Error,
+    >,
+    T::Future: Send + 'static,
+{
+    if let Err(err) = hyper::server::conn::http1::Builder::new()
+        .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+        .with_upgrades()
+        .await
+    {
+        log::warn!("Error serving connection: {:?}", err);
+    }
 }
 
+/// Create the HTTP web server of the http-ingress resource provider.
+///
+/// - `dataplane_provider`: gives access to the EDGELESS dataplane.
+/// - `ingress_id`: the identifier of the resource provider.
+/// - `ingress_url`: the URL of the web server, e.g., http://0.0.0.0:7035
+///   or https://0.0.0.0:7036 to use TLS.
+/// - `tls_cert_key`: the paths of the PEM files of the TLS certificate
+///   and private key, required if the scheme of the URL is https.
+/// - `routed_outputs`: the output channels, besides new_request, to which
+///   the resource instances can route requests.
 pub async fn ingress_task(
     dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
     ingress_id: edgeless_api::function_instance::InstanceId,
     ingress_url: String,
+    tls_cert_key: Option<(String, String)>,
+    routed_outputs: Vec<String>,
+) -> anyhow::Result<Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId>>> {
+    let web_server = WebServer::bind(&ingress_url, tls_cert_key).await?;
+    Ok(ingress_provider(dataplane_provider, ingress_id, web_server, routed_outputs).await)
+}
+
+/// Create the http-ingress resource provider serving the requests received
+/// by the given web server, see `ingress_task`.
+async fn ingress_provider(
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    ingress_id: edgeless_api::function_instance::InstanceId,
+    web_server: WebServer,
+    routed_outputs: Vec<String>,
 ) -> Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId>> {
     let mut provider = dataplane_provider;
-    let (_, host, port) = edgeless_api::util::parse_http_host(&ingress_url).unwrap();
-    let addr = std::net::SocketAddr::from((std::net::IpAddr::from_str(&host).unwrap(), port));
+    let listen_addr = web_server.listen_addr().to_string();
 
     let dataplane = provider.get_handle_for(ingress_id).await;
 
     let ingress_state = std::sync::Arc::new(tokio::sync::Mutex::new(IngressState {
         interests: Vec::<HTTPIngressInterest>::new(),
         active_resources: std::collections::HashMap::new(),
-        dataplane,
     }));
 
     let cloned_interests = ingress_state.clone();
 
-    let _web_task: tokio::task::JoinHandle<anyhow::Result<()>> = tokio::spawn(async move {
-        let listener = tokio::net::TcpListener::bind(addr).await?;
-        loop {
-            let (stream, _) = match listener.accept().await {
-                Ok(val) => val,
-                Err(_) => {
-                    log::error!("Accept Error");
-                    continue;
-                }
-            };
-            let io = hyper_util::rt::TokioIo::new(stream);
-            let cloned_interests = cloned_interests.clone();
-            let cloned_host = host.clone();
-            let cloned_port = port;
-            tokio::task::spawn(async move {
-                if let Err(err) = hyper::server::conn::http1::Builder::new()
-                    .serve_connection(
-                        io,
-                        IngressService {
-                            interests: cloned_interests,
-                            listen_addr: format!("{}:{}", cloned_host, cloned_port).to_string(),
-                        },
-                    )
-                    .await
-                {
-                    println!("Error serving connection: {:?}", err);
-                }
-            });
-        }
+    web_server.serve(move || IngressService {
+        interests: cloned_interests.clone(),
+        listen_addr: listen_addr.clone(),
+        dataplane: dataplane.clone(),
     });
 
     Box::new(IngressResource {
         own_node_id: ingress_id.node_id,
         configuration_state: ingress_state,
+        routed_outputs,
     })
 }
 
@@ -186,6 +481,8 @@ pub async fn ingress_task(
 struct IngressResource {
     own_node_id: uuid::Uuid,
     configuration_state: std::sync::Arc<tokio::sync::Mutex<IngressState>>,
+    // Output channels, besides new_request, advertised by the provider.
+    routed_outputs: Vec<String>,
 }
 
 #[async_trait::async_trait]
@@ -194,67 +491,61 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         &mut self,
         instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
     ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let conf = match HttpIngressConfiguration::from(&instance_specification.configuration).and_then(|conf| {
+            match conf
+                .routes
+                .iter()
+                .find(|(_, output)| output != "new_request" && !self.routed_outputs.contains(output))
+            {
+                Some((_, output)) => Err(anyhow::anyhow!("Output channel not advertised by the provider: {}", output)),
+                None => Ok(conf),
+            }
+        }) {
+            Ok(conf) => conf,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
+            }
+        };
+
+        // Assign a new component identifier to the newly-created  resource.
+        let resource_id = edgeless_api::function_instance::InstanceId::new(self.own_node_id);
         let mut lck = self.configuration_state.lock().await;
-        if let (Some(host), Some(methods)) = (
-            instance_specification.configuration.get("host"),
-            instance_specification.configuration.get("methods"),
-        ) {
-            // Assign a new component identifier to the newly-created  resource.
-            let resource_id = edgeless_api::function_instance::InstanceId::new(self.own_node_id);
-            lck.active_resources.insert(
-                resource_id.function_id,
-                ResourceDesc {
-                    host: host.clone(),
-                    allow: methods
-                        .split(",")
-                        .filter_map(|str_method| match edgeless_http::string_method_to_edgeless(str_method) {
-                            Ok(val) => Some(val),
-                            Err(_) => {
-                                log::warn!("Bad HTTP Method");
-                                None
-                            }
-                        })
-                        .collect(),
-                },
-            );
-            Ok(edgeless_api::common::StartComponentResponse::InstanceId(resource_id))
-        } else {
-            Ok(edgeless_api::common::StartComponentResponse::ResponseError(
-                edgeless_api::common::ResponseError {
-                    summary: "Error when creating a resource".to_string(),
-                    detail: Some("Missing Resource Configuration".to_string()),
-                },
-            ))
-        }
+        lck.active_resources.insert(resource_id.function_id, std::sync::Arc::new(conf));
+        Ok(edgeless_api::common::StartComponentResponse::InstanceId(resource_id))
     }
+
     async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
         let mut lck = self.configuration_state.lock().await;
         lck.interests.retain(|item| item.resource_id != resource_id);
+        lck.active_resources.remove(&resource_id.function_id);
         Ok(())
     }
 
     async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
-        let target = match update.output_mapping.get("new_request") {
-            Some(val) => *val,
-            None => {
-                return Err(anyhow::anyhow!("Missing mapping of channel: new_request"));
-            }
-        };
+        if update.output_mapping.is_empty() {
+            return Err(anyhow::anyhow!("Missing mapping of channel: new_request"));
+        }
         let mut lck = self.configuration_state.lock().await;
-        let (host, allow) = match lck.active_resources.get(&update.function_id) {
-            Some(val) => (val.host.clone(), val.allow.clone()),
+        let conf = match lck.active_resources.get(&update.function_id) {
+            Some(val) => val.clone(),
             None => {
                 return Err(anyhow::anyhow!("Patching a non-existing resource: {}", update.function_id));
             }
         };
+        let resource_id = InstanceId {
+            node_id: self.own_node_id,
+            function_id: update.function_id,
+        };
+        lck.interests.retain(|item| item.resource_id != resource_id);
         lck.interests.push(HTTPIngressInterest {
-            resource_id: InstanceId {
-                node_id: self.own_node_id,
-                function_id: update.function_id,
-            },
-            host,
-            allow,
-            target,
+            resource_id,
+            conf,
+            targets: update.output_mapping,
         });
 
         Ok(())
@@ -263,7 +554,185 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
 
 struct HTTPIngressInterest {
     resource_id: edgeless_api::function_instance::InstanceId,
-    host: String,
-    allow: std::collections::HashSet<edgeless_http::EdgelessHTTPMethod>,
-    target: edgeless_api::function_instance::InstanceId,
+    conf: std::sync::Arc<HttpIngressConfiguration>,
+    // Components receiving the requests, by output channel.
+    targets: std::collections::HashMap<String, edgeless_api::function_instance::InstanceId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn test_http_ingress_parse_configuration() {
+        let mut configuration = std::collections::HashMap::from([("host".to_string(), "demo.edgeless.com".to_string())]);
+        assert!(HttpIngressConfiguration::from(&configuration).is_err());
+        configuration.insert("methods".to_string(), "POST,get".to_string());
+        assert_eq!(
+            HttpIngressConfiguration {
+                host: "demo.edgeless.com".to_string(),
+                allow: std::collections::HashSet::from([edgeless_http::EdgelessHTTPMethod::Post, edgeless_http::EdgelessHTTPMethod::Get]),
+                routes: vec![],
+                mode: HttpIngressMode::Call,
+                timeout: DEFAULT_TIMEOUT,
+                max_body_size: None,
+            },
+            HttpIngressConfiguration::from(&configuration).unwrap()
+        );
+
+        configuration.insert("routes".to_string(), "/api=api, /api/v2/=v2,/static=files".to_string());
+        configuration.insert("mode".to_string(), "sync".to_string());
+        configuration.insert("timeout_ms".to_string(), "500".to_string());
+        configuration.insert("max_body_size".to_string(), "1024".to_string());
+        let conf = HttpIngressConfiguration::from(&configuration).unwrap();
+        assert_eq!(HttpIngressMode::Sync, conf.mode);
+        assert_eq!(std::time::Duration::from_millis(500), conf.timeout);
+        assert_eq!(Some(1024), conf.max_body_size);
+        assert_eq!("v2", conf.output("/api/v2/items"));
+        assert_eq!("api", conf.output("/api/v2"));
+        assert_eq!("api", conf.output("/api"));
+        assert_eq!("api", conf.output("/api/v1/items"));
+        assert_eq!("new_request", conf.output("/apis"));
+        assert_eq!("files", conf.output("/static/index.html"));
+        assert_eq!("new_request", conf.output("/"));
+
+        for (key, value) in [
+            ("methods", "POST,CONNECT"),
+            ("routes", "api=api"),
+            ("routes", "/api="),
+            ("mode", "stream"),
+            ("timeout_ms", "0"),
+            ("max_body_size", "1MB"),
+        ] {
+            let mut configuration = configuration.clone();
+            configuration.insert(key.to_string(), value.to_string());
+            assert!(HttpIngressConfiguration::from(&configuration).is_err(), "{}={}", key, value);
+        }
+    }
+
+    /// Receive the next call, returning the event and the HTTP request.
+    async fn receive_call(
+        handle: &mut edgeless_dataplane::handle::DataplaneHandle,
+    ) -> (edgeless_dataplane::core::DataplaneEvent, edgeless_http::EdgelessHTTPRequest) {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), handle.receive_next())
+            .await
+            .expect("request not received");
+        match &event.message {
+            edgeless_dataplane::core::Message::Call(msg) => {
+                let request = serde_json::from_str(msg).unwrap();
+                (event, request)
+            }
+            message => panic!("unexpected message: {:?}", message),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_ingress_concurrent_requests() {
+        let mut node = TestNode::new().await;
+        let web_server = WebServer::bind("http://127.0.0.1:0", None).await.unwrap();
+        let listen_addr = web_server.listen_addr().to_string();
+        let mut ingress = ingress_provider(node.dataplane_provider.clone(), node.provider_id(), web_server, vec!["slow".to_string()]).await;
+
+        // Only the output channels advertised can be routed to.
+        let configuration = [
+            ("host", listen_addr.as_str()),
+            ("methods", "POST"),
+            ("mode", "sync"),
+            ("timeout_ms", "5000"),
+        ];
+        assert!(matches!(
+            try_start(
+                ingress.as_mut(),
+                "http-ingress",
+                &[configuration.as_slice(), &[("routes", "/other=other")]].concat()
+            )
+            .await,
+            edgeless_api::common::StartComponentResponse::ResponseError(_)
+        ));
+        let resource_id = start(
+            ingress.as_mut(),
+            "http-ingress",
+            &[configuration.as_slice(), &[("routes", "/slow=slow")]].concat(),
+        )
+        .await;
+        let (target_id, mut target_handle) = node.function().await;
+        ingress
+            .patch(edgeless_api::common::PatchRequest {
+                function_id: resource_id.function_id,
+                output_mapping: std::collections::HashMap::from([("new_request".to_string(), target_id), ("slow".to_string(), target_id)]),
+            })
+            .await
+            .unwrap();
+
+        let post = |path: &str| {
+            let url = format!("http://{}{}", listen_addr, path);
+            tokio::spawn(async move { reqwest::Client::new().post(url).send().await.unwrap().text().await.unwrap() })
+        };
+        // A request is answered while the reply to another one is pending.
+        let slow_response = post("/slow");
+        let (slow_event, slow_request) = receive_call(&mut target_handle).await;
+        assert_eq!("/slow", slow_request.path);
+        let fast_response = post("/fast");
+        let (fast_event, fast_request) = receive_call(&mut target_handle).await;
+        assert_eq!("/fast", fast_request.path);
+        target_handle
+            .reply(
+                fast_event.source_id,
+                fast_event.channel_id,
+                edgeless_dataplane::core::CallRet::Reply("fast".to_string()),
+            )
+            .await;
+        assert_eq!("fast", fast_response.await.unwrap());
+        target_handle
+            .reply(
+                slow_event.source_id,
+                slow_event.channel_id,
+                edgeless_dataplane::core::CallRet::Reply("slow".to_string()),
+            )
+            .await;
+        assert_eq!("slow", slow_response.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_http_ingress_call_mode() {
+        let mut node = TestNode::new().await;
+        let web_server = WebServer::bind("http://127.0.0.1:0", None).await.unwrap();
+        let listen_addr = web_server.listen_addr().to_string();
+        let mut ingress = ingress_provider(node.dataplane_provider.clone(), node.provider_id(), web_server, vec![]).await;
+        let resource_id = start(
+            ingress.as_mut(),
+            "http-ingress",
+            &[("host", listen_addr.as_str()), ("methods", "GET"), ("timeout_ms", "5000")],
+        )
+        .await;
+        let (target_id, mut target_handle) = node.function().await;
+        patch(ingress.as_mut(), resource_id, "new_request", target_id).await;
+
+        // The response of the target is returned as it is, unless its
+        // status is invalid.
+        for (status, expected_status) in [(201, reqwest::StatusCode::CREATED), (1000, reqwest::StatusCode::BAD_GATEWAY)] {
+            let url = format!("http://{}/", listen_addr);
+            let response = tokio::spawn(async move { reqwest::get(url).await.unwrap() });
+            let (event, _request) = receive_call(&mut target_handle).await;
+            let reply = edgeless_http::EdgelessHTTPResponse {
+                body: Some(b"created".to_vec()),
+                status,
+                headers: std::collections::HashMap::from([("x-test".to_string(), "1".to_string())]),
+            };
+            target_handle
+                .reply(
+                    event.source_id,
+                    event.channel_id,
+                    edgeless_dataplane::core::CallRet::Reply(serde_json::to_string(&reply).unwrap()),
+                )
+                .await;
+            let response = response.await.unwrap();
+            assert_eq!(expected_status, response.status());
+            if expected_status == reqwest::StatusCode::CREATED {
+                assert_eq!("1", response.headers()["x-test"]);
+                assert_eq!("created", response.text().await.unwrap());
+            }
+        }
+    }
 }
@@ -0,0 +1,395 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use rdkafka::consumer::Consumer;
+use rdkafka::Message;
+
+pub struct KafkaIngressResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for KafkaIngressResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("kafka-ingress")
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![String::from("new_message")]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (
+                String::from("brokers"),
+                String::from("Comma-separated list of initial brokers to access the cluster"),
+            ),
+            (
+                String::from("topic"),
+                String::from("Comma-separated list of topics to which to subscribe"),
+            ),
+            (
+                String::from("group_id"),
+                String::from("Consumer group, by default edgeless- followed by the sorted topics"),
+            ),
+            (
+                String::from("auto_offset_reset"),
+                String::from("Where to start consuming without a committed offset: latest (default) or earliest"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KafkaIngressConfiguration {
+    // Comma-separated list of initial brokers.
+    brokers: String,
+    topics: Vec<String>,
+    group_id: String,
+    // Value of the auto.offset.reset property of the consumer.
+    auto_offset_reset: String,
+}
+
+impl KafkaIngressConfiguration {
+    /// Parse the run-time configuration of a kafka-ingress resource instance.
+    ///
+    /// If not specified, the consumer group is derived from the topics, so
+    /// that it survives the restart or migration of the resource instance.
+    fn from(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let brokers = match configuration.get("brokers") {
+            Some(brokers) if !brokers.is_empty() => brokers.clone(),
+            _ => anyhow::bail!("Missing Kafka brokers"),
+        };
+        let topics = configuration
+            .get("topic")
+            .map(|x| {
+                x.split(',')
+                    .map(|x| x.trim().to_string())
+                    .filter(|x| !x.is_empty())
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        if topics.is_empty() {
+            anyhow::bail!("Missing Kafka topic");
+        }
+        let group_id = match configuration.get("group_id") {
+            Some(group_id) if !group_id.is_empty() => group_id.clone(),
+            _ => {
+                let mut sorted = topics.clone();
+                sorted.sort();
+                format!("edgeless-{}", sorted.join(","))
+            }
+        };
+        let auto_offset_reset = match configuration.get("auto_offset_reset").map(|x| x.to_lowercase()) {
+            None => String::from("latest"),
+            Some(reset) => match reset.as_str() {
+                "" | "latest" => String::from("latest"),
+                "earliest" => String::from("earliest"),
+                _ => anyhow::bail!("Invalid Kafka offset reset: {}", reset),
+            },
+        };
+        Ok(Self {
+            brokers,
+            topics,
+            group_id,
+            auto_offset_reset,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct KafkaIngressResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<KafkaIngressResourceProviderInner>>,
+}
+
+pub struct KafkaIngressResourceProviderInner {
+    resource_provider_id: edgeless_api::function_instance::InstanceId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, KafkaIngressResource>,
+}
+
+pub struct KafkaIngressResource {
+    // Component receiving the records on the new_message output channel.
+    target_sender: tokio::sync::watch::Sender<Option<edgeless_api::function_instance::InstanceId>>,
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for KafkaIngressResource {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+impl KafkaIngressResource {
+    /// Create a new Kafka ingress resource, which consumes the records of
+    /// the topics and calls the component mapped to the new_message output
+    /// channel with their payloads.
+    ///
+    /// The offset of a record is committed only after the call has returned
+    /// without an error, otherwise the call is retried: no record is
+    /// consumed while the output channel is not mapped, and the records
+    /// not processed when the resource is stopped are consumed again by the
+    /// next consumer of the same group.
+    ///
+    /// - `dataplane_handle`: gives access to the EDGELESS dataplane.
+    /// - `configuration`: the configuration of the resource instance.
+    async fn new(dataplane_handle: edgeless_dataplane::handle::DataplaneHandle, configuration: KafkaIngressConfiguration) -> anyhow::Result<Self> {
+        let mut dataplane_handle = dataplane_handle;
+
+        let consumer: rdkafka::consumer::StreamConsumer = rdkafka::config::ClientConfig::new()
+            .set("bootstrap.servers", &configuration.brokers)
+            .set("group.id", &configuration.group_id)
+            .set("auto.offset.reset", &configuration.auto_offset_reset)
+            .set("enable.auto.commit", "false")
+            .create()?;
+        consumer.subscribe(&configuration.topics.iter().map(|x| x.as_str()).collect::<Vec<&str>>())?;
+
+        log::info!(
+            "KafkaIngressResource created, brokers: {}, topics: {}, group: {}, offset reset: {}",
+            configuration.brokers,
+            configuration.topics.join(","),
+            configuration.group_id,
+            configuration.auto_offset_reset
+        );
+
+        let (target_sender, mut target_receiver) = tokio::sync::watch::channel(None);
+
+        let handle = tokio::spawn(async move {
+            loop {
+                // Do not consume records until the target is known.
+                if target_receiver.wait_for(|target| target.is_some()).await.is_err() {
+                    return;
+                }
+                match consumer.recv().await {
+                    Ok(record) => {
+                        let payload = String::from_utf8_lossy(record.payload().unwrap_or_default()).to_string();
+                        if !deliver(&mut dataplane_handle, &mut target_receiver, payload, RETRY_INTERVAL).await {
+                            return;
+                        }
+                        if let Err(err) = consumer.commit_message(&record, rdkafka::consumer::CommitMode::Async) {
+                            log::warn!(
+                                "Could not commit offset {} of Kafka topic '{}' partition {}: {}",
+                                record.offset(),
+                                record.topic(),
+                                record.partition(),
+                                err
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        log::warn!("Error from the Kafka brokers {}: {}", configuration.brokers, err);
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            target_sender,
+            join_handle: handle,
+        })
+    }
+}
+
+/// Interval between the calls with a record that have failed.
+const RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Call the target component with the payload of a record until the call
+/// returns without an error, waiting `retry_interval` after every failure.
+///
+/// Return false if the resource is stopped before the record is processed.
+async fn deliver(
+    dataplane_handle: &mut edgeless_dataplane::handle::DataplaneHandle,
+    target_receiver: &mut tokio::sync::watch::Receiver<Option<edgeless_api::function_instance::InstanceId>>,
+    payload: String,
+    retry_interval: std::time::Duration,
+) -> bool {
+    loop {
+        // The target may change, e.g., after a patch, between the retries.
+        let target = match target_receiver.wait_for(|target| target.is_some()).await {
+            Ok(target) => target.expect("target must be set"),
+            Err(_) => return false,
+        };
+        match dataplane_handle.call(target, payload.clone()).await {
+            edgeless_dataplane::core::CallRet::Err => {
+                log::warn!("Could not deliver a Kafka record to {}, retrying", target.function_id);
+                tokio::time::sleep(retry_interval).await;
+            }
+            _ => return true,
+        }
+    }
+}
+
+impl KafkaIngressResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        resource_provider_id: edgeless_api::function_instance::InstanceId,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(KafkaIngressResourceProviderInner {
+                resource_provider_id,
+                dataplane_provider,
+                instances: std::collections::HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for KafkaIngressResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+
+        let configuration = match KafkaIngressConfiguration::from(&instance_specification.configuration) {
+            Ok(configuration) => configuration,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
+            }
+        };
+
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+
+        match KafkaIngressResource::new(dataplane_handle, configuration).await {
+            Ok(resource) => {
+                lck.instances.insert(new_id.function_id, resource);
+                Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+            }
+            Err(err) => Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Invalid resource configuration".to_string(),
+                    detail: Some(err.to_string()),
+                },
+            )),
+        }
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        // Find the target component to which we have to send the records
+        // on the "new_message" output channel.
+        let target = match update.output_mapping.get("new_message") {
+            Some(val) => *val,
+            None => {
+                anyhow::bail!("Missing mapping of channel: new_message");
+            }
+        };
+
+        let lck = self.inner.lock().await;
+        match lck.instances.get(&update.function_id) {
+            Some(resource) => {
+                resource.target_sender.send_replace(Some(target));
+                Ok(())
+            }
+            None => {
+                anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kafka_ingress_parse_configuration() {
+        let mut configuration = std::collections::HashMap::from([("brokers".to_string(), "localhost:9092".to_string())]);
+        assert!(KafkaIngressConfiguration::from(&configuration).is_err());
+        configuration.insert("topic".to_string(), " ,".to_string());
+        assert!(KafkaIngressConfiguration::from(&configuration).is_err());
+
+        // The default consumer group depends only on the topics.
+        configuration.insert("topic".to_string(), "t2, t1".to_string());
+        assert_eq!(
+            KafkaIngressConfiguration {
+                brokers: "localhost:9092".to_string(),
+                topics: vec!["t2".to_string(), "t1".to_string()],
+                group_id: "edgeless-t1,t2".to_string(),
+                auto_offset_reset: "latest".to_string(),
+            },
+            KafkaIngressConfiguration::from(&configuration).unwrap()
+        );
+
+        configuration.insert("group_id".to_string(), "my-group".to_string());
+        configuration.insert("auto_offset_reset".to_string(), "Earliest".to_string());
+        let conf = KafkaIngressConfiguration::from(&configuration).unwrap();
+        assert_eq!("my-group", conf.group_id);
+        assert_eq!("earliest", conf.auto_offset_reset);
+
+        configuration.insert("auto_offset_reset".to_string(), "none".to_string());
+        assert!(KafkaIngressConfiguration::from(&configuration).is_err());
+        configuration.remove("brokers");
+        configuration.remove("auto_offset_reset");
+        assert!(KafkaIngressConfiguration::from(&configuration).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_kafka_ingress_deliver() {
+        let node_id = uuid::Uuid::new_v4();
+        let mut dataplane_provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, "http://127.0.0.1:0".to_string(), None).await;
+        let resource_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let target_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let mut resource_handle = dataplane_provider.get_handle_for(resource_id).await;
+        let mut target_handle = dataplane_provider.get_handle_for(target_id).await;
+        let (target_sender, mut target_receiver) = tokio::sync::watch::channel(None);
+
+        // The target fails to process the record the first time.
+        let target_task = tokio::spawn(async move {
+            let mut payloads = vec![];
+            for ret in [edgeless_dataplane::core::CallRet::Err, edgeless_dataplane::core::CallRet::NoReply] {
+                let event = target_handle.receive_next().await;
+                match event.message {
+                    edgeless_dataplane::core::Message::Call(payload) => payloads.push(payload),
+                    message => panic!("unexpected message: {:?}", message),
+                }
+                target_handle.reply(event.source_id, event.channel_id, ret).await;
+            }
+            payloads
+        });
+
+        // The record is not delivered, hence not committed, until the
+        // target is known and processes it.
+        let delivery = tokio::spawn(async move {
+            deliver(
+                &mut resource_handle,
+                &mut target_receiver,
+                "record".to_string(),
+                std::time::Duration::from_millis(10),
+            )
+            .await
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!delivery.is_finished());
+        target_sender.send_replace(Some(target_id));
+        assert!(tokio::time::timeout(std::time::Duration::from_secs(5), delivery).await.unwrap().unwrap());
+        assert_eq!(vec!["record".to_string(), "record".to_string()], target_task.await.unwrap());
+
+        // The resource is stopped before the record is delivered.
+        let (target_sender, mut target_receiver) = tokio::sync::watch::channel(None);
+        drop(target_sender);
+        let mut resource_handle = dataplane_provider.get_handle_for(resource_id).await;
+        assert!(
+            !deliver(
+                &mut resource_handle,
+                &mut target_receiver,
+                "record".to_string(),
+                std::time::Duration::from_millis(10)
+            )
+            .await
+        );
+    }
+}
@@ -150,6 +150,376 @@ impl std::fmt::Display for RedisCommand {
     }
 }
 
+/// Default upper bounds of the histogram buckets, in ms.
+pub const DEFAULT_HISTOGRAM_BUCKETS: [f64; 13] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0];
+
+/// Default percentiles of the latency distributions.
+pub const DEFAULT_PERCENTILES: [f64; 4] = [50.0, 90.0, 95.0, 99.0];
+
+/// Options of the latency statistics computed by the metrics-collector
+/// resource provider.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LatencyStatisticsOptions {
+    /// Upper bounds of the histogram buckets, in ms, in increasing order.
+    pub buckets: Vec<f64>,
+    /// Percentiles estimated from the histograms, in (0, 100].
+    pub percentiles: Vec<f64>,
+    /// If set, the statistics of every epoch are saved to this file in
+    /// CSV format.
+    pub csv_filename: Option<String>,
+}
+
+impl Default for LatencyStatisticsOptions {
+    fn default() -> Self {
+        Self {
+            buckets: DEFAULT_HISTOGRAM_BUCKETS.to_vec(),
+            percentiles: DEFAULT_PERCENTILES.to_vec(),
+            csv_filename: None,
+        }
+    }
+}
+
+impl LatencyStatisticsOptions {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(!self.buckets.is_empty(), "empty histogram buckets");
+        anyhow::ensure!(
+            self.buckets.iter().all(|x| x.is_finite() && *x > 0.0) && self.buckets.windows(2).all(|x| x[0] < x[1]),
+            "histogram buckets must be positive and increasing: {:?}",
+            self.buckets
+        );
+        anyhow::ensure!(
+            self.percentiles.iter().all(|x| *x > 0.0 && *x <= 100.0),
+            "percentiles must be in (0, 100]: {:?}",
+            self.percentiles
+        );
+        Ok(())
+    }
+}
+
+/// Histogram of the latency samples of a workflow or function, in ms.
+#[derive(Clone, Debug, PartialEq)]
+struct LatencyHistogram {
+    // Number of samples in every bucket, the last one is unbounded.
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl LatencyHistogram {
+    fn new(buckets: &[f64]) -> Self {
+        Self {
+            counts: vec![0; buckets.len() + 1],
+            count: 0,
+            sum: 0.0,
+            min: f64::MAX,
+            max: f64::MIN,
+        }
+    }
+
+    fn add(&mut self, buckets: &[f64], value: f64) {
+        let bucket = buckets.iter().position(|bound| value <= *bound).unwrap_or(buckets.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn mean(&self) -> f64 {
+        match self.count {
+            0 => 0.0,
+            count => self.sum / count as f64,
+        }
+    }
+
+    /// Estimate a percentile, in (0, 100], by linear interpolation within
+    /// the bucket that contains it, whose bounds are clamped to the minimum
+    /// and maximum values observed.
+    fn percentile(&self, buckets: &[f64], percentile: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let rank = percentile / 100.0 * self.count as f64;
+        let mut cumulative = 0;
+        for (i, count) in self.counts.iter().enumerate().filter(|(_, count)| **count > 0) {
+            if (cumulative + count) as f64 >= rank {
+                let lower = if i == 0 { self.min } else { buckets[i - 1].max(self.min) };
+                let upper = if i == buckets.len() { self.max } else { buckets[i].min(self.max) };
+                let fraction = ((rank - cumulative as f64) / *count as f64).clamp(0.0, 1.0);
+                return lower + (upper - lower) * fraction;
+            }
+            cumulative += count;
+        }
+        self.max
+    }
+
+    /// Cumulative number of samples for every bucket bound, excluding the
+    /// unbounded one, which is equal to the total number of samples.
+    fn cumulative(&self, buckets: &[f64]) -> Vec<(f64, u64)> {
+        buckets
+            .iter()
+            .zip(self.counts.iter().scan(0, |acc, count| {
+                *acc += count;
+                Some(*acc)
+            }))
+            .map(|(bound, count)| (*bound, count))
+            .collect()
+    }
+}
+
+/// Latency statistics of the workflows and functions, by epoch.
+struct LatencyStatistics {
+    options: LatencyStatisticsOptions,
+    // Sequence number of the current epoch, incremented at every reset.
+    epoch: u64,
+    // Histograms of the current epoch, by type (workflow or function) and
+    // identifier.
+    histograms: std::collections::BTreeMap<(String, String), LatencyHistogram>,
+    // CSV rows of the past epochs not yet taken to be saved.
+    past_rows: Vec<String>,
+    // True if the statistics changed since they were last taken to be saved.
+    dirty: bool,
+}
+
+impl LatencyStatistics {
+    fn new(options: LatencyStatisticsOptions) -> Self {
+        Self {
+            options,
+            epoch: 0,
+            histograms: std::collections::BTreeMap::new(),
+            past_rows: vec![],
+            dirty: false,
+        }
+    }
+
+    /// Add a sample and return the updated statistics of the workflow or
+    /// function in JSON format.
+    fn add(&mut self, kind: &str, id: &str, value: f64) -> String {
+        let buckets = &self.options.buckets;
+        let histogram = self
+            .histograms
+            .entry((kind.to_string(), id.to_string()))
+            .or_insert_with(|| LatencyHistogram::new(buckets));
+        histogram.add(buckets, value);
+        self.dirty = true;
+        serde_json::json!({
+            "count": histogram.count,
+            "mean": histogram.mean(),
+            "min": histogram.min,
+            "max": histogram.max,
+            "percentiles": self.options.percentiles.iter().map(|p| (*p, histogram.percentile(buckets, *p))).collect::<Vec<(f64, f64)>>(),
+            "buckets": histogram.cumulative(buckets),
+        })
+        .to_string()
+    }
+
+    /// Start a new epoch.
+    fn reset(&mut self) {
+        let rows = self.rows();
+        self.past_rows.extend(rows);
+        self.histograms.clear();
+        self.epoch += 1;
+        self.dirty = true;
+    }
+
+    fn csv_header(&self) -> String {
+        let mut header = String::from("epoch,type,id,count,mean,min,max");
+        for percentile in &self.options.percentiles {
+            header.push_str(&format!(",p{}", percentile));
+        }
+        header
+    }
+
+    /// CSV rows of the current epoch.
+    fn rows(&self) -> Vec<String> {
+        let buckets = &self.options.buckets;
+        self.histograms
+            .iter()
+            .map(|((kind, id), histogram)| {
+                let mut row = format!(
+                    "{},{},{},{},{},{},{}",
+                    self.epoch,
+                    kind,
+                    id,
+                    histogram.count,
+                    histogram.mean(),
+                    histogram.min,
+                    histogram.max
+                );
+                for percentile in &self.options.percentiles {
+                    row.push_str(&format!(",{}", histogram.percentile(buckets, *percentile)));
+                }
+                row
+            })
+            .collect()
+    }
+
+    /// Take the CSV rows of the past epochs not taken yet and those of the
+    /// current epoch, if the statistics changed since the last time.
+    fn take_rows(&mut self) -> Option<(Vec<String>, Vec<String>)> {
+        if !self.dirty {
+            return None;
+        }
+        self.dirty = false;
+        Some((std::mem::take(&mut self.past_rows), self.rows()))
+    }
+
+    /// Statistics of the current epoch in the Prometheus text format.
+    fn prometheus(&self) -> String {
+        let buckets = &self.options.buckets;
+        let mut ret = String::from("# HELP edgeless_latency_ms Latency of workflows and functions, in ms.\n# TYPE edgeless_latency_ms histogram\n");
+        for ((kind, id), histogram) in &self.histograms {
+            let labels = format!("type=\"{}\",id=\"{}\"", escape_label(kind), escape_label(id));
+            for (bound, count) in histogram.cumulative(buckets) {
+                ret.push_str(&format!("edgeless_latency_ms_bucket{{{},le=\"{}\"}} {}\n", labels, bound, count));
+            }
+            ret.push_str(&format!("edgeless_latency_ms_bucket{{{},le=\"+Inf\"}} {}\n", labels, histogram.count));
+            ret.push_str(&format!("edgeless_latency_ms_sum{{{}}} {}\n", labels, histogram.sum));
+            ret.push_str(&format!("edgeless_latency_ms_count{{{}}} {}\n", labels, histogram.count));
+        }
+        ret.push_str("# HELP edgeless_latency_percentile_ms Percentiles of the latency of workflows and functions, in ms.\n");
+        ret.push_str("# TYPE edgeless_latency_percentile_ms gauge\n");
+        for ((kind, id), histogram) in &self.histograms {
+            for percentile in &self.options.percentiles {
+                ret.push_str(&format!(
+                    "edgeless_latency_percentile_ms{{type=\"{}\",id=\"{}\",percentile=\"{}\"}} {}\n",
+                    escape_label(kind),
+                    escape_label(id),
+                    percentile,
+                    histogram.percentile(buckets, *percentile)
+                ));
+            }
+        }
+        ret
+    }
+}
+
+/// Escape a label value of the Prometheus text format.
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Maximum number of CSV rows of the past epochs waiting to be saved,
+/// beyond which the oldest ones are dropped.
+const MAX_PENDING_ROWS: usize = 10000;
+
+/// Save the latency statistics to a CSV file: the rows of the past epochs,
+/// which do not change, are appended once, while those of the current epoch
+/// are overwritten every time.
+struct CsvWriter {
+    filename: String,
+    header: String,
+    // Length of the header and rows of the past epochs saved, in bytes, or
+    // None if the file has not been created yet.
+    saved_len: Option<u64>,
+    // Rows of the past epochs not saved yet.
+    pending_rows: std::collections::VecDeque<String>,
+}
+
+impl CsvWriter {
+    fn new(filename: String, header: String) -> Self {
+        Self {
+            filename,
+            header,
+            saved_len: None,
+            pending_rows: std::collections::VecDeque::new(),
+        }
+    }
+
+    /// Save the statistics, if changed since the last time.
+    async fn save(&mut self, statistics: &std::sync::Mutex<LatencyStatistics>) -> anyhow::Result<()> {
+        let current_rows = match statistics.lock().expect("poisoned lock").take_rows() {
+            Some((past_rows, current_rows)) => {
+                self.pending_rows.extend(past_rows);
+                current_rows
+            }
+            None => return Ok(()),
+        };
+        if self.pending_rows.len() > MAX_PENDING_ROWS {
+            log::warn!(
+                "dropping {} rows of the latency statistics not saved",
+                self.pending_rows.len() - MAX_PENDING_ROWS
+            );
+            self.pending_rows.drain(..self.pending_rows.len() - MAX_PENDING_ROWS);
+        }
+
+        let res = self.write(&current_rows).await;
+        if res.is_err() {
+            // Try again at the next save.
+            statistics.lock().expect("poisoned lock").dirty = true;
+        }
+        res
+    }
+
+    /// Append the pending rows of the past epochs and overwrite those of the
+    /// current epoch.
+    async fn write(&mut self, current_rows: &[String]) -> anyhow::Result<()> {
+        use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.filename)
+            .await?;
+        let mut saved_len = match self.saved_len {
+            Some(saved_len) => saved_len,
+            None => {
+                let header = format!("{}\n", self.header);
+                file.set_len(0).await?;
+                file.write_all(header.as_bytes()).await?;
+                self.saved_len = Some(header.len() as u64);
+                header.len() as u64
+            }
+        };
+
+        file.seek(std::io::SeekFrom::Start(saved_len)).await?;
+        let past_rows: String = self.pending_rows.iter().map(|row| format!("{}\n", row)).collect();
+        file.write_all(past_rows.as_bytes()).await?;
+        saved_len += past_rows.len() as u64;
+        self.saved_len = Some(saved_len);
+        self.pending_rows.clear();
+
+        let current_rows: String = current_rows.iter().map(|row| format!("{}\n", row)).collect();
+        file.write_all(current_rows.as_bytes()).await?;
+        file.set_len(saved_len + current_rows.len() as u64).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Web service exposing the latency statistics to Prometheus at /metrics.
+#[derive(Clone)]
+struct PrometheusService {
+    statistics: std::sync::Arc<std::sync::Mutex<LatencyStatistics>>,
+}
+
+impl hyper::service::Service<hyper::Request<hyper::body::Incoming>> for PrometheusService {
+    type Response = hyper::Response<http_body_util::Full<hyper::body::Bytes>>;
+
+    type Error = anyhow::Error;
+
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn call(&self, req: hyper::Request<hyper::body::Incoming>) -> Self::Future {
+        let statistics = self.statistics.clone();
+        Box::pin(async move {
+            if req.method() != hyper::Method::GET || req.uri().path() != "/metrics" {
+                return Ok(super::http_ingress::simple_response(hyper::StatusCode::NOT_FOUND, "Not Found"));
+            }
+            let body = statistics.lock().expect("poisoned lock").prometheus();
+            let mut response = super::http_ingress::simple_response(hyper::StatusCode::OK, &body);
+            response.headers_mut().insert(
+                hyper::header::CONTENT_TYPE,
+                hyper::header::HeaderValue::from_static("text/plain; version=0.0.4"),
+            );
+            Ok(response)
+        })
+    }
+}
+
 pub struct MetricsCollectorResourceProviderInner {
     resource_provider_id: edgeless_api::function_instance::InstanceId,
     dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
@@ -251,11 +621,39 @@ impl MetricsCollectorResource {
 }
 
 impl MetricsCollectorResourceProvider {
+    /// Create the metrics-collector resource provider.
+    ///
+    /// - `dataplane_provider`: gives access to the EDGELESS dataplane.
+    /// - `resource_provider_id`: the identifier of the resource provider.
+    /// - `redis_connection`: the connection to the Redis where the metrics
+    ///   are saved.
+    /// - `options`: the options of the latency statistics.
+    /// - `prometheus_url`: if set, the URL of the web server exposing the
+    ///   latency statistics to Prometheus at /metrics, e.g.,
+    ///   http://0.0.0.0:7005.
     pub async fn new(
         dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
         resource_provider_id: edgeless_api::function_instance::InstanceId,
         redis_connection: redis::Connection,
-    ) -> Self {
+        options: LatencyStatisticsOptions,
+        prometheus_url: Option<String>,
+    ) -> anyhow::Result<Self> {
+        options.validate()?;
+        let statistics = LatencyStatistics::new(options);
+        let mut csv_writer = statistics
+            .options
+            .csv_filename
+            .clone()
+            .map(|filename| CsvWriter::new(filename, statistics.csv_header()));
+        let statistics = std::sync::Arc::new(std::sync::Mutex::new(statistics));
+        if let Some(prometheus_url) = prometheus_url {
+            let web_server = super::http_ingress::WebServer::bind(&prometheus_url, None).await?;
+            let cloned_statistics = statistics.clone();
+            web_server.serve(move || PrometheusService {
+                statistics: cloned_statistics.clone(),
+            });
+        }
+
         // Create a channel for:
         // - single receiver: the loop in the task below
         // - multiple senders: the resource instances that will be created
@@ -267,7 +665,24 @@ impl MetricsCollectorResourceProvider {
             let mut keys = std::collections::HashSet::new();
             let mut ts = std::time::Instant::now();
             let mut warmup = std::time::Duration::from_secs(0);
-            while let Some(command) = receiver.next().await {
+            // The CSV file, if any, is saved at most once per second.
+            let mut save_interval = tokio::time::interval(std::time::Duration::from_secs(1));
+            loop {
+                let command = tokio::select! {
+                    command = receiver.next() => match command {
+                        Some(command) => command,
+                        None => break,
+                    },
+                    _ = save_interval.tick() => {
+                        if let Some(csv_writer) = &mut csv_writer {
+                            if let Err(err) = csv_writer.save(&statistics).await {
+                                log::error!("could not save the latency statistics: {}", err);
+                            }
+                        }
+                        continue;
+                    }
+                };
+
                 // If this is a non-reset command we check if we are still in
                 // the warm-up period of this epoch, in which case we skip this
                 // command.
@@ -278,9 +693,20 @@ impl MetricsCollectorResourceProvider {
                 let res = match &command {
                     RedisCommand::Push(key, value, instant) => {
                         keys.insert(key.to_string());
-                        redis_connection
+                        let res = redis_connection
                             .rpush::<&str, String, usize>(key, format!("{},{}", *value, RedisCommand::timestamp(instant)))
-                            .err()
+                            .err();
+                        // Update the statistics of the samples in
+                        // workflow:WF_NAME:samples and function:UUID:samples.
+                        match key.strip_suffix(":samples").and_then(|x| x.split_once(':')) {
+                            Some((kind, id)) => {
+                                let stats_key = format!("{}:{}:stats", kind, id);
+                                let stats = statistics.lock().expect("poisoned lock").add(kind, id, *value as f64);
+                                keys.insert(stats_key.clone());
+                                res.or(redis_connection.set::<&str, String, String>(&stats_key, stats).err())
+                            }
+                            None => res,
+                        }
                     }
                     RedisCommand::Set(key, value) => {
                         keys.insert(key.to_string());
@@ -294,6 +720,10 @@ impl MetricsCollectorResourceProvider {
                             let _ = redis_connection.del::<String, usize>(key);
                         }
 
+                        // Keep the statistics of the previous epoch only in
+                        // the CSV file.
+                        statistics.lock().expect("poisoned lock").reset();
+
                         // Restart the timer for detecting the warmup.
                         ts = std::time::Instant::now();
                         warmup = std::time::Duration::from_millis(*new_warmup);
@@ -304,8 +734,13 @@ impl MetricsCollectorResourceProvider {
                     log::error!("Redis error when setting {}: {}", command, err);
                 }
             }
+            if let Some(csv_writer) = &mut csv_writer {
+                if let Err(err) = csv_writer.save(&statistics).await {
+                    log::error!("could not save the latency statistics: {}", err);
+                }
+            }
         });
-        Self {
+        Ok(Self {
             inner: std::sync::Arc::new(tokio::sync::Mutex::new(MetricsCollectorResourceProviderInner {
                 resource_provider_id,
                 dataplane_provider,
@@ -313,7 +748,7 @@ impl MetricsCollectorResourceProvider {
                 sender,
                 _handle,
             })),
-        }
+        })
     }
 }
 
@@ -404,4 +839,174 @@ mod tests {
         println!("start");
         let _ = futures::join!(handle_receiver, handle_sender_set, handle_sender_push);
     }
+
+    #[test]
+    fn test_metrics_collector_latency_histogram() {
+        let buckets = vec![10.0, 20.0, 50.0];
+        let mut histogram = LatencyHistogram::new(&buckets);
+        assert_eq!(0.0, histogram.percentile(&buckets, 50.0));
+        for value in 1..=100 {
+            histogram.add(&buckets, value as f64);
+        }
+        assert_eq!(vec![10, 10, 30, 50], histogram.counts);
+        assert_eq!(vec![(10.0, 10), (20.0, 20), (50.0, 50)], histogram.cumulative(&buckets));
+        assert_eq!(50.5, histogram.mean());
+        assert_eq!(10.0, histogram.percentile(&buckets, 10.0));
+        assert_eq!(15.0, histogram.percentile(&buckets, 15.0));
+        assert_eq!(50.0, histogram.percentile(&buckets, 50.0));
+        assert_eq!(90.0, histogram.percentile(&buckets, 90.0));
+        assert_eq!(100.0, histogram.percentile(&buckets, 100.0));
+
+        // Bucket bounds are clamped to the values observed.
+        let mut histogram = LatencyHistogram::new(&buckets);
+        histogram.add(&buckets, 12.0);
+        histogram.add(&buckets, 14.0);
+        assert_eq!(13.0, histogram.percentile(&buckets, 50.0));
+        assert_eq!(14.0, histogram.percentile(&buckets, 100.0));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_collector_latency_statistics() {
+        assert!(LatencyStatisticsOptions::default().validate().is_ok());
+        for (buckets, percentiles) in [
+            (vec![], vec![50.0]),
+            (vec![10.0, 5.0], vec![50.0]),
+            (vec![10.0], vec![0.0]),
+            (vec![10.0], vec![101.0]),
+        ] {
+            let options = LatencyStatisticsOptions {
+                buckets,
+                percentiles,
+                csv_filename: None,
+            };
+            assert!(options.validate().is_err(), "{:?}", options);
+        }
+
+        let filename = std::env::temp_dir().join(format!("metrics-collector-{}.csv", uuid::Uuid::new_v4()));
+        let mut statistics = LatencyStatistics::new(LatencyStatisticsOptions {
+            buckets: vec![10.0, 100.0],
+            percentiles: vec![50.0, 100.0],
+            csv_filename: Some(filename.to_string_lossy().to_string()),
+        });
+        let mut csv_writer = CsvWriter::new(filename.to_string_lossy().to_string(), statistics.csv_header());
+
+        let stats: serde_json::Value = serde_json::from_str(&statistics.add("workflow", "wf1", 5.0)).unwrap();
+        assert_eq!(
+            serde_json::json!({"count": 1, "mean": 5.0, "min": 5.0, "max": 5.0, "percentiles": [[50.0, 5.0], [100.0, 5.0]], "buckets": [[10.0, 1], [100.0, 1]]}),
+            stats
+        );
+        statistics.add("workflow", "wf1", 15.0);
+        statistics.add("function", "f1", 200.0);
+
+        let metrics = statistics.prometheus();
+        for line in [
+            "# TYPE edgeless_latency_ms histogram",
+            "edgeless_latency_ms_bucket{type=\"workflow\",id=\"wf1\",le=\"10\"} 1",
+            "edgeless_latency_ms_bucket{type=\"workflow\",id=\"wf1\",le=\"100\"} 2",
+            "edgeless_latency_ms_bucket{type=\"workflow\",id=\"wf1\",le=\"+Inf\"} 2",
+            "edgeless_latency_ms_sum{type=\"workflow\",id=\"wf1\"} 20",
+            "edgeless_latency_ms_count{type=\"function\",id=\"f1\"} 1",
+            "edgeless_latency_percentile_ms{type=\"workflow\",id=\"wf1\",percentile=\"50\"} 10",
+            "edgeless_latency_percentile_ms{type=\"function\",id=\"f1\",percentile=\"100\"} 200",
+        ] {
+            assert!(metrics.lines().any(|x| x == line), "missing: {}", line);
+        }
+
+        let statistics = std::sync::Mutex::new(statistics);
+        csv_writer.save(&statistics).await.unwrap();
+        assert_eq!(
+            "epoch,type,id,count,mean,min,max,p50,p100\n0,function,f1,1,200,200,200,200,200\n0,workflow,wf1,2,10,5,15,10,15\n",
+            std::fs::read_to_string(&filename).unwrap()
+        );
+
+        // The statistics of past epochs are kept in the CSV file only.
+        statistics.lock().unwrap().reset();
+        statistics.lock().unwrap().add("workflow", "wf1", 1.0);
+        assert!(!statistics.lock().unwrap().prometheus().contains("id=\"f1\""));
+        assert!(statistics.lock().unwrap().past_rows.len() == 2);
+        csv_writer.save(&statistics).await.unwrap();
+        assert!(statistics.lock().unwrap().past_rows.is_empty());
+        assert!(csv_writer.pending_rows.is_empty());
+        assert_eq!(
+            "epoch,type,id,count,mean,min,max,p50,p100\n0,function,f1,1,200,200,200,200,200\n0,workflow,wf1,2,10,5,15,10,15\n1,workflow,wf1,1,1,1,1,1,1\n",
+            std::fs::read_to_string(&filename).unwrap()
+        );
+
+        // Only the rows of the current epoch are overwritten.
+        statistics.lock().unwrap().add("workflow", "wf1", 3.0);
+        csv_writer.save(&statistics).await.unwrap();
+        assert_eq!(
+            "epoch,type,id,count,mean,min,max,p50,p100\n0,function,f1,1,200,200,200,200,200\n0,workflow,wf1,2,10,5,15,10,15\n1,workflow,wf1,2,2,1,3,2,3\n",
+            std::fs::read_to_string(&filename).unwrap()
+        );
+        statistics.lock().unwrap().reset();
+        csv_writer.save(&statistics).await.unwrap();
+        assert_eq!(
+            "epoch,type,id,count,mean,min,max,p50,p100\n0,function,f1,1,200,200,200,200,200\n0,workflow,wf1,2,10,5,15,10,15\n1,workflow,wf1,2,2,1,3,2,3\n",
+            std::fs::read_to_string(&filename).unwrap()
+        );
+        let _ = std::fs::remove_file(&filename);
+
+        // The rows not saved are bounded.
+        let mut csv_writer = CsvWriter::new(
+            std::env::temp_dir()
+                .join(uuid::Uuid::new_v4().to_string())
+                .join("missing.csv")
+                .to_string_lossy()
+                .to_string(),
+            String::new(),
+        );
+        statistics.lock().unwrap().past_rows = vec![String::from("row"); MAX_PENDING_ROWS + 1];
+        statistics.lock().unwrap().dirty = true;
+        assert!(csv_writer.save(&statistics).await.is_err());
+        assert_eq!(MAX_PENDING_ROWS, csv_writer.pending_rows.len());
+        assert!(statistics.lock().unwrap().dirty);
+    }
+
+    #[test]
+    fn test_metrics_collector_prometheus_labels() {
+        let mut statistics = LatencyStatistics::new(LatencyStatisticsOptions {
+            buckets: vec![10.0],
+            percentiles: vec![50.0],
+            csv_filename: None,
+        });
+        statistics.add("workflow", "a\\b\"c\nd", 5.0);
+        let metrics = statistics.prometheus();
+        assert!(
+            metrics
+                .lines()
+                .any(|x| x == r#"edgeless_latency_ms_count{type="workflow",id="a\\b\"c\nd"} 1"#),
+            "{}",
+            metrics
+        );
+        assert!(
+            metrics
+                .lines()
+                .any(|x| x == r#"edgeless_latency_percentile_ms{type="workflow",id="a\\b\"c\nd",percentile="50"} 5"#),
+            "{}",
+            metrics
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metrics_collector_prometheus() {
+        let statistics = std::sync::Arc::new(std::sync::Mutex::new(LatencyStatistics::new(LatencyStatisticsOptions::default())));
+        statistics.lock().unwrap().add("workflow", "wf1", 42.0);
+        let web_server = super::super::http_ingress::WebServer::bind("http://127.0.0.1:0", None).await.unwrap();
+        let url = |path: &str| format!("http://{}{}", web_server.listen_addr(), path);
+        let (metrics_url, other_url) = (url("/metrics"), url("/other"));
+        let cloned_statistics = statistics.clone();
+        web_server.serve(move || PrometheusService {
+            statistics: cloned_statistics.clone(),
+        });
+
+        let response = reqwest::get(metrics_url).await.unwrap();
+        assert_eq!(reqwest::StatusCode::OK, response.status());
+        assert_eq!("text/plain; version=0.0.4", response.headers()[reqwest::header::CONTENT_TYPE]);
+        let body = response.text().await.unwrap();
+        assert!(body.contains("edgeless_latency_ms_count{type=\"workflow\",id=\"wf1\"} 1"), "{}", body);
+
+        let response = reqwest::get(other_url).await.unwrap();
+        assert_eq!(reqwest::StatusCode::NOT_FOUND, response.status());
+    }
 }
@@ -1,13 +1,23 @@
 // SPDX-FileCopyrightText: © 2023 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
 // SPDX-FileCopyrightText: © 2023 Siemens AG
 // SPDX-License-Identifier: MIT
+pub mod dataset_replay;
 pub mod dda;
 pub mod file_log;
 pub mod http_egress;
 pub mod http_ingress;
 #[cfg(feature = "rdkafka")]
 pub mod kafka_egress;
+#[cfg(feature = "rdkafka")]
+pub mod kafka_ingress;
 pub mod metrics_collector;
+pub mod mqtt_egress;
+pub mod mqtt_ingress;
 pub mod ollama;
 pub mod redis;
 pub mod resource_provider_specs;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub mod timer;
+pub mod tsdb_sink;
+pub mod websocket;
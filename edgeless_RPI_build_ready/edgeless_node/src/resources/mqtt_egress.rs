@@ -0,0 +1,256 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+pub struct MqttEgressResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for MqttEgressResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("mqtt-egress")
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (
+                String::from("broker"),
+                String::from("Address of the MQTT broker, e.g., mqtt://127.0.0.1:1883 or 127.0.0.1"),
+            ),
+            (String::from("topic"), String::from("Topic to which messages are published")),
+            (
+                String::from("qos"),
+                String::from("Quality of service of the messages: 0 (default), 1, or 2"),
+            ),
+            (
+                String::from("retain"),
+                String::from("If true, messages are retained by the broker (default: false)"),
+            ),
+            (
+                String::from("client_id"),
+                String::from("Identifier of the MQTT client, by default derived from the resource instance"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+#[derive(Clone)]
+pub struct MqttEgressResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<MqttEgressResourceProviderInner>>,
+}
+
+pub struct MqttEgressResourceProviderInner {
+    resource_provider_id: edgeless_api::function_instance::InstanceId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    instances: std::collections::HashMap<edgeless_api::function_instance::InstanceId, MqttEgressResource>,
+}
+
+pub struct MqttEgressResource {
+    join_handle: tokio::task::JoinHandle<()>,
+    eventloop_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for MqttEgressResource {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+        self.eventloop_handle.abort();
+    }
+}
+
+impl MqttEgressResource {
+    /// Create a new MQTT egress resource, which publishes the payload of
+    /// every message received from the dataplane on a topic of an MQTT
+    /// broker.
+    ///
+    /// - `dataplane_handle`: gives access to the EDGELESS dataplane.
+    /// - `options`: the options of the MQTT client.
+    /// - `topic`: the topic on which messages are published.
+    /// - `qos`: the quality of service of the messages published.
+    /// - `retain`: true if the messages must be retained by the broker.
+    async fn new(
+        dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+        options: rumqttc::MqttOptions,
+        topic: String,
+        qos: rumqttc::QoS,
+        retain: bool,
+    ) -> anyhow::Result<Self> {
+        let mut dataplane_handle = dataplane_handle;
+
+        log::info!(
+            "MqttEgressResource created, broker: {}:{}, topic: {}, qos: {:?}, retain: {}",
+            options.broker_address().0,
+            options.broker_address().1,
+            topic,
+            qos,
+            retain
+        );
+
+        let (client, mut eventloop) = rumqttc::AsyncClient::new(options, 10);
+
+        // The event loop must be polled for the messages to be published.
+        // Publishing only enqueues the message for the event loop, hence the
+        // latter keeps track of whether the broker is currently reachable.
+        let connected = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let eventloop_topic = topic.clone();
+        let eventloop_connected = connected.clone();
+        let eventloop_handle = tokio::spawn(async move {
+            loop {
+                match eventloop.poll().await {
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(_))) => {
+                        eventloop_connected.store(true, std::sync::atomic::Ordering::SeqCst);
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        eventloop_connected.store(false, std::sync::atomic::Ordering::SeqCst);
+                        log::warn!("Error from the MQTT broker on topic '{}': {}", eventloop_topic, err);
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        let handle = tokio::spawn(async move {
+            loop {
+                let edgeless_dataplane::core::DataplaneEvent {
+                    source_id,
+                    channel_id,
+                    message,
+                } = dataplane_handle.receive_next().await;
+
+                let mut need_reply = false;
+                let message_data = match message {
+                    edgeless_dataplane::core::Message::Call(data) => {
+                        need_reply = true;
+                        data
+                    }
+                    edgeless_dataplane::core::Message::Cast(data) => data,
+                    _ => {
+                        continue;
+                    }
+                };
+
+                let res = if connected.load(std::sync::atomic::Ordering::SeqCst) {
+                    client.publish(&topic, qos, retain, message_data).await.map_err(|err| err.to_string())
+                } else {
+                    Err(String::from("not connected to the broker"))
+                };
+                if let Err(err) = &res {
+                    log::error!("Failed to publish message on MQTT topic '{}': {}", topic, err);
+                }
+
+                if need_reply {
+                    let reply = match res {
+                        Ok(_) => edgeless_dataplane::core::CallRet::Reply("".to_string()),
+                        Err(_) => edgeless_dataplane::core::CallRet::Err,
+                    };
+                    dataplane_handle.reply(source_id, channel_id, reply).await;
+                }
+            }
+        });
+
+        Ok(Self {
+            join_handle: handle,
+            eventloop_handle,
+        })
+    }
+}
+
+impl MqttEgressResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        resource_provider_id: edgeless_api::function_instance::InstanceId,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(MqttEgressResourceProviderInner {
+                resource_provider_id,
+                dataplane_provider,
+                instances: std::collections::HashMap::<edgeless_api::function_instance::InstanceId, MqttEgressResource>::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for MqttEgressResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+
+        let options = super::mqtt_ingress::mqtt_options(&instance_specification.configuration, format!("edgeless-{}", new_id.function_id));
+        let retain = match instance_specification.configuration.get("retain").map(|x| x.to_lowercase()) {
+            None => Ok(false),
+            Some(retain) => match retain.as_str() {
+                "" | "false" => Ok(false),
+                "true" => Ok(true),
+                _ => Err(anyhow::anyhow!("Invalid MQTT retain flag: {}", retain)),
+            },
+        };
+        let (options, topic, qos, retain) = match (options, retain) {
+            (Ok((options, topic, qos)), Ok(retain)) => (options, topic, qos, retain),
+            (Err(err), _) | (_, Err(err)) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
+            }
+        };
+
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+
+        match MqttEgressResource::new(dataplane_handle, options, topic, qos, retain).await {
+            Ok(resource) => {
+                lck.instances.insert(new_id, resource);
+                Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+            }
+            Err(err) => Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Invalid resource configuration".to_string(),
+                    detail: Some(err.to_string()),
+                },
+            )),
+        }
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.inner.lock().await.instances.remove(&resource_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, _update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    #[tokio::test]
+    async fn test_mqtt_egress_call_broker_unreachable() {
+        // Find a local port on which nobody is listening.
+        let broker = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        let mut node = TestNode::new().await;
+        let mut egress = MqttEgressResourceProvider::new(node.dataplane_provider.clone(), node.provider_id()).await;
+        let egress_id = start(&mut egress, "mqtt-egress", &[("broker", broker.as_str()), ("topic", "edgeless/test")]).await;
+        let (_, mut source_handle) = node.function().await;
+
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Err,
+            source_handle.call(egress_id, "message".to_string()).await
+        );
+
+        egress.stop(egress_id).await.unwrap();
+    }
+}
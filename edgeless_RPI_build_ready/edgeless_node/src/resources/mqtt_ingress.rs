@@ -0,0 +1,420 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+pub struct MqttIngressResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for MqttIngressResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("mqtt-ingress")
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![String::from("new_message")]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (
+                String::from("broker"),
+                String::from("Address of the MQTT broker, e.g., mqtt://127.0.0.1:1883 or 127.0.0.1"),
+            ),
+            (String::from("topic"), String::from("Topic (filter) to which the resource subscribes")),
+            (
+                String::from("qos"),
+                String::from("Quality of service of the subscription: 0 (default), 1, or 2"),
+            ),
+            (
+                String::from("client_id"),
+                String::from("Identifier of the MQTT client, by default derived from the resource instance"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+/// Default port of MQTT brokers, if not specified in the configuration.
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+
+/// Parse the address of an MQTT broker, which may have an optional
+/// `mqtt://` or `tcp://` scheme and an optional port number.
+pub fn parse_broker(broker: &str) -> anyhow::Result<(String, u16)> {
+    let address = broker
+        .strip_prefix("mqtt://")
+        .or_else(|| broker.strip_prefix("tcp://"))
+        .unwrap_or(broker)
+        .trim_end_matches('/');
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => match port.parse::<u16>() {
+            Ok(port) => (host, port),
+            Err(_) => anyhow::bail!("Invalid port in MQTT broker address: {}", broker),
+        },
+        None => (address, DEFAULT_MQTT_PORT),
+    };
+    if host.is_empty() || host.contains('/') {
+        anyhow::bail!("Invalid MQTT broker address: {}", broker);
+    }
+    Ok((host.to_string(), port))
+}
+
+/// Parse the MQTT quality of service, which is 0 (at most once) if not
+/// specified.
+pub fn parse_qos(qos: Option<&String>) -> anyhow::Result<rumqttc::QoS> {
+    match qos.map(|x| x.as_str()) {
+        None | Some("") | Some("0") => Ok(rumqttc::QoS::AtMostOnce),
+        Some("1") => Ok(rumqttc::QoS::AtLeastOnce),
+        Some("2") => Ok(rumqttc::QoS::ExactlyOnce),
+        Some(qos) => anyhow::bail!("Invalid MQTT QoS: {}", qos),
+    }
+}
+
+/// Create the options of an MQTT client from the resource configuration.
+///
+/// - `configuration`: the run-time configuration of the resource instance.
+/// - `default_client_id`: the client identifier used if not specified
+///   in the configuration.
+pub fn mqtt_options(
+    configuration: &std::collections::HashMap<String, String>,
+    default_client_id: String,
+) -> anyhow::Result<(rumqttc::MqttOptions, String, rumqttc::QoS)> {
+    let (host, port) = match configuration.get("broker") {
+        Some(broker) => parse_broker(broker)?,
+        None => anyhow::bail!("Missing MQTT broker"),
+    };
+    let topic = match configuration.get("topic") {
+        Some(topic) if !topic.is_empty() => topic.clone(),
+        _ => anyhow::bail!("Missing MQTT topic"),
+    };
+    let qos = parse_qos(configuration.get("qos"))?;
+    let client_id = match configuration.get("client_id") {
+        Some(client_id) if !client_id.is_empty() => client_id.clone(),
+        _ => default_client_id,
+    };
+
+    let mut options = rumqttc::MqttOptions::new(client_id, host, port);
+    options.set_keep_alive(std::time::Duration::from_secs(5));
+    Ok((options, topic, qos))
+}
+
+#[derive(Clone)]
+pub struct MqttIngressResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<MqttIngressResourceProviderInner>>,
+}
+
+pub struct MqttIngressResourceProviderInner {
+    resource_provider_id: edgeless_api::function_instance::InstanceId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, MqttIngressResource>,
+}
+
+pub struct MqttIngressResource {
+    // Component receiving the messages on the new_message output channel.
+    target: std::sync::Arc<tokio::sync::Mutex<Option<edgeless_api::function_instance::InstanceId>>>,
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for MqttIngressResource {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+impl MqttIngressResource {
+    /// Create a new MQTT ingress resource, which subscribes to a topic
+    /// on an MQTT broker and casts the payload of every message received
+    /// to the component mapped to the new_message output channel.
+    ///
+    /// - `dataplane_handle`: gives access to the EDGELESS dataplane.
+    /// - `options`: the options of the MQTT client.
+    /// - `topic`: the topic to which to subscribe.
+    /// - `qos`: the quality of service of the subscription.
+    async fn new(
+        dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+        options: rumqttc::MqttOptions,
+        topic: String,
+        qos: rumqttc::QoS,
+    ) -> anyhow::Result<Self> {
+        let mut dataplane_handle = dataplane_handle;
+        let target = std::sync::Arc::new(tokio::sync::Mutex::new(None));
+        let target_cloned = target.clone();
+
+        log::info!(
+            "MqttIngressResource created, broker: {}:{}, topic: {}, qos: {:?}",
+            options.broker_address().0,
+            options.broker_address().1,
+            topic,
+            qos
+        );
+
+        let (client, mut eventloop) = rumqttc::AsyncClient::new(options, 10);
+
+        let handle = tokio::spawn(async move {
+            loop {
+                match eventloop.poll().await {
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(_))) => {
+                        // Subscriptions are not persisted across
+                        // re-connections with clean sessions.
+                        if let Err(err) = client.try_subscribe(&topic, qos) {
+                            log::error!("Could not subscribe to MQTT topic '{}': {}", topic, err);
+                        }
+                    }
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish))) => {
+                        let target = *target_cloned.lock().await;
+                        match target {
+                            Some(target) => {
+                                dataplane_handle.send(target, String::from_utf8_lossy(&publish.payload).to_string()).await;
+                            }
+                            None => {
+                                log::debug!("Message received on MQTT topic '{}' without a target: dropped", publish.topic);
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        log::warn!("Error from the MQTT broker on topic '{}': {}", topic, err);
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Self { target, join_handle: handle })
+    }
+}
+
+impl MqttIngressResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        resource_provider_id: edgeless_api::function_instance::InstanceId,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(MqttIngressResourceProviderInner {
+                resource_provider_id,
+                dataplane_provider,
+                instances: std::collections::HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for MqttIngressResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+
+        let (options, topic, qos) = match mqtt_options(&instance_specification.configuration, format!("edgeless-{}", new_id.function_id)) {
+            Ok(val) => val,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
+            }
+        };
+
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+
+        match MqttIngressResource::new(dataplane_handle, options, topic, qos).await {
+            Ok(resource) => {
+                lck.instances.insert(new_id.function_id, resource);
+                Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+            }
+            Err(err) => Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Invalid resource configuration".to_string(),
+                    detail: Some(err.to_string()),
+                },
+            )),
+        }
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        // Find the target component to which we have to send the messages
+        // received on the "new_message" output channel.
+        let target = match update.output_mapping.get("new_message") {
+            Some(val) => *val,
+            None => {
+                anyhow::bail!("Missing mapping of channel: new_message");
+            }
+        };
+
+        let lck = self.inner.lock().await;
+        match lck.instances.get(&update.function_id) {
+            Some(resource) => {
+                *resource.target.lock().await = Some(target);
+                Ok(())
+            }
+            None => {
+                anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    #[test]
+    fn test_mqtt_parse_configuration() {
+        assert_eq!(("127.0.0.1".to_string(), 1883), parse_broker("127.0.0.1").unwrap());
+        assert_eq!(("localhost".to_string(), 1884), parse_broker("localhost:1884").unwrap());
+        assert_eq!(("localhost".to_string(), 1883), parse_broker("mqtt://localhost").unwrap());
+        assert_eq!(("broker.local".to_string(), 8883), parse_broker("tcp://broker.local:8883/").unwrap());
+        assert!(parse_broker("").is_err());
+        assert!(parse_broker("mqtt://").is_err());
+        assert!(parse_broker("localhost:port").is_err());
+        assert!(parse_broker("localhost:1883/path").is_err());
+
+        assert_eq!(rumqttc::QoS::AtMostOnce, parse_qos(None).unwrap());
+        assert_eq!(rumqttc::QoS::AtMostOnce, parse_qos(Some(&"0".to_string())).unwrap());
+        assert_eq!(rumqttc::QoS::AtLeastOnce, parse_qos(Some(&"1".to_string())).unwrap());
+        assert_eq!(rumqttc::QoS::ExactlyOnce, parse_qos(Some(&"2".to_string())).unwrap());
+        assert!(parse_qos(Some(&"3".to_string())).is_err());
+
+        let mut configuration = std::collections::HashMap::from([("broker".to_string(), "localhost".to_string())]);
+        assert!(mqtt_options(&configuration, "id".to_string()).is_err());
+        configuration.insert("topic".to_string(), "sensors/#".to_string());
+        let (options, topic, qos) = mqtt_options(&configuration, "id".to_string()).unwrap();
+        assert_eq!(("localhost".to_string(), 1883), options.broker_address());
+        assert_eq!("id", options.client_id());
+        assert_eq!("sensors/#", topic);
+        assert_eq!(rumqttc::QoS::AtMostOnce, qos);
+        configuration.insert("client_id".to_string(), "my-client".to_string());
+        configuration.insert("qos".to_string(), "1".to_string());
+        let (options, _topic, qos) = mqtt_options(&configuration, "id".to_string()).unwrap();
+        assert_eq!("my-client", options.client_id());
+        assert_eq!(rumqttc::QoS::AtLeastOnce, qos);
+    }
+
+    /// Serve a single client of the broker stand-in: only QoS 0/1 and exact
+    /// topic matching are supported.
+    async fn serve_mqtt_client(mut stream: tokio::net::TcpStream, published: tokio::sync::broadcast::Sender<rumqttc::Publish>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let mut published_receiver = published.subscribe();
+        let mut topics = std::collections::HashSet::new();
+        let mut read_buf = bytes::BytesMut::new();
+        loop {
+            let mut write_buf = bytes::BytesMut::new();
+            tokio::select! {
+                res = stream.read_buf(&mut read_buf) => {
+                    if !matches!(res, Ok(size) if size > 0) {
+                        return;
+                    }
+                    while let Ok(packet) = rumqttc::mqttbytes::v4::read(&mut read_buf, 1 << 20) {
+                        match packet {
+                            rumqttc::Packet::Connect(_) => {
+                                rumqttc::ConnAck::new(rumqttc::ConnectReturnCode::Success, false).write(&mut write_buf).unwrap();
+                            }
+                            rumqttc::Packet::Subscribe(subscribe) => {
+                                topics.extend(subscribe.filters.iter().map(|filter| filter.path.clone()));
+                                let return_codes = subscribe.filters.iter().map(|filter| rumqttc::SubscribeReasonCode::Success(filter.qos)).collect();
+                                rumqttc::SubAck::new(subscribe.pkid, return_codes).write(&mut write_buf).unwrap();
+                            }
+                            rumqttc::Packet::Publish(publish) => {
+                                if publish.qos != rumqttc::QoS::AtMostOnce {
+                                    rumqttc::PubAck::new(publish.pkid).write(&mut write_buf).unwrap();
+                                }
+                                let _ = published.send(publish);
+                            }
+                            rumqttc::Packet::PingReq => {
+                                rumqttc::PingResp.write(&mut write_buf).unwrap();
+                            }
+                            rumqttc::Packet::Disconnect => return,
+                            _ => {}
+                        }
+                    }
+                }
+                publish = published_receiver.recv() => {
+                    if let Ok(publish) = publish {
+                        if topics.contains(&publish.topic) {
+                            rumqttc::Publish::new(publish.topic, rumqttc::QoS::AtMostOnce, publish.payload.to_vec())
+                                .write(&mut write_buf)
+                                .unwrap();
+                        }
+                    }
+                }
+            }
+            if stream.write_all(&write_buf).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Start a minimal in-process stand-in of an MQTT broker, which forwards
+    /// the messages published to the clients subscribed to the same topic.
+    /// Return the address of the broker.
+    async fn start_mqtt_broker() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (published, _) = tokio::sync::broadcast::channel::<rumqttc::Publish>(100);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_mqtt_client(stream, published.clone()));
+            }
+        });
+        address
+    }
+
+    #[tokio::test]
+    async fn test_mqtt_ingress_egress() {
+        check_mqtt_ingress_egress(start_mqtt_broker().await).await;
+    }
+
+    #[ignore]
+    #[tokio::test]
+    async fn test_mqtt_ingress_egress_broker() {
+        // Requires an MQTT broker, e.g.: mosquitto -p 1883
+        check_mqtt_ingress_egress(std::env::var("MQTT_BROKER").unwrap_or("127.0.0.1:1883".to_string())).await;
+    }
+
+    async fn check_mqtt_ingress_egress(broker: String) {
+        let topic = format!("edgeless/test/{}", uuid::Uuid::new_v4());
+        let configuration = [("broker", broker.as_str()), ("topic", topic.as_str()), ("qos", "1")];
+        let mut node = TestNode::new().await;
+
+        // Create the ingress resource and a fake function receiving its messages.
+        let mut ingress = MqttIngressResourceProvider::new(node.dataplane_provider.clone(), node.provider_id()).await;
+        let ingress_id = start(&mut ingress, "mqtt-ingress", &configuration).await;
+        let (target_id, mut target_handle) = node.function().await;
+        patch(&mut ingress, ingress_id, "new_message", target_id).await;
+
+        // Create the egress resource and a fake function sending messages to it.
+        let mut egress = super::super::mqtt_egress::MqttEgressResourceProvider::new(node.dataplane_provider.clone(), node.provider_id()).await;
+        let egress_id = start(&mut egress, "mqtt-egress", &configuration).await;
+        let (_, mut source_handle) = node.function().await;
+
+        // Wait for the subscription to be established.
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+        for i in 0..3 {
+            source_handle.send(egress_id, format!("message-{}", i)).await;
+            assert_eq!((ingress_id, format!("message-{}", i)), receive_cast(&mut target_handle).await);
+        }
+
+        // A call is answered once the message is published.
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply("".to_string()),
+            source_handle.call(egress_id, "message-call".to_string()).await
+        );
+        assert_eq!((ingress_id, "message-call".to_string()), receive_cast(&mut target_handle).await);
+
+        ingress.stop(ingress_id).await.unwrap();
+        egress.stop(egress_id).await.unwrap();
+    }
+}
@@ -4,7 +4,7 @@
 // SPDX-License-Identifier: MIT
 use edgeless_dataplane::core::Message;
 extern crate redis;
-use redis::Commands;
+use redis::{AsyncCommands, Commands};
 
 pub struct RedisResourceSpec {}
 
@@ -14,18 +14,120 @@ impl super::resource_provider_specs::ResourceProviderSpecs for RedisResourceSpec
     }
 
     fn outputs(&self) -> Vec<String> {
-        vec![]
+        vec![String::from("new_message")]
     }
 
     fn configurations(&self) -> std::collections::HashMap<String, String> {
         std::collections::HashMap::from([
             (String::from("url"), String::from("URL of the Redis server to use")),
-            (String::from("key"), String::from("Key to set")),
+            (
+                String::from("key"),
+                String::from("Key to set or get, list to append to, or stream to append to or read from"),
+            ),
+            (String::from("mode"), String::from("One of: set (default), get, rpush, xadd, subscribe")),
+            (
+                String::from("ttl"),
+                String::from("If specified, time-to-live of the key after every update, in s (modes: set, rpush, xadd)"),
+            ),
+            (
+                String::from("field"),
+                String::from("Field of the stream entries holding the payload (default: payload)"),
+            ),
+            (
+                String::from("channel"),
+                String::from("Pub/sub channel to which to subscribe, instead of reading from the stream in key (mode: subscribe)"),
+            ),
         ])
     }
 
     fn version(&self) -> String {
-        String::from("1.0")
+        String::from("1.1")
+    }
+}
+
+/// Default field of the stream entries holding the payload.
+const DEFAULT_STREAM_FIELD: &str = "payload";
+
+/// Operation carried out by a redis resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RedisMode {
+    /// SET the key to the payload of every message received.
+    Set,
+    /// GET the value of the key and return it in the reply to calls.
+    Get,
+    /// RPUSH the payload of every message received to the list in key.
+    RPush,
+    /// XADD the payload of every message received to the stream in key.
+    XAdd,
+    /// Cast the messages arriving on a pub/sub channel or stream.
+    Subscribe,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedisConfiguration {
+    // URL of the Redis server.
+    url: String,
+    mode: RedisMode,
+    // Key, list or stream, not set only if subscribing to a channel.
+    key: Option<String>,
+    // Pub/sub channel, only in subscribe mode.
+    channel: Option<String>,
+    // Field of the stream entries holding the payload.
+    field: String,
+    // Time-to-live of the key after an update, in s.
+    ttl: Option<i64>,
+}
+
+impl RedisConfiguration {
+    /// Parse the run-time configuration of a redis resource instance.
+    fn from(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let get = |name: &str| configuration.get(name).filter(|x| !x.is_empty()).cloned();
+
+        let url = match get("url") {
+            Some(url) => url,
+            None => anyhow::bail!("Missing Redis URL"),
+        };
+        let mode = match get("mode").map(|x| x.to_lowercase()) {
+            None => RedisMode::Set,
+            Some(mode) => match mode.as_str() {
+                "set" => RedisMode::Set,
+                "get" => RedisMode::Get,
+                "rpush" => RedisMode::RPush,
+                "xadd" => RedisMode::XAdd,
+                "subscribe" => RedisMode::Subscribe,
+                _ => anyhow::bail!("Invalid Redis mode: {}", mode),
+            },
+        };
+        let key = get("key");
+        let channel = get("channel");
+        match (&mode, &key, &channel) {
+            (RedisMode::Subscribe, Some(_), Some(_)) => anyhow::bail!("Only one of Redis key and channel can be specified in subscribe mode"),
+            (RedisMode::Subscribe, None, None) => anyhow::bail!("Missing Redis key or channel"),
+            (RedisMode::Subscribe, _, _) => {}
+            (_, _, Some(_)) => anyhow::bail!("Redis channel can only be specified in subscribe mode"),
+            (_, None, _) => anyhow::bail!("Missing Redis key"),
+            (_, Some(_), None) => {}
+        }
+        let field = get("field").unwrap_or(DEFAULT_STREAM_FIELD.to_string());
+        let ttl = match get("ttl") {
+            None => None,
+            Some(_) if matches!(mode, RedisMode::Get | RedisMode::Subscribe) => {
+                anyhow::bail!("Redis TTL can only be specified in set, rpush, and xadd modes")
+            }
+            Some(ttl) => match ttl.parse::<i64>() {
+                Ok(ttl) if ttl > 0 => Some(ttl),
+                _ => anyhow::bail!("Invalid Redis TTL: {}", ttl),
+            },
+        };
+
+        Ok(Self {
+            url,
+            mode,
+            key,
+            channel,
+            field,
+            ttl,
+        })
     }
 }
 
@@ -37,10 +139,12 @@ pub struct RedisResourceProvider {
 pub struct RedisResourceProviderInner {
     resource_provider_id: edgeless_api::function_instance::InstanceId,
     dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
-    instances: std::collections::HashMap<edgeless_api::function_instance::InstanceId, RedisResource>,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, RedisResource>,
 }
 
 pub struct RedisResource {
+    // Component receiving the messages on the new_message output channel.
+    target: std::sync::Arc<tokio::sync::Mutex<Option<edgeless_api::function_instance::InstanceId>>>,
     join_handle: tokio::task::JoinHandle<()>,
 }
 
@@ -51,14 +155,63 @@ impl Drop for RedisResource {
 }
 
 impl RedisResource {
-    async fn new(dataplane_handle: edgeless_dataplane::handle::DataplaneHandle, redis_url: &str, redis_key: &str) -> anyhow::Result<Self> {
+    /// Create a new redis resource.
+    ///
+    /// In subscribe mode, the messages arriving on the pub/sub channel or
+    /// stream are cast to the component mapped to the new_message output
+    /// channel. Otherwise, the command of the mode is executed for every
+    /// message received from the dataplane and its result is returned in
+    /// the reply to calls: if the command fails because the connection to
+    /// the server is lost, the error is returned and the connection is
+    /// re-established for the next messages.
+    ///
+    /// - `dataplane_handle`: gives access to the EDGELESS dataplane.
+    /// - `conf`: the run-time configuration of the resource instance.
+    async fn new(dataplane_handle: edgeless_dataplane::handle::DataplaneHandle, conf: RedisConfiguration) -> anyhow::Result<Self> {
         let mut dataplane_handle = dataplane_handle;
-        let redis_key = redis_key.to_string();
+        let target = std::sync::Arc::new(tokio::sync::Mutex::new(None));
+        let target_cloned = target.clone();
 
-        let mut connection = redis::Client::open(redis_url)?.get_connection()?;
+        let client = redis::Client::open(conf.url.as_str())?;
 
-        log::info!("RedisResource created, URL: {}", redis_url);
+        log::info!("RedisResource created, URL: {}, mode: {:?}", conf.url, conf.mode);
 
+        if conf.mode == RedisMode::Subscribe {
+            // Only the entries of the stream appended after the resource
+            // creation are read, also after reconnecting to the server.
+            let (connection, last_id) = tokio::task::spawn_blocking({
+                let client = client.clone();
+                let conf = conf.clone();
+                move || -> redis::RedisResult<(redis::Connection, String)> {
+                    let mut connection = client.get_connection()?;
+                    let last_id = match conf.channel {
+                        Some(_) => String::new(),
+                        None => last_stream_id(&mut connection, conf.key.as_deref().unwrap_or_default())?,
+                    };
+                    Ok((connection, last_id))
+                }
+            })
+            .await??;
+
+            // The redis client is blocking: the messages are received in a
+            // separate thread, which terminates when the resource is dropped.
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<String>();
+            std::thread::spawn(move || subscribe_loop(client, connection, conf, last_id, sender));
+
+            let handle = tokio::spawn(async move {
+                while let Some(payload) = receiver.recv().await {
+                    let target = *target_cloned.lock().await;
+                    match target {
+                        Some(target) => dataplane_handle.send(target, payload).await,
+                        None => log::debug!("Message received from Redis without a target: dropped"),
+                    }
+                }
+            });
+
+            return Ok(Self { target, join_handle: handle });
+        }
+
+        let mut connection = client.get_connection_manager().await?;
         let handle = tokio::spawn(async move {
             loop {
                 let edgeless_dataplane::core::DataplaneEvent {
@@ -79,20 +232,156 @@ impl RedisResource {
                     }
                 };
 
-                if let Err(e) = connection.set::<&str, &str, std::string::String>(&redis_key, &message_data) {
-                    log::error!("Could not set key '{}' to value '{}': {}", redis_key, &message_data, e);
-                }
+                let ret = match execute(&mut connection, &conf, &message_data).await {
+                    Ok(ret) => edgeless_dataplane::core::CallRet::Reply(ret),
+                    Err(e) => {
+                        log::error!(
+                            "Could not execute {:?} on key '{}': {}",
+                            conf.mode,
+                            conf.key.as_deref().unwrap_or_default(),
+                            e
+                        );
+                        edgeless_dataplane::core::CallRet::Err
+                    }
+                };
 
                 if need_reply {
-                    dataplane_handle
-                        .reply(source_id, channel_id, edgeless_dataplane::core::CallRet::Reply("".to_string()))
-                        .await;
+                    dataplane_handle.reply(source_id, channel_id, ret).await;
                 }
             }
         });
 
-        Ok(Self { join_handle: handle })
+        Ok(Self { target, join_handle: handle })
+    }
+}
+
+/// Execute the command of a redis resource not in subscribe mode for a
+/// given payload, and return the result to be included in the reply:
+///
+/// - set: empty string;
+/// - get: the value of the key, or an empty string if not existing;
+/// - rpush: the length of the list after the operation;
+/// - xadd: the identifier of the entry added.
+async fn execute(connection: &mut redis::aio::ConnectionManager, conf: &RedisConfiguration, payload: &str) -> redis::RedisResult<String> {
+    let key = conf.key.as_deref().unwrap_or_default();
+    let ret = match conf.mode {
+        RedisMode::Set => {
+            let mut cmd = redis::cmd("SET");
+            cmd.arg(key).arg(payload);
+            if let Some(ttl) = conf.ttl {
+                cmd.arg("EX").arg(ttl);
+            }
+            cmd.query_async::<_, ()>(connection).await?;
+            String::new()
+        }
+        RedisMode::Get => connection.get::<&str, Option<String>>(key).await?.unwrap_or_default(),
+        RedisMode::RPush => {
+            let (len,) = with_ttl(redis::pipe().atomic().rpush(key, payload), key, conf.ttl)
+                .query_async::<_, (i64,)>(connection)
+                .await?;
+            len.to_string()
+        }
+        RedisMode::XAdd => {
+            let (id,) = with_ttl(redis::pipe().atomic().xadd(key, "*", &[(conf.field.as_str(), payload)]), key, conf.ttl)
+                .query_async::<_, (String,)>(connection)
+                .await?;
+            id
+        }
+        RedisMode::Subscribe => String::new(),
+    };
+    Ok(ret)
+}
+
+/// Add to a transaction the expiration of a key, if a TTL is given, so that
+/// the key cannot be left without expiration if the connection fails.
+fn with_ttl<'a>(pipe: &'a mut redis::Pipeline, key: &str, ttl: Option<i64>) -> &'a mut redis::Pipeline {
+    if let Some(ttl) = ttl {
+        pipe.expire(key, ttl).ignore();
     }
+    pipe
+}
+
+/// Return the identifier of the last entry of a stream, or "0-0" if the
+/// stream is empty or does not exist.
+fn last_stream_id(connection: &mut redis::Connection, key: &str) -> redis::RedisResult<String> {
+    let reply = connection.xrevrange_count::<&str, &str, &str, usize, redis::streams::StreamRangeReply>(key, "+", "-", 1)?;
+    Ok(reply.ids.into_iter().next().map_or_else(|| String::from("0-0"), |entry| entry.id))
+}
+
+/// Receive the messages from a pub/sub channel, or the entries appended to
+/// a stream after `last_id`, and forward their payloads to `sender`, until
+/// it is closed. The connection is re-established in case of errors and
+/// the stream is read from the last entry received.
+fn subscribe_loop(
+    client: redis::Client,
+    connection: redis::Connection,
+    conf: RedisConfiguration,
+    last_id: String,
+    sender: tokio::sync::mpsc::UnboundedSender<String>,
+) {
+    let mut connection = Some(connection);
+    let mut last_id = last_id;
+    while !sender.is_closed() {
+        let res = match connection.take().map_or_else(|| client.get_connection(), Ok) {
+            Ok(mut connection) => match &conf.channel {
+                Some(channel) => subscribe_channel(&mut connection, channel, &sender),
+                None => subscribe_stream(&mut connection, &conf, &mut last_id, &sender),
+            },
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            log::warn!("Error from the Redis server {}: {}", conf.url, e);
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+    }
+}
+
+/// Interval after which the subscription loops check if they must terminate.
+const SUBSCRIBE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+fn subscribe_channel(
+    connection: &mut redis::Connection,
+    channel: &str,
+    sender: &tokio::sync::mpsc::UnboundedSender<String>,
+) -> redis::RedisResult<()> {
+    let mut pubsub = connection.as_pubsub();
+    pubsub.subscribe(channel)?;
+    pubsub.set_read_timeout(Some(SUBSCRIBE_POLL_INTERVAL))?;
+    while !sender.is_closed() {
+        match pubsub.get_message() {
+            Ok(msg) => {
+                let _ = sender.send(String::from_utf8_lossy(msg.get_payload_bytes()).to_string());
+            }
+            Err(e) if e.is_timeout() => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+fn subscribe_stream(
+    connection: &mut redis::Connection,
+    conf: &RedisConfiguration,
+    last_id: &mut String,
+    sender: &tokio::sync::mpsc::UnboundedSender<String>,
+) -> redis::RedisResult<()> {
+    let key = conf.key.as_deref().unwrap_or_default();
+    let options = redis::streams::StreamReadOptions::default()
+        .block(SUBSCRIBE_POLL_INTERVAL.as_millis() as usize)
+        .count(100);
+    while !sender.is_closed() {
+        let reply = connection.xread_options::<&str, &str, Option<redis::streams::StreamReadReply>>(&[key], &[last_id.as_str()], &options)?;
+        for entry in reply.into_iter().flat_map(|x| x.keys).flat_map(|x| x.ids) {
+            match entry.get::<String>(&conf.field) {
+                Some(payload) => {
+                    let _ = sender.send(payload);
+                }
+                None => log::warn!("Entry {} of Redis stream '{}' without field '{}': skipped", entry.id, key, conf.field),
+            }
+            *last_id = entry.id;
+        }
+    }
+    Ok(())
 }
 
 impl RedisResourceProvider {
@@ -104,7 +393,7 @@ impl RedisResourceProvider {
             inner: std::sync::Arc::new(tokio::sync::Mutex::new(RedisResourceProviderInner {
                 resource_provider_id,
                 dataplane_provider,
-                instances: std::collections::HashMap::<edgeless_api::function_instance::InstanceId, RedisResource>::new(),
+                instances: std::collections::HashMap::<edgeless_api::function_instance::ComponentId, RedisResource>::new(),
             })),
         }
     }
@@ -116,45 +405,377 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         &mut self,
         instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
     ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
-        if let (Some(url), Some(key)) = (
-            instance_specification.configuration.get("url"),
-            instance_specification.configuration.get("key"),
-        ) {
-            let mut lck = self.inner.lock().await;
-            let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
-            let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
-
-            match RedisResource::new(dataplane_handle, url, key).await {
-                Ok(resource) => {
-                    lck.instances.insert(new_id, resource);
-                    return Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id));
-                }
-                Err(err) => {
-                    return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
-                        edgeless_api::common::ResponseError {
-                            summary: "Invalid resource configuration".to_string(),
-                            detail: Some(err.to_string()),
-                        },
-                    ));
-                }
+        let conf = match RedisConfiguration::from(&instance_specification.configuration) {
+            Ok(conf) => conf,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
             }
-        }
+        };
 
-        Ok(edgeless_api::common::StartComponentResponse::ResponseError(
-            edgeless_api::common::ResponseError {
-                summary: "Invalid resource configuration".to_string(),
-                detail: Some("One of the fields 'url' or 'key' is missing".to_string()),
-            },
-        ))
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+
+        match RedisResource::new(dataplane_handle, conf).await {
+            Ok(resource) => {
+                lck.instances.insert(new_id.function_id, resource);
+                Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+            }
+            Err(err) => Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Invalid resource configuration".to_string(),
+                    detail: Some(err.to_string()),
+                },
+            )),
+        }
     }
 
     async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
-        self.inner.lock().await.instances.remove(&resource_id);
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
         Ok(())
     }
 
-    async fn patch(&mut self, _update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
-        // the resource has no channels: nothing to be patched
-        Ok(())
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        // Only resources in subscribe mode use the new_message output
+        // channel, which may be left unmapped in the other modes.
+        let target = match update.output_mapping.get("new_message") {
+            Some(val) => *val,
+            None => return Ok(()),
+        };
+
+        let lck = self.inner.lock().await;
+        match lck.instances.get(&update.function_id) {
+            Some(resource) => {
+                *resource.target.lock().await = Some(target);
+                Ok(())
+            }
+            None => {
+                anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    #[test]
+    fn test_redis_parse_configuration() {
+        let url = ("url", "redis://127.0.0.1:6379/");
+
+        // Default mode, as in previous versions.
+        assert_eq!(
+            RedisConfiguration {
+                url: url.1.to_string(),
+                mode: RedisMode::Set,
+                key: Some("k".to_string()),
+                channel: None,
+                field: "payload".to_string(),
+                ttl: None,
+            },
+            RedisConfiguration::from(&configuration(&[url, ("key", "k")])).unwrap()
+        );
+
+        let conf = RedisConfiguration::from(&configuration(&[url, ("key", "k"), ("mode", "XADD"), ("field", "f"), ("ttl", "60")])).unwrap();
+        assert_eq!(RedisMode::XAdd, conf.mode);
+        assert_eq!("f", conf.field);
+        assert_eq!(Some(60), conf.ttl);
+
+        let conf = RedisConfiguration::from(&configuration(&[url, ("mode", "subscribe"), ("channel", "c")])).unwrap();
+        assert_eq!(RedisMode::Subscribe, conf.mode);
+        assert_eq!(None, conf.key);
+        assert_eq!(Some("c".to_string()), conf.channel);
+        assert!(RedisConfiguration::from(&configuration(&[url, ("mode", "subscribe"), ("key", "s")])).is_ok());
+
+        for invalid in [
+            vec![("key", "k")],
+            vec![url],
+            vec![url, ("key", "k"), ("mode", "del")],
+            vec![url, ("key", "k"), ("channel", "c")],
+            vec![url, ("mode", "subscribe")],
+            vec![url, ("mode", "subscribe"), ("key", "s"), ("channel", "c")],
+            vec![url, ("key", "k"), ("mode", "get"), ("ttl", "60")],
+            vec![url, ("key", "k"), ("mode", "rpush"), ("ttl", "0")],
+            vec![url, ("key", "k"), ("ttl", "1h")],
+        ] {
+            assert!(RedisConfiguration::from(&configuration(&invalid)).is_err(), "{:?}", invalid);
+        }
+    }
+
+    /// Content of the Redis stand-in, which is kept across restarts.
+    #[derive(Default)]
+    struct StandInData {
+        // Commands received.
+        commands: Vec<Vec<String>>,
+        // Values of the keys set.
+        values: std::collections::HashMap<String, String>,
+        // Number of items in the lists.
+        lists: std::collections::HashMap<String, usize>,
+        // Field and value of the entries of the streams, with identifiers
+        // equal to their position starting from 1, e.g., 1-0, 2-0, ...
+        streams: std::collections::HashMap<String, Vec<(String, String)>>,
+    }
+
+    type SharedStandInData = std::sync::Arc<std::sync::Mutex<StandInData>>;
+
+    /// Encode the entries of a stream starting from a given position.
+    fn stream_entries(entries: &[(String, String)], first: usize) -> String {
+        format!(
+            "*{}\r\n{}",
+            entries.len() - first,
+            entries[first..]
+                .iter()
+                .enumerate()
+                .map(|(i, (field, value))| format!(
+                    "*2\r\n{}*2\r\n{}{}",
+                    resp_bulk(Some(&format!("{}-0", first + i + 1))),
+                    resp_bulk(Some(field)),
+                    resp_bulk(Some(value))
+                ))
+                .collect::<String>()
+        )
+    }
+
+    /// Start a stand-in of a Redis server on `listener`, which records the
+    /// commands received and supports those used by the resource.
+    fn redis_stand_in_with_data(listener: tokio::net::TcpListener, data: SharedStandInData) -> tokio::task::JoinHandle<()> {
+        redis_stand_in(listener, move |command| {
+            let mut lck = data.lock().unwrap();
+            lck.commands.push(command.to_vec());
+            match command[0].to_uppercase().as_str() {
+                "SET" => {
+                    lck.values.insert(command[1].clone(), command[2].clone());
+                    String::from("+OK\r\n")
+                }
+                "GET" => resp_bulk(lck.values.get(&command[1]).map(|x| x.as_str())),
+                "RPUSH" => {
+                    let len = lck.lists.entry(command[1].clone()).or_default();
+                    *len += 1;
+                    format!(":{}\r\n", len)
+                }
+                "EXPIRE" => String::from(":1\r\n"),
+                "XADD" => {
+                    let entries = lck.streams.entry(command[1].clone()).or_default();
+                    entries.push((command[3].clone(), command[4].clone()));
+                    resp_bulk(Some(&format!("{}-0", entries.len())))
+                }
+                "XREVRANGE" => {
+                    let entries = lck.streams.get(&command[1]).cloned().unwrap_or_default();
+                    stream_entries(&entries, entries.len().saturating_sub(1))
+                }
+                "XREAD" => {
+                    // XREAD [BLOCK ms] [COUNT count] STREAMS key id
+                    let arg = |name: &str| &command[command.iter().position(|x| x.eq_ignore_ascii_case(name)).unwrap() + 1];
+                    let block = std::time::Duration::from_millis(arg("BLOCK").parse().unwrap());
+                    let key = arg("STREAMS").clone();
+                    let first: usize = command[command.len() - 1].split('-').next().unwrap().parse().unwrap();
+                    drop(lck);
+                    let deadline = std::time::Instant::now() + block;
+                    loop {
+                        let entries = data.lock().unwrap().streams.get(&key).cloned().unwrap_or_default();
+                        if entries.len() > first {
+                            break format!("*1\r\n*2\r\n{}{}", resp_bulk(Some(&key)), stream_entries(&entries, first));
+                        }
+                        if std::time::Instant::now() >= deadline {
+                            break String::from("*-1\r\n");
+                        }
+                        std::thread::sleep(std::time::Duration::from_millis(10));
+                    }
+                }
+                _ => String::from("+OK\r\n"),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_redis_execute_with_ttl() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}/", listener.local_addr().unwrap());
+        let data = SharedStandInData::default();
+        let stand_in = redis_stand_in_with_data(listener, data.clone());
+        let client = redis::Client::open(url.as_str()).unwrap();
+        let mut connection = client.get_connection_manager().await.unwrap();
+        let conf = |entries: &[(&str, &str)]| {
+            let mut entries = entries.to_vec();
+            entries.extend([("url", url.as_str()), ("key", "k"), ("ttl", "60")]);
+            RedisConfiguration::from(&configuration(&entries)).unwrap()
+        };
+        let command = |args: &[&str]| args.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+
+        // The TTL is set together with the value.
+        assert_eq!("", execute(&mut connection, &conf(&[]), "v").await.unwrap());
+        assert_eq!("1", execute(&mut connection, &conf(&[("mode", "rpush")]), "v").await.unwrap());
+        assert_eq!("1-0", execute(&mut connection, &conf(&[("mode", "xadd")]), "v").await.unwrap());
+        let commands: Vec<Vec<String>> = data.lock().unwrap().commands.iter().filter(|x| x[0] != "CLIENT").cloned().collect();
+        assert_eq!(
+            vec![
+                command(&["SET", "k", "v", "EX", "60"]),
+                command(&["MULTI"]),
+                command(&["RPUSH", "k", "v"]),
+                command(&["EXPIRE", "k", "60"]),
+                command(&["EXEC"]),
+                command(&["MULTI"]),
+                command(&["XADD", "k", "*", "payload", "v"]),
+                command(&["EXPIRE", "k", "60"]),
+                command(&["EXEC"]),
+            ],
+            commands
+        );
+
+        // A stream is read from its last entry.
+        let last_ids = tokio::task::spawn_blocking(move || {
+            let mut connection = client.get_connection().unwrap();
+            ["k", "other"].map(|key| last_stream_id(&mut connection, key).unwrap())
+        })
+        .await
+        .unwrap();
+        assert_eq!(["1-0", "0-0"], last_ids);
+
+        stand_in.abort();
+    }
+
+    /// Call a resource until it does not return an error, for at most 5 s.
+    async fn call_until_reply(
+        handle: &mut edgeless_dataplane::handle::DataplaneHandle,
+        target: edgeless_api::function_instance::InstanceId,
+        payload: &str,
+    ) -> edgeless_dataplane::core::CallRet {
+        let mut reply = edgeless_dataplane::core::CallRet::Err;
+        for _ in 0..50 {
+            reply = handle.call(target, payload.to_string()).await;
+            if reply != edgeless_dataplane::core::CallRet::Err {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        reply
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_redis_modes_reconnect() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let url = format!("redis://{}/", address);
+        let url = ("url", url.as_str());
+        let data = SharedStandInData::default();
+        let stand_in = redis_stand_in_with_data(listener, data.clone());
+
+        let mut node = TestNode::new().await;
+        let mut provider = RedisResourceProvider::new(node.dataplane_provider.clone(), node.provider_id()).await;
+        let (_, mut source_handle) = node.function().await;
+        let (target_id, mut target_handle) = node.function().await;
+
+        // Values set are returned by calls in get mode.
+        let set_id = start(&mut provider, "redis", &[url, ("key", "k")]).await;
+        let get_id = start(&mut provider, "redis", &[url, ("key", "k"), ("mode", "get")]).await;
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply(String::new()),
+            source_handle.call(get_id, String::new()).await
+        );
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply(String::new()),
+            source_handle.call(set_id, "42".to_string()).await
+        );
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply("42".to_string()),
+            source_handle.call(get_id, String::new()).await
+        );
+
+        // Only the entries added to a stream after the creation of the
+        // resource are cast to the target in subscribe mode.
+        data.lock()
+            .unwrap()
+            .streams
+            .insert("s".to_string(), vec![("payload".to_string(), "old".to_string())]);
+        let xadd_id = start(&mut provider, "redis", &[url, ("key", "s"), ("mode", "xadd")]).await;
+        let subscribe_id = start(&mut provider, "redis", &[url, ("key", "s"), ("mode", "subscribe")]).await;
+        patch(&mut provider, subscribe_id, "new_message", target_id).await;
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply("2-0".to_string()),
+            source_handle.call(xadd_id, "entry-1".to_string()).await
+        );
+        assert_eq!((subscribe_id, "entry-1".to_string()), receive_cast(&mut target_handle).await);
+
+        // Restart the server: the connections are re-established, possibly
+        // after failing the messages in the meanwhile.
+        stand_in.abort();
+        let _ = stand_in.await;
+        let stand_in = redis_stand_in_with_data(tokio::net::TcpListener::bind(address).await.unwrap(), data.clone());
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply(String::new()),
+            call_until_reply(&mut source_handle, set_id, "43").await
+        );
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply("43".to_string()),
+            call_until_reply(&mut source_handle, get_id, "").await
+        );
+        data.lock()
+            .unwrap()
+            .streams
+            .get_mut("s")
+            .unwrap()
+            .push(("payload".to_string(), "entry-2".to_string()));
+        assert_eq!((subscribe_id, "entry-2".to_string()), receive_cast(&mut target_handle).await);
+
+        for id in [set_id, get_id, xadd_id, subscribe_id] {
+            provider.stop(id).await.unwrap();
+        }
+        stand_in.abort();
+    }
+
+    #[ignore]
+    #[tokio::test]
+    async fn test_redis_modes() {
+        // Requires a Redis server, e.g.: redis-server --port 6379
+        let url = std::env::var("REDIS_URL").unwrap_or("redis://127.0.0.1:6379/".to_string());
+        let key = format!("edgeless-test-{}", uuid::Uuid::new_v4());
+        let url = ("url", url.as_str());
+        let mut node = TestNode::new().await;
+        let mut provider = RedisResourceProvider::new(node.dataplane_provider.clone(), node.provider_id()).await;
+        let (_, mut source_handle) = node.function().await;
+        let (target_id, mut target_handle) = node.function().await;
+
+        // Values set are returned by calls in get mode.
+        let set_id = start(&mut provider, "redis", &[url, ("key", key.as_str())]).await;
+        let get_id = start(&mut provider, "redis", &[url, ("key", key.as_str()), ("mode", "get")]).await;
+        source_handle.send(set_id, "42".to_string()).await;
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply("42".to_string()),
+            source_handle.call(get_id, String::new()).await
+        );
+
+        // Calls in rpush mode return the length of the list.
+        let list = format!("{}-list", key);
+        let rpush_id = start(&mut provider, "redis", &[url, ("key", list.as_str()), ("mode", "rpush"), ("ttl", "10")]).await;
+        for i in 1..=3 {
+            assert_eq!(
+                edgeless_dataplane::core::CallRet::Reply(i.to_string()),
+                source_handle.call(rpush_id, format!("item-{}", i)).await
+            );
+        }
+
+        // Entries added to a stream are cast to the target in subscribe mode.
+        let stream = format!("{}-stream", key);
+        let xadd_id = start(&mut provider, "redis", &[url, ("key", stream.as_str()), ("mode", "xadd")]).await;
+        let subscribe_id = start(&mut provider, "redis", &[url, ("key", stream.as_str()), ("mode", "subscribe")]).await;
+        patch(&mut provider, subscribe_id, "new_message", target_id).await;
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        for i in 0..3 {
+            source_handle.send(xadd_id, format!("entry-{}", i)).await;
+            assert_eq!((subscribe_id, format!("entry-{}", i)), receive_cast(&mut target_handle).await);
+        }
+
+        for id in [set_id, get_id, rpush_id, xadd_id, subscribe_id] {
+            provider.stop(id).await.unwrap();
+        }
     }
 }
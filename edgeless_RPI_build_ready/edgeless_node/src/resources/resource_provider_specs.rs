@@ -4,10 +4,41 @@
 pub trait ResourceProviderSpecs {
     fn class_type(&self) -> String;
     fn outputs(&self) -> Vec<String>;
+    /// Output channels of a resource instance with the given run-time
+    /// configuration, by default those advertised by the provider.
+    fn instance_outputs(&self, _configuration: &std::collections::HashMap<String, String>) -> Vec<String> {
+        self.outputs()
+    }
     fn configurations(&self) -> std::collections::HashMap<String, String>;
     fn version(&self) -> String;
 }
 
+/// Specifications of all the resource providers that can be hosted by a
+/// node built with the current features.
+pub fn available_resource_specs() -> Vec<Box<dyn ResourceProviderSpecs>> {
+    #[allow(unused_mut)]
+    let mut specs: Vec<Box<dyn ResourceProviderSpecs>> = vec![
+        Box::new(super::dataset_replay::DatasetReplayResourceSpec {}),
+        Box::new(super::dda::DdaResourceSpec {}),
+        Box::new(super::file_log::FileLogResourceSpec {}),
+        Box::new(super::http_egress::HttpEgressResourceSpec {}),
+        Box::new(super::http_ingress::HttpIngressResourceSpec {}),
+        Box::new(super::ollama::OllamasResourceSpec {}),
+        Box::new(super::redis::RedisResourceSpec {}),
+        Box::new(super::metrics_collector::MetricsCollectorResourceSpec {}),
+        Box::new(super::mqtt_egress::MqttEgressResourceSpec {}),
+        Box::new(super::mqtt_ingress::MqttIngressResourceSpec {}),
+        Box::new(super::timer::TimerResourceSpec {}),
+        Box::new(super::tsdb_sink::TsdbSinkResourceSpec {}),
+        Box::new(super::websocket::WebSocketResourceSpec {}),
+    ];
+    #[cfg(feature = "rdkafka")]
+    specs.push(Box::new(super::kafka_egress::KafkaEgressResourceSpec {}));
+    #[cfg(feature = "rdkafka")]
+    specs.push(Box::new(super::kafka_ingress::KafkaIngressResourceSpec {}));
+    specs
+}
+
 #[derive(serde::Serialize)]
 pub struct ResourceProviderSpecOutput {
     class_type: String,
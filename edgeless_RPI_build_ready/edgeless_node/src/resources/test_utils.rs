@@ -0,0 +1,183 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Helpers shared by the tests of the resource providers.
+
+use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+/// Build the configuration of a resource instance from key-value pairs.
+pub fn configuration(entries: &[(&str, &str)]) -> std::collections::HashMap<String, String> {
+    entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+/// Parse a date and time in RFC 3339 format.
+pub fn datetime(value: &str) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&chrono::Utc)
+}
+
+/// Node hosting the resource instances under test and fake functions
+/// exchanging messages with them through the dataplane.
+pub struct TestNode {
+    pub node_id: uuid::Uuid,
+    pub dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+}
+
+impl TestNode {
+    /// Create a node whose dataplane is reachable at a port chosen by the OS.
+    pub async fn new() -> Self {
+        let node_id = uuid::Uuid::new_v4();
+        let dataplane_provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, "http://127.0.0.1:0".to_string(), None).await;
+        Self { node_id, dataplane_provider }
+    }
+
+    /// Identifier of a resource provider on this node.
+    pub fn provider_id(&self) -> edgeless_api::function_instance::InstanceId {
+        edgeless_api::function_instance::InstanceId::new(self.node_id)
+    }
+
+    /// Create a fake function, returning its identifier and dataplane handle.
+    pub async fn function(&mut self) -> (edgeless_api::function_instance::InstanceId, edgeless_dataplane::handle::DataplaneHandle) {
+        let id = edgeless_api::function_instance::InstanceId::new(self.node_id);
+        (id, self.dataplane_provider.get_handle_for(id).await)
+    }
+}
+
+/// Start a resource instance with the given configuration.
+pub async fn try_start(
+    provider: &mut (impl ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> + ?Sized),
+    class_type: &str,
+    entries: &[(&str, &str)],
+) -> edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId> {
+    provider
+        .start(edgeless_api::resource_configuration::ResourceInstanceSpecification {
+            class_type: class_type.to_string(),
+            output_mapping: std::collections::HashMap::new(),
+            configuration: configuration(entries),
+        })
+        .await
+        .unwrap()
+}
+
+/// Start a resource instance with the given configuration, which must be
+/// accepted by the provider.
+pub async fn start(
+    provider: &mut (impl ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> + ?Sized),
+    class_type: &str,
+    entries: &[(&str, &str)],
+) -> edgeless_api::function_instance::InstanceId {
+    match try_start(provider, class_type, entries).await {
+        edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    }
+}
+
+/// Map an output channel of a resource instance to a target component.
+pub async fn patch(
+    provider: &mut (impl ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> + ?Sized),
+    resource_id: edgeless_api::function_instance::InstanceId,
+    channel: &str,
+    target_id: edgeless_api::function_instance::InstanceId,
+) {
+    provider
+        .patch(edgeless_api::common::PatchRequest {
+            function_id: resource_id.function_id,
+            output_mapping: std::collections::HashMap::from([(channel.to_string(), target_id)]),
+        })
+        .await
+        .unwrap();
+}
+
+/// Receive the next cast from the dataplane, returning its source and
+/// payload, and fail if nothing arrives within 5 seconds.
+pub async fn receive_cast(handle: &mut edgeless_dataplane::handle::DataplaneHandle) -> (edgeless_api::function_instance::InstanceId, String) {
+    let event = tokio::time::timeout(std::time::Duration::from_secs(5), handle.receive_next())
+        .await
+        .expect("message not received");
+    match event.message {
+        edgeless_dataplane::core::Message::Cast(payload) => (event.source_id, payload),
+        message => panic!("unexpected message: {:?}", message),
+    }
+}
+
+/// Encode a bulk string, or a nil value, in the Redis protocol.
+pub fn resp_bulk(value: Option<&str>) -> String {
+    match value {
+        Some(value) => format!("${}\r\n{}\r\n", value.len(), value),
+        None => String::from("$-1\r\n"),
+    }
+}
+
+/// Function returning the reply of the Redis stand-in to a command.
+type RedisStandInHandler = std::sync::Arc<dyn Fn(&[String]) -> String + Send + Sync>;
+
+/// Start a minimal stand-in of a Redis server accepting connections on
+/// `listener`, which replies to every command with the Redis protocol
+/// encoding returned by `handler`. Commands within a transaction are passed
+/// to the handler at EXEC, before EXEC itself; the replies of the handler to
+/// MULTI and EXEC are ignored. The handler is called in a blocking thread, so
+/// it can wait to emulate blocking commands. The connections are closed
+/// when the returned task is aborted.
+pub fn redis_stand_in(
+    listener: tokio::net::TcpListener,
+    handler: impl Fn(&[String]) -> String + Send + Sync + 'static,
+) -> tokio::task::JoinHandle<()> {
+    let handler: RedisStandInHandler = std::sync::Arc::new(handler);
+    tokio::spawn(async move {
+        let mut connections = tokio::task::JoinSet::new();
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            connections.spawn(serve_redis_stand_in(stream, handler.clone()));
+        }
+    })
+}
+
+async fn serve_redis_stand_in(stream: tokio::net::TcpStream, handler: RedisStandInHandler) {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = tokio::io::BufReader::new(reader);
+    let mut queued: Option<Vec<Vec<String>>> = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.unwrap_or(0) == 0 {
+            return;
+        }
+        let num_args: usize = line.trim_end().trim_start_matches('*').parse().unwrap();
+        let mut command = vec![];
+        for _ in 0..num_args {
+            line.clear();
+            reader.read_line(&mut line).await.unwrap();
+            let len: usize = line.trim_end().trim_start_matches('$').parse().unwrap();
+            let mut buf = vec![0; len + 2];
+            reader.read_exact(&mut buf).await.unwrap();
+            buf.truncate(len);
+            command.push(String::from_utf8_lossy(&buf).to_string());
+        }
+
+        let handler = handler.clone();
+        let reply = match (command[0].to_uppercase().as_str(), &mut queued) {
+            ("MULTI", _) => {
+                queued = Some(vec![]);
+                tokio::task::spawn_blocking(move || handler(&command)).await.unwrap();
+                String::from("+OK\r\n")
+            }
+            ("EXEC", _) => {
+                let commands = queued.take().unwrap_or_default();
+                tokio::task::spawn_blocking(move || {
+                    let replies = commands.iter().map(|x| handler(x)).collect::<String>();
+                    handler(&command);
+                    format!("*{}\r\n{}", commands.len(), replies)
+                })
+                .await
+                .unwrap()
+            }
+            (_, Some(queued)) => {
+                queued.push(command);
+                String::from("+QUEUED\r\n")
+            }
+            (_, None) => tokio::task::spawn_blocking(move || handler(&command)).await.unwrap(),
+        };
+        if writer.write_all(reply.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,395 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use rand::Rng;
+
+pub struct TimerResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for TimerResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("timer")
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![String::from("tick")]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (String::from("interval_ms"), String::from("Interval between consecutive ticks, in ms")),
+            (
+                String::from("cron"),
+                String::from("Cron expression with seconds, e.g., '0 */5 * * * *', in UTC, alternative to interval_ms"),
+            ),
+            (
+                String::from("jitter_ms"),
+                String::from("Maximum random delay added to every tick, in ms (default: 0)"),
+            ),
+            (
+                String::from("max_count"),
+                String::from("Maximum number of ticks, after which the timer stops (default: unlimited)"),
+            ),
+            (
+                String::from("payload"),
+                String::from("Content of the ticks (default: sequence number of the tick, from 0)"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+/// Schedule of the ticks of a timer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimerSchedule {
+    /// Fixed interval between consecutive ticks, starting one interval
+    /// after the timer is activated.
+    Interval(std::time::Duration),
+    /// Ticks at the times matching a cron expression, in UTC.
+    Cron(Box<cron::Schedule>),
+}
+
+impl std::fmt::Display for TimerSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TimerSchedule::Interval(interval) => write!(f, "every {} ms", interval.as_millis()),
+            TimerSchedule::Cron(schedule) => write!(f, "cron '{}'", schedule),
+        }
+    }
+}
+
+/// Configuration of a timer resource instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerConfiguration {
+    pub schedule: TimerSchedule,
+    pub jitter: std::time::Duration,
+    pub max_count: Option<u64>,
+    pub payload: Option<String>,
+}
+
+impl TimerConfiguration {
+    /// Parse the run-time configuration of a resource instance.
+    pub fn from(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let get = |key: &str| configuration.get(key).filter(|x| !x.is_empty());
+        let parse_u64 = |key: &str| -> anyhow::Result<Option<u64>> {
+            match get(key) {
+                Some(value) => match value.parse::<u64>() {
+                    Ok(value) => Ok(Some(value)),
+                    Err(_) => anyhow::bail!("Invalid {}: {}", key, value),
+                },
+                None => Ok(None),
+            }
+        };
+
+        let schedule = match (parse_u64("interval_ms")?, get("cron")) {
+            (Some(_), Some(_)) => anyhow::bail!("Only one of interval_ms and cron can be specified"),
+            (None, None) => anyhow::bail!("One of interval_ms or cron must be specified"),
+            (Some(0), None) => anyhow::bail!("Invalid interval_ms: 0"),
+            (Some(interval_ms), None) => TimerSchedule::Interval(std::time::Duration::from_millis(interval_ms)),
+            (None, Some(expression)) => match <cron::Schedule as std::str::FromStr>::from_str(expression) {
+                Ok(schedule) => TimerSchedule::Cron(Box::new(schedule)),
+                Err(err) => anyhow::bail!("Invalid cron expression '{}': {}", expression, err),
+            },
+        };
+
+        Ok(Self {
+            schedule,
+            jitter: std::time::Duration::from_millis(parse_u64("jitter_ms")?.unwrap_or_default()),
+            max_count: parse_u64("max_count")?,
+            payload: get("payload").cloned(),
+        })
+    }
+}
+
+impl TimerSchedule {
+    /// Return the time of the next tick, without jitter.
+    ///
+    /// - `start`: when the timer was activated.
+    /// - `count`: the number of ticks already produced.
+    /// - `last`: the time of the last tick produced, if any.
+    /// - `now`: the current time.
+    pub fn next(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        count: u64,
+        last: Option<chrono::DateTime<chrono::Utc>>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        match self {
+            TimerSchedule::Interval(interval) => {
+                // Ticks do not drift even if some of them are late.
+                let elapsed = chrono::Duration::from_std(interval.checked_mul(u32::try_from(count + 1).ok()?)?).ok()?;
+                start.checked_add_signed(elapsed)
+            }
+            TimerSchedule::Cron(schedule) => {
+                // Times missed, e.g., because of a large jitter, are skipped.
+                let after = match last {
+                    Some(last) => std::cmp::max(last, now),
+                    None => now,
+                };
+                schedule.after(&after).next()
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TimerResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<TimerResourceProviderInner>>,
+}
+
+pub struct TimerResourceProviderInner {
+    resource_provider_id: edgeless_api::function_instance::InstanceId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, TimerResource>,
+}
+
+pub struct TimerResource {
+    // Component receiving the ticks on the tick output channel.
+    target_sender: tokio::sync::watch::Sender<Option<edgeless_api::function_instance::InstanceId>>,
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for TimerResource {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+impl TimerResource {
+    /// Create a new timer resource, which casts ticks to the component
+    /// mapped to the tick output channel, as soon as the latter becomes known.
+    ///
+    /// - `dataplane_handle`: gives access to the EDGELESS dataplane.
+    /// - `configuration`: the configuration of the resource instance.
+    async fn new(dataplane_handle: edgeless_dataplane::handle::DataplaneHandle, configuration: TimerConfiguration) -> anyhow::Result<Self> {
+        let mut dataplane_handle = dataplane_handle;
+
+        log::info!(
+            "TimerResource created, schedule: {}, jitter: {} ms, max_count: {:?}",
+            configuration.schedule,
+            configuration.jitter.as_millis(),
+            configuration.max_count
+        );
+
+        let (target_sender, mut target_receiver) = tokio::sync::watch::channel(None);
+
+        let handle = tokio::spawn(async move {
+            // Do not start the timer until the target is known.
+            if target_receiver.wait_for(|target| target.is_some()).await.is_err() {
+                return;
+            }
+
+            let start = chrono::Utc::now();
+            let mut last = None;
+            let mut count: u64 = 0;
+            while !matches!(configuration.max_count, Some(max_count) if count >= max_count) {
+                let next = match configuration.schedule.next(start, count, last, chrono::Utc::now()) {
+                    Some(next) => next,
+                    None => {
+                        log::info!("No more ticks scheduled");
+                        break;
+                    }
+                };
+                let jitter = match configuration.jitter.is_zero() {
+                    true => std::time::Duration::ZERO,
+                    false => rand::thread_rng().gen_range(std::time::Duration::ZERO..=configuration.jitter),
+                };
+                let delay = (next - chrono::Utc::now()).to_std().unwrap_or(std::time::Duration::ZERO);
+                tokio::time::sleep(delay + jitter).await;
+
+                let payload = match &configuration.payload {
+                    Some(payload) => payload.clone(),
+                    None => count.to_string(),
+                };
+                let target = *target_receiver.borrow();
+                if let Some(target) = target {
+                    dataplane_handle.send(target, payload).await;
+                }
+                last = Some(next);
+                count += 1;
+            }
+        });
+
+        Ok(Self {
+            target_sender,
+            join_handle: handle,
+        })
+    }
+}
+
+impl TimerResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        resource_provider_id: edgeless_api::function_instance::InstanceId,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(TimerResourceProviderInner {
+                resource_provider_id,
+                dataplane_provider,
+                instances: std::collections::HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for TimerResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let configuration = match TimerConfiguration::from(&instance_specification.configuration) {
+            Ok(configuration) => configuration,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
+            }
+        };
+
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+
+        match TimerResource::new(dataplane_handle, configuration).await {
+            Ok(resource) => {
+                lck.instances.insert(new_id.function_id, resource);
+                Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+            }
+            Err(err) => Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Invalid resource configuration".to_string(),
+                    detail: Some(err.to_string()),
+                },
+            )),
+        }
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        // Find the target component to which we have to send the ticks
+        // on the "tick" output channel.
+        let target = match update.output_mapping.get("tick") {
+            Some(val) => *val,
+            None => {
+                anyhow::bail!("Missing mapping of channel: tick");
+            }
+        };
+
+        let lck = self.inner.lock().await;
+        match lck.instances.get(&update.function_id) {
+            Some(resource) => {
+                resource.target_sender.send_replace(Some(target));
+                Ok(())
+            }
+            None => {
+                anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    #[test]
+    fn test_timer_configuration() {
+        let conf = TimerConfiguration::from(&configuration(&[("interval_ms", "500")])).unwrap();
+        assert_eq!(
+            TimerConfiguration {
+                schedule: TimerSchedule::Interval(std::time::Duration::from_millis(500)),
+                jitter: std::time::Duration::ZERO,
+                max_count: None,
+                payload: None,
+            },
+            conf
+        );
+
+        let conf = TimerConfiguration::from(&configuration(&[
+            ("cron", "0 */5 * * * *"),
+            ("jitter_ms", "100"),
+            ("max_count", "10"),
+            ("payload", "go"),
+        ]))
+        .unwrap();
+        assert_eq!("cron '0 */5 * * * *'", conf.schedule.to_string());
+        assert_eq!(std::time::Duration::from_millis(100), conf.jitter);
+        assert_eq!(Some(10), conf.max_count);
+        assert_eq!(Some("go".to_string()), conf.payload);
+
+        for wrong in [
+            vec![],
+            vec![("interval_ms", "100"), ("cron", "0 * * * * *")],
+            vec![("interval_ms", "0")],
+            vec![("interval_ms", "-1")],
+            vec![("cron", "every minute")],
+            vec![("interval_ms", "100"), ("jitter_ms", "x")],
+            vec![("interval_ms", "100"), ("max_count", "1.5")],
+        ] {
+            assert!(TimerConfiguration::from(&configuration(&wrong)).is_err(), "{:?}", wrong);
+        }
+    }
+
+    #[test]
+    fn test_timer_schedule_next() {
+        let start = datetime("2024-01-01T10:00:00Z");
+
+        let schedule = TimerSchedule::Interval(std::time::Duration::from_millis(1500));
+        assert_eq!(Some(datetime("2024-01-01T10:00:01.5Z")), schedule.next(start, 0, None, start));
+        assert_eq!(
+            Some(datetime("2024-01-01T10:00:04.5Z")),
+            schedule.next(start, 2, Some(datetime("2024-01-01T10:00:03Z")), datetime("2024-01-01T10:00:10Z"))
+        );
+
+        let schedule = TimerSchedule::Cron(Box::new(<cron::Schedule as std::str::FromStr>::from_str("0 */5 * * * *").unwrap()));
+        assert_eq!(
+            Some(datetime("2024-01-01T10:05:00Z")),
+            schedule.next(start, 0, None, datetime("2024-01-01T10:00:00Z"))
+        );
+        assert_eq!(
+            Some(datetime("2024-01-01T10:10:00Z")),
+            schedule.next(start, 1, Some(datetime("2024-01-01T10:05:00Z")), datetime("2024-01-01T10:04:59.999Z"))
+        );
+        assert_eq!(
+            Some(datetime("2024-01-01T10:15:00Z")),
+            schedule.next(start, 1, Some(datetime("2024-01-01T10:05:00Z")), datetime("2024-01-01T10:12:00Z"))
+        );
+
+        let schedule = TimerSchedule::Cron(Box::new(<cron::Schedule as std::str::FromStr>::from_str("0 0 0 1 1 * 2020").unwrap()));
+        assert_eq!(None, schedule.next(start, 0, None, start));
+    }
+
+    #[tokio::test]
+    async fn test_timer_resource() {
+        let mut node = TestNode::new().await;
+        let mut provider = TimerResourceProvider::new(node.dataplane_provider.clone(), node.provider_id()).await;
+
+        let resource_id = start(&mut provider, "timer", &[("interval_ms", "20"), ("jitter_ms", "5"), ("max_count", "3")]).await;
+        let (target_id, mut target_handle) = node.function().await;
+        patch(&mut provider, resource_id, "tick", target_id).await;
+
+        for expected in ["0", "1", "2"] {
+            assert_eq!((resource_id, expected.to_string()), receive_cast(&mut target_handle).await);
+        }
+
+        // No more ticks after max_count.
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(100), target_handle.receive_next())
+            .await
+            .is_err());
+
+        provider.stop(resource_id).await.unwrap();
+    }
+}
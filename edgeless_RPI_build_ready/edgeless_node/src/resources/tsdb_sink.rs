@@ -0,0 +1,786 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+pub struct TsdbSinkResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for TsdbSinkResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("tsdb-sink")
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (
+                String::from("backend"),
+                String::from("One of: influxdb (default), file, sqlite (requires the rusqlite feature)"),
+            ),
+            (
+                String::from("url"),
+                String::from("URL of the InfluxDB write API, e.g., http://127.0.0.1:8086/api/v2/write?org=my-org&bucket=my-bucket"),
+            ),
+            (String::from("token"), String::from("If specified, InfluxDB API token")),
+            (
+                String::from("filename"),
+                String::from("Name of the node-local file where to save the line protocol (file) or the database (sqlite)"),
+            ),
+            (
+                String::from("measurement"),
+                String::from("Name of the measurement, also used as the SQLite table (default: edgeless)"),
+            ),
+            (
+                String::from("tags"),
+                String::from("Comma-separated list of payload keys saved as tags, optionally renamed with key:name"),
+            ),
+            (
+                String::from("fields"),
+                String::from("Comma-separated list of payload keys saved as fields, optionally renamed with key:name (default: all but tags)"),
+            ),
+            (
+                String::from("timestamp_field"),
+                String::from("Payload key of the timestamp, since the Unix epoch, by default the time of reception is used"),
+            ),
+            (
+                String::from("time_unit"),
+                String::from("Unit of the timestamps: s, ms (default), us, or ns"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+/// Default name of the measurement.
+const DEFAULT_MEASUREMENT: &str = "edgeless";
+
+/// Where the points are written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TsdbBackend {
+    /// InfluxDB line protocol, posted to the write API of a server.
+    InfluxDb { url: String, token: Option<String> },
+    /// InfluxDB line protocol, appended to a node-local file.
+    File(String),
+    /// Rows of a table, with one column per tag and field, in a node-local
+    /// SQLite database.
+    Sqlite(String),
+}
+
+/// Value of a field of a point.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    Float(f64),
+    Bool(bool),
+    String(String),
+}
+
+/// A point of a time series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Point {
+    measurement: String,
+    // Sorted by name.
+    tags: Vec<(String, String)>,
+    fields: Vec<(String, FieldValue)>,
+    // Nanoseconds since the Unix epoch.
+    timestamp: i64,
+}
+
+/// Escape the measurement, tag keys and values, and field keys of the line
+/// protocol, i.e., commas, spaces, (unless in measurements) equal signs,
+/// and line breaks, which would otherwise end the point.
+fn escape(value: &str, equal: bool) -> String {
+    let mut ret = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            ',' | ' ' => {
+                ret.push('\\');
+                ret.push(c);
+            }
+            '=' if equal => {
+                ret.push('\\');
+                ret.push(c);
+            }
+            _ => ret.push(c),
+        }
+    }
+    ret
+}
+
+/// Escape a string field value of the line protocol, to be double-quoted.
+fn escape_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+impl Point {
+    /// Format the point in InfluxDB line protocol, with nanosecond precision.
+    ///
+    /// Fails if a field is NaN or infinite, which the line protocol cannot
+    /// represent.
+    pub fn to_line_protocol(&self) -> anyhow::Result<String> {
+        let mut ret = escape(&self.measurement, false);
+        for (name, value) in &self.tags {
+            ret.push_str(&format!(",{}={}", escape(name, true), escape(value, true)));
+        }
+        for (i, (name, value)) in self.fields.iter().enumerate() {
+            let value = match value {
+                FieldValue::Float(value) if !value.is_finite() => anyhow::bail!("Non-finite value of field {}: {}", name, value),
+                FieldValue::Float(value) => value.to_string(),
+                FieldValue::Bool(value) => value.to_string(),
+                FieldValue::String(value) => format!("\"{}\"", escape_string(value)),
+            };
+            ret.push(if i == 0 { ' ' } else { ',' });
+            ret.push_str(&format!("{}={}", escape(name, true), value));
+        }
+        ret.push_str(&format!(" {}", self.timestamp));
+        Ok(ret)
+    }
+}
+
+/// Parse a payload, which is either a JSON object or a comma-separated list
+/// of key=value pairs, whose values are numbers or booleans, if possible,
+/// and strings otherwise.
+fn parse_payload(payload: &str) -> anyhow::Result<Vec<(String, serde_json::Value)>> {
+    let payload = payload.trim();
+    if payload.starts_with('{') {
+        let object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(payload)?;
+        return Ok(object.into_iter().collect());
+    }
+    let mut ret = vec![];
+    for pair in payload.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()) {
+        let (key, value) = match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => (key.trim(), value.trim()),
+            _ => anyhow::bail!("Invalid key=value pair: {}", pair),
+        };
+        let value = if let Ok(number) = value.parse::<i64>() {
+            serde_json::Value::Number(number.into())
+        } else if let Some(number) = value.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+            serde_json::Value::Number(number)
+        } else if let Ok(value) = value.parse::<bool>() {
+            serde_json::Value::Bool(value)
+        } else {
+            serde_json::Value::String(value.to_string())
+        };
+        ret.push((key.to_string(), value));
+    }
+    Ok(ret)
+}
+
+/// Parse a comma-separated list of payload keys, each optionally renamed
+/// with key:name.
+fn parse_mapping(value: &str) -> Vec<(String, String)> {
+    value
+        .split(',')
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .map(|x| match x.split_once(':') {
+            Some((key, name)) => (key.trim().to_string(), name.trim().to_string()),
+            None => (x.to_string(), x.to_string()),
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TsdbSinkConfiguration {
+    backend: TsdbBackend,
+    measurement: String,
+    // Payload keys and names of the tags.
+    tags: Vec<(String, String)>,
+    // Payload keys and names of the fields, all but tags if not set.
+    fields: Option<Vec<(String, String)>>,
+    timestamp_field: Option<String>,
+    // Duration of a time unit, in ns.
+    time_unit: i64,
+}
+
+impl TsdbSinkConfiguration {
+    /// Parse the run-time configuration of a tsdb-sink resource instance.
+    fn from(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let get = |name: &str| configuration.get(name).filter(|x| !x.is_empty());
+
+        let backend = match get("backend").map(|x| x.to_lowercase()) {
+            None => String::from("influxdb"),
+            Some(backend) => backend,
+        };
+        let backend = match (backend.as_str(), get("url"), get("filename")) {
+            ("influxdb", Some(url), _) => TsdbBackend::InfluxDb {
+                url: url.clone(),
+                token: get("token").cloned(),
+            },
+            ("influxdb", None, _) => anyhow::bail!("Missing InfluxDB URL"),
+            ("file", _, Some(filename)) => TsdbBackend::File(filename.clone()),
+            ("sqlite", _, Some(filename)) => TsdbBackend::Sqlite(filename.clone()),
+            ("file" | "sqlite", _, None) => anyhow::bail!("Missing filename"),
+            _ => anyhow::bail!("Invalid backend: {}", backend),
+        };
+        let time_unit = match get("time_unit").map(|x| x.as_str()) {
+            Some("s") => 1_000_000_000,
+            None | Some("ms") => 1_000_000,
+            Some("us") => 1_000,
+            Some("ns") => 1,
+            Some(value) => anyhow::bail!("Invalid time_unit: {}", value),
+        };
+
+        Ok(Self {
+            backend,
+            measurement: get("measurement").cloned().unwrap_or(DEFAULT_MEASUREMENT.to_string()),
+            tags: parse_mapping(get("tags").map(|x| x.as_str()).unwrap_or_default()),
+            fields: get("fields").map(|x| parse_mapping(x)),
+            timestamp_field: get("timestamp_field").cloned(),
+            time_unit,
+        })
+    }
+
+    /// Create a point from a payload.
+    ///
+    /// - `payload`: the payload of the message received.
+    /// - `now`: the time of reception, in ns since the Unix epoch.
+    fn point(&self, payload: &str, now: i64) -> anyhow::Result<Point> {
+        let values = parse_payload(payload)?;
+        let get = |key: &str| values.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+
+        let timestamp = match &self.timestamp_field {
+            None => now,
+            Some(key) => match get(key) {
+                // Integer timestamps are converted without loss of precision.
+                Some(serde_json::Value::Number(timestamp)) if timestamp.is_i64() => {
+                    timestamp.as_i64().unwrap_or_default().saturating_mul(self.time_unit)
+                }
+                Some(serde_json::Value::Number(timestamp)) if timestamp.as_f64().is_some_and(|x| x.is_finite()) => {
+                    (timestamp.as_f64().unwrap_or_default() * self.time_unit as f64) as i64
+                }
+                _ => anyhow::bail!("Missing or invalid timestamp field: {}", key),
+            },
+        };
+
+        let mut tags = vec![];
+        for (key, name) in &self.tags {
+            match get(key) {
+                None | Some(serde_json::Value::Null) => {}
+                Some(serde_json::Value::String(value)) => tags.push((name.clone(), value.clone())),
+                Some(value) => tags.push((name.clone(), value.to_string())),
+            }
+        }
+        tags.sort();
+
+        let mapping = match &self.fields {
+            Some(fields) => fields.clone(),
+            None => values
+                .iter()
+                .map(|(key, _)| key)
+                .filter(|key| !self.tags.iter().any(|(tag, _)| tag == *key) && self.timestamp_field.as_ref() != Some(*key))
+                .map(|key| (key.clone(), key.clone()))
+                .collect(),
+        };
+        let mut fields = vec![];
+        for (key, name) in mapping {
+            let value = match get(&key) {
+                None | Some(serde_json::Value::Null) => continue,
+                Some(serde_json::Value::Number(value)) => match value.as_f64() {
+                    Some(value) if value.is_finite() => FieldValue::Float(value),
+                    // NaN and infinite values cannot be stored: skip the field.
+                    _ => {
+                        log::warn!("Skipping non-finite value of field {}: {}", key, value);
+                        continue;
+                    }
+                },
+                Some(serde_json::Value::Bool(value)) => FieldValue::Bool(*value),
+                Some(serde_json::Value::String(value)) => FieldValue::String(value.clone()),
+                Some(value) => FieldValue::String(value.to_string()),
+            };
+            fields.push((name, value));
+        }
+        if fields.is_empty() {
+            anyhow::bail!("No fields in payload: {}", payload);
+        }
+
+        Ok(Point {
+            measurement: self.measurement.clone(),
+            tags,
+            fields,
+            timestamp,
+        })
+    }
+}
+
+/// Write the points to the backend of a tsdb-sink resource instance.
+///
+/// The node-local backends use blocking I/O, which is run on the thread
+/// pool of tokio dedicated to blocking tasks.
+enum TsdbWriter {
+    InfluxDb {
+        client: reqwest::Client,
+        url: String,
+        token: Option<String>,
+    },
+    File(std::sync::Arc<std::sync::Mutex<std::fs::File>>),
+    #[cfg(feature = "rusqlite")]
+    Sqlite(std::sync::Arc<std::sync::Mutex<SqliteWriter>>),
+}
+
+/// Write the points as rows of the tables of an SQLite database.
+#[cfg(feature = "rusqlite")]
+struct SqliteWriter {
+    connection: rusqlite::Connection,
+    // Columns of the tables already known to exist.
+    columns: std::collections::HashMap<String, std::collections::HashSet<String>>,
+}
+
+/// Quote an SQLite identifier.
+#[cfg(feature = "rusqlite")]
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(feature = "rusqlite")]
+impl SqliteWriter {
+    fn write(&mut self, point: &Point) -> anyhow::Result<()> {
+        let table = &point.measurement;
+        if !self.columns.contains_key(table) {
+            self.connection
+                .execute(&format!("CREATE TABLE IF NOT EXISTS {} (time INTEGER)", quote(table)), ())?;
+            let mut statement = self.connection.prepare(&format!("PRAGMA table_info({})", quote(table)))?;
+            let existing = statement
+                .query_map((), |row| row.get::<_, String>(1))?
+                .collect::<Result<std::collections::HashSet<String>, _>>()?;
+            self.columns.insert(table.clone(), existing);
+        }
+        let known = self.columns.get_mut(table).expect("table columns must be known");
+
+        let mut names = vec![String::from("time")];
+        let mut values = vec![rusqlite::types::Value::Integer(point.timestamp)];
+        let tags = point.tags.iter().map(|(name, value)| (name, rusqlite::types::Value::Text(value.clone())));
+        let fields = point.fields.iter().map(|(name, value)| {
+            (
+                name,
+                match value {
+                    FieldValue::Float(value) => rusqlite::types::Value::Real(*value),
+                    FieldValue::Bool(value) => rusqlite::types::Value::Integer(*value as i64),
+                    FieldValue::String(value) => rusqlite::types::Value::Text(value.clone()),
+                },
+            )
+        });
+        for (name, value) in tags.chain(fields) {
+            if names.contains(name) {
+                continue;
+            }
+            if !known.contains(name) {
+                self.connection
+                    .execute(&format!("ALTER TABLE {} ADD COLUMN {}", quote(table), quote(name)), ())?;
+                known.insert(name.clone());
+            }
+            names.push(name.clone());
+            values.push(value);
+        }
+
+        self.connection.execute(
+            &format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                quote(table),
+                names.iter().map(|x| quote(x)).collect::<Vec<String>>().join(","),
+                vec!["?"; names.len()].join(",")
+            ),
+            rusqlite::params_from_iter(values),
+        )?;
+        Ok(())
+    }
+}
+
+impl TsdbWriter {
+    async fn new(backend: &TsdbBackend) -> anyhow::Result<Self> {
+        match backend {
+            TsdbBackend::InfluxDb { url, token } => Ok(Self::InfluxDb {
+                client: reqwest::Client::new(),
+                url: url.clone(),
+                token: token.clone(),
+            }),
+            TsdbBackend::File(filename) => {
+                let file = tokio::fs::OpenOptions::new().create(true).append(true).open(filename).await?;
+                Ok(Self::File(std::sync::Arc::new(std::sync::Mutex::new(file.into_std().await))))
+            }
+            #[cfg(feature = "rusqlite")]
+            TsdbBackend::Sqlite(filename) => {
+                let filename = filename.clone();
+                let connection = tokio::task::spawn_blocking(move || rusqlite::Connection::open(filename)).await??;
+                Ok(Self::Sqlite(std::sync::Arc::new(std::sync::Mutex::new(SqliteWriter {
+                    connection,
+                    columns: std::collections::HashMap::new(),
+                }))))
+            }
+            #[cfg(not(feature = "rusqlite"))]
+            TsdbBackend::Sqlite(_) => anyhow::bail!("SQLite backend disabled at compile time"),
+        }
+    }
+
+    async fn write(&mut self, point: &Point) -> anyhow::Result<()> {
+        match self {
+            Self::InfluxDb { client, url, token } => {
+                let mut request = client.post(url.as_str()).body(point.to_line_protocol()?);
+                if let Some(token) = token {
+                    request = request.header(reqwest::header::AUTHORIZATION, format!("Token {}", token));
+                }
+                let response = request.send().await?;
+                if !response.status().is_success() {
+                    anyhow::bail!("InfluxDB error {}: {}", response.status(), response.text().await.unwrap_or_default());
+                }
+                Ok(())
+            }
+            Self::File(file) => {
+                let line = point.to_line_protocol()?;
+                let file = file.clone();
+                tokio::task::spawn_blocking(move || {
+                    use std::io::Write;
+                    let mut file = file.lock().unwrap();
+                    Ok(writeln!(file, "{}", line)?)
+                })
+                .await?
+            }
+            #[cfg(feature = "rusqlite")]
+            Self::Sqlite(writer) => {
+                let point = point.clone();
+                let writer = writer.clone();
+                tokio::task::spawn_blocking(move || writer.lock().unwrap().write(&point)).await?
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TsdbSinkResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<TsdbSinkResourceProviderInner>>,
+}
+
+pub struct TsdbSinkResourceProviderInner {
+    resource_provider_id: edgeless_api::function_instance::InstanceId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, TsdbSinkResource>,
+}
+
+pub struct TsdbSinkResource {
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for TsdbSinkResource {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+impl TsdbSinkResource {
+    /// Create a new tsdb-sink resource, which writes a point of a time
+    /// series for every message received from the dataplane.
+    ///
+    /// - `dataplane_handle`: gives access to the EDGELESS dataplane.
+    /// - `configuration`: the configuration of the resource instance.
+    async fn new(dataplane_handle: edgeless_dataplane::handle::DataplaneHandle, configuration: TsdbSinkConfiguration) -> anyhow::Result<Self> {
+        let mut dataplane_handle = dataplane_handle;
+        let mut writer = TsdbWriter::new(&configuration.backend).await?;
+
+        log::info!(
+            "TsdbSinkResource created, backend: {:?}, measurement: {}",
+            configuration.backend,
+            configuration.measurement
+        );
+
+        let handle = tokio::spawn(async move {
+            loop {
+                let edgeless_dataplane::core::DataplaneEvent {
+                    source_id,
+                    channel_id,
+                    message,
+                } = dataplane_handle.receive_next().await;
+
+                let mut need_reply = false;
+                let message_data = match message {
+                    edgeless_dataplane::core::Message::Call(data) => {
+                        need_reply = true;
+                        data
+                    }
+                    edgeless_dataplane::core::Message::Cast(data) => data,
+                    _ => {
+                        continue;
+                    }
+                };
+
+                let now = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+                let res = match configuration.point(&message_data, now) {
+                    Ok(point) => writer.write(&point).await,
+                    Err(err) => Err(err),
+                };
+                let ret = match res {
+                    Ok(_) => edgeless_dataplane::core::CallRet::Reply("".to_string()),
+                    Err(err) => {
+                        log::error!("Could not write point of measurement '{}': {}", configuration.measurement, err);
+                        edgeless_dataplane::core::CallRet::Err
+                    }
+                };
+
+                if need_reply {
+                    dataplane_handle.reply(source_id, channel_id, ret).await;
+                }
+            }
+        });
+
+        Ok(Self { join_handle: handle })
+    }
+}
+
+impl TsdbSinkResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        resource_provider_id: edgeless_api::function_instance::InstanceId,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(TsdbSinkResourceProviderInner {
+                resource_provider_id,
+                dataplane_provider,
+                instances: std::collections::HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for TsdbSinkResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let configuration = match TsdbSinkConfiguration::from(&instance_specification.configuration) {
+            Ok(configuration) => configuration,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
+            }
+        };
+
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+
+        match TsdbSinkResource::new(dataplane_handle, configuration).await {
+            Ok(resource) => {
+                lck.instances.insert(new_id.function_id, resource);
+                Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+            }
+            Err(err) => Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Invalid resource configuration".to_string(),
+                    detail: Some(err.to_string()),
+                },
+            )),
+        }
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, _update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        // the resource has no channels: nothing to be patched
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::*;
+    use super::*;
+
+    #[test]
+    fn test_tsdb_sink_parse_configuration() {
+        assert_eq!(
+            TsdbSinkConfiguration {
+                backend: TsdbBackend::InfluxDb {
+                    url: "http://127.0.0.1:8086/api/v2/write".to_string(),
+                    token: None
+                },
+                measurement: DEFAULT_MEASUREMENT.to_string(),
+                tags: vec![],
+                fields: None,
+                timestamp_field: None,
+                time_unit: 1_000_000,
+            },
+            TsdbSinkConfiguration::from(&configuration(&[("url", "http://127.0.0.1:8086/api/v2/write")])).unwrap()
+        );
+
+        let conf = TsdbSinkConfiguration::from(&configuration(&[
+            ("backend", "file"),
+            ("filename", "points.lp"),
+            ("measurement", "aoi"),
+            ("tags", "node, function:fun"),
+            ("fields", "value:aoi"),
+            ("timestamp_field", "ts"),
+            ("time_unit", "s"),
+        ]))
+        .unwrap();
+        assert_eq!(TsdbBackend::File("points.lp".to_string()), conf.backend);
+        assert_eq!(
+            vec![("node".to_string(), "node".to_string()), ("function".to_string(), "fun".to_string())],
+            conf.tags
+        );
+        assert_eq!(Some(vec![("value".to_string(), "aoi".to_string())]), conf.fields);
+        assert_eq!(1_000_000_000, conf.time_unit);
+
+        for invalid in [
+            vec![],
+            vec![("backend", "file")],
+            vec![("backend", "sqlite"), ("url", "http://127.0.0.1:8086")],
+            vec![("backend", "prometheus"), ("filename", "points.lp")],
+            vec![("backend", "file"), ("filename", "points.lp"), ("time_unit", "h")],
+        ] {
+            assert!(TsdbSinkConfiguration::from(&configuration(&invalid)).is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_tsdb_sink_point() {
+        let conf = TsdbSinkConfiguration::from(&configuration(&[
+            ("backend", "file"),
+            ("filename", "points.lp"),
+            ("measurement", "my measurement"),
+            ("tags", "node,class:label"),
+            ("timestamp_field", "ts"),
+        ]))
+        .unwrap();
+
+        // JSON payload, fields are all the keys but tags and timestamp.
+        let point = conf
+            .point(
+                r#"{"node": "n 1", "class": 3, "ts": 1700000000123, "latency": 1.5, "ok": true, "msg": "a \"b\""}"#,
+                0,
+            )
+            .unwrap();
+        assert_eq!(
+            r#"my\ measurement,label=3,node=n\ 1 latency=1.5,msg="a \"b\"",ok=true 1700000000123000000"#,
+            point.to_line_protocol().unwrap()
+        );
+
+        // key=value payload, missing tags are skipped.
+        let point = conf.point("ts=1700000000123, aoi=12, state=idle", 0).unwrap();
+        assert_eq!(
+            "my\\ measurement aoi=12,state=\"idle\" 1700000000123000000",
+            point.to_line_protocol().unwrap()
+        );
+
+        // Line breaks are escaped, not to end the point.
+        let point = conf.point(r#"{"node": "n\n1", "ts": 1, "msg": "a\nb\r\n"}"#, 0).unwrap();
+        assert_eq!(r#"my\ measurement,node=n\n1 msg="a\nb\r\n" 1000000"#, point.to_line_protocol().unwrap());
+
+        assert!(conf.point("aoi=12", 0).is_err());
+        assert!(conf.point("ts=1000,node=n1", 0).is_err());
+        assert!(conf.point("ts=1000,aoi", 0).is_err());
+        assert!(conf.point("{not json", 0).is_err());
+
+        // Explicit fields, timestamp of reception.
+        let conf = TsdbSinkConfiguration::from(&configuration(&[
+            ("backend", "file"),
+            ("filename", "points.lp"),
+            ("fields", "aoi:age,missing"),
+        ]))
+        .unwrap();
+        let point = conf.point("aoi=12,other=1", 42).unwrap();
+        assert_eq!("edgeless age=12 42", point.to_line_protocol().unwrap());
+
+        // NaN and infinite values cannot be written in line protocol.
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let point = Point {
+                fields: vec![("age".to_string(), FieldValue::Float(value))],
+                ..point.clone()
+            };
+            assert!(point.to_line_protocol().is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tsdb_sink_writers() {
+        let point = TsdbSinkConfiguration::from(&configuration(&[("backend", "file"), ("filename", "unused"), ("tags", "node")]))
+            .unwrap()
+            .point("node=n1,value=1", 1000)
+            .unwrap();
+
+        // Plain file.
+        let filename = std::env::temp_dir().join(format!("tsdb-sink-{}.lp", uuid::Uuid::new_v4()));
+        let mut writer = TsdbWriter::new(&TsdbBackend::File(filename.to_string_lossy().to_string())).await.unwrap();
+        writer.write(&point).await.unwrap();
+        writer.write(&point).await.unwrap();
+        assert_eq!(
+            "edgeless,node=n1 value=1 1000\nedgeless,node=n1 value=1 1000\n",
+            std::fs::read_to_string(&filename).unwrap()
+        );
+        let _ = std::fs::remove_file(&filename);
+
+        // Local stand-in for the InfluxDB server, which replies to one request.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/v2/write?bucket=test", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![];
+            let mut buf = [0; 1024];
+            while !String::from_utf8_lossy(&request).ends_with("1000") {
+                let n = stream.read(&mut buf).await.unwrap();
+                assert!(n > 0);
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        let mut writer = TsdbWriter::new(&TsdbBackend::InfluxDb {
+            url,
+            token: Some("secret".to_string()),
+        })
+        .await
+        .unwrap();
+        writer.write(&point).await.unwrap();
+        let request = server.await.unwrap().to_lowercase();
+        assert!(request.starts_with("post /api/v2/write?bucket=test "));
+        assert!(request.contains("authorization: token secret\r\n"));
+        assert!(request.ends_with("\r\n\r\nedgeless,node=n1 value=1 1000"));
+    }
+
+    #[cfg(feature = "rusqlite")]
+    #[tokio::test]
+    async fn test_tsdb_sink_sqlite() {
+        let conf = TsdbSinkConfiguration::from(&configuration(&[("backend", "file"), ("filename", "unused"), ("tags", "node")])).unwrap();
+        let filename = std::env::temp_dir().join(format!("tsdb-sink-{}.db", uuid::Uuid::new_v4()));
+        let mut writer = TsdbWriter::new(&TsdbBackend::Sqlite(filename.to_string_lossy().to_string()))
+            .await
+            .unwrap();
+        writer.write(&conf.point("node=n1,value=1", 1000).unwrap()).await.unwrap();
+        writer.write(&conf.point("node=n2,value=2,state=busy", 2000).unwrap()).await.unwrap();
+
+        let connection = rusqlite::Connection::open(&filename).unwrap();
+        let mut statement = connection.prepare("SELECT time, node, value, state FROM edgeless ORDER BY time").unwrap();
+        let rows = statement
+            .query_map((), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, f64>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            vec![
+                (1000, "n1".to_string(), 1.0, None),
+                (2000, "n2".to_string(), 2.0, Some("busy".to_string()))
+            ],
+            rows
+        );
+        let _ = std::fs::remove_file(&filename);
+    }
+}
@@ -0,0 +1,539 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use futures::{SinkExt, StreamExt};
+
+pub struct WebSocketResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for WebSocketResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("websocket")
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![String::from("new_message")]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (
+                String::from("path"),
+                String::from("Path of the URL to which the WebSocket clients connect, e.g., /dashboard"),
+            ),
+            (
+                String::from("max_clients"),
+                String::from("Maximum number of clients connected at the same time (default: unlimited)"),
+            ),
+            (
+                String::from("buffer_size"),
+                String::from("Number of messages buffered for every client, older messages are skipped by slow clients (default: 128)"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+/// Default number of messages buffered for every client.
+const DEFAULT_BUFFER_SIZE: usize = 128;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebSocketConfiguration {
+    path: String,
+    // Maximum number of connected clients, unlimited if not set.
+    max_clients: Option<usize>,
+    buffer_size: usize,
+}
+
+impl WebSocketConfiguration {
+    /// Parse the run-time configuration of a websocket resource instance.
+    fn from(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let get = |name: &str| configuration.get(name).filter(|x| !x.is_empty());
+
+        let path = match get("path") {
+            Some(path) if path.starts_with('/') => path.clone(),
+            Some(path) => anyhow::bail!("Invalid WebSocket path: {}", path),
+            None => anyhow::bail!("Missing WebSocket path"),
+        };
+        let max_clients = match get("max_clients") {
+            None => None,
+            Some(value) => match value.parse::<usize>() {
+                Ok(max_clients) if max_clients > 0 => Some(max_clients),
+                _ => anyhow::bail!("Invalid max_clients: {}", value),
+            },
+        };
+        let buffer_size = match get("buffer_size") {
+            None => DEFAULT_BUFFER_SIZE,
+            Some(value) => match value.parse::<usize>() {
+                Ok(buffer_size) if buffer_size > 0 => buffer_size,
+                _ => anyhow::bail!("Invalid buffer_size: {}", value),
+            },
+        };
+        Ok(Self {
+            path,
+            max_clients,
+            buffer_size,
+        })
+    }
+}
+
+/// Endpoint of a websocket resource instance, to which the clients connect.
+#[derive(Clone)]
+struct WebSocketEndpoint {
+    max_clients: Option<usize>,
+    // Broadcasts the messages received on the dataplane to the clients.
+    outbound: tokio::sync::broadcast::Sender<String>,
+    // Forwards the messages received from the clients to the dataplane.
+    inbound: tokio::sync::mpsc::Sender<String>,
+}
+
+/// Endpoints of the active resource instances, by path.
+type WebSocketEndpoints = std::sync::Arc<tokio::sync::Mutex<std::collections::HashMap<String, WebSocketEndpoint>>>;
+
+#[derive(Clone)]
+struct WebSocketService {
+    endpoints: WebSocketEndpoints,
+}
+
+impl hyper::service::Service<hyper::Request<hyper::body::Incoming>> for WebSocketService {
+    type Response = hyper::Response<http_body_util::Full<hyper::body::Bytes>>;
+
+    type Error = anyhow::Error;
+
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn call(&self, req: hyper::Request<hyper::body::Incoming>) -> Self::Future {
+        let endpoints = self.endpoints.clone();
+        Box::pin(async move {
+            let mut req = req;
+            let endpoint = match endpoints.lock().await.get(req.uri().path()) {
+                Some(endpoint) => endpoint.clone(),
+                None => return Ok(super::http_ingress::simple_response(hyper::StatusCode::NOT_FOUND, "Not Found")),
+            };
+
+            let is_upgrade = req
+                .headers()
+                .get(hyper::header::UPGRADE)
+                .and_then(|x| x.to_str().ok())
+                .map(|x| x.eq_ignore_ascii_case("websocket"))
+                .unwrap_or(false);
+            let accept_key = match (is_upgrade, req.headers().get(hyper::header::SEC_WEBSOCKET_KEY)) {
+                (true, Some(key)) => tokio_tungstenite::tungstenite::handshake::derive_accept_key(key.as_bytes()),
+                _ => {
+                    return Ok(super::http_ingress::simple_response(
+                        hyper::StatusCode::UPGRADE_REQUIRED,
+                        "Upgrade Required",
+                    ))
+                }
+            };
+
+            // The clients are the only receivers of the broadcast channel.
+            if endpoint
+                .max_clients
+                .is_some_and(|max_clients| endpoint.outbound.receiver_count() >= max_clients)
+            {
+                return Ok(super::http_ingress::simple_response(
+                    hyper::StatusCode::SERVICE_UNAVAILABLE,
+                    "Service Unavailable",
+                ));
+            }
+
+            // Subscribe before the handshake completes, so that the client
+            // receives all the messages broadcast after its connection.
+            let outbound = endpoint.outbound.subscribe();
+            let inbound = endpoint.inbound.clone();
+            tokio::spawn(async move {
+                match hyper::upgrade::on(&mut req).await {
+                    Ok(upgraded) => {
+                        let stream = tokio_tungstenite::WebSocketStream::from_raw_socket(
+                            hyper_util::rt::TokioIo::new(upgraded),
+                            tokio_tungstenite::tungstenite::protocol::Role::Server,
+                            None,
+                        )
+                        .await;
+                        serve_client(stream, outbound, inbound).await;
+                    }
+                    Err(err) => log::warn!("WebSocket upgrade error: {}", err),
+                }
+            });
+
+            let mut response = super::http_ingress::simple_response(hyper::StatusCode::SWITCHING_PROTOCOLS, "");
+            let headers = response.headers_mut();
+            headers.insert(hyper::header::CONNECTION, hyper::header::HeaderValue::from_static("Upgrade"));
+            headers.insert(hyper::header::UPGRADE, hyper::header::HeaderValue::from_static("websocket"));
+            headers.insert(hyper::header::SEC_WEBSOCKET_ACCEPT, hyper::header::HeaderValue::from_str(&accept_key)?);
+            Ok(response)
+        })
+    }
+}
+
+/// Serve a WebSocket client until the connection or the resource instance
+/// is closed.
+///
+/// - `stream`: the WebSocket connection with the client.
+/// - `outbound`: the messages to be sent to the client.
+/// - `inbound`: where to forward the messages received from the client.
+async fn serve_client<S>(
+    stream: tokio_tungstenite::WebSocketStream<S>,
+    outbound: tokio::sync::broadcast::Receiver<String>,
+    inbound: tokio::sync::mpsc::Sender<String>,
+) where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut outbound = outbound;
+    let (mut sink, mut stream) = stream.split();
+    loop {
+        tokio::select! {
+            msg = outbound.recv() => match msg {
+                Ok(msg) => {
+                    if let Err(err) = sink.send(tokio_tungstenite::tungstenite::Message::Text(msg)).await {
+                        log::debug!("Could not send to WebSocket client: {}", err);
+                        break;
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Slow WebSocket client, {} messages skipped", skipped);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            },
+            msg = stream.next() => {
+                let msg = match msg {
+                    Some(Ok(tokio_tungstenite::tungstenite::Message::Text(msg))) => msg,
+                    Some(Ok(tokio_tungstenite::tungstenite::Message::Binary(msg))) => String::from_utf8_lossy(&msg).to_string(),
+                    Some(Ok(tokio_tungstenite::tungstenite::Message::Close(_))) | None => break,
+                    // Pings are answered by the WebSocket stream itself.
+                    Some(Ok(_)) => continue,
+                    Some(Err(err)) => {
+                        log::debug!("Error from WebSocket client: {}", err);
+                        break;
+                    }
+                };
+                if inbound.send(msg).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    let _ = sink.close().await;
+}
+
+#[derive(Clone)]
+pub struct WebSocketResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<WebSocketResourceProviderInner>>,
+}
+
+pub struct WebSocketResourceProviderInner {
+    resource_provider_id: edgeless_api::function_instance::InstanceId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    endpoints: WebSocketEndpoints,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, WebSocketResource>,
+}
+
+pub struct WebSocketResource {
+    path: String,
+    // Component receiving the messages on the new_message output channel.
+    target_sender: tokio::sync::watch::Sender<Option<edgeless_api::function_instance::InstanceId>>,
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for WebSocketResource {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+impl WebSocketResource {
+    /// Create a new websocket resource, which broadcasts the messages
+    /// received from the dataplane to the connected clients and casts the
+    /// messages received from the clients to the component mapped to the
+    /// new_message output channel.
+    ///
+    /// The calls are answered with the number of clients to which the
+    /// message has been sent.
+    ///
+    /// - `dataplane_handle`: gives access to the EDGELESS dataplane.
+    /// - `configuration`: the configuration of the resource instance.
+    ///
+    /// Return the resource and the endpoint to which the clients connect.
+    fn new(dataplane_handle: edgeless_dataplane::handle::DataplaneHandle, configuration: &WebSocketConfiguration) -> (Self, WebSocketEndpoint) {
+        let mut dataplane_handle = dataplane_handle;
+        let (outbound, _) = tokio::sync::broadcast::channel(configuration.buffer_size);
+        let (inbound, mut inbound_receiver) = tokio::sync::mpsc::channel(configuration.buffer_size);
+        let (target_sender, target_receiver) = tokio::sync::watch::channel(None);
+
+        log::info!(
+            "WebSocketResource created, path: {}, max clients: {}, buffer size: {}",
+            configuration.path,
+            configuration.max_clients.map(|x| x.to_string()).unwrap_or(String::from("unlimited")),
+            configuration.buffer_size
+        );
+
+        let endpoint = WebSocketEndpoint {
+            max_clients: configuration.max_clients,
+            outbound: outbound.clone(),
+            inbound,
+        };
+
+        let handle = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = dataplane_handle.receive_next() => {
+                        let edgeless_dataplane::core::DataplaneEvent {
+                            source_id,
+                            channel_id,
+                            message,
+                        } = event;
+                        let (need_reply, message_data) = match message {
+                            edgeless_dataplane::core::Message::Call(data) => (true, data),
+                            edgeless_dataplane::core::Message::Cast(data) => (false, data),
+                            _ => continue,
+                        };
+
+                        // Fails if there are no clients connected.
+                        let clients = outbound.send(message_data).unwrap_or(0);
+
+                        if need_reply {
+                            dataplane_handle
+                                .reply(source_id, channel_id, edgeless_dataplane::core::CallRet::Reply(clients.to_string()))
+                                .await;
+                        }
+                    }
+                    Some(msg) = inbound_receiver.recv() => {
+                        let target = *target_receiver.borrow();
+                        match target {
+                            Some(target) => dataplane_handle.send(target, msg).await,
+                            None => log::warn!("Discarded message from WebSocket client: missing mapping of channel new_message"),
+                        }
+                    }
+                }
+            }
+        });
+
+        (
+            Self {
+                path: configuration.path.clone(),
+                target_sender,
+                join_handle: handle,
+            },
+            endpoint,
+        )
+    }
+}
+
+impl WebSocketResourceProvider {
+    /// Create the websocket resource provider and its web server.
+    ///
+    /// - `dataplane_provider`: gives access to the EDGELESS dataplane.
+    /// - `resource_provider_id`: the identifier of the resource provider.
+    /// - `websocket_url`: the URL of the web server, e.g., http://0.0.0.0:7040
+    ///   or https://0.0.0.0:7040 to use TLS.
+    /// - `tls_cert_key`: the paths of the PEM files of the TLS certificate
+    ///   and private key, required if the scheme of the URL is https.
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        resource_provider_id: edgeless_api::function_instance::InstanceId,
+        websocket_url: String,
+        tls_cert_key: Option<(String, String)>,
+    ) -> anyhow::Result<Self> {
+        let web_server = super::http_ingress::WebServer::bind(&websocket_url, tls_cert_key).await?;
+        Ok(Self::with_web_server(dataplane_provider, resource_provider_id, web_server))
+    }
+
+    /// Create the websocket resource provider serving the connections
+    /// received by the given web server, see `new`.
+    pub(crate) fn with_web_server(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        resource_provider_id: edgeless_api::function_instance::InstanceId,
+        web_server: super::http_ingress::WebServer,
+    ) -> Self {
+        let endpoints: WebSocketEndpoints = std::sync::Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new()));
+
+        let cloned_endpoints = endpoints.clone();
+        web_server.serve(move || WebSocketService {
+            endpoints: cloned_endpoints.clone(),
+        });
+
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(WebSocketResourceProviderInner {
+                resource_provider_id,
+                dataplane_provider,
+                endpoints,
+                instances: std::collections::HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for WebSocketResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let mut lck = self.inner.lock().await;
+
+        let configuration = match WebSocketConfiguration::from(&instance_specification.configuration) {
+            Ok(configuration) if lck.endpoints.lock().await.contains_key(&configuration.path) => {
+                Err(anyhow::anyhow!("WebSocket path already in use: {}", configuration.path))
+            }
+            res => res,
+        };
+        let configuration = match configuration {
+            Ok(configuration) => configuration,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ))
+            }
+        };
+
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+
+        let (resource, endpoint) = WebSocketResource::new(dataplane_handle, &configuration);
+        lck.endpoints.lock().await.insert(configuration.path, endpoint);
+        lck.instances.insert(new_id.function_id, resource);
+        Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        let mut lck = self.inner.lock().await;
+        if let Some(resource) = lck.instances.remove(&resource_id.function_id) {
+            // The clients are disconnected when the endpoint is dropped.
+            lck.endpoints.lock().await.remove(&resource.path);
+        }
+        Ok(())
+    }
+
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        // The resource can be used only to broadcast messages to the
+        // clients, in which case the new_message channel is not mapped.
+        let target = update.output_mapping.get("new_message").copied();
+
+        let lck = self.inner.lock().await;
+        match lck.instances.get(&update.function_id) {
+            Some(resource) => {
+                resource.target_sender.send_replace(target);
+                Ok(())
+            }
+            None => {
+                anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    #[test]
+    fn test_websocket_parse_configuration() {
+        let mut configuration = std::collections::HashMap::new();
+        assert!(WebSocketConfiguration::from(&configuration).is_err());
+        configuration.insert("path".to_string(), "dashboard".to_string());
+        assert!(WebSocketConfiguration::from(&configuration).is_err());
+
+        configuration.insert("path".to_string(), "/dashboard".to_string());
+        assert_eq!(
+            WebSocketConfiguration {
+                path: "/dashboard".to_string(),
+                max_clients: None,
+                buffer_size: DEFAULT_BUFFER_SIZE,
+            },
+            WebSocketConfiguration::from(&configuration).unwrap()
+        );
+
+        configuration.insert("max_clients".to_string(), "2".to_string());
+        configuration.insert("buffer_size".to_string(), "16".to_string());
+        let conf = WebSocketConfiguration::from(&configuration).unwrap();
+        assert_eq!(Some(2), conf.max_clients);
+        assert_eq!(16, conf.buffer_size);
+
+        for (key, value) in [("max_clients", "0"), ("max_clients", "many"), ("buffer_size", "0")] {
+            let mut configuration = configuration.clone();
+            configuration.insert(key.to_string(), value.to_string());
+            assert!(WebSocketConfiguration::from(&configuration).is_err(), "{}={}", key, value);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_clients() {
+        let node_id = uuid::Uuid::new_v4();
+        let mut dataplane_provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, "http://127.0.0.1:0".to_string(), None).await;
+        let web_server = super::super::http_ingress::WebServer::bind("http://127.0.0.1:0", None).await.unwrap();
+        let url = |path: &str| format!("ws://{}{}", web_server.listen_addr(), path);
+        let (other_url, dashboard_url) = (url("/other"), url("/dashboard"));
+        let mut provider = WebSocketResourceProvider::with_web_server(
+            dataplane_provider.clone(),
+            edgeless_api::function_instance::InstanceId::new(node_id),
+            web_server,
+        );
+
+        let start = |path: &str| edgeless_api::resource_configuration::ResourceInstanceSpecification {
+            class_type: "websocket".to_string(),
+            output_mapping: std::collections::HashMap::new(),
+            configuration: std::collections::HashMap::from([("path".to_string(), path.to_string()), ("max_clients".to_string(), "2".to_string())]),
+        };
+        let resource_id = match provider.start(start("/dashboard")).await.unwrap() {
+            edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        };
+        assert!(matches!(
+            provider.start(start("/dashboard")).await.unwrap(),
+            edgeless_api::common::StartComponentResponse::ResponseError(_)
+        ));
+
+        let target_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let mut target_handle = dataplane_provider.get_handle_for(target_id).await;
+        provider
+            .patch(edgeless_api::common::PatchRequest {
+                function_id: resource_id.function_id,
+                output_mapping: std::collections::HashMap::from([("new_message".to_string(), target_id)]),
+            })
+            .await
+            .unwrap();
+
+        assert!(tokio_tungstenite::connect_async(other_url.as_str()).await.is_err());
+        let (mut client1, _) = tokio_tungstenite::connect_async(dashboard_url.as_str()).await.unwrap();
+        let (mut client2, _) = tokio_tungstenite::connect_async(dashboard_url.as_str()).await.unwrap();
+        assert!(tokio_tungstenite::connect_async(dashboard_url.as_str()).await.is_err());
+
+        // Messages received from the dataplane are broadcast to all the clients.
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply("2".to_string()),
+            target_handle.call(resource_id, "hello".to_string()).await
+        );
+        for client in [&mut client1, &mut client2] {
+            assert_eq!(
+                tokio_tungstenite::tungstenite::Message::Text("hello".to_string()),
+                client.next().await.unwrap().unwrap()
+            );
+        }
+
+        // Messages received from the clients are cast to the target.
+        client2
+            .send(tokio_tungstenite::tungstenite::Message::Text("ack".to_string()))
+            .await
+            .unwrap();
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), target_handle.receive_next())
+            .await
+            .unwrap();
+        assert_eq!(resource_id, event.source_id);
+        assert_eq!(edgeless_dataplane::core::Message::Cast("ack".to_string()), event.message);
+
+        // Stopping the resource disconnects the clients.
+        provider.stop(resource_id).await.unwrap();
+        match tokio::time::timeout(std::time::Duration::from_secs(5), client1.next()).await.unwrap() {
+            Some(Ok(tokio_tungstenite::tungstenite::Message::Close(_))) | None | Some(Err(_)) => {}
+            Some(Ok(msg)) => panic!("unexpected message: {:?}", msg),
+        }
+        assert!(tokio_tungstenite::connect_async(dashboard_url.as_str()).await.is_err());
+    }
+}
@@ -1,15 +1,19 @@
 // SPDX-FileCopyrightText: © 2023 Technical University of Munich, Chair of Connected Mobility
 // SPDX-FileCopyrightText: © 2023 Siemens AG
 // SPDX-License-Identifier: MIT
+mod redis_state_provider;
+
 #[derive(Clone)]
 pub struct StateManager {
-    handlers: std::sync::Arc<tokio::sync::Mutex<StateProviders>>,
+    handlers: std::sync::Arc<StateProviders>,
 }
 
+/// Provider of the state of the functions, which is shared by all the
+/// state handles and must allow concurrent operations.
 #[async_trait::async_trait]
 trait StateProvider: Sync + Send {
-    fn get(&mut self, state_id: uuid::Uuid) -> Option<String>;
-    fn set(&mut self, state_id: uuid::Uuid, serialized_state: String);
+    async fn get(&self, state_id: uuid::Uuid) -> anyhow::Result<Option<String>>;
+    async fn set(&self, state_id: uuid::Uuid, serialized_state: String) -> anyhow::Result<()>;
 }
 
 struct FileStateProvider {
@@ -25,17 +29,19 @@ impl FileStateProvider {
     }
 }
 
+#[async_trait::async_trait]
 impl StateProvider for FileStateProvider {
-    fn get(&mut self, state_id: uuid::Uuid) -> Option<String> {
+    async fn get(&self, state_id: uuid::Uuid) -> anyhow::Result<Option<String>> {
         let state_file = self.base_path.join(state_id.to_string());
-        if state_file.exists() {
-            return Some(std::fs::read_to_string(state_file).unwrap());
+        if tokio::fs::try_exists(&state_file).await? {
+            return Ok(Some(tokio::fs::read_to_string(state_file).await?));
         }
-        None
+        Ok(None)
     }
-    fn set(&mut self, state_id: uuid::Uuid, serialized_state: String) {
+    async fn set(&self, state_id: uuid::Uuid, serialized_state: String) -> anyhow::Result<()> {
         let state_file = self.base_path.join(state_id.to_string());
-        std::fs::write(state_file, serialized_state).unwrap();
+        tokio::fs::write(state_file, serialized_state).await?;
+        Ok(())
     }
 }
 
@@ -51,11 +57,30 @@ pub trait StateManagerAPI: Send {
 
 impl StateManager {
     pub async fn new() -> Self {
+        Self::new_with_settings(None).await
+    }
+
+    /// Create a state manager with a file-based node-local state provider
+    /// and, if configured, a Redis global state provider, which connects
+    /// (and reconnects) to the server when needed.
+    pub async fn new_with_settings(settings: Option<crate::EdgelessNodeStateSettings>) -> Self {
+        let global: Option<Box<dyn StateProvider>> = match settings {
+            Some(settings) if !settings.global_redis_url.is_empty() => {
+                match redis_state_provider::RedisStateProvider::new(&settings.global_redis_url, settings.global_compare_and_set) {
+                    Ok(provider) => Some(Box::new(provider)),
+                    Err(err) => {
+                        log::error!("invalid Redis URL {} for the global state: {}", settings.global_redis_url, err);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
         Self {
-            handlers: std::sync::Arc::new(tokio::sync::Mutex::new(StateProviders {
+            handlers: std::sync::Arc::new(StateProviders {
                 node_local: Some(Box::new(FileStateProvider::new())),
-                global: None,
-            })),
+                global,
+            }),
         }
     }
 }
@@ -74,11 +99,12 @@ impl StateManagerAPI for StateManager {
 #[async_trait::async_trait]
 pub trait StateHandleAPI: Send {
     async fn get(&mut self) -> Option<String>;
-    async fn set(&mut self, serialized_state: String);
+    /// Save the state, returning an error if it could not be saved.
+    async fn set(&mut self, serialized_state: String) -> anyhow::Result<()>;
 }
 
 pub struct StateHandle {
-    handlers: std::sync::Arc<tokio::sync::Mutex<StateProviders>>,
+    handlers: std::sync::Arc<StateProviders>,
     state_id: uuid::Uuid,
     state_policy: edgeless_api::function_instance::StatePolicy,
 }
@@ -86,37 +112,35 @@ pub struct StateHandle {
 #[async_trait::async_trait]
 impl StateHandleAPI for StateHandle {
     async fn get(&mut self) -> Option<String> {
-        let mut handles = self.handlers.lock().await;
-        match self.state_policy {
-            edgeless_api::function_instance::StatePolicy::NodeLocal => {
-                if let Some(provider) = &mut handles.node_local {
-                    return provider.get(self.state_id);
-                }
-            }
-            edgeless_api::function_instance::StatePolicy::Global => {
-                if let Some(provider) = &mut handles.global {
-                    return provider.get(self.state_id);
-                }
-            }
-            _ => {}
-        }
-        None
+        let res = match self.state_policy {
+            edgeless_api::function_instance::StatePolicy::NodeLocal => match &self.handlers.node_local {
+                Some(provider) => provider.get(self.state_id).await,
+                None => Ok(None),
+            },
+            edgeless_api::function_instance::StatePolicy::Global => match &self.handlers.global {
+                Some(provider) => provider.get(self.state_id).await,
+                None => Err(anyhow::anyhow!("no global state provider configured")),
+            },
+            _ => Ok(None),
+        };
+        res.unwrap_or_else(|err| {
+            log::error!("could not read state {}: {}", self.state_id, err);
+            None
+        })
     }
 
-    async fn set(&mut self, serialized_state: String) {
-        let mut handles = self.handlers.lock().await;
+    async fn set(&mut self, serialized_state: String) -> anyhow::Result<()> {
         match self.state_policy {
-            edgeless_api::function_instance::StatePolicy::NodeLocal => {
-                if let Some(provider) = &mut handles.node_local {
-                    return provider.set(self.state_id, serialized_state);
-                }
-            }
-            edgeless_api::function_instance::StatePolicy::Global => {
-                if let Some(provider) = &mut handles.global {
-                    return provider.set(self.state_id, serialized_state);
-                }
-            }
-            _ => {}
+            edgeless_api::function_instance::StatePolicy::NodeLocal => match &self.handlers.node_local {
+                Some(provider) => provider.set(self.state_id, serialized_state).await,
+                None => Ok(()),
+            },
+            edgeless_api::function_instance::StatePolicy::Global => match &self.handlers.global {
+                Some(provider) => provider.set(self.state_id, serialized_state).await,
+                None => Err(anyhow::anyhow!("no global state provider configured")),
+            },
+            _ => Ok(()),
         }
+        .map_err(|err| anyhow::anyhow!("could not write state {}: {}", self.state_id, err))
     }
 }
@@ -0,0 +1,292 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// Atomically write the state and increment its version.
+///
+/// KEYS: state key, version key
+/// ARGV: serialized state, compare-and-set flag, expected version
+///
+/// Return: 1 if written (0 otherwise) and the version before the write.
+const SET_SCRIPT: &str = r#"
+local version = tonumber(redis.call('GET', KEYS[2]) or '0')
+if ARGV[2] == '1' and version ~= tonumber(ARGV[3]) then
+    return {0, version}
+end
+redis.call('SET', KEYS[1], ARGV[1])
+redis.call('INCR', KEYS[2])
+return {1, version}
+"#;
+
+// Back-off when (re)connecting to Redis: retry up to RETRY_NUMBER times,
+// waiting a random time up to RETRY_FACTOR * RETRY_EXPONENT_BASE^n ms.
+const RETRY_EXPONENT_BASE: u64 = 2;
+const RETRY_FACTOR: u64 = 100;
+const RETRY_NUMBER: usize = 3;
+
+/// State provider saving the state of the functions in a Redis server,
+/// so that it can be retrieved by any node connected to the same server.
+///
+/// The following keys are written:
+/// - state:UUID, where UUID is the state identifier, with the serialized state
+/// - state:version:UUID, with the number of times the state has been written
+///
+/// The version is used to detect concurrent writers of the same state: a
+/// write is reported if the state has been written by another node after it
+/// was last read or written by this node and, with compare-and-set, rejected.
+///
+/// The connection is established at the first operation, so that the node
+/// can start even if the Redis server is not reachable yet, and it is
+/// re-established after a connection failure. Concurrent operations are
+/// multiplexed on the same connection.
+pub struct RedisStateProvider {
+    client: redis::Client,
+    // Locked only to connect to the server, if not connected yet.
+    connection: tokio::sync::Mutex<Option<redis::aio::ConnectionManager>>,
+    compare_and_set: bool,
+    set_script: redis::Script,
+    // Version of every state last read or written by this node.
+    versions: std::sync::Mutex<std::collections::HashMap<uuid::Uuid, u64>>,
+}
+
+impl RedisStateProvider {
+    pub fn new(redis_url: &str, compare_and_set: bool) -> anyhow::Result<Self> {
+        log::info!(
+            "creating Redis state provider at URL {} ({})",
+            redis_url,
+            if compare_and_set { "compare-and-set" } else { "last writer wins" }
+        );
+        Ok(Self {
+            client: redis::Client::open(redis_url)?,
+            connection: tokio::sync::Mutex::new(None),
+            compare_and_set,
+            set_script: redis::Script::new(SET_SCRIPT),
+            versions: std::sync::Mutex::new(std::collections::HashMap::new()),
+        })
+    }
+
+    fn state_key(state_id: &uuid::Uuid) -> String {
+        format!("state:{}", state_id)
+    }
+
+    fn version_key(state_id: &uuid::Uuid) -> String {
+        format!("state:version:{}", state_id)
+    }
+
+    /// Return a clone of the connection to the Redis server, connecting if
+    /// needed, which can be used without blocking the other operations.
+    async fn connection(&self) -> redis::RedisResult<redis::aio::ConnectionManager> {
+        let mut lck = self.connection.lock().await;
+        if let Some(connection) = lck.as_ref() {
+            return Ok(connection.clone());
+        }
+        let connection = self
+            .client
+            .get_connection_manager_with_backoff(RETRY_EXPONENT_BASE, RETRY_FACTOR, RETRY_NUMBER)
+            .await?;
+        log::info!("connected to Redis at {}", self.client.get_connection_info().addr);
+        *lck = Some(connection.clone());
+        Ok(connection)
+    }
+}
+
+/// Query to the Redis server.
+enum Query<'a> {
+    Cmd(redis::Cmd),
+    Script(redis::ScriptInvocation<'a>),
+}
+
+impl Query<'_> {
+    /// Run the query, which is retried once if the connection was lost,
+    /// since in this case the connection manager reconnects to the server.
+    async fn run<T: redis::FromRedisValue>(&self, connection: &mut redis::aio::ConnectionManager) -> redis::RedisResult<T> {
+        match self.run_once(connection).await {
+            Err(err) if err.is_io_error() || err.is_connection_dropped() => {
+                log::warn!("connection to Redis lost, retrying: {}", err);
+                self.run_once(connection).await
+            }
+            res => res,
+        }
+    }
+
+    async fn run_once<T: redis::FromRedisValue>(&self, connection: &mut redis::aio::ConnectionManager) -> redis::RedisResult<T> {
+        match self {
+            Query::Cmd(cmd) => cmd.query_async(connection).await,
+            Query::Script(invocation) => invocation.invoke_async(connection).await,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl super::StateProvider for RedisStateProvider {
+    async fn get(&self, state_id: uuid::Uuid) -> anyhow::Result<Option<String>> {
+        let mut connection = self.connection().await?;
+        let mut cmd = redis::cmd("MGET");
+        cmd.arg(Self::state_key(&state_id)).arg(Self::version_key(&state_id));
+        let (serialized_state, version) = Query::Cmd(cmd).run::<(Option<String>, Option<u64>)>(&mut connection).await?;
+        self.versions.lock().unwrap().insert(state_id, version.unwrap_or_default());
+        Ok(serialized_state)
+    }
+
+    async fn set(&self, state_id: uuid::Uuid, serialized_state: String) -> anyhow::Result<()> {
+        let mut connection = self.connection().await?;
+        let expected = self.versions.lock().unwrap().get(&state_id).copied().unwrap_or_default();
+        let mut invocation = self.set_script.prepare_invoke();
+        invocation
+            .key(Self::state_key(&state_id))
+            .key(Self::version_key(&state_id))
+            .arg(serialized_state)
+            .arg(if self.compare_and_set { "1" } else { "0" })
+            .arg(expected);
+        let (written, version) = Query::Script(invocation).run::<(u8, u64)>(&mut connection).await?;
+        if written == 1 {
+            if version != expected {
+                log::warn!(
+                    "state {} overwritten after being written by another node (version {}, expected {})",
+                    state_id,
+                    version,
+                    expected
+                );
+            }
+            self.versions.lock().unwrap().insert(state_id, version + 1);
+            Ok(())
+        } else {
+            // The version is not updated, so that this node cannot write
+            // the state again until it reads the current one.
+            anyhow::bail!(
+                "state written by another node in the meanwhile (version {}, expected {})",
+                version,
+                expected
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state_management::StateProvider;
+
+    type StandInData = std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, String>>>;
+
+    /// Start a stand-in of the Redis server on `port`, which supports the
+    /// commands used by the provider, with SET_SCRIPT emulated.
+    async fn redis_stand_in(port: u16, data: StandInData) -> tokio::task::JoinHandle<()> {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+        // The script is lost when the server restarts.
+        let script_loaded = std::sync::atomic::AtomicBool::new(false);
+        crate::resources::test_utils::redis_stand_in(listener, move |args| {
+            let bulk = |val: Option<&String>| crate::resources::test_utils::resp_bulk(val.map(|x| x.as_str()));
+            match args[0].to_uppercase().as_str() {
+                "MGET" => {
+                    let data = data.lock().unwrap();
+                    format!(
+                        "*{}\r\n{}",
+                        args.len() - 1,
+                        args[1..].iter().map(|key| bulk(data.get(key))).collect::<String>()
+                    )
+                }
+                "SCRIPT" => {
+                    script_loaded.store(true, std::sync::atomic::Ordering::SeqCst);
+                    bulk(Some(&"0".repeat(40)))
+                }
+                "EVALSHA" if !script_loaded.load(std::sync::atomic::Ordering::SeqCst) => "-NOSCRIPT No matching script.\r\n".to_string(),
+                "EVALSHA" => {
+                    // Keys: state, version; args: state, compare-and-set, expected version.
+                    let mut data = data.lock().unwrap();
+                    let version: u64 = data.get(&args[4]).map(|x| x.parse().unwrap()).unwrap_or_default();
+                    if args[6] == "1" && version.to_string() != args[7] {
+                        format!("*2\r\n:0\r\n:{}\r\n", version)
+                    } else {
+                        data.insert(args[3].clone(), args[5].clone());
+                        data.insert(args[4].clone(), (version + 1).to_string());
+                        format!("*2\r\n:1\r\n:{}\r\n", version)
+                    }
+                }
+                _ => "+OK\r\n".to_string(),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_redis_state_provider_reconnect() {
+        let port = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+        let redis_url = format!("redis://127.0.0.1:{}", port);
+        let data = StandInData::default();
+        let state_id = uuid::Uuid::new_v4();
+
+        // The server is not reachable yet: the failures are reported.
+        let provider = RedisStateProvider::new(&redis_url, true).unwrap();
+        assert!(provider.get(state_id).await.is_err());
+        assert!(provider.set(state_id, "state-1".to_string()).await.is_err());
+
+        let stand_in = redis_stand_in(port, data.clone()).await;
+        assert_eq!(None, provider.get(state_id).await.unwrap());
+        provider.set(state_id, "state-1".to_string()).await.unwrap();
+        assert_eq!(
+            Some(&"state-1".to_string()),
+            data.lock().unwrap().get(&RedisStateProvider::state_key(&state_id))
+        );
+
+        // Restart the server: the provider reconnects.
+        stand_in.abort();
+        let _ = stand_in.await;
+        let stand_in = redis_stand_in(port, data.clone()).await;
+        provider.set(state_id, "state-2".to_string()).await.unwrap();
+        assert_eq!(Some("state-2".to_string()), provider.get(state_id).await.unwrap());
+
+        // A concurrent write with compare-and-set is reported as a failure.
+        let other_provider = RedisStateProvider::new(&redis_url, true).unwrap();
+        assert_eq!(Some("state-2".to_string()), other_provider.get(state_id).await.unwrap());
+        other_provider.set(state_id, "state-3".to_string()).await.unwrap();
+        assert!(provider.set(state_id, "state-4".to_string()).await.is_err());
+        assert_eq!(Some("state-3".to_string()), provider.get(state_id).await.unwrap());
+
+        stand_in.abort();
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_redis_state_provider() {
+        let redis_url = "redis://127.0.0.1:6379";
+        let state_id = uuid::Uuid::new_v4();
+
+        let provider1 = RedisStateProvider::new(redis_url, false).unwrap();
+        let provider2 = RedisStateProvider::new(redis_url, false).unwrap();
+
+        assert_eq!(None, provider1.get(state_id).await.unwrap());
+        provider1.set(state_id, "state-1".to_string()).await.unwrap();
+        assert_eq!(Some("state-1".to_string()), provider2.get(state_id).await.unwrap());
+        provider2.set(state_id, "state-2".to_string()).await.unwrap();
+
+        // last writer wins
+        provider1.set(state_id, "state-3".to_string()).await.unwrap();
+        assert_eq!(Some("state-3".to_string()), provider2.get(state_id).await.unwrap());
+        assert_eq!(Some(&3), provider2.versions.lock().unwrap().get(&state_id));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_redis_state_provider_compare_and_set() {
+        let redis_url = "redis://127.0.0.1:6379";
+        let state_id = uuid::Uuid::new_v4();
+
+        let provider1 = RedisStateProvider::new(redis_url, true).unwrap();
+        let provider2 = RedisStateProvider::new(redis_url, true).unwrap();
+
+        assert_eq!(None, provider1.get(state_id).await.unwrap());
+        provider1.set(state_id, "state-1".to_string()).await.unwrap();
+        assert_eq!(Some("state-1".to_string()), provider2.get(state_id).await.unwrap());
+        provider2.set(state_id, "state-2".to_string()).await.unwrap();
+
+        // provider1 has not read the state written by provider2
+        assert!(provider1.set(state_id, "state-3".to_string()).await.is_err());
+        assert_eq!(Some("state-2".to_string()), provider2.get(state_id).await.unwrap());
+        assert!(provider1.set(state_id, "state-3".to_string()).await.is_err());
+        assert_eq!(Some("state-2".to_string()), provider2.get(state_id).await.unwrap());
+
+        // after reading it, it can write again
+        assert_eq!(Some("state-2".to_string()), provider1.get(state_id).await.unwrap());
+        provider1.set(state_id, "state-3".to_string()).await.unwrap();
+        assert_eq!(Some("state-3".to_string()), provider2.get(state_id).await.unwrap());
+    }
+}
@@ -42,29 +42,40 @@ pub struct WASMFunctionInstance {
     edgefunctione_handle_stop: wasmtime::TypedFunc<(), ()>,
     memory: wasmtime::Memory,
     store: wasmtime::Store<super::guest_api_binding::GuestAPI>,
+    limits: crate::base_runtime::limits::FunctionInstanceLimits,
+    // Keeps the module in the cache until the instance is dropped.
+    _module: super::module_cache::ModuleLease,
 }
 
 #[async_trait::async_trait]
 impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
+    type Context = std::sync::Arc<super::module_cache::ModuleCache>;
+
     async fn instantiate(
+        module_cache: &Self::Context,
         _instance_id: &edgeless_api::function_instance::InstanceId,
         _runtime_configuration: std::collections::HashMap<String, String>,
         guest_api_host: &mut Option<crate::base_runtime::guest_api::GuestAPIHost>,
         code: &[u8],
+        limits: &crate::base_runtime::limits::FunctionInstanceLimits,
     ) -> Result<Box<Self>, crate::base_runtime::FunctionInstanceError> {
-        let mut config = wasmtime::Config::new();
-        config.async_support(true);
-        let engine = wasmtime::Engine::new(&config).map_err(|_err| crate::base_runtime::FunctionInstanceError::InternalError)?;
-        let module = wasmtime::Module::from_binary(&engine, code)
+        // The engine is shared and the module is compiled only once for
+        // all the instances of the same function class.
+        let module = module_cache
+            .acquire(code, limits)
             .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("instantiate failed: {}", e)))?;
-        let mut linker = wasmtime::Linker::new(&engine);
+        let engine = module.module().engine();
+        let mut linker = wasmtime::Linker::new(engine);
 
         let mut store: wasmtime::Store<super::guest_api_binding::GuestAPI> = wasmtime::Store::new(
-            &engine,
+            engine,
             super::guest_api_binding::GuestAPI {
                 host: guest_api_host.take().expect("the impossible happened: no GuestAPIHost"),
+                limiter: crate::base_runtime::limits::MemoryLimiter::new(limits.max_memory),
             },
         );
+        store.limiter(|state| &mut state.limiter);
+        Self::reset_limits(&mut store, limits)?;
 
         linker
             .func_wrap4_async(
@@ -153,11 +164,14 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
                 Box::new(super::guest_api_binding::sync(store, state_ptr, state_len))
             })
             .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+        linker
+            .func_wrap("env", "time_ms_asm", super::guest_api_binding::time_ms)
+            .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
 
         let instance = linker
-            .instantiate_async(&mut store, &module)
+            .instantiate_async(&mut store, module.module())
             .await
-            .expect("could not instantiate async linker");
+            .map_err(|e| Self::error(&store, "instantiate failed: ", e))?;
 
         Ok(Box::new(Self {
             edgeless_mem_alloc: instance
@@ -185,10 +199,14 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
                 .get_memory(&mut store, "memory")
                 .ok_or_else(|| (crate::base_runtime::FunctionInstanceError::BadCode("memory not available".to_string())))?,
             store,
+            limits: limits.clone(),
+            _module: module,
         }))
     }
 
     async fn init(&mut self, init_payload: Option<&str>, serialized_state: Option<&str>) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+        Self::reset_limits(&mut self.store, &self.limits)?;
+
         let (init_payload_ptr, init_payload_len) = match init_payload {
             Some(payload) => {
                 let len = payload.len();
@@ -199,7 +217,7 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
                     payload.as_bytes(),
                 )
                 .await
-                .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("init failed: {}", e)))?;
+                .map_err(|e| Self::error(&self.store, "init failed: ", e))?;
                 (ptr, len as i32)
             }
             None => (0i32, 0i32),
@@ -210,7 +228,7 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
                 let len = state.len();
                 let ptr = super::helpers::copy_to_vm(&mut self.store.as_context_mut(), &self.memory, &self.edgeless_mem_alloc, state.as_bytes())
                     .await
-                    .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("init failed: {}", e)))?;
+                    .map_err(|e| Self::error(&self.store, "init failed: ", e))?;
                 (ptr, len as i32)
             }
             None => (0i32, 0i32),
@@ -223,7 +241,10 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
                     (init_payload_ptr, init_payload_len, serialized_state_ptr, serialized_state_len),
                 )
                 .await
-                .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+                .map_err(|e| match Self::limit_exceeded(&self.store, &e) {
+                    Some(limit) => crate::base_runtime::FunctionInstanceError::LimitExceeded(limit),
+                    None => crate::base_runtime::FunctionInstanceError::InternalError,
+                })?;
             Ok(())
         };
 
@@ -245,12 +266,14 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
     }
 
     async fn cast(&mut self, src: &edgeless_api::function_instance::InstanceId, msg: &str) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+        Self::reset_limits(&mut self.store, &self.limits)?;
+
         // Depending on the Function, we might employ a basic arena/bump allocator that we must reset at the end of a transaction.
         // This might be a noop if the function defines a working version of `edgeless_mem_free`.
         self.edgeless_mem_clear
             .call_async(&mut self.store, ())
             .await
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: mem_clear {}", e)))?;
+            .map_err(|e| Self::error(&self.store, "cast failed: mem_clear ", e))?;
 
         let component_id_ptr = super::helpers::copy_to_vm(
             &mut self.store.as_context_mut(),
@@ -259,7 +282,7 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
             src.function_id.as_bytes(),
         )
         .await
-        .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: copy_to_vm1 {}", e)))?;
+        .map_err(|e| Self::error(&self.store, "cast failed: copy_to_vm1 ", e))?;
         let node_id_ptr = super::helpers::copy_to_vm(
             &mut self.store.as_context_mut(),
             &self.memory,
@@ -267,18 +290,18 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
             src.node_id.as_bytes(),
         )
         .await
-        .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: copy_to_vm2 {}", e)))?;
+        .map_err(|e| Self::error(&self.store, "cast failed: copy_to_vm2 ", e))?;
 
         let payload_len = msg.as_bytes().len();
         let payload_ptr = super::helpers::copy_to_vm(&mut self.store.as_context_mut(), &self.memory, &self.edgeless_mem_alloc, msg.as_bytes())
             .await
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: copy_to_vm3 {}", e)))?;
+            .map_err(|e| Self::error(&self.store, "cast failed: copy_to_vm3 ", e))?;
 
         let ret = {
             self.edgefunctione_handle_cast
                 .call_async(&mut self.store, (node_id_ptr, component_id_ptr, payload_ptr, payload_len as i32))
                 .await
-                .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: call_async {}", e)))?;
+                .map_err(|e| Self::error(&self.store, "cast failed: call_async ", e))?;
             Ok(())
         };
 
@@ -304,10 +327,12 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
         src: &edgeless_api::function_instance::InstanceId,
         msg: &str,
     ) -> Result<edgeless_dataplane::core::CallRet, crate::base_runtime::FunctionInstanceError> {
+        Self::reset_limits(&mut self.store, &self.limits)?;
+
         self.edgeless_mem_clear
             .call_async(&mut self.store, ())
             .await
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, "call failed: ", e))?;
 
         let component_id_ptr = super::helpers::copy_to_vm(
             &mut self.store.as_context_mut(),
@@ -316,7 +341,7 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
             src.function_id.as_bytes(),
         )
         .await
-        .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+        .map_err(|e| Self::error(&self.store, "call failed: ", e))?;
 
         let node_id_ptr = super::helpers::copy_to_vm(
             &mut self.store.as_context_mut(),
@@ -325,24 +350,24 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
             src.node_id.as_bytes(),
         )
         .await
-        .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+        .map_err(|e| Self::error(&self.store, "call failed: ", e))?;
 
         let payload_len = msg.as_bytes().len();
         let payload_ptr = super::helpers::copy_to_vm(&mut self.store.as_context_mut(), &self.memory, &self.edgeless_mem_alloc, msg.as_bytes())
             .await
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, "call failed: ", e))?;
 
         let out_ptr_ptr = self
             .edgeless_mem_alloc
             .call_async(&mut self.store, 4)
             .await
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, "call failed: ", e))?;
 
         let out_len_ptr = self
             .edgeless_mem_alloc
             .call_async(&mut self.store, 4)
             .await
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, "call failed: ", e))?;
 
         let callret_type = self
             .edgefunctione_handle_call
@@ -351,7 +376,7 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
                 (node_id_ptr, component_id_ptr, payload_ptr, payload_len as i32, out_ptr_ptr, out_len_ptr),
             )
             .await
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, "call failed: ", e))?;
 
         let ret = match callret_type {
             0 => Ok(edgeless_dataplane::core::CallRet::NoReply),
@@ -407,13 +432,59 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
     }
 
     async fn stop(&mut self) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+        Self::reset_limits(&mut self.store, &self.limits)?;
+
         self.edgeless_mem_clear
             .call_async(&mut self.store, ())
             .await
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("stop failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, "stop failed: ", e))?;
         self.edgefunctione_handle_stop
             .call_async(&mut self.store, ())
             .await
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("stop failed: {}", e)))
+            .map_err(|e| Self::error(&self.store, "stop failed: ", e))
+    }
+}
+
+impl WASMFunctionInstance {
+    /// Reset the limits before the next invocation, i.e., the fuel, the
+    /// epoch deadline and the memory limit exceeded flag. Fuel and epoch
+    /// are only enabled in the engine if the corresponding limit is set.
+    fn reset_limits(
+        store: &mut wasmtime::Store<super::guest_api_binding::GuestAPI>,
+        limits: &crate::base_runtime::limits::FunctionInstanceLimits,
+    ) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+        if let Some(max_fuel) = limits.max_fuel {
+            store
+                .set_fuel(max_fuel)
+                .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+        }
+        if let Some(max_invocation_time) = limits.max_invocation_time {
+            store.set_epoch_deadline(super::module_cache::epoch_deadline(max_invocation_time));
+        }
+        store.data_mut().limiter.reset();
+        Ok(())
+    }
+
+    /// Return the limit exceeded, if any, that caused the given error.
+    fn limit_exceeded(store: &wasmtime::Store<super::guest_api_binding::GuestAPI>, err: &wasmtime::Error) -> Option<String> {
+        if store.data().limiter.exceeded() {
+            return Some("memory limit exceeded".to_string());
+        }
+        match err.downcast_ref::<wasmtime::Trap>() {
+            Some(wasmtime::Trap::OutOfFuel) => Some("fuel limit exceeded".to_string()),
+            Some(wasmtime::Trap::Interrupt) => Some("invocation time limit exceeded".to_string()),
+            _ => None,
+        }
+    }
+
+    fn error(
+        store: &wasmtime::Store<super::guest_api_binding::GuestAPI>,
+        context: &str,
+        err: wasmtime::Error,
+    ) -> crate::base_runtime::FunctionInstanceError {
+        match Self::limit_exceeded(store, &err) {
+            Some(limit) => crate::base_runtime::FunctionInstanceError::LimitExceeded(limit),
+            None => crate::base_runtime::FunctionInstanceError::BadCode(format!("{}{}", context, err)),
+        }
     }
 }
@@ -8,6 +8,17 @@ use wasmtime::AsContextMut;
 /// Binds the WASM component's imports to the function's GuestAPIHost.
 pub struct GuestAPI {
     pub host: crate::base_runtime::guest_api::GuestAPIHost,
+    pub limiter: crate::base_runtime::limits::MemoryLimiter,
+}
+
+impl wasmtime::ResourceLimiter for crate::base_runtime::limits::MemoryLimiter {
+    fn memory_growing(&mut self, _current: usize, desired: usize, _maximum: Option<usize>) -> wasmtime::Result<bool> {
+        Ok(self.allow(desired))
+    }
+
+    fn table_growing(&mut self, _current: u32, _desired: u32, _maximum: Option<u32>) -> wasmtime::Result<bool> {
+        Ok(true)
+    }
 }
 
 pub async fn telemetry_log(
@@ -199,6 +210,10 @@ pub async fn slf(mut caller: wasmtime::Caller<'_, GuestAPI>, out_node_id_ptr: i3
     Ok(())
 }
 
+pub fn time_ms(caller: wasmtime::Caller<'_, GuestAPI>) -> i64 {
+    caller.data().host.time_ms()
+}
+
 pub(crate) fn get_memory(caller: &mut wasmtime::Caller<'_, super::guest_api_binding::GuestAPI>) -> wasmtime::Result<wasmtime::Memory> {
     caller
         .get_export("memory")
@@ -10,6 +10,9 @@ pub mod function_instance;
 /// Bridge between the guest_api_host and the interface defined in the wit binding
 pub mod guest_api_binding;
 
+/// Engines and cache of the compiled WASM modules.
+pub mod module_cache;
+
 pub mod runtime;
 
 mod helpers;
@@ -0,0 +1,337 @@
+// SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// Cache of the compiled WASM modules, keyed by the SHA-256 hash of the
+/// function code and by the resource limits enforced, together with the
+/// engines used to compile them, which must also be used to instantiate
+/// the modules.
+///
+/// Fuel consumption and epoch interruption slow down the execution of the
+/// functions, hence they are enabled in the engine only for the functions
+/// that have the corresponding limits.
+///
+/// The modules used by at least one function instance are always kept in
+/// memory, while at most `capacity` unused modules are kept, the least
+/// recently used being evicted first. If a directory is specified, then the
+/// modules are also serialized to disk, so that they survive an eviction or
+/// a node restart.
+pub struct ModuleCache {
+    engines: std::sync::Mutex<std::collections::HashMap<EngineFlavor, CachedEngine>>,
+    modules: std::sync::Mutex<CachedModules>,
+    // Locks of the modules being loaded or compiled, held until they are in
+    // memory, so that the same module is never compiled twice concurrently.
+    pending: std::sync::Mutex<std::collections::HashMap<String, std::sync::Arc<std::sync::Mutex<()>>>>,
+    cache_dir: Option<std::path::PathBuf>,
+    capacity: usize,
+}
+
+/// Features of an engine needed to enforce the limits of a function instance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct EngineFlavor {
+    fuel: bool,
+    epoch: bool,
+}
+
+impl EngineFlavor {
+    fn new(limits: &crate::base_runtime::limits::FunctionInstanceLimits) -> Self {
+        Self {
+            fuel: limits.max_fuel.is_some(),
+            epoch: limits.max_invocation_time.is_some(),
+        }
+    }
+
+    /// Suffix of the cache keys of the modules compiled with this flavor.
+    fn suffix(&self) -> &'static str {
+        match (self.fuel, self.epoch) {
+            (false, false) => "",
+            (true, false) => "-fuel",
+            (false, true) => "-epoch",
+            (true, true) => "-fuel-epoch",
+        }
+    }
+}
+
+struct CachedEngine {
+    engine: wasmtime::Engine,
+    // Only for the engines with epoch interruption.
+    _ticker: Option<EpochTicker>,
+}
+
+/// Thread incrementing the epoch of an engine every `EPOCH_TICK`, which is
+/// stopped when dropped.
+struct EpochTicker {
+    stop: Option<std::sync::mpsc::Sender<()>>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl EpochTicker {
+    fn new(engine: wasmtime::Engine) -> Self {
+        let (stop, stopped) = std::sync::mpsc::channel::<()>();
+        let thread = std::thread::Builder::new()
+            .name("wasmtime-epoch".to_string())
+            .spawn(move || {
+                // Returns an error only when the sender has been dropped.
+                while let Err(std::sync::mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(EPOCH_TICK) {
+                    engine.increment_epoch();
+                }
+            })
+            .expect("could not spawn the wasmtime epoch thread");
+        Self {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Default maximum number of unused modules kept in memory.
+pub const DEFAULT_CAPACITY: usize = 16;
+
+#[derive(Default)]
+struct CachedModules {
+    entries: std::collections::HashMap<String, CachedModule>,
+    // Incremented every time a module is released.
+    clock: u64,
+}
+
+struct CachedModule {
+    module: wasmtime::Module,
+    // Number of function instances using the module.
+    users: usize,
+    // Value of the clock when the module was last released.
+    last_used: u64,
+}
+
+/// Module acquired from a `ModuleCache` by a function instance, which is
+/// released when dropped.
+pub struct ModuleLease {
+    cache: std::sync::Arc<ModuleCache>,
+    key: String,
+    module: wasmtime::Module,
+}
+
+impl ModuleLease {
+    pub fn module(&self) -> &wasmtime::Module {
+        &self.module
+    }
+}
+
+impl Drop for ModuleLease {
+    fn drop(&mut self) {
+        self.cache.release(&self.key);
+    }
+}
+
+/// Interval at which the epoch of the engine is incremented, which is the
+/// granularity of the invocation time limit of the function instances.
+pub const EPOCH_TICK: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// Return the epoch deadline, in ticks, corresponding to the given
+/// maximum invocation time.
+pub fn epoch_deadline(max_invocation_time: std::time::Duration) -> u64 {
+    // The current tick may be about to expire: add one more.
+    (max_invocation_time.as_millis().div_ceil(EPOCH_TICK.as_millis()) as u64).saturating_add(1)
+}
+
+impl ModuleCache {
+    pub fn new(cache_dir: Option<std::path::PathBuf>, capacity: usize) -> Self {
+        let cache_dir = match cache_dir {
+            Some(cache_dir) => match std::fs::create_dir_all(&cache_dir) {
+                Ok(_) => {
+                    log::info!("persisting compiled WASM modules to {}", cache_dir.display());
+                    Some(cache_dir)
+                }
+                Err(err) => {
+                    log::warn!("could not create the WASM module cache directory {}: {}", cache_dir.display(), err);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Self {
+            engines: std::sync::Mutex::new(std::collections::HashMap::new()),
+            modules: std::sync::Mutex::new(CachedModules::default()),
+            pending: std::sync::Mutex::new(std::collections::HashMap::new()),
+            cache_dir,
+            capacity,
+        }
+    }
+
+    /// Return the engine of the given flavor, which is created if needed.
+    fn engine(&self, flavor: EngineFlavor) -> anyhow::Result<wasmtime::Engine> {
+        let mut engines = self.engines.lock().unwrap();
+        if let Some(cached) = engines.get(&flavor) {
+            return Ok(cached.engine.clone());
+        }
+
+        let mut config = wasmtime::Config::new();
+        config.async_support(true);
+        config.consume_fuel(flavor.fuel);
+        config.epoch_interruption(flavor.epoch);
+        let engine = wasmtime::Engine::new(&config)?;
+        let ticker = match flavor.epoch {
+            true => Some(EpochTicker::new(engine.clone())),
+            false => None,
+        };
+        engines.insert(
+            flavor,
+            CachedEngine {
+                engine: engine.clone(),
+                _ticker: ticker,
+            },
+        );
+        Ok(engine)
+    }
+
+    /// Number of modules in memory.
+    pub fn len(&self) -> usize {
+        self.modules.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Acquire the module compiled from the given code with an engine that
+    /// can enforce the given limits. The module is looked up in memory first,
+    /// then on disk, and it is compiled only if not found.
+    pub fn acquire(
+        self: &std::sync::Arc<Self>,
+        code: &[u8],
+        limits: &crate::base_runtime::limits::FunctionInstanceLimits,
+    ) -> anyhow::Result<ModuleLease> {
+        let flavor = EngineFlavor::new(limits);
+        let key = Self::key(code, flavor);
+        if let Some(lease) = self.lease(&key) {
+            return Ok(lease);
+        }
+
+        // Only one function instance loads or compiles the module, while the
+        // others with the same code wait for it and then find it in memory.
+        let pending = self.pending.lock().unwrap().entry(key.clone()).or_default().clone();
+        let _guard = pending.lock().unwrap();
+        if let Some(lease) = self.lease(&key) {
+            return Ok(lease);
+        }
+
+        let res = self.engine(flavor).and_then(|engine| match self.load(&engine, &key) {
+            Some(module) => Ok(module),
+            None => {
+                let start = std::time::Instant::now();
+                let module = wasmtime::Module::from_binary(&engine, code)?;
+                log::info!("WASM module {} compiled in {} ms", key, start.elapsed().as_millis());
+                self.save(&key, &module);
+                Ok(module)
+            }
+        });
+        if let Ok(module) = &res {
+            self.modules.lock().unwrap().entries.insert(
+                key.clone(),
+                CachedModule {
+                    module: module.clone(),
+                    users: 1,
+                    last_used: 0,
+                },
+            );
+        }
+        self.pending.lock().unwrap().remove(&key);
+
+        Ok(ModuleLease {
+            cache: self.clone(),
+            key,
+            module: res?,
+        })
+    }
+
+    /// Acquire a module already in memory, if any.
+    fn lease(self: &std::sync::Arc<Self>, key: &str) -> Option<ModuleLease> {
+        let mut modules = self.modules.lock().unwrap();
+        let entry = modules.entries.get_mut(key)?;
+        log::debug!("WASM module {} found in memory", key);
+        entry.users += 1;
+        Some(ModuleLease {
+            cache: self.clone(),
+            key: key.to_string(),
+            module: entry.module.clone(),
+        })
+    }
+
+    /// Release a module acquired before, then evict the least recently
+    /// used modules not in use until at most `capacity` are left.
+    fn release(&self, key: &str) {
+        let mut modules = self.modules.lock().unwrap();
+        modules.clock += 1;
+        let clock = modules.clock;
+        if let Some(entry) = modules.entries.get_mut(key) {
+            entry.users = entry.users.saturating_sub(1);
+            entry.last_used = clock;
+        }
+
+        while modules.entries.values().filter(|entry| entry.users == 0).count() > self.capacity {
+            let evicted = modules
+                .entries
+                .iter()
+                .filter(|(_, entry)| entry.users == 0)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+                .unwrap();
+            log::debug!("WASM module {} evicted from memory", evicted);
+            modules.entries.remove(&evicted);
+        }
+    }
+
+    fn key(code: &[u8], flavor: EngineFlavor) -> String {
+        use sha2::Digest;
+        let hash: String = sha2::Sha256::digest(code).iter().map(|x| format!("{:02x}", x)).collect();
+        format!("{}{}", hash, flavor.suffix())
+    }
+
+    fn path(&self, key: &str) -> Option<std::path::PathBuf> {
+        self.cache_dir.as_ref().map(|cache_dir| cache_dir.join(format!("{}.cwasm", key)))
+    }
+
+    fn load(&self, engine: &wasmtime::Engine, key: &str) -> Option<wasmtime::Module> {
+        let path = self.path(key)?;
+        if !path.exists() {
+            return None;
+        }
+        // SAFETY: the files in the cache directory are only written by
+        // save(), and wasmtime rejects the modules that have been serialized
+        // with an incompatible engine configuration or version.
+        match unsafe { wasmtime::Module::deserialize_file(engine, &path) } {
+            Ok(module) => {
+                log::debug!("WASM module {} loaded from {}", key, path.display());
+                Some(module)
+            }
+            Err(err) => {
+                log::warn!("could not load the WASM module {} from {}: {}", key, path.display(), err);
+                None
+            }
+        }
+    }
+
+    fn save(&self, key: &str, module: &wasmtime::Module) {
+        if let Some(path) = self.path(key) {
+            // Write to a temporary file first, so that a crash while saving
+            // never leaves a truncated module behind.
+            let tmp_path = path.with_extension("cwasm.tmp");
+            let res = module.serialize().and_then(|bytes| {
+                std::fs::write(&tmp_path, bytes)?;
+                std::fs::rename(&tmp_path, &path)?;
+                Ok(())
+            });
+            if let Err(err) = res {
+                log::warn!("could not save the WASM module {} to {}: {}", key, path.display(), err);
+            }
+        }
+    }
+}
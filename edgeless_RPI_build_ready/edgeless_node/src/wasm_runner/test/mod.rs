@@ -60,8 +60,9 @@ impl crate::state_management::StateHandleAPI for MockStateHandle {
         self.output_mocks.lock().await.get(&self.state_id).cloned()
     }
 
-    async fn set(&mut self, serialized_state: String) {
+    async fn set(&mut self, serialized_state: String) -> anyhow::Result<()> {
         self.state_set_sender.send((self.state_id, serialized_state)).await.unwrap();
+        Ok(())
     }
 }
 
@@ -69,6 +70,10 @@ fn mock_runtime() -> std::sync::Arc<tokio::sync::Mutex<Box<dyn crate::base_runti
     std::sync::Arc::new(tokio::sync::Mutex::new(Box::new(super::runtime::WasmRuntime::new())))
 }
 
+fn mock_module_cache() -> std::sync::Arc<super::module_cache::ModuleCache> {
+    std::sync::Arc::new(super::module_cache::ModuleCache::new(None, super::module_cache::DEFAULT_CAPACITY))
+}
+
 #[tokio::test]
 async fn basic_lifecycle() {
     let node_id = uuid::Uuid::new_v4();
@@ -90,6 +95,7 @@ async fn basic_lifecycle() {
         state_manager,
         telemetry_handle,
         mock_runtime(),
+        mock_module_cache(),
     );
 
     tokio::spawn(async move { rt_task.run().await });
@@ -230,6 +236,7 @@ async fn messaging_test_setup() -> (
         state_manager,
         telemetry_handle,
         mock_runtime(),
+        mock_module_cache(),
     );
 
     tokio::spawn(async move { rt_task.run().await });
@@ -543,6 +550,7 @@ async fn state_management() {
         mock_state_manager,
         telemetry_handle,
         mock_runtime(),
+        mock_module_cache(),
     );
 
     tokio::spawn(async move { rt_task.run().await });
@@ -632,3 +640,232 @@ async fn state_management() {
     assert!(telemetry_mock_receiver.try_recv().is_ok());
     assert!(telemetry_mock_receiver.try_recv().is_err());
 }
+
+#[test]
+fn module_cache() {
+    let code = include_bytes!("../../../../functions/messaging_test/messaging_test.wasm");
+    let limits = crate::base_runtime::limits::FunctionInstanceLimits::default();
+    let cache_dir = std::env::temp_dir().join(format!("edgeless_module_cache_{}", uuid::Uuid::new_v4()));
+
+    let cache = std::sync::Arc::new(super::module_cache::ModuleCache::new(Some(cache_dir.clone()), 1));
+    assert!(cache.is_empty());
+    let module = cache.acquire(code, &limits);
+    assert!(module.is_ok());
+    assert_eq!(1, cache.len());
+    assert_eq!(1, std::fs::read_dir(&cache_dir).unwrap().count());
+
+    // Same code: the module is not compiled again.
+    assert!(cache.acquire(code, &limits).is_ok());
+    assert_eq!(1, cache.len());
+
+    // A new cache loads the module from disk.
+    let cache = std::sync::Arc::new(super::module_cache::ModuleCache::new(Some(cache_dir.clone()), 0));
+    let module = cache.acquire(code, &limits);
+    assert!(module.is_ok());
+    assert_eq!(1, cache.len());
+
+    // Invalid code is not cached.
+    assert!(cache.acquire(&[0, 1, 2, 3], &limits).is_err());
+    assert_eq!(1, cache.len());
+
+    // The module is evicted from memory, but not from disk, when no more in use.
+    drop(module);
+    assert!(cache.is_empty());
+    assert_eq!(1, std::fs::read_dir(&cache_dir).unwrap().count());
+
+    let _ = std::fs::remove_dir_all(cache_dir);
+}
+
+#[test]
+fn module_cache_concurrent_acquire() {
+    let code = include_bytes!("../../../../functions/messaging_test/messaging_test.wasm");
+    let cache = std::sync::Arc::new(super::module_cache::ModuleCache::new(None, 0));
+
+    // Function instances with the same code start at the same time.
+    let barrier = std::sync::Arc::new(std::sync::Barrier::new(4));
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let cache = cache.clone();
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                cache
+                    .acquire(code, &crate::base_runtime::limits::FunctionInstanceLimits::default())
+                    .unwrap()
+            })
+        })
+        .collect();
+    let mut modules: Vec<_> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+    assert_eq!(1, cache.len());
+
+    // The module is in use until the last instance stops.
+    while modules.len() > 1 {
+        modules.pop();
+        assert_eq!(1, cache.len());
+    }
+    modules.pop();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn module_cache_eviction() {
+    let code = include_bytes!("../../../../functions/messaging_test/messaging_test.wasm");
+    let other_code = include_bytes!("../../../../functions/state_test/state_test.wasm");
+    let limits = crate::base_runtime::limits::FunctionInstanceLimits::default();
+
+    let cache = std::sync::Arc::new(super::module_cache::ModuleCache::new(None, 1));
+    let module = cache.acquire(code, &limits).unwrap();
+    let other_module = cache.acquire(other_code, &limits).unwrap();
+    assert_eq!(2, cache.len());
+
+    // At most one unused module is kept in memory, the least recently used
+    // being evicted first.
+    drop(module);
+    assert_eq!(2, cache.len());
+    drop(other_module);
+    assert_eq!(1, cache.len());
+    let _module = cache.acquire(other_code, &limits).unwrap();
+    assert_eq!(1, cache.len());
+
+    // The same code is compiled again with the features needed to enforce
+    // the limits.
+    let _limited_module = cache
+        .acquire(
+            other_code,
+            &crate::base_runtime::limits::FunctionInstanceLimits {
+                max_fuel: Some(1000),
+                max_memory: None,
+                max_invocation_time: Some(std::time::Duration::from_secs(1)),
+            },
+        )
+        .unwrap();
+    assert_eq!(2, cache.len());
+}
+
+#[tokio::test]
+async fn fuel_limit_exceeded() {
+    let node_id = uuid::Uuid::new_v4();
+    let instance_id = edgeless_api::function_instance::InstanceId::new(node_id);
+
+    let state_manager = Box::new(crate::state_management::StateManager::new().await);
+    let dataplane_provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, "http://127.0.0.1:7002".to_string(), None).await;
+
+    let (telemetry_mock_sender, telemetry_mock_receiver) = std::sync::mpsc::channel::<(
+        edgeless_telemetry::telemetry_events::TelemetryEvent,
+        std::collections::BTreeMap<String, String>,
+    )>();
+    let telemetry_handle = Box::new(MockTelemetryHandle {
+        sender: telemetry_mock_sender,
+    });
+
+    let (mut client, mut rt_task) = crate::base_runtime::runtime::create::<super::function_instance::WASMFunctionInstance>(
+        dataplane_provider,
+        state_manager,
+        telemetry_handle,
+        mock_runtime(),
+        mock_module_cache(),
+    );
+
+    tokio::spawn(async move { rt_task.run().await });
+
+    let spawn_req = edgeless_api::function_instance::SpawnFunctionRequest {
+        instance_id: Some(instance_id),
+        code: edgeless_api::function_instance::FunctionClassSpecification {
+            function_class_id: "EXAMPLE_1".to_string(),
+            function_class_type: "RUST_WASM".to_string(),
+            function_class_version: "0.1".to_string(),
+            function_class_code: include_bytes!("../../../../functions/messaging_test/messaging_test.wasm").to_vec(),
+            function_class_outputs: vec![],
+        },
+        annotations: std::collections::HashMap::from([("max_fuel".to_string(), "1".to_string())]),
+        state_specification: edgeless_api::function_instance::StateSpecification {
+            state_id: instance_id.function_id,
+            state_policy: edgeless_api::function_instance::StatePolicy::Transient,
+        },
+    };
+
+    let _res = client.start(spawn_req).await;
+
+    // The function runs out of fuel during init.
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    let exit_status = telemetry_mock_receiver.try_iter().find_map(|(event, _tags)| match event {
+        edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionExit(status) => Some(status),
+        _ => None,
+    });
+    assert_eq!(Some(edgeless_telemetry::telemetry_events::FunctionExitStatus::InternalError), exit_status);
+}
+
+#[test]
+fn epoch_deadline() {
+    assert_eq!(2, super::module_cache::epoch_deadline(std::time::Duration::from_millis(1)));
+    assert_eq!(2, super::module_cache::epoch_deadline(super::module_cache::EPOCH_TICK));
+    assert_eq!(101, super::module_cache::epoch_deadline(std::time::Duration::from_secs(1)));
+}
+
+#[tokio::test]
+async fn restart_on_failure() {
+    let node_id = uuid::Uuid::new_v4();
+    let instance_id = edgeless_api::function_instance::InstanceId::new(node_id);
+
+    let state_manager = Box::new(crate::state_management::StateManager::new().await);
+    let dataplane_provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, "http://127.0.0.1:7002".to_string(), None).await;
+
+    let (telemetry_mock_sender, telemetry_mock_receiver) = std::sync::mpsc::channel::<(
+        edgeless_telemetry::telemetry_events::TelemetryEvent,
+        std::collections::BTreeMap<String, String>,
+    )>();
+    let telemetry_handle = Box::new(MockTelemetryHandle {
+        sender: telemetry_mock_sender,
+    });
+
+    let (mut client, mut rt_task) = crate::base_runtime::runtime::create::<super::function_instance::WASMFunctionInstance>(
+        dataplane_provider,
+        state_manager,
+        telemetry_handle,
+        mock_runtime(),
+        mock_module_cache(),
+    );
+
+    tokio::spawn(async move { rt_task.run().await });
+
+    // The function always fails during init because it runs out of fuel.
+    let spawn_req = edgeless_api::function_instance::SpawnFunctionRequest {
+        instance_id: Some(instance_id),
+        code: edgeless_api::function_instance::FunctionClassSpecification {
+            function_class_id: "EXAMPLE_1".to_string(),
+            function_class_type: "RUST_WASM".to_string(),
+            function_class_version: "0.1".to_string(),
+            function_class_code: include_bytes!("../../../../functions/messaging_test/messaging_test.wasm").to_vec(),
+            function_class_outputs: vec![],
+        },
+        annotations: std::collections::HashMap::from([
+            ("max_fuel".to_string(), "1".to_string()),
+            ("restart_policy".to_string(), "on-failure:2".to_string()),
+            ("restart_backoff_ms".to_string(), "10".to_string()),
+        ]),
+        state_specification: edgeless_api::function_instance::StateSpecification {
+            state_id: instance_id.function_id,
+            state_policy: edgeless_api::function_instance::StatePolicy::Transient,
+        },
+    };
+
+    let _res = client.start(spawn_req).await;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+
+    let mut exits = 0;
+    let mut restarts = vec![];
+    for (event, _tags) in telemetry_mock_receiver.try_iter() {
+        match event {
+            edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionExit(status) => {
+                assert_eq!(edgeless_telemetry::telemetry_events::FunctionExitStatus::InternalError, status);
+                exits += 1;
+            }
+            edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionRestart(restart) => restarts.push(restart),
+            _ => {}
+        }
+    }
+    assert_eq!(3, exits);
+    assert_eq!(vec![1, 2], restarts);
+}
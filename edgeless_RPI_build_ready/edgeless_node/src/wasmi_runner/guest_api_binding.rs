@@ -6,6 +6,17 @@ use wasmi::AsContextMut;
 use super::helpers::*;
 pub struct GuestAPI {
     pub host: crate::base_runtime::guest_api::GuestAPIHost,
+    pub limiter: crate::base_runtime::limits::MemoryLimiter,
+}
+
+impl wasmi::ResourceLimiter for crate::base_runtime::limits::MemoryLimiter {
+    fn memory_growing(&mut self, _current: usize, desired: usize, _maximum: Option<usize>) -> Result<bool, wasmi::errors::MemoryError> {
+        Ok(self.allow(desired))
+    }
+
+    fn table_growing(&mut self, _current: u32, _desired: u32, _maximum: Option<u32>) -> Result<bool, wasmi::errors::TableError> {
+        Ok(true)
+    }
 }
 
 pub fn telemetry_log(
@@ -178,6 +189,10 @@ pub fn slf(mut caller: wasmi::Caller<'_, GuestAPI>, out_node_id_ptr: i32, out_co
     Ok(())
 }
 
+pub fn time_ms(caller: wasmi::Caller<'_, GuestAPI>) -> i64 {
+    caller.data().host.time_ms()
+}
+
 pub(crate) fn get_memory(caller: &mut wasmi::Caller<'_, super::guest_api_binding::GuestAPI>) -> Result<wasmi::Memory, wasmi::core::Trap> {
     caller
         .get_export("memory")
@@ -49,26 +49,44 @@ pub struct WASMIFunctionInstance {
     edgefunctione_handle_stop: wasmi::TypedFunc<(), ()>,
     memory: wasmi::Memory,
     store: wasmi::Store<guest_api_binding::GuestAPI>,
+    limits: crate::base_runtime::limits::FunctionInstanceLimits,
+    // Total fuel added to the store since its creation.
+    fuel_added: u64,
 }
 
 #[async_trait::async_trait]
 impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
+    type Context = ();
+
     async fn instantiate(
+        _context: &Self::Context,
         _instance_id: &edgeless_api::function_instance::InstanceId,
         _runtime_configuration: std::collections::HashMap<String, String>,
         guest_api_host: &mut Option<crate::base_runtime::guest_api::GuestAPIHost>,
         code: &[u8],
+        limits: &crate::base_runtime::limits::FunctionInstanceLimits,
     ) -> Result<Box<Self>, crate::base_runtime::FunctionInstanceError> {
-        let _comfig = wasmi::Config::default();
+        // wasmi cannot interrupt a running function.
+        if limits.max_invocation_time.is_some() {
+            return Err(crate::base_runtime::FunctionInstanceError::BadCode(
+                "the invocation time limit is not supported by the wasmi run-time".to_string(),
+            ));
+        }
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(limits.max_fuel.is_some());
 
-        let engine = wasmi::Engine::default();
+        let engine = wasmi::Engine::new(&config);
         let module = wasmi::Module::new(&engine, &code[..]).map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
         let mut store = wasmi::Store::new(
             &engine,
             guest_api_binding::GuestAPI {
                 host: guest_api_host.take().expect("the impossible happened: no GuestAPIHost"),
+                limiter: crate::base_runtime::limits::MemoryLimiter::new(limits.max_memory),
             },
         );
+        store.limiter(|state| &mut state.limiter);
+        let mut fuel_added = 0;
+        Self::reset_fuel(&mut store, &mut fuel_added, limits)?;
         let mut linker = wasmi::Linker::<guest_api_binding::GuestAPI>::new(&engine);
 
         linker
@@ -99,12 +117,15 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
         linker
             .define("env", "sync_asm", wasmi::Func::wrap(&mut store, guest_api_binding::sync))
             .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+        linker
+            .define("env", "time_ms_asm", wasmi::Func::wrap(&mut store, guest_api_binding::time_ms))
+            .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
 
         let instance = linker
             .instantiate(&mut store, &module)
             .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?
             .start(&mut store)
-            .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+            .map_err(|e| Self::error(&store, fuel_added, "instantiate failed: ", e))?;
 
         Ok(Box::new(Self {
             edgeless_mem_alloc: instance
@@ -132,10 +153,14 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
                 .get_memory(&mut store, "memory")
                 .ok_or_else(|| (crate::base_runtime::FunctionInstanceError::BadCode(format!("memory not available"))))?,
             store: store,
+            limits: limits.clone(),
+            fuel_added,
         }))
     }
 
     async fn init(&mut self, init_payload: Option<&str>, serialized_state: Option<&str>) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+        Self::reset_fuel(&mut self.store, &mut self.fuel_added, &self.limits)?;
+
         let (init_payload_ptr, init_payload_len) = match init_payload {
             Some(payload) => {
                 let len = payload.len();
@@ -145,7 +170,7 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
                     &self.edgeless_mem_alloc,
                     payload.as_bytes(),
                 )
-                .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("init failed: {}", e)))?;
+                .map_err(|e| Self::error(&self.store, self.fuel_added, "init failed: ", e))?;
                 (ptr, len as i32)
             }
             None => (0i32, 0i32),
@@ -155,7 +180,7 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
             Some(state) => {
                 let len = state.len();
                 let ptr = helpers::copy_to_vm(&mut self.store.as_context_mut(), &self.memory, &self.edgeless_mem_alloc, state.as_bytes())
-                    .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("init failed: {}", e)))?;
+                    .map_err(|e| Self::error(&self.store, self.fuel_added, "init failed: ", e))?;
                 (ptr, len as i32)
             }
             None => (0i32, 0i32),
@@ -167,7 +192,13 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
                     &mut self.store,
                     (init_payload_ptr, init_payload_len, serialized_state_ptr, serialized_state_len),
                 )
-                .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+                .map_err(|e| match Self::limit_exceeded(&self.store, self.fuel_added) {
+                    Some(limit) => crate::base_runtime::FunctionInstanceError::LimitExceeded(limit),
+                    None => {
+                        log::debug!("init failed: {}", e);
+                        crate::base_runtime::FunctionInstanceError::InternalError
+                    }
+                })?;
             Ok(())
         });
 
@@ -187,11 +218,13 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
     }
 
     async fn cast(&mut self, src: &edgeless_api::function_instance::InstanceId, msg: &str) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+        Self::reset_fuel(&mut self.store, &mut self.fuel_added, &self.limits)?;
+
         // Depending on the Function, we might employ a basic arena/bump allocator that we must reset at the end of a transaction.
         // This might be a noop if the function defines a working version of `edgeless_mem_free`.
         self.edgeless_mem_clear
             .call(&mut self.store, ())
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, self.fuel_added, "cast failed: ", e))?;
 
         let component_id_ptr = helpers::copy_to_vm(
             &mut self.store.as_context_mut(),
@@ -199,23 +232,23 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
             &self.edgeless_mem_alloc,
             src.function_id.as_bytes(),
         )
-        .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: {}", e)))?;
+        .map_err(|e| Self::error(&self.store, self.fuel_added, "cast failed: ", e))?;
         let node_id_ptr = helpers::copy_to_vm(
             &mut self.store.as_context_mut(),
             &self.memory,
             &self.edgeless_mem_alloc,
             src.node_id.as_bytes(),
         )
-        .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: {}", e)))?;
+        .map_err(|e| Self::error(&self.store, self.fuel_added, "cast failed: ", e))?;
 
         let payload_len = msg.as_bytes().len();
         let payload_ptr = helpers::copy_to_vm(&mut self.store.as_context_mut(), &self.memory, &self.edgeless_mem_alloc, msg.as_bytes())
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, self.fuel_added, "cast failed: ", e))?;
 
         let ret = tokio::task::block_in_place(|| {
             self.edgefunctione_handle_cast
                 .call(&mut self.store, (node_id_ptr, component_id_ptr, payload_ptr, payload_len as i32))
-                .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("cast failed: {}", e)))?;
+                .map_err(|e| Self::error(&self.store, self.fuel_added, "cast failed: ", e))?;
             Ok(())
         });
 
@@ -238,9 +271,11 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
         src: &edgeless_api::function_instance::InstanceId,
         msg: &str,
     ) -> Result<edgeless_dataplane::core::CallRet, crate::base_runtime::FunctionInstanceError> {
+        Self::reset_fuel(&mut self.store, &mut self.fuel_added, &self.limits)?;
+
         self.edgeless_mem_clear
             .call(&mut self.store, ())
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, self.fuel_added, "call failed: ", e))?;
 
         let component_id_ptr = helpers::copy_to_vm(
             &mut self.store.as_context_mut(),
@@ -248,7 +283,7 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
             &self.edgeless_mem_alloc,
             src.function_id.as_bytes(),
         )
-        .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+        .map_err(|e| Self::error(&self.store, self.fuel_added, "call failed: ", e))?;
 
         let node_id_ptr = helpers::copy_to_vm(
             &mut self.store.as_context_mut(),
@@ -256,21 +291,21 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
             &self.edgeless_mem_alloc,
             src.node_id.as_bytes(),
         )
-        .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+        .map_err(|e| Self::error(&self.store, self.fuel_added, "call failed: ", e))?;
 
         let payload_len = msg.as_bytes().len();
         let payload_ptr = helpers::copy_to_vm(&mut self.store.as_context_mut(), &self.memory, &self.edgeless_mem_alloc, msg.as_bytes())
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, self.fuel_added, "call failed: ", e))?;
 
         let out_ptr_ptr = self
             .edgeless_mem_alloc
             .call(&mut self.store, 4)
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, self.fuel_added, "call failed: ", e))?;
 
         let out_len_ptr = self
             .edgeless_mem_alloc
             .call(&mut self.store, 4)
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, self.fuel_added, "call failed: ", e))?;
 
         let callret_type = tokio::task::block_in_place(|| {
             self.edgefunctione_handle_call
@@ -278,7 +313,7 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
                     &mut self.store,
                     (node_id_ptr, component_id_ptr, payload_ptr, payload_len as i32, out_ptr_ptr, out_len_ptr),
                 )
-                .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("call failed: {}", e)))
+                .map_err(|e| Self::error(&self.store, self.fuel_added, "call failed: ", e))
         })?;
 
         let ret = match callret_type {
@@ -330,13 +365,60 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
     }
 
     async fn stop(&mut self) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+        Self::reset_fuel(&mut self.store, &mut self.fuel_added, &self.limits)?;
+
         self.edgeless_mem_clear
             .call(&mut self.store, ())
-            .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("stop failed: {}", e)))?;
+            .map_err(|e| Self::error(&self.store, self.fuel_added, "stop failed: ", e))?;
         tokio::task::block_in_place(|| {
             self.edgefunctione_handle_stop
                 .call(&mut self.store, ())
-                .map_err(|e| crate::base_runtime::FunctionInstanceError::BadCode(format!("stop failed: {}", e)))
+                .map_err(|e| Self::error(&self.store, self.fuel_added, "stop failed: ", e))
         })
     }
 }
+
+impl WASMIFunctionInstance {
+    /// Top up the fuel so that the next invocation can consume at most the
+    /// maximum fuel, if limited, and clear the memory limit exceeded flag.
+    fn reset_fuel(
+        store: &mut wasmi::Store<guest_api_binding::GuestAPI>,
+        fuel_added: &mut u64,
+        limits: &crate::base_runtime::limits::FunctionInstanceLimits,
+    ) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+        if let (Some(max_fuel), Some(fuel_consumed)) = (limits.max_fuel, store.fuel_consumed()) {
+            let remaining = fuel_added.saturating_sub(fuel_consumed);
+            if remaining < max_fuel {
+                store
+                    .add_fuel(max_fuel - remaining)
+                    .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+                *fuel_added += max_fuel - remaining;
+            }
+        }
+        store.data_mut().limiter.reset();
+        Ok(())
+    }
+
+    /// Return the limit exceeded, if any.
+    fn limit_exceeded(store: &wasmi::Store<guest_api_binding::GuestAPI>, fuel_added: u64) -> Option<String> {
+        if store.data().limiter.exceeded() {
+            return Some("memory limit exceeded".to_string());
+        }
+        match store.fuel_consumed() {
+            Some(fuel_consumed) if fuel_consumed >= fuel_added => Some("fuel limit exceeded".to_string()),
+            _ => None,
+        }
+    }
+
+    fn error(
+        store: &wasmi::Store<guest_api_binding::GuestAPI>,
+        fuel_added: u64,
+        context: &str,
+        err: impl std::fmt::Display,
+    ) -> crate::base_runtime::FunctionInstanceError {
+        match Self::limit_exceeded(store, fuel_added) {
+            Some(limit) => crate::base_runtime::FunctionInstanceError::LimitExceeded(limit),
+            None => crate::base_runtime::FunctionInstanceError::BadCode(format!("{}{}", context, err)),
+        }
+    }
+}
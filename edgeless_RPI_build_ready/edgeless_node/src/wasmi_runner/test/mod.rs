@@ -59,8 +59,9 @@ impl crate::state_management::StateHandleAPI for MockStateHandle {
         self.output_mocks.lock().await.get(&self.state_id).cloned()
     }
 
-    async fn set(&mut self, serialized_state: String) {
+    async fn set(&mut self, serialized_state: String) -> anyhow::Result<()> {
         self.state_set_sender.send((self.state_id.clone(), serialized_state)).await.unwrap();
+        Ok(())
     }
 }
 
@@ -21,6 +21,7 @@ struct Args {
 #[derive(Debug, clap::Subcommand)]
 enum DumpCommands {
     Performance {},
+    Statistics {},
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -162,6 +163,40 @@ fn main() -> anyhow::Result<()> {
                     }
                 }
             }
+            DumpCommands::Statistics {} => {
+                let statistics = proxy.fetch_latency_statistics();
+                let entries = statistics
+                    .iter()
+                    .flat_map(|(kind, inner_map)| inner_map.iter().map(move |(id, value)| (kind, id, value)))
+                    .sorted_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)))
+                    .collect::<Vec<_>>();
+
+                // The percentiles are the same for all the entries.
+                let mut statistics_content = String::from("type,id,count,mean,min,max");
+                if let Some((_, _, value)) = entries.first() {
+                    for (percentile, _) in &value.percentiles {
+                        statistics_content.push_str(&format!(",p{}", percentile));
+                    }
+                }
+                statistics_content.push('\n');
+                let mut histograms_content = String::from("type,id,le,count\n");
+                for (kind, id, value) in entries {
+                    statistics_content.push_str(&format!("{},{},{},{},{},{}", kind, id, value.count, value.mean, value.min, value.max));
+                    for (_, percentile_value) in &value.percentiles {
+                        statistics_content.push_str(&format!(",{}", percentile_value));
+                    }
+                    statistics_content.push('\n');
+                    for (bound, count) in &value.buckets {
+                        histograms_content.push_str(&format!("{},{},{},{}\n", kind, id, bound, count));
+                    }
+                    histograms_content.push_str(&format!("{},{},+Inf,{}\n", kind, id, value.count));
+                }
+
+                for (filename, content) in [("statistics.csv", statistics_content), ("histograms.csv", histograms_content)] {
+                    println!("saving to {}", filename);
+                    std::fs::write(filename, content).unwrap_or_else(|_| panic!("could not write to file '{}'", filename));
+                }
+            }
         },
     }
 
@@ -54,12 +54,20 @@ impl DeploymentRequirements {
     pub fn from_annotations(annotations: &std::collections::HashMap<String, String>) -> Self {
         let mut max_instances = 0;
         if let Some(val) = annotations.get("max_instances") {
-            max_instances = val.parse::<usize>().unwrap_or_default();
+            match val.parse::<usize>() {
+                Ok(val) => max_instances = val,
+                Err(_) => log::warn!("ignoring invalid value of annotation max_instances: {}", val),
+            }
         }
 
         let mut node_id_match_any = vec![];
         if let Some(val) = annotations.get("node_id_match_any") {
-            node_id_match_any = val.split(",").filter_map(|x| uuid::Uuid::parse_str(x).ok()).collect();
+            for node_id in val.split(",") {
+                match uuid::Uuid::parse_str(node_id) {
+                    Ok(node_id) => node_id_match_any.push(node_id),
+                    Err(_) => log::warn!("ignoring invalid node identifier in annotation node_id_match_any: {}", node_id),
+                }
+            }
         }
 
         let mut label_match_all = vec![];
@@ -23,6 +23,7 @@ impl DomainSubscriber {
         orchestrator_url: String,
         controller_url: String,
         subscription_refresh_interval_sec: u64,
+        inter_domain_latency: std::collections::HashMap<String, u32>,
     ) -> (
         Self,
         std::pin::Pin<Box<dyn Future<Output = ()> + Send>>,
@@ -39,6 +40,7 @@ impl DomainSubscriber {
                 orchestrator_url,
                 controller_url,
                 subscription_refresh_interval_sec,
+                inter_domain_latency,
                 nonce,
                 receiver,
             )
@@ -66,6 +68,7 @@ impl DomainSubscriber {
         orchestrator_url: String,
         controller_url: String,
         subscription_refresh_interval_sec: u64,
+        inter_domain_latency: std::collections::HashMap<String, u32>,
         nonce: u64,
         receiver: futures::channel::mpsc::UnboundedReceiver<DomainSubscriberRequest>,
     ) {
@@ -73,7 +76,10 @@ impl DomainSubscriber {
 
         let mut client: edgeless_api::grpc_impl::outer::domain_register::DomainRegisterAPIClient =
             edgeless_api::grpc_impl::outer::domain_register::DomainRegisterAPIClient::new(controller_url).await;
-        let mut last_caps = edgeless_api::domain_registration::DomainCapabilities::default();
+        let mut last_caps = edgeless_api::domain_registration::DomainCapabilities {
+            inter_domain_latency: inter_domain_latency.clone(),
+            ..Default::default()
+        };
         let mut counter = 0;
         let mut orc_sender = None;
 
@@ -83,6 +89,9 @@ impl DomainSubscriber {
                     log::debug!("Subscriber Update {:?}", new_caps);
                     counter += 1;
                     last_caps = *new_caps;
+                    // The inter-domain latencies are not known by the
+                    // orchestrator, they are taken from the configuration.
+                    last_caps.inter_domain_latency = inter_domain_latency.clone();
                 }
                 DomainSubscriberRequest::RegisterOrcSender(new_orc_sender) => {
                     log::info!("Orchestrator registered with domain subscriber");
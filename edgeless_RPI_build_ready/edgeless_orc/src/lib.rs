@@ -45,6 +45,10 @@ pub struct EdgelessOrcGeneralSettings {
     pub node_register_url: String,
     /// The CoAP URL of the node register.
     pub node_register_coap_url: Option<String>,
+    /// The estimated latency from this domain to other domains, in ms,
+    /// advertised to the controller with the domain capabilities.
+    /// Key: identifier of the remote domain.
+    pub inter_domain_latency: Option<std::collections::HashMap<String, u32>>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -109,6 +113,7 @@ pub async fn edgeless_orc_main(settings: EdgelessOrcSettings) {
         settings.general.orchestrator_url.clone(),
         settings.general.domain_register_url,
         settings.general.subscription_refresh_interval_sec,
+        settings.general.inter_domain_latency.unwrap_or_default(),
     )
     .await;
 
@@ -176,6 +181,8 @@ orchestrator_url_announced = "http://127.0.0.1:7011"
 node_register_url = "http://0.0.0.0:7012"
 node_register_coap_url = "coap://0.0.0.0:7050"
 
+[general.inter_domain_latency]
+
 [baseline]
 orchestration_strategy = "Random"
 
@@ -90,7 +90,9 @@ impl NodeRegister {
                             log::info!("Removing node '{}' because it is stale", stale_node);
                             registered.remove(&stale_node);
 
-                            let _ = orchestrator_sender.send(super::orchestrator::OrchestratorRequest::DelNode(stale_node)).await;
+                            if let Err(err) = orchestrator_sender.send(super::orchestrator::OrchestratorRequest::DelNode(stale_node)).await {
+                                log::error!("NodeRegister could not remove stale node '{}' from the orchestrator: {}", stale_node, err);
+                            }
                         }
 
                         let _ = reply_sender.send(());
@@ -116,21 +118,37 @@ impl NodeRegister {
                                     existing_node.refresh_deadline = request.refresh_deadline;
                                     false
                                 } else {
-                                    let _ = orchestrator_sender.send(crate::orchestrator::OrchestratorRequest::DelNode(request.node_id)).await;
+                                    if let Err(err) = orchestrator_sender.send(crate::orchestrator::OrchestratorRequest::DelNode(request.node_id)).await {
+                                        log::error!("NodeRegister could not remove node '{}' from the orchestrator: {}", request.node_id, err);
+                                    }
                                     true
                                 }
                             }
                         };
                         if add_node {
-                            if let Ok(client_desc) = crate::client_desc::ClientDesc::from(&request).await {
-                                let _ = orchestrator_sender.send(crate::orchestrator::OrchestratorRequest::AddNode(
-                                    request.node_id,
-                                    client_desc,
-                                    request.resource_providers
-                                )).await;
+                            match crate::client_desc::ClientDesc::from(&request).await {
+                                Ok(client_desc) => {
+                                    if let Err(err) = orchestrator_sender.send(crate::orchestrator::OrchestratorRequest::AddNode(
+                                        request.node_id,
+                                        client_desc,
+                                        request.resource_providers
+                                    )).await {
+                                        log::error!("NodeRegister could not add node '{}' to the orchestrator: {}", request.node_id, err);
+                                    }
+                                }
+                                Err(err) => log::error!("NodeRegister could not create the clients of node '{}': {}", request.node_id, err),
                             }
                         }
 
+                        // Let the orchestrator keep track of the status of
+                        // the function instances.
+                        if let Err(err) = orchestrator_sender.send(crate::orchestrator::OrchestratorRequest::PerformanceSamples(
+                            request.node_id,
+                            request.performance_samples.clone(),
+                        )).await {
+                            log::error!("NodeRegister could not send the performance samples of node '{}' to the orchestrator: {}", request.node_id, err);
+                        }
+
                         // Push the dynamic data to the proxy.
                         let mut proxy = proxy.lock().await;
                         proxy.push_node_health(&request.node_id, request.health_status);
@@ -52,6 +52,20 @@ pub enum OrchestratorRequest {
         Vec<edgeless_api::node_registration::ResourceProviderSpecification>,
     ),
     DelNode(uuid::Uuid),
+    PerformanceSamples(uuid::Uuid, edgeless_api::node_registration::NodePerformanceSamples),
+    InstanceStatus(
+        Vec<edgeless_api::function_instance::DomainManagedInstanceId>,
+        tokio::sync::oneshot::Sender<
+            std::collections::HashMap<edgeless_api::function_instance::DomainManagedInstanceId, edgeless_api::instance_status::ComponentStatus>,
+        >,
+    ),
+    InstanceLogs(
+        Vec<edgeless_api::function_instance::DomainManagedInstanceId>,
+        edgeless_api::instance_status::LogPosition,
+        tokio::sync::oneshot::Sender<
+            std::collections::HashMap<edgeless_api::function_instance::DomainManagedInstanceId, Vec<edgeless_api::instance_status::InstanceLogEntry>>,
+        >,
+    ),
     Refresh(
         // Reply Channel
         tokio::sync::oneshot::Sender<()>,
@@ -63,6 +77,7 @@ pub struct OrchestratorClient {
     function_instance_client: Box<dyn edgeless_api::function_instance::FunctionInstanceAPI<edgeless_api::function_instance::DomainManagedInstanceId>>,
     resource_configuration_client:
         Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::DomainManagedInstanceId>>,
+    instance_status_client: Box<dyn edgeless_api::instance_status::InstanceStatusAPI>,
 }
 
 impl edgeless_api::outer::orc::OrchestratorAPI for OrchestratorClient {
@@ -77,6 +92,10 @@ impl edgeless_api::outer::orc::OrchestratorAPI for OrchestratorClient {
     ) -> Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::DomainManagedInstanceId>> {
         self.resource_configuration_client.clone()
     }
+
+    fn instance_status_api(&mut self) -> Box<dyn edgeless_api::instance_status::InstanceStatusAPI> {
+        self.instance_status_client.clone()
+    }
 }
 
 #[derive(Clone)]
@@ -89,6 +108,11 @@ pub struct ResourceConfigurationClient {
     sender: futures::channel::mpsc::UnboundedSender<OrchestratorRequest>,
 }
 
+#[derive(Clone)]
+pub struct InstanceStatusClient {
+    sender: futures::channel::mpsc::UnboundedSender<OrchestratorRequest>,
+}
+
 impl Orchestrator {
     pub async fn new(
         settings: crate::EdgelessOrcBaselineSettings,
@@ -142,6 +166,7 @@ impl Orchestrator {
         Box::new(OrchestratorClient {
             function_instance_client: Box::new(OrchestratorFunctionInstanceOrcClient { sender: self.sender.clone() }),
             resource_configuration_client: Box::new(ResourceConfigurationClient { sender: self.sender.clone() }),
+            instance_status_client: Box::new(InstanceStatusClient { sender: self.sender.clone() }),
         })
     }
 }
@@ -245,3 +270,53 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         }
     }
 }
+
+#[async_trait::async_trait]
+impl edgeless_api::instance_status::InstanceStatusAPI for InstanceStatusClient {
+    async fn status(
+        &mut self,
+        ids: Vec<edgeless_api::function_instance::DomainManagedInstanceId>,
+    ) -> anyhow::Result<
+        std::collections::HashMap<edgeless_api::function_instance::DomainManagedInstanceId, edgeless_api::instance_status::ComponentStatus>,
+    > {
+        log::debug!("InstanceStatusAPI::status() {:?}", ids);
+        let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel();
+        if let Err(err) = self.sender.send(OrchestratorRequest::InstanceStatus(ids, reply_sender)).await {
+            return Err(anyhow::anyhow!(
+                "Orchestrator channel error when retrieving the instance status: {}",
+                err.to_string()
+            ));
+        }
+        match reply_receiver.await {
+            Ok(status) => Ok(status),
+            Err(err) => Err(anyhow::anyhow!(
+                "Orchestrator channel error when retrieving the instance status: {}",
+                err.to_string()
+            )),
+        }
+    }
+
+    async fn logs(
+        &mut self,
+        ids: Vec<edgeless_api::function_instance::DomainManagedInstanceId>,
+        since: edgeless_api::instance_status::LogPosition,
+    ) -> anyhow::Result<
+        std::collections::HashMap<edgeless_api::function_instance::DomainManagedInstanceId, Vec<edgeless_api::instance_status::InstanceLogEntry>>,
+    > {
+        log::debug!("InstanceStatusAPI::logs() {:?} since {:?}", ids, since);
+        let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel();
+        if let Err(err) = self.sender.send(OrchestratorRequest::InstanceLogs(ids, since, reply_sender)).await {
+            return Err(anyhow::anyhow!(
+                "Orchestrator channel error when retrieving the instance logs: {}",
+                err.to_string()
+            ));
+        }
+        match reply_receiver.await {
+            Ok(logs) => Ok(logs),
+            Err(err) => Err(anyhow::anyhow!(
+                "Orchestrator channel error when retrieving the instance logs: {}",
+                err.to_string()
+            )),
+        }
+    }
+}
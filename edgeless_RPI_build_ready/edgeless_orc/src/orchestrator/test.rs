@@ -7,6 +7,7 @@ use crate::affinity_level::AffinityLevel;
 use crate::deployment_requirements::DeploymentRequirements;
 use crate::domain_subscriber::DomainSubscriberRequest;
 use edgeless_api::function_instance::{FunctionClassSpecification, StatePolicy, StateSpecification};
+use edgeless_api::instance_status::{InstanceStatusAPI, LogPosition};
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 
 use super::*;
@@ -406,6 +407,166 @@ async fn test_orc_single_node_function_start_stop() {
     no_function_event(&mut nodes).await;
 }
 
+#[tokio::test]
+async fn test_orc_instance_status() {
+    let (mut fun_client, mut _res_client, mut nodes, _, _, mut orc_sender) = test_setup(1, 0).await;
+    let (node_id, mock_node_receiver) = nodes.iter_mut().next().unwrap();
+    let node_id = *node_id;
+    let mut status_client = InstanceStatusClient { sender: orc_sender.clone() };
+
+    // Start a function.
+    let lid = match fun_client.start(make_spawn_function_request("fc-1")).await.unwrap() {
+        edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    };
+    let pid = match wait_for_function_event(mock_node_receiver).await {
+        MockAgentEvent::StartFunction((new_instance_id, _spawn_req_rcvd)) => new_instance_id.function_id,
+        _ => panic!("wrong event received"),
+    };
+
+    // The function is running, but it has never been invoked.
+    let unknown_lid = uuid::Uuid::new_v4();
+    let status = status_client.status(vec![lid, unknown_lid]).await.unwrap();
+    assert_eq!(2, status.len());
+    assert_eq!(
+        edgeless_api::instance_status::ComponentStatus {
+            state: edgeless_api::instance_status::ComponentState::Running,
+            node_ids: vec![node_id],
+            last_invocation: None,
+            error_count: 0,
+            restart_count: 0,
+        },
+        status[&lid]
+    );
+    assert_eq!(edgeless_api::instance_status::ComponentStatus::failed(), status[&unknown_lid]);
+
+    // The node reports invocations and errors.
+    for _ in 0..2 {
+        let _ = orc_sender
+            .send(OrchestratorRequest::PerformanceSamples(
+                node_id,
+                edgeless_api::node_registration::NodePerformanceSamples {
+                    function_execution_times: std::collections::HashMap::from([(pid, vec![0.1, 0.2])]),
+                    function_errors: std::collections::HashMap::from([(pid, 2)]),
+                    function_restarts: std::collections::HashMap::from([(pid, 1)]),
+                    function_logs: std::collections::HashMap::new(),
+                    function_exits: std::collections::HashMap::from([(pid, 1)]),
+                },
+            ))
+            .await;
+    }
+    let status = status_client.status(vec![lid]).await.unwrap();
+    assert_eq!(edgeless_api::instance_status::ComponentState::Running, status[&lid].state);
+    assert!(status[&lid].last_invocation.is_some());
+    assert_eq!(4, status[&lid].error_count);
+    assert_eq!(2, status[&lid].restart_count);
+
+    // The function instance terminates and it is not restarted by the node.
+    let _ = orc_sender
+        .send(OrchestratorRequest::PerformanceSamples(
+            node_id,
+            edgeless_api::node_registration::NodePerformanceSamples {
+                function_execution_times: std::collections::HashMap::new(),
+                function_errors: std::collections::HashMap::from([(pid, 1)]),
+                function_restarts: std::collections::HashMap::new(),
+                function_logs: std::collections::HashMap::new(),
+                function_exits: std::collections::HashMap::from([(pid, 1)]),
+            },
+        ))
+        .await;
+    let status = status_client.status(vec![lid]).await.unwrap();
+    assert_eq!(edgeless_api::instance_status::ComponentState::Failed, status[&lid].state);
+    assert!(status[&lid].node_ids.is_empty());
+    assert_eq!(5, status[&lid].error_count);
+
+    // The only node disappears: the function cannot be assigned anywhere.
+    let _ = orc_sender.send(OrchestratorRequest::DelNode(node_id)).await;
+    let status = status_client.status(vec![lid]).await.unwrap();
+    assert_eq!(
+        edgeless_api::instance_status::ComponentStatus {
+            state: edgeless_api::instance_status::ComponentState::Migrating,
+            node_ids: vec![],
+            last_invocation: None,
+            error_count: 0,
+            restart_count: 0,
+        },
+        status[&lid]
+    );
+}
+
+#[tokio::test]
+async fn test_orc_instance_logs() {
+    let (mut fun_client, mut _res_client, mut nodes, _, _, mut orc_sender) = test_setup(1, 0).await;
+    let (node_id, mock_node_receiver) = nodes.iter_mut().next().unwrap();
+    let node_id = *node_id;
+    let mut status_client = InstanceStatusClient { sender: orc_sender.clone() };
+
+    // Start a function.
+    let lid = match fun_client.start(make_spawn_function_request("fc-1")).await.unwrap() {
+        edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    };
+    let pid = match wait_for_function_event(mock_node_receiver).await {
+        MockAgentEvent::StartFunction((new_instance_id, _spawn_req_rcvd)) => new_instance_id.function_id,
+        _ => panic!("wrong event received"),
+    };
+
+    // No log entries have been reported yet.
+    let unknown_lid = uuid::Uuid::new_v4();
+    let logs = status_client.logs(vec![lid, unknown_lid], LogPosition::default()).await.unwrap();
+    assert_eq!(2, logs.len());
+    assert!(logs[&lid].is_empty());
+    assert!(logs[&unknown_lid].is_empty());
+
+    // The node reports log entries in two batches.
+    let make_entry = |message: &str| edgeless_api::node_registration::FunctionLogEntry {
+        timestamp: std::time::SystemTime::now(),
+        level: "Info".to_string(),
+        target: "fc-1".to_string(),
+        message: message.to_string(),
+    };
+    for messages in [vec!["a", "b"], vec!["c"]] {
+        let _ = orc_sender
+            .send(OrchestratorRequest::PerformanceSamples(
+                node_id,
+                edgeless_api::node_registration::NodePerformanceSamples {
+                    function_execution_times: std::collections::HashMap::new(),
+                    function_errors: std::collections::HashMap::new(),
+                    function_restarts: std::collections::HashMap::new(),
+                    function_logs: std::collections::HashMap::from([(pid, messages.into_iter().map(make_entry).collect())]),
+                    function_exits: std::collections::HashMap::new(),
+                },
+            ))
+            .await;
+    }
+    let logs = status_client.logs(vec![lid], LogPosition::default()).await.unwrap();
+    assert_eq!(
+        vec!["a", "b", "c"],
+        logs[&lid].iter().map(|x| x.entry.message.as_str()).collect::<Vec<&str>>()
+    );
+    assert!(logs[&lid].iter().all(|x| x.node_id == node_id));
+    assert!(logs[&lid].windows(2).all(|x| x[0].seq < x[1].seq && x[0].epoch == x[1].epoch));
+
+    // Only the entries after the given sequence number are returned.
+    let since = LogPosition::of(&logs[&lid][1]);
+    let logs = status_client.logs(vec![lid], since).await.unwrap();
+    assert_eq!(vec!["c"], logs[&lid].iter().map(|x| x.entry.message.as_str()).collect::<Vec<&str>>());
+
+    // All the entries are returned if the sequence number is of another
+    // epoch, e.g., of the orchestrator before a restart.
+    let other_epoch = LogPosition {
+        epoch: since.epoch.wrapping_add(1),
+        seq: 100,
+    };
+    let logs = status_client.logs(vec![lid], other_epoch).await.unwrap();
+    assert_eq!(3, logs[&lid].len());
+
+    // The log entries are removed with the function.
+    fun_client.stop(lid).await.unwrap();
+    let logs = status_client.logs(vec![lid], LogPosition::default()).await.unwrap();
+    assert!(logs[&lid].is_empty());
+}
+
 #[tokio::test]
 async fn test_orc_multiple_nodes_function_start_stop() {
     let (mut fun_client, mut _res_client, mut nodes, _, _, _orc_sender) = test_setup(3, 0).await;
@@ -26,6 +26,26 @@ impl Pid {
     }
 }
 
+// Maximum number of log entries kept for each function instance:
+// the oldest are dropped first.
+const MAX_INSTANCE_LOG_ENTRIES: usize = 1000;
+
+// Statistics of a function/resource instance, as reported by the node.
+#[derive(Default)]
+struct InstanceStats {
+    // Last time the node reported samples for this instance.
+    last_invocation: Option<std::time::SystemTime>,
+    // Number of errors reported by the node.
+    error_count: u64,
+    // Number of restarts reported by the node.
+    restart_count: u64,
+    // Number of terminations reported by the node: if greater than the
+    // number of restarts, then the instance is not running anymore.
+    exit_count: u64,
+    // Most recent log entries emitted by the instance, in chronological order.
+    logs: std::collections::VecDeque<edgeless_api::instance_status::InstanceLogEntry>,
+}
+
 pub(crate) struct OrchestratorTask {
     receiver: futures::channel::mpsc::UnboundedReceiver<crate::orchestrator::OrchestratorRequest>,
     nodes: std::collections::HashMap<uuid::Uuid, crate::client_desc::ClientDesc>,
@@ -48,6 +68,13 @@ pub(crate) struct OrchestratorTask {
     dependency_graph: std::collections::HashMap<uuid::Uuid, std::collections::HashMap<String, uuid::Uuid>>,
     dependency_graph_changed: bool,
     last_domain_capabilities: edgeless_api::domain_registration::DomainCapabilities,
+    // statistics of the function/resource instances
+    // key: pid
+    instance_stats: std::collections::HashMap<uuid::Uuid, InstanceStats>,
+    // sequence number of the last log entry received from the nodes
+    last_log_seq: u64,
+    // epoch of the sequence numbers of the log entries, drawn at random
+    log_epoch: u64,
 }
 
 impl OrchestratorTask {
@@ -85,6 +112,9 @@ impl OrchestratorTask {
             dependency_graph: std::collections::HashMap::new(),
             dependency_graph_changed: false,
             last_domain_capabilities: edgeless_api::domain_registration::DomainCapabilities::default(),
+            instance_stats: std::collections::HashMap::new(),
+            last_log_seq: 0,
+            log_epoch: rand::random(),
         }
     }
 
@@ -132,6 +162,24 @@ impl OrchestratorTask {
                     self.update_domain().await;
                     self.refresh().await;
                 }
+                crate::orchestrator::OrchestratorRequest::PerformanceSamples(node_id, performance_samples) => {
+                    log::debug!("Orchestrator PerformanceSamples {:?}", node_id);
+                    self.update_instance_stats(node_id, performance_samples);
+                }
+                crate::orchestrator::OrchestratorRequest::InstanceStatus(lids, reply_sender) => {
+                    log::debug!("Orchestrator InstanceStatus {:?}", lids);
+                    let status = lids.into_iter().map(|lid| (lid, self.instance_status(&lid))).collect();
+                    if let Err(err) = reply_sender.send(status) {
+                        log::error!("Orchestrator channel error in INSTANCESTATUS: {:?}", err);
+                    }
+                }
+                crate::orchestrator::OrchestratorRequest::InstanceLogs(lids, since, reply_sender) => {
+                    log::debug!("Orchestrator InstanceLogs {:?} since {:?}", lids, since);
+                    let logs = lids.into_iter().map(|lid| (lid, self.instance_logs(&lid, since))).collect();
+                    if let Err(err) = reply_sender.send(logs) {
+                        log::error!("Orchestrator channel error in INSTANCELOGS: {:?}", err);
+                    }
+                }
                 crate::orchestrator::OrchestratorRequest::Refresh(reply_sender) => {
                     log::debug!("Orchestrator Refresh");
                     self.refresh().await;
@@ -145,6 +193,108 @@ impl OrchestratorTask {
         }
     }
 
+    /// Update the statistics of the function instances with the
+    /// performance samples received from a node.
+    fn update_instance_stats(&mut self, node_id: uuid::Uuid, performance_samples: edgeless_api::node_registration::NodePerformanceSamples) {
+        let now = std::time::SystemTime::now();
+        for (pid, samples) in performance_samples.function_execution_times {
+            if !samples.is_empty() {
+                self.instance_stats.entry(pid).or_default().last_invocation = Some(now);
+            }
+        }
+        for (pid, errors) in performance_samples.function_errors {
+            self.instance_stats.entry(pid).or_default().error_count += errors;
+        }
+        for (pid, restarts) in performance_samples.function_restarts {
+            self.instance_stats.entry(pid).or_default().restart_count += restarts;
+        }
+        for (pid, exits) in performance_samples.function_exits {
+            self.instance_stats.entry(pid).or_default().exit_count += exits;
+        }
+        for (pid, entries) in performance_samples.function_logs {
+            let logs = &mut self.instance_stats.entry(pid).or_default().logs;
+            for entry in entries {
+                if logs.len() == MAX_INSTANCE_LOG_ENTRIES {
+                    logs.pop_front();
+                }
+                self.last_log_seq += 1;
+                logs.push_back(edgeless_api::instance_status::InstanceLogEntry {
+                    seq: self.last_log_seq,
+                    epoch: self.log_epoch,
+                    node_id,
+                    entry,
+                });
+            }
+        }
+    }
+
+    /// Return the log entries of the instances of a function that follow
+    /// `since`, i.e., all of them if `since` is of another epoch, in
+    /// chronological order.
+    /// No entries are returned if the logical identifier is not known.
+    fn instance_logs(
+        &self,
+        lid: &edgeless_api::function_instance::ComponentId,
+        since: edgeless_api::instance_status::LogPosition,
+    ) -> Vec<edgeless_api::instance_status::InstanceLogEntry> {
+        let active_instance = match self.active_instances.get(lid) {
+            Some(active_instance) => active_instance,
+            None => return vec![],
+        };
+
+        let mut logs = vec![];
+        for instance_id in active_instance.instance_ids() {
+            if let Some(stats) = self.instance_stats.get(&instance_id.function_id) {
+                logs.extend(stats.logs.iter().filter(|x| since.precedes(x)).cloned());
+            }
+        }
+        logs.sort_by_key(|x| x.seq);
+        logs
+    }
+
+    /// Return the status of a function/resource:
+    /// - failed, if the logical identifier is not known or all its
+    ///   instances on nodes currently active have terminated, e.g., because
+    ///   of a trap, without being restarted by the node;
+    /// - migrating, if there are no instances on nodes currently active;
+    /// - running, otherwise.
+    fn instance_status(&self, lid: &edgeless_api::function_instance::ComponentId) -> edgeless_api::instance_status::ComponentStatus {
+        let active_instance = match self.active_instances.get(lid) {
+            Some(active_instance) => active_instance,
+            None => return edgeless_api::instance_status::ComponentStatus::failed(),
+        };
+
+        let mut status = edgeless_api::instance_status::ComponentStatus {
+            state: edgeless_api::instance_status::ComponentState::Migrating,
+            node_ids: vec![],
+            last_invocation: None,
+            error_count: 0,
+            restart_count: 0,
+        };
+        let mut terminated = false;
+        for instance_id in active_instance.instance_ids() {
+            if instance_id.is_none() || !self.nodes.contains_key(&instance_id.node_id) {
+                continue;
+            }
+            let stats = self.instance_stats.get(&instance_id.function_id);
+            if let Some(stats) = stats {
+                status.last_invocation = status.last_invocation.max(stats.last_invocation);
+                status.error_count += stats.error_count;
+                status.restart_count += stats.restart_count;
+            }
+            if stats.is_some_and(|stats| stats.exit_count > stats.restart_count) {
+                terminated = true;
+                continue;
+            }
+            status.state = edgeless_api::instance_status::ComponentState::Running;
+            status.node_ids.push(instance_id.node_id);
+        }
+        if terminated && status.state != edgeless_api::instance_status::ComponentState::Running {
+            status.state = edgeless_api::instance_status::ComponentState::Failed;
+        }
+        status
+    }
+
     fn lid_to_pid(&self, lid: &edgeless_api::function_instance::ComponentId) -> Vec<Pid> {
         match self.active_instances.get(lid) {
             Some(active_instance) => match active_instance {
@@ -546,6 +696,7 @@ impl OrchestratorTask {
     ///
     /// * `instance_id` - The function instance to be stopped.
     async fn stop_function(&mut self, instance_id: &edgeless_api::function_instance::InstanceId) {
+        self.instance_stats.remove(&instance_id.function_id);
         match self.nodes.get_mut(&instance_id.node_id) {
             Some(client_desc) => match client_desc.api.function_instance_api().stop(*instance_id).await {
                 Ok(_) => {
@@ -591,6 +742,7 @@ impl OrchestratorTask {
     ///
     /// * `instance_id` - The resource instance to be stopped.
     async fn stop_resource(&mut self, instance_id: &edgeless_api::function_instance::InstanceId) {
+        self.instance_stats.remove(&instance_id.function_id);
         match self.nodes.get_mut(&instance_id.node_id) {
             Some(node_client) => match node_client.api.resource_configuration_api().stop(*instance_id).await {
                 Ok(_) => {
@@ -7,6 +7,20 @@ pub enum Instance {
     Resource(edgeless_api::function_instance::ComponentId),
 }
 
+/// Latency statistics of a workflow or function, as saved by the
+/// metrics-collector resource provider.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize)]
+pub struct LatencyStatistics {
+    pub count: u64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Pairs of (percentile, value).
+    pub percentiles: Vec<(f64, f64)>,
+    /// Pairs of (bucket upper bound, cumulative number of samples).
+    pub buckets: Vec<(f64, u64)>,
+}
+
 #[async_trait::async_trait]
 pub trait Proxy: Sync + Send {
     /// Update the info on the currently actives nodes as given.
@@ -49,6 +63,10 @@ pub trait Proxy: Sync + Send {
     /// Fetch the performance samples.
     fn fetch_performance_samples(&mut self) -> std::collections::HashMap<String, std::collections::HashMap<String, Vec<(f64, f64)>>>;
 
+    /// Fetch the latency statistics of workflows and functions, by type
+    /// (workflow or function) and identifier.
+    fn fetch_latency_statistics(&mut self) -> std::collections::HashMap<String, std::collections::HashMap<String, LatencyStatistics>>;
+
     /// Fetch the mapping between active function instances and nodes.
     fn fetch_function_instances_to_nodes(
         &mut self,
@@ -31,6 +31,9 @@ impl super::proxy::Proxy for ProxyNone {
     fn fetch_performance_samples(&mut self) -> std::collections::HashMap<String, std::collections::HashMap<String, Vec<(f64, f64)>>> {
         std::collections::HashMap::new()
     }
+    fn fetch_latency_statistics(&mut self) -> std::collections::HashMap<String, std::collections::HashMap<String, super::proxy::LatencyStatistics>> {
+        std::collections::HashMap::new()
+    }
     fn fetch_function_instances_to_nodes(
         &mut self,
     ) -> std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::function_instance::NodeId>> {
@@ -504,6 +504,30 @@ impl super::proxy::Proxy for ProxyRedis {
         samples
     }
 
+    fn fetch_latency_statistics(&mut self) -> std::collections::HashMap<String, std::collections::HashMap<String, super::proxy::LatencyStatistics>> {
+        let mut statistics = std::collections::HashMap::new();
+        for kind in ["workflow", "function"] {
+            for stats_key in self.connection.keys::<String, Vec<String>>(format!("{}:*:stats", kind)).unwrap_or(vec![]) {
+                let tokens: Vec<&str> = stats_key.split(':').collect();
+                if tokens.len() != 3 {
+                    continue;
+                }
+                if let Ok(value) = self.connection.get::<&str, String>(&stats_key) {
+                    match serde_json::from_str::<super::proxy::LatencyStatistics>(&value) {
+                        Ok(value) => {
+                            statistics
+                                .entry(kind.to_string())
+                                .or_insert(std::collections::HashMap::new())
+                                .insert(tokens[1].to_string(), value);
+                        }
+                        Err(err) => log::warn!("invalid latency statistics in {}: {}", stats_key, err),
+                    }
+                }
+            }
+        }
+        statistics
+    }
+
     fn fetch_function_instances_to_nodes(
         &mut self,
     ) -> std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::function_instance::NodeId>> {
@@ -733,6 +757,10 @@ mod test {
             &node_id_perf,
             edgeless_api::node_registration::NodePerformanceSamples {
                 function_execution_times: std::collections::HashMap::from([(fid_perf_1, samples_1.clone()), (fid_perf_2, samples_2.clone())]),
+                function_errors: std::collections::HashMap::new(),
+                function_restarts: std::collections::HashMap::new(),
+                function_logs: std::collections::HashMap::new(),
+                function_exits: std::collections::HashMap::new(),
             },
         );
 
@@ -746,6 +774,26 @@ mod test {
         let samples_2_res = entry.get(&fid_perf_2.to_string()).unwrap();
         assert_eq!(samples_1, samples_1_res.iter().map(|x| x.0).collect::<Vec<f64>>());
         assert_eq!(samples_2, samples_2_res.iter().map(|x| x.0).collect::<Vec<f64>>());
+
+        // Latency statistics saved by the metrics-collector.
+        let _ = redis_proxy.connection.set::<&str, &str, String>(
+            "workflow:wf1:stats",
+            r#"{"count":2,"mean":10.0,"min":5.0,"max":15.0,"percentiles":[[50.0,10.0]],"buckets":[[10.0,1],[100.0,2]]}"#,
+        );
+        let _ = redis_proxy.connection.set::<&str, &str, String>("function:f1:stats", "invalid");
+        let statistics = redis_proxy.fetch_latency_statistics();
+        assert_eq!(1, statistics.len());
+        assert_eq!(
+            Some(&crate::proxy::LatencyStatistics {
+                count: 2,
+                mean: 10.0,
+                min: 5.0,
+                max: 15.0,
+                percentiles: vec![(50.0, 10.0)],
+                buckets: vec![(10.0, 1), (100.0, 2)],
+            }),
+            statistics.get("workflow").unwrap().get("wf1")
+        );
     }
 
     #[test]
@@ -49,6 +49,7 @@ mod system_tests {
                     orchestrator_url_announced: "".to_string(),
                     node_register_url: node_register_url.clone(),
                     node_register_coap_url: None,
+                    inter_domain_latency: None,
                 },
                 baseline: edgeless_orc::EdgelessOrcBaselineSettings {
                     orchestration_strategy: edgeless_orc::OrchestrationStrategy::RoundRobin,
@@ -93,20 +94,36 @@ mod system_tests {
                         log_level: None,
                         performance_samples: false,
                     },
-                    wasm_runtime: Some(edgeless_node::EdgelessNodeWasmRuntimeSettings { enabled: true }),
+                    wasm_runtime: Some(edgeless_node::EdgelessNodeWasmRuntimeSettings {
+                        enabled: true,
+                        module_cache_dir: None,
+                        module_cache_capacity: None,
+                    }),
                     container_runtime: None,
                     resources: Some(edgeless_node::EdgelessNodeResourceSettings {
                         http_ingress_url: None,
+                        http_ingress_tls_cert: None,
+                        http_ingress_tls_key: None,
                         http_ingress_provider: None,
+                        http_ingress_outputs: None,
                         http_egress_provider: None,
                         file_log_provider,
                         redis_provider: None,
                         dda_provider: None,
                         ollama_provider: None,
                         kafka_egress_provider: None,
+                        kafka_ingress_provider: None,
+                        mqtt_ingress_provider: None,
+                        mqtt_egress_provider: None,
+                        dataset_replay_provider: None,
+                        timer_provider: None,
+                        tsdb_sink_provider: None,
+                        websocket_url: None,
+                        websocket_provider: None,
                         metrics_collector_provider: None,
                     }),
                     user_node_capabilities: None,
+                    state: None,
                 }));
                 tokio::spawn(task);
                 abort_handles_nodes.insert(node_id, handle);
@@ -116,6 +133,7 @@ mod system_tests {
         let (task, abort_handle_controller) = futures::future::abortable(edgeless_con::edgeless_con_main(edgeless_con::EdgelessConSettings {
             controller_url: controller_url.clone(),
             domain_register_url: domain_register_url.clone(),
+            persistence: None,
         }));
         tokio::spawn(task);
 
@@ -3,8 +3,25 @@
 
 use std::str::FromStr;
 
+/// Maximum number of log entries kept for each function between two
+/// consecutive calls to `get_metrics()`: the oldest are dropped first.
+pub const MAX_LOG_ENTRIES: usize = 1000;
+
 pub struct Metrics {
     pub function_execution_times: std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<f64>>,
+    // Number of function instances that exited with an error and of
+    // error log entries emitted by the functions.
+    pub function_errors: std::collections::HashMap<edgeless_api::function_instance::ComponentId, u64>,
+    // Number of function instances restarted by the node.
+    pub function_restarts: std::collections::HashMap<edgeless_api::function_instance::ComponentId, u64>,
+    // Number of function instances terminated, normally or with an error.
+    pub function_exits: std::collections::HashMap<edgeless_api::function_instance::ComponentId, u64>,
+    // Log entries emitted by the functions, in chronological order.
+    // At most MAX_LOG_ENTRIES are kept for each function.
+    pub function_logs: std::collections::HashMap<
+        edgeless_api::function_instance::ComponentId,
+        std::collections::VecDeque<edgeless_api::node_registration::FunctionLogEntry>,
+    >,
 }
 
 /// Non thread-safe data structure holding performance-related per-node metrics.
@@ -23,6 +40,10 @@ impl PerformanceTarget {
         Self {
             metrics: Metrics {
                 function_execution_times: std::collections::HashMap::new(),
+                function_errors: std::collections::HashMap::new(),
+                function_restarts: std::collections::HashMap::new(),
+                function_logs: std::collections::HashMap::new(),
+                function_exits: std::collections::HashMap::new(),
             },
         }
     }
@@ -31,6 +52,10 @@ impl PerformanceTarget {
     pub fn get_metrics(&mut self) -> Metrics {
         Metrics {
             function_execution_times: std::mem::take(&mut self.metrics.function_execution_times),
+            function_errors: std::mem::take(&mut self.metrics.function_errors),
+            function_restarts: std::mem::take(&mut self.metrics.function_restarts),
+            function_logs: std::mem::take(&mut self.metrics.function_logs),
+            function_exits: std::mem::take(&mut self.metrics.function_exits),
         }
     }
 }
@@ -41,15 +66,46 @@ impl crate::telemetry_events::EventProcessor for PerformanceTarget {
         event: &crate::telemetry_events::TelemetryEvent,
         event_tags: &std::collections::BTreeMap<String, String>,
     ) -> crate::telemetry_events::TelemetryProcessingResult {
+        let function_id = event_tags
+            .get("FUNCTION_ID")
+            .and_then(|function_id| uuid::Uuid::from_str(function_id).ok());
         match event {
             crate::telemetry_events::TelemetryEvent::FunctionInvocationCompleted(lat) => {
-                if let Some(function_id) = event_tags.get("FUNCTION_ID") {
-                    if let Ok(function_id) = uuid::Uuid::from_str(function_id) {
-                        let res = self.metrics.function_execution_times.entry(function_id).or_default();
-                        res.push(lat.as_secs_f64());
+                if let Some(function_id) = function_id {
+                    let res = self.metrics.function_execution_times.entry(function_id).or_default();
+                    res.push(lat.as_secs_f64());
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::FunctionExit(status) => {
+                if let Some(function_id) = function_id {
+                    *self.metrics.function_exits.entry(function_id).or_default() += 1;
+                    if *status != crate::telemetry_events::FunctionExitStatus::Ok {
+                        *self.metrics.function_errors.entry(function_id).or_default() += 1;
                     }
                 }
             }
+            crate::telemetry_events::TelemetryEvent::FunctionLogEntry(level, target, message) => {
+                if let Some(function_id) = function_id {
+                    if *level == crate::telemetry_events::TelemetryLogLevel::Error {
+                        *self.metrics.function_errors.entry(function_id).or_default() += 1;
+                    }
+                    let entries = self.metrics.function_logs.entry(function_id).or_default();
+                    if entries.len() == MAX_LOG_ENTRIES {
+                        entries.pop_front();
+                    }
+                    entries.push_back(edgeless_api::node_registration::FunctionLogEntry {
+                        timestamp: std::time::SystemTime::now(),
+                        level: crate::telemetry_events::telemetry_to_api(level.clone()),
+                        target: target.clone(),
+                        message: message.clone(),
+                    });
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::FunctionRestart(_) => {
+                if let Some(function_id) = function_id {
+                    *self.metrics.function_restarts.entry(function_id).or_default() += 1;
+                }
+            }
             _ => {
                 return crate::telemetry_events::TelemetryProcessingResult::PASSED;
             }
@@ -137,4 +193,84 @@ mod tests {
 
         assert!(target.get_metrics().function_execution_times.is_empty());
     }
+
+    #[test]
+    fn test_performance_target_function_errors() {
+        let mut target = PerformanceTarget::new();
+        let fid = uuid::Uuid::new_v4();
+        let event_tags = std::collections::BTreeMap::from([("FUNCTION_ID".to_string(), fid.to_string())]);
+
+        assert!(target.get_metrics().function_errors.is_empty());
+
+        for level in [
+            crate::telemetry_events::TelemetryLogLevel::Error,
+            crate::telemetry_events::TelemetryLogLevel::Warn,
+            crate::telemetry_events::TelemetryLogLevel::Error,
+        ] {
+            target.handle(
+                &crate::telemetry_events::TelemetryEvent::FunctionLogEntry(level, "target".to_string(), "msg".to_string()),
+                &event_tags,
+            );
+        }
+        target.handle(
+            &crate::telemetry_events::TelemetryEvent::FunctionExit(crate::telemetry_events::FunctionExitStatus::Ok),
+            &event_tags,
+        );
+        target.handle(
+            &crate::telemetry_events::TelemetryEvent::FunctionExit(crate::telemetry_events::FunctionExitStatus::CodeError),
+            &event_tags,
+        );
+
+        target.handle(&crate::telemetry_events::TelemetryEvent::FunctionRestart(1), &event_tags);
+
+        let metrics = target.get_metrics();
+        assert_eq!(Some(&3), metrics.function_errors.get(&fid));
+        assert_eq!(Some(&1), metrics.function_restarts.get(&fid));
+        assert_eq!(Some(&2), metrics.function_exits.get(&fid));
+        assert!(metrics.function_execution_times.is_empty());
+
+        let metrics = target.get_metrics();
+        assert!(metrics.function_errors.is_empty());
+        assert!(metrics.function_restarts.is_empty());
+        assert!(metrics.function_exits.is_empty());
+    }
+
+    #[test]
+    fn test_performance_target_function_logs() {
+        let mut target = PerformanceTarget::new();
+        let fid = uuid::Uuid::new_v4();
+        let event_tags = std::collections::BTreeMap::from([("FUNCTION_ID".to_string(), fid.to_string())]);
+
+        assert!(target.get_metrics().function_logs.is_empty());
+
+        for i in 0..(MAX_LOG_ENTRIES + 10) {
+            target.handle(
+                &crate::telemetry_events::TelemetryEvent::FunctionLogEntry(
+                    crate::telemetry_events::TelemetryLogLevel::Info,
+                    "target".to_string(),
+                    format!("msg-{}", i),
+                ),
+                &event_tags,
+            );
+        }
+        target.handle(
+            &crate::telemetry_events::TelemetryEvent::FunctionLogEntry(
+                crate::telemetry_events::TelemetryLogLevel::Info,
+                "target".to_string(),
+                "no function".to_string(),
+            ),
+            &std::collections::BTreeMap::new(),
+        );
+
+        let metrics = target.get_metrics();
+        assert_eq!(1, metrics.function_logs.len());
+        let entries = metrics.function_logs.get(&fid).unwrap();
+        assert_eq!(MAX_LOG_ENTRIES, entries.len());
+        assert_eq!("msg-10", entries.front().unwrap().message);
+        assert_eq!(format!("msg-{}", MAX_LOG_ENTRIES + 9), entries.back().unwrap().message);
+        assert!(entries.iter().all(|x| x.level == "Info" && x.target == "target"));
+        assert!(metrics.function_errors.is_empty());
+
+        assert!(target.get_metrics().function_logs.is_empty());
+    }
 }
@@ -5,7 +5,7 @@
 
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TelemetryLogLevel {
     Error,
     Warn,
@@ -49,6 +49,7 @@ pub enum TelemetryEvent {
     FunctionInvocationCompleted(std::time::Duration),
     FunctionStop(std::time::Duration),
     FunctionExit(FunctionExitStatus),
+    FunctionRestart(u32), // number of restarts so far
 }
 
 #[derive(Clone)]
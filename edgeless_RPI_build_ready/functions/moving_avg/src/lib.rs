@@ -10,12 +10,14 @@ struct InitState {
     num_values: usize,
 }
 
-struct State {
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct Values {
     values: VecDeque<f32>,
 }
 
 static INIT_STATE: std::sync::OnceLock<InitState> = std::sync::OnceLock::new();
-static STATE: std::sync::OnceLock<std::sync::Mutex<State>> = std::sync::OnceLock::new();
+// The values are kept in memory only, i.e., the state is never synced.
+static STATE: State<Values> = State::new(SyncPolicy::Manual);
 
 impl EdgeFunction for MovingAvgFunction {
     fn handle_cast(src: InstanceId, encoded_message: &[u8]) {
@@ -32,14 +34,19 @@ impl EdgeFunction for MovingAvgFunction {
 
         match str_message.parse::<f32>() {
             Ok(val) => {
-                let mut state = STATE.get().unwrap().lock().unwrap();
-                if state.values.len() == init_state.num_values {
-                    state.values.pop_back();
-                }
-                state.values.push_front(val);
-                if state.values.len() == init_state.num_values {
-                    let average: f32 = state.values.iter().sum();
-                    cast(&"output", format!("{}", average / state.values.len() as f32).as_bytes());
+                let average = STATE.update(|state| {
+                    if state.values.len() == init_state.num_values {
+                        state.values.pop_back();
+                    }
+                    state.values.push_front(val);
+                    if state.values.len() == init_state.num_values {
+                        Some(state.values.iter().sum::<f32>() / state.values.len() as f32)
+                    } else {
+                        None
+                    }
+                });
+                if let Some(average) = average {
+                    cast(&"output", format!("{}", average).as_bytes());
                 }
             }
             Err(err) => cast(&"error", format!("invalid event payload '{:?}': {}", &encoded_message, err).as_bytes()),
@@ -50,7 +57,7 @@ impl EdgeFunction for MovingAvgFunction {
         CallRet::NoReply
     }
 
-    fn handle_init(payload: Option<&[u8]>, _serialized_state: Option<&[u8]>) {
+    fn handle_init(payload: Option<&[u8]>, serialized_state: Option<&[u8]>) {
         edgeless_function::init_logger();
 
         if let Some(payload) = payload {
@@ -60,7 +67,7 @@ impl EdgeFunction for MovingAvgFunction {
                 Err(_) => 0,
             };
             let _ = INIT_STATE.set(InitState { num_values });
-            let _ = STATE.set(std::sync::Mutex::new(State { values: VecDeque::new() }));
+            STATE.init(serialized_state, Values::default);
 
             log::info!("moving_avg initialized with size = {}", num_values);
         }
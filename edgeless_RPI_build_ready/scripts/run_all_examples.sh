@@ -1,8 +1,8 @@
 #!/bin/bash
 
-logs="build.log build_functions.log edgeless_bal.log edgeless_con.log edgeless_orc.log edgeless_node.log my-local-file.log reading-errors.log"
+logs="build.log build_functions.log edgeless_bal.log edgeless_con.log edgeless_orc.log edgeless_node.log my-local-file.log my-local-file.lp reading-errors.log"
 confs="balancer.toml controller.toml orchestrator.toml node.toml cli.toml"
-specialized_workflows="container dda_demo dda_test esp32_resources redis vector_mul matrix_mul ollama kafka_egress"
+specialized_workflows="container dda_demo dda_test esp32_resources redis vector_mul matrix_mul ollama kafka_egress kafka_ingress mqtt"
 
 echo "checking for existing files"
 existing_files=""
@@ -1,3 +1,3 @@
 #!/bin/bash
 
-lxterminal -e "bash -c 'cd edgeless/target/release && RUST_LOG=info ./edgeless_cli workflow start ../../../accelerometer_classification_workflow/workflow.json; exec bash'" &
+lxterminal -e "bash -c 'cd edgeless/target/release && RUST_LOG=info ./edgeless_cli workflow start ../../../accelerometer_classification_workflow/workflow.json --params ../../../accelerometer_classification_workflow/params.toml; exec bash'" &